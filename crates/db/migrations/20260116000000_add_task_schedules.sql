@@ -0,0 +1,25 @@
+-- Scheduled and recurring tasks: a template that creates and starts a task at fire time
+CREATE TABLE task_schedules (
+    id                  BLOB PRIMARY KEY,
+    project_id          BLOB NOT NULL,
+    title               TEXT NOT NULL,
+    description         TEXT,
+    executor_profile_id TEXT NOT NULL,             -- JSON ExecutorProfileId
+    repos               TEXT NOT NULL,             -- JSON array of { repo_id, target_branch }
+    cron_expression     TEXT,                      -- recurring schedules
+    run_at              TEXT,                      -- one-off schedules
+    catch_up_missed     INTEGER NOT NULL DEFAULT 0,
+    enabled             INTEGER NOT NULL DEFAULT 1,
+    next_run_at         TEXT,
+    last_run_at         TEXT,
+    last_task_id        BLOB,
+    missed_runs         INTEGER NOT NULL DEFAULT 0,
+    created_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    FOREIGN KEY (last_task_id) REFERENCES tasks(id) ON DELETE SET NULL,
+    CHECK ((cron_expression IS NULL) != (run_at IS NULL))
+);
+
+CREATE INDEX idx_task_schedules_project_id ON task_schedules(project_id);
+CREATE INDEX idx_task_schedules_next_run_at ON task_schedules(enabled, next_run_at);
@@ -0,0 +1,3 @@
+-- Explicit git hosting provider for repos on self-hosted instances whose remote URL
+-- can't be recognised (NULL = detect from the remote URL)
+ALTER TABLE repos ADD COLUMN git_host_provider TEXT;
@@ -0,0 +1,18 @@
+-- Token usage per model for each execution process, reported by the executor log normalizers
+CREATE TABLE execution_process_usage (
+    id                   BLOB PRIMARY KEY,
+    execution_process_id BLOB NOT NULL,
+    executor_profile     TEXT NOT NULL,                -- EXECUTOR or EXECUTOR:VARIANT
+    model                TEXT NOT NULL,
+    input_tokens         INTEGER NOT NULL DEFAULT 0,
+    output_tokens        INTEGER NOT NULL DEFAULT 0,
+    cache_read_tokens    INTEGER NOT NULL DEFAULT 0,
+    cache_write_tokens   INTEGER NOT NULL DEFAULT 0,
+    cost_usd             REAL,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE,
+    UNIQUE (execution_process_id, model)
+);
+
+CREATE INDEX idx_execution_process_usage_created_at ON execution_process_usage(created_at);
@@ -0,0 +1,13 @@
+-- Why a process was stopped early, set when it is stopped automatically (e.g. over budget)
+ALTER TABLE execution_processes ADD COLUMN stop_reason TEXT;
+
+-- Per-task run limits; take precedence over the limits of the executor profile variant
+CREATE TABLE task_budgets (
+    task_id               BLOB PRIMARY KEY,
+    budget_max_tokens     INTEGER,
+    budget_max_minutes    INTEGER,
+    budget_max_tool_calls INTEGER,
+    created_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
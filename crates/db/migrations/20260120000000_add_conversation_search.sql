@@ -0,0 +1,40 @@
+-- Searchable conversation entries (user/assistant messages and tool uses) per execution process.
+-- Entries are keyed by their index in the normalized conversation, which is patched in place
+-- while the agent streams, so rows are upserted rather than appended.
+CREATE TABLE conversation_entries (
+    id                   INTEGER PRIMARY KEY,
+    execution_process_id BLOB NOT NULL,
+    entry_index          INTEGER NOT NULL,
+    entry_type           TEXT NOT NULL
+                            CHECK (entry_type IN ('user_message', 'assistant_message', 'tool_use')),
+    content              TEXT NOT NULL,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE,
+    UNIQUE (execution_process_id, entry_index)
+);
+
+CREATE INDEX idx_conversation_entries_updated_at ON conversation_entries(updated_at);
+
+-- External-content FTS5 index over conversation_entries.content, kept in sync by triggers
+CREATE VIRTUAL TABLE conversation_entries_fts USING fts5(
+    content,
+    content = 'conversation_entries',
+    content_rowid = 'id',
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+CREATE TRIGGER conversation_entries_ai AFTER INSERT ON conversation_entries BEGIN
+    INSERT INTO conversation_entries_fts (rowid, content) VALUES (new.id, new.content);
+END;
+
+CREATE TRIGGER conversation_entries_ad AFTER DELETE ON conversation_entries BEGIN
+    INSERT INTO conversation_entries_fts (conversation_entries_fts, rowid, content)
+    VALUES ('delete', old.id, old.content);
+END;
+
+CREATE TRIGGER conversation_entries_au AFTER UPDATE OF content ON conversation_entries BEGIN
+    INSERT INTO conversation_entries_fts (conversation_entries_fts, rowid, content)
+    VALUES ('delete', old.id, old.content);
+    INSERT INTO conversation_entries_fts (rowid, content) VALUES (new.id, new.content);
+END;
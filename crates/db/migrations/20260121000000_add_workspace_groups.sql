@@ -0,0 +1,24 @@
+-- Workspaces started together for one task with different executor profiles, so their results
+-- can be compared and a winner picked
+CREATE TABLE workspace_groups (
+    id                  BLOB PRIMARY KEY,
+    task_id             BLOB NOT NULL,
+    winner_workspace_id BLOB,
+    created_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (winner_workspace_id) REFERENCES workspaces(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_workspace_groups_task_id ON workspace_groups(task_id);
+
+CREATE TABLE workspace_group_members (
+    workspace_id        BLOB PRIMARY KEY,
+    workspace_group_id  BLOB NOT NULL,
+    executor_profile_id TEXT NOT NULL,
+    created_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (workspace_group_id) REFERENCES workspace_groups(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_workspace_group_members_group_id ON workspace_group_members(workspace_group_id);
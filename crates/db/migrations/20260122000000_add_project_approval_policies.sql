@@ -0,0 +1,8 @@
+-- Approval rules of a project; evaluated before the rules of the executor profile variant
+CREATE TABLE project_approval_policies (
+    project_id BLOB PRIMARY KEY,
+    rules      TEXT NOT NULL DEFAULT '[]',
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
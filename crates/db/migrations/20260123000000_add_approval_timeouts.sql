@@ -0,0 +1,17 @@
+-- Approval requests nobody responded to before they timed out, kept for auditing
+CREATE TABLE approval_timeouts (
+    approval_id          TEXT PRIMARY KEY,
+    execution_process_id BLOB NOT NULL,
+    tool_name            TEXT NOT NULL,
+    tool_input           TEXT NOT NULL,
+    tool_call_id         TEXT NOT NULL,
+    resolution           TEXT NOT NULL
+                         CHECK (resolution IN ('denied', 'approved', 'waiting')),
+    requested_at         TEXT NOT NULL,
+    timed_out_at         TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_approval_timeouts_execution_process_id
+    ON approval_timeouts(execution_process_id);
+CREATE INDEX idx_approval_timeouts_timed_out_at ON approval_timeouts(timed_out_at);
@@ -0,0 +1,29 @@
+-- Workspaces waiting to be merged into a repo's target branch one at a time. Each entry is
+-- rebased onto the current target and checked with the repo's verify script before merging;
+-- entries that fail are ejected with the reason.
+CREATE TABLE merge_queue_entries (
+    id                 BLOB PRIMARY KEY,
+    workspace_id       BLOB NOT NULL,
+    repo_id            BLOB NOT NULL,
+    target_branch_name TEXT NOT NULL,
+    strategy           TEXT NOT NULL DEFAULT 'squash'
+                       CHECK (strategy IN ('squash', 'fast_forward')),
+    status             TEXT NOT NULL DEFAULT 'queued'
+                       CHECK (status IN ('queued', 'running', 'merged', 'ejected', 'cancelled')),
+    failure_reason     TEXT,
+    -- The direct merge created when the entry was merged
+    merge_id           BLOB,
+    created_at         TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at         TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE,
+    FOREIGN KEY (merge_id) REFERENCES merges(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_merge_queue_entries_repo_status ON merge_queue_entries(repo_id, status);
+CREATE INDEX idx_merge_queue_entries_workspace_id ON merge_queue_entries(workspace_id);
+
+-- A workspace can only wait in a repo's queue once
+CREATE UNIQUE INDEX idx_merge_queue_entries_active
+    ON merge_queue_entries(workspace_id, repo_id)
+    WHERE status IN ('queued', 'running');
@@ -0,0 +1,4 @@
+-- Workspaces stacked on another workspace's branch instead of a repo branch
+ALTER TABLE workspaces ADD COLUMN base_workspace_id BLOB REFERENCES workspaces(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_workspaces_base_workspace_id ON workspaces(base_workspace_id);
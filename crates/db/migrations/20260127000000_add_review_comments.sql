@@ -0,0 +1,28 @@
+-- Comments left on line ranges of a workspace's diff, to be sent to the agent in a follow-up.
+-- `anchor_text` holds the commented lines as they were when the comment was made, so later
+-- changes to them can be detected.
+CREATE TABLE review_comments (
+    id                   BLOB PRIMARY KEY,
+    workspace_id         BLOB NOT NULL,
+    repo_id              BLOB NOT NULL,
+    file_path            TEXT NOT NULL,
+    -- 1-based, inclusive, in the workspace's version of the file
+    line_start           INTEGER NOT NULL CHECK (line_start >= 1),
+    line_end             INTEGER NOT NULL CHECK (line_end >= line_start),
+    body                 TEXT NOT NULL,
+    -- HEAD of the workspace branch the comment was made against
+    commit_sha           TEXT NOT NULL,
+    anchor_text          TEXT NOT NULL,
+    status               TEXT NOT NULL DEFAULT 'open'
+                         CHECK (status IN ('open', 'sent', 'outdated', 'resolved')),
+    -- The coding agent turn the comment was sent in
+    execution_process_id BLOB,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_review_comments_workspace_id ON review_comments(workspace_id);
+CREATE INDEX idx_review_comments_execution_process_id ON review_comments(execution_process_id);
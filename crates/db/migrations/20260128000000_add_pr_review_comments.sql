@@ -0,0 +1,38 @@
+-- Whether review comments on a project's PRs are sent to the workspace's agent automatically
+CREATE TABLE project_pr_review_settings (
+    project_id     BLOB PRIMARY KEY,
+    auto_follow_up INTEGER NOT NULL DEFAULT 0,
+    created_at     TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at     TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+-- Inline review comments found on open PRs of workspaces. `comment_id` is the git host's id of
+-- the comment, so each comment is only picked up once.
+CREATE TABLE pr_review_comments (
+    id                   BLOB PRIMARY KEY,
+    merge_id             BLOB NOT NULL,
+    workspace_id         BLOB NOT NULL,
+    repo_id              BLOB NOT NULL,
+    comment_id           TEXT NOT NULL,
+    author               TEXT NOT NULL,
+    body                 TEXT NOT NULL,
+    path                 TEXT NOT NULL,
+    line                 INTEGER,
+    diff_hunk            TEXT,
+    url                  TEXT,
+    status               TEXT NOT NULL DEFAULT 'pending'
+                         CHECK (status IN ('pending', 'sent', 'addressed', 'failed')),
+    -- The coding agent turn the comment was sent in
+    execution_process_id BLOB,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (merge_id) REFERENCES merges(id) ON DELETE CASCADE,
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE SET NULL,
+    UNIQUE (merge_id, comment_id)
+);
+
+CREATE INDEX idx_pr_review_comments_workspace_id ON pr_review_comments(workspace_id);
+CREATE INDEX idx_pr_review_comments_execution_process_id ON pr_review_comments(execution_process_id);
@@ -0,0 +1,8 @@
+-- CI checks of a PR as last reported by the git host, as a JSON array
+ALTER TABLE merges ADD COLUMN pr_checks TEXT;
+-- The failing checks last reported to the user or sent to the agent, so each failure is only
+-- handled once
+ALTER TABLE merges ADD COLUMN pr_checks_failure_key TEXT;
+
+-- Whether failing CI checks of a project's PRs are sent to the workspace's agent to fix
+ALTER TABLE project_pr_review_settings ADD COLUMN auto_fix_checks INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,18 @@
+-- Issues on the git host that tasks were imported from. The issue is commented on and closed
+-- once a PR of the task merges.
+CREATE TABLE task_issue_links (
+    id           BLOB PRIMARY KEY,
+    task_id      BLOB NOT NULL UNIQUE,
+    repo_id      BLOB NOT NULL,
+    -- Remote the issue was imported through, so it is closed on the same host
+    remote_url   TEXT NOT NULL,
+    -- Issue number on GitHub, work item id on Azure Boards
+    issue_number INTEGER NOT NULL,
+    issue_url    TEXT NOT NULL,
+    closed_at    TEXT,
+    created_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_task_issue_links_issue_url ON task_issue_links(issue_url);
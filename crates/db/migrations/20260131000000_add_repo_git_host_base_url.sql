@@ -0,0 +1,3 @@
+-- Web base URL of a self-hosted GitLab or Gitea instance, for repos whose remote URL doesn't
+-- point at it (NULL = derive it from the remote URL)
+ALTER TABLE repos ADD COLUMN git_host_base_url TEXT;
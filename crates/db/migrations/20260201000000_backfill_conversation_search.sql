@@ -0,0 +1,14 @@
+-- Coding agent processes that ran before conversation search existed. They are indexed from
+-- their stored logs at startup and removed from this table once indexed.
+CREATE TABLE conversation_search_backfill (
+    execution_process_id BLOB PRIMARY KEY,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+INSERT INTO conversation_search_backfill (execution_process_id)
+SELECT ep.id
+FROM execution_processes ep
+WHERE ep.run_reason = 'codingagent'
+  AND NOT EXISTS (
+      SELECT 1 FROM conversation_entries ce WHERE ce.execution_process_id = ep.id
+  );
@@ -0,0 +1,64 @@
+-- When auto follow-up was last turned on for a project. Review comments posted before then are
+-- not sent to the agent.
+ALTER TABLE project_pr_review_settings ADD COLUMN auto_follow_up_enabled_at TEXT;
+UPDATE project_pr_review_settings SET auto_follow_up_enabled_at = updated_at WHERE auto_follow_up = 1;
+
+-- Add the `skipped` status, for review comments that are never sent to the agent: those posted
+-- before auto follow-up was turned on, and those whose thread was resolved first.
+-- Migration steps following the official SQLite "12-step generalized ALTER TABLE" procedure:
+-- https://www.sqlite.org/lang_altertable.html#otheralter
+--
+PRAGMA foreign_keys = OFF;
+
+-- This is a sqlx workaround to enable BEGIN TRANSACTION in this migration, until `-- no-transaction` lands in sqlx-sqlite.
+-- https://github.com/launchbadge/sqlx/issues/2085#issuecomment-1499859906
+COMMIT TRANSACTION;
+
+BEGIN TRANSACTION;
+
+CREATE TABLE pr_review_comments_new (
+    id                   BLOB PRIMARY KEY,
+    merge_id             BLOB NOT NULL,
+    workspace_id         BLOB NOT NULL,
+    repo_id              BLOB NOT NULL,
+    comment_id           TEXT NOT NULL,
+    author               TEXT NOT NULL,
+    body                 TEXT NOT NULL,
+    path                 TEXT NOT NULL,
+    line                 INTEGER,
+    diff_hunk            TEXT,
+    url                  TEXT,
+    status               TEXT NOT NULL DEFAULT 'pending'
+                         CHECK (status IN ('pending', 'sent', 'addressed', 'failed', 'skipped')),
+    -- The coding agent turn the comment was sent in
+    execution_process_id BLOB,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (merge_id) REFERENCES merges(id) ON DELETE CASCADE,
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE SET NULL,
+    UNIQUE (merge_id, comment_id)
+);
+
+INSERT INTO pr_review_comments_new
+SELECT id, merge_id, workspace_id, repo_id, comment_id, author, body, path, line, diff_hunk, url,
+       status, execution_process_id, created_at, updated_at
+FROM pr_review_comments;
+
+DROP TABLE pr_review_comments;
+
+ALTER TABLE pr_review_comments_new RENAME TO pr_review_comments;
+
+CREATE INDEX idx_pr_review_comments_workspace_id ON pr_review_comments(workspace_id);
+CREATE INDEX idx_pr_review_comments_execution_process_id ON pr_review_comments(execution_process_id);
+
+-- Verify foreign key constraints before committing the transaction.
+PRAGMA foreign_key_check;
+
+COMMIT;
+
+PRAGMA foreign_keys = ON;
+
+-- sqlx workaround due to lack of `-- no-transaction` in sqlx-sqlite.
+BEGIN TRANSACTION;
@@ -0,0 +1,553 @@
+//! Backups of the SQLite database: copies taken with SQLite's online backup API while the app
+//! keeps running, and restores that are checked against this build's migrations first.
+
+use std::{
+    collections::HashSet,
+    ffi::{CStr, CString, c_int},
+    fs,
+    path::{Path, PathBuf},
+    ptr,
+    str::FromStr,
+};
+
+use chrono::{DateTime, NaiveDateTime, SubsecRound, Utc};
+use libsqlite3_sys as ffi;
+use sqlx::{
+    Connection, SqliteConnection, SqlitePool,
+    sqlite::{SqliteConnectOptions, SqlitePoolOptions},
+};
+use strum_macros::{Display, EnumString};
+use thiserror::Error;
+use utils::assets::asset_dir;
+
+use crate::MIGRATOR;
+
+const BACKUP_EXTENSION: &str = "sqlite";
+const TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+/// Pages copied per backup step. Other connections can write between steps.
+const PAGES_PER_STEP: c_int = 256;
+/// Pause between backup steps, in milliseconds
+const STEP_PAUSE_MS: c_int = 10;
+/// How often in a row a backup step is retried while another connection is writing, 100ms apart
+const BUSY_RETRIES: u32 = 50;
+
+#[derive(Debug, Error)]
+pub enum BackupError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("SQLite backup failed: {0}")]
+    Sqlite(String),
+    #[error("Invalid backup: {0}")]
+    Invalid(String),
+}
+
+impl From<BackupError> for sqlx::Error {
+    fn from(error: BackupError) -> Self {
+        match error {
+            BackupError::Database(e) => e,
+            BackupError::Io(e) => sqlx::Error::Io(e),
+            e => sqlx::Error::Io(std::io::Error::other(e)),
+        }
+    }
+}
+
+/// Why a backup was taken; part of its file name
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display, EnumString)]
+#[strum(serialize_all = "kebab-case")]
+pub enum BackupKind {
+    /// Taken periodically and rotated
+    Scheduled,
+    /// Taken at startup before new migrations run
+    PreMigration,
+    /// The database a restore replaced
+    PreRestore,
+}
+
+impl BackupKind {
+    fn file_name(self, created_at: DateTime<Utc>) -> String {
+        format!(
+            "{self}-{}.{BACKUP_EXTENSION}",
+            created_at.format(TIMESTAMP_FORMAT)
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackupFile {
+    pub path: PathBuf,
+    pub kind: BackupKind,
+    pub created_at: DateTime<Utc>,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackupInfo {
+    pub applied_migrations: usize,
+    /// Migrations of this build the backup has not run; they run at the next start
+    pub pending_migrations: usize,
+}
+
+pub fn backup_dir() -> PathBuf {
+    asset_dir().join("backups")
+}
+
+/// Backups in `dir`, newest first. Other files, such as unfinished backups, are ignored.
+pub fn list_backups(dir: &Path) -> Result<Vec<BackupFile>, BackupError> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut backups = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let Some((kind, created_at)) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(parse_file_name)
+        else {
+            continue;
+        };
+        backups.push(BackupFile {
+            size: entry.metadata()?.len(),
+            path,
+            kind,
+            created_at,
+        });
+    }
+    backups.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    Ok(backups)
+}
+
+fn parse_file_name(name: &str) -> Option<(BackupKind, DateTime<Utc>)> {
+    let stem = name.strip_suffix(BACKUP_EXTENSION)?.strip_suffix('.')?;
+    let (kind, timestamp) = stem.rsplit_once('-')?;
+    let created_at = NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT)
+        .ok()?
+        .and_utc();
+    Some((BackupKind::from_str(kind).ok()?, created_at))
+}
+
+/// Copy the database file behind `pool` into a new file in `dir`. The copy is a consistent
+/// snapshot even while other connections write, and only appears under its final name once
+/// complete. It runs on a connection of its own, so the pool stays available meanwhile.
+pub async fn create_backup(
+    pool: &SqlitePool,
+    dir: &Path,
+    kind: BackupKind,
+) -> Result<BackupFile, BackupError> {
+    fs::create_dir_all(dir)?;
+    let created_at = Utc::now().trunc_subsecs(0);
+    let path = dir.join(kind.file_name(created_at));
+    let partial = path.with_extension(format!("{BACKUP_EXTENSION}.partial"));
+
+    let source = pool.connect_options().get_filename().to_path_buf();
+    let dest = partial.clone();
+    let copied = tokio::task::spawn_blocking(move || copy_database(&source, &dest))
+        .await
+        .map_err(|e| BackupError::Io(std::io::Error::other(e)))
+        .and_then(|copied| copied);
+    if let Err(e) = copied {
+        let _ = fs::remove_file(&partial);
+        return Err(e);
+    }
+    fs::rename(&partial, &path)?;
+
+    Ok(BackupFile {
+        size: fs::metadata(&path)?.len(),
+        path,
+        kind,
+        created_at,
+    })
+}
+
+/// Run SQLite's backup of the database at `source` into a new database at `path`, a few pages at
+/// a time so writers are only held up briefly. Blocks until the copy is complete.
+fn copy_database(source: &Path, path: &Path) -> Result<(), BackupError> {
+    let c_path = |path: &Path| {
+        path.to_str()
+            .and_then(|path| CString::new(path).ok())
+            .ok_or_else(|| {
+                BackupError::Invalid(format!("Unsupported database path {}", path.display()))
+            })
+    };
+    let c_source = c_path(source)?;
+    let c_dest = c_path(path)?;
+    let main = c"main";
+
+    // SAFETY: `src` and `dest` are only used here, and the backup is finished before either is
+    // closed.
+    unsafe {
+        let mut src = ptr::null_mut();
+        let rc = ffi::sqlite3_open_v2(
+            c_source.as_ptr(),
+            &mut src,
+            ffi::SQLITE_OPEN_READONLY,
+            ptr::null(),
+        );
+        if rc != ffi::SQLITE_OK {
+            ffi::sqlite3_close(src);
+            return Err(BackupError::Sqlite(error_string(rc)));
+        }
+        let mut dest = ptr::null_mut();
+        let rc = ffi::sqlite3_open_v2(
+            c_dest.as_ptr(),
+            &mut dest,
+            ffi::SQLITE_OPEN_READWRITE | ffi::SQLITE_OPEN_CREATE,
+            ptr::null(),
+        );
+        if rc != ffi::SQLITE_OK {
+            ffi::sqlite3_close(dest);
+            ffi::sqlite3_close(src);
+            return Err(BackupError::Sqlite(error_string(rc)));
+        }
+
+        let backup = ffi::sqlite3_backup_init(dest, main.as_ptr(), src, main.as_ptr());
+        if backup.is_null() {
+            let error = error_string(ffi::sqlite3_errcode(dest));
+            ffi::sqlite3_close(dest);
+            ffi::sqlite3_close(src);
+            return Err(BackupError::Sqlite(error));
+        }
+
+        let mut retries = 0;
+        let rc = loop {
+            match ffi::sqlite3_backup_step(backup, PAGES_PER_STEP) {
+                ffi::SQLITE_OK => {
+                    retries = 0;
+                    ffi::sqlite3_sleep(STEP_PAUSE_MS);
+                }
+                ffi::SQLITE_BUSY | ffi::SQLITE_LOCKED if retries < BUSY_RETRIES => {
+                    retries += 1;
+                    ffi::sqlite3_sleep(100);
+                }
+                rc => break rc,
+            }
+        };
+        ffi::sqlite3_backup_finish(backup);
+        ffi::sqlite3_close(dest);
+        ffi::sqlite3_close(src);
+
+        if rc == ffi::SQLITE_DONE {
+            Ok(())
+        } else {
+            Err(BackupError::Sqlite(error_string(rc)))
+        }
+    }
+}
+
+fn error_string(rc: c_int) -> String {
+    // SAFETY: sqlite3_errstr returns a static string for any result code
+    unsafe { CStr::from_ptr(ffi::sqlite3_errstr(rc)) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Versions and checksums of the migrations recorded in a database, or `None` when it has no
+/// migration history
+async fn recorded_migrations(
+    conn: &mut SqliteConnection,
+) -> Result<Option<Vec<(i64, bool, Vec<u8>)>>, sqlx::Error> {
+    let has_history: bool = sqlx::query_scalar(
+        "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = '_sqlx_migrations')",
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+    if !has_history {
+        return Ok(None);
+    }
+    let migrations = sqlx::query_as("SELECT version, success, checksum FROM _sqlx_migrations")
+        .fetch_all(&mut *conn)
+        .await?;
+    Ok(Some(migrations))
+}
+
+fn pending_migrations(applied: &HashSet<i64>) -> usize {
+    MIGRATOR
+        .iter()
+        .filter(|m| !m.migration_type.is_down_migration() && !applied.contains(&m.version))
+        .count()
+}
+
+/// Check that `path` holds an intact database made by this build or an older one, so that it
+/// can be restored
+pub async fn validate_backup(path: &Path) -> Result<BackupInfo, BackupError> {
+    if !path.is_file() {
+        return Err(BackupError::Invalid(format!(
+            "{} does not exist",
+            path.display()
+        )));
+    }
+    let options = SqliteConnectOptions::new().filename(path).read_only(true);
+    let mut conn = SqliteConnection::connect_with(&options).await?;
+    let info = inspect_backup(&mut conn).await;
+    conn.close().await?;
+    info
+}
+
+async fn inspect_backup(conn: &mut SqliteConnection) -> Result<BackupInfo, BackupError> {
+    let integrity: String = sqlx::query_scalar("PRAGMA integrity_check")
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| BackupError::Invalid(format!("Not a readable database: {e}")))?;
+    if integrity != "ok" {
+        return Err(BackupError::Invalid(format!(
+            "Integrity check failed: {integrity}"
+        )));
+    }
+
+    let Some(recorded) = recorded_migrations(conn).await? else {
+        return Err(BackupError::Invalid(
+            "No migration history, this is not a vibe-kanban database".to_string(),
+        ));
+    };
+    let mut applied = HashSet::new();
+    for (version, success, checksum) in recorded {
+        if !success {
+            return Err(BackupError::Invalid(format!(
+                "Migration {version} did not complete in this backup"
+            )));
+        }
+        let Some(migration) = MIGRATOR.iter().find(|m| m.version == version) else {
+            return Err(BackupError::Invalid(format!(
+                "Migration {version} is unknown, the backup was made by a newer version"
+            )));
+        };
+        // Windows checkouts can change line endings; run_migrations repairs those checksums
+        if !cfg!(windows) && *migration.checksum != *checksum {
+            return Err(BackupError::Invalid(format!(
+                "Migration {version} differs from the one in this version"
+            )));
+        }
+        applied.insert(version);
+    }
+
+    Ok(BackupInfo {
+        applied_migrations: applied.len(),
+        pending_migrations: pending_migrations(&applied),
+    })
+}
+
+/// Back up the database before this build runs migrations it has not run yet. Databases without
+/// any migrations yet are new and have nothing to keep.
+pub(crate) async fn snapshot_before_migrations(
+    pool: &SqlitePool,
+) -> Result<Option<BackupFile>, BackupError> {
+    let recorded = {
+        let mut conn = pool.acquire().await?;
+        recorded_migrations(&mut conn).await?.unwrap_or_default()
+    };
+    if recorded.is_empty() {
+        return Ok(None);
+    }
+    let applied = recorded.iter().map(|(version, ..)| *version).collect();
+    let pending = pending_migrations(&applied);
+    if pending == 0 {
+        return Ok(None);
+    }
+
+    let backup = create_backup(pool, &backup_dir(), BackupKind::PreMigration).await?;
+    tracing::info!(
+        "Backed up the database to {} before running {} new migrations",
+        backup.path.display(),
+        pending
+    );
+    Ok(Some(backup))
+}
+
+/// Replace the database at `database` with a validated backup. The replaced database is kept in
+/// `dir` as a pre-restore backup. Nothing may be using the database meanwhile.
+pub async fn restore_backup(
+    backup: &Path,
+    database: &Path,
+    dir: &Path,
+) -> Result<BackupInfo, BackupError> {
+    let info = validate_backup(backup).await?;
+
+    if database.exists() {
+        let options = SqliteConnectOptions::new().filename(database);
+        let current = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await?;
+        let kept = create_backup(&current, dir, BackupKind::PreRestore).await;
+        current.close().await;
+        if let Err(e) = kept {
+            // A damaged database may not back up; keep its bytes as they are
+            tracing::warn!("Failed to back up the current database, copying it instead: {e}");
+            fs::copy(
+                database,
+                dir.join(BackupKind::PreRestore.file_name(Utc::now().trunc_subsecs(0))),
+            )?;
+        }
+    }
+
+    let mut staged = database.as_os_str().to_owned();
+    staged.push(".restore");
+    fs::copy(backup, &staged)?;
+    // A leftover journal would be applied to the restored database on its next open
+    for suffix in ["-journal", "-wal", "-shm"] {
+        let mut sidecar = database.as_os_str().to_owned();
+        sidecar.push(suffix);
+        if Path::new(&sidecar).exists() {
+            fs::remove_file(&sidecar)?;
+        }
+    }
+    fs::rename(&staged, database)?;
+
+    Ok(info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::tag::{CreateTag, Tag};
+
+    async fn file_pool(path: &Path) -> SqlitePool {
+        let options = SqliteConnectOptions::new()
+            .filename(path)
+            .create_if_missing(true);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .unwrap();
+        crate::run_migrations(&pool).await.unwrap();
+        pool
+    }
+
+    async fn add_tag(pool: &SqlitePool, tag_name: &str) {
+        Tag::create(
+            pool,
+            &CreateTag {
+                tag_name: tag_name.to_string(),
+                content: "content".to_string(),
+            },
+        )
+        .await
+        .unwrap();
+    }
+
+    async fn tag_names(path: &Path) -> Vec<String> {
+        let pool = file_pool(path).await;
+        let mut names: Vec<String> = Tag::find_all(&pool)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|tag| tag.tag_name)
+            .collect();
+        names.sort();
+        pool.close().await;
+        names
+    }
+
+    #[tokio::test]
+    async fn test_backup_and_restore() {
+        let dir = tempfile::tempdir().unwrap();
+        let database = dir.path().join("db.sqlite");
+        let backups = dir.path().join("backups");
+
+        let pool = file_pool(&database).await;
+        add_tag(&pool, "before").await;
+        let backup = create_backup(&pool, &backups, BackupKind::Scheduled)
+            .await
+            .unwrap();
+        add_tag(&pool, "after").await;
+        pool.close().await;
+
+        assert_eq!(list_backups(&backups).unwrap(), vec![backup.clone()]);
+        let info = validate_backup(&backup.path).await.unwrap();
+        assert_eq!(info.pending_migrations, 0);
+        assert_eq!(info.applied_migrations, MIGRATOR.iter().count());
+
+        restore_backup(&backup.path, &database, &backups)
+            .await
+            .unwrap();
+        assert_eq!(tag_names(&database).await, vec!["before"]);
+
+        let replaced = list_backups(&backups)
+            .unwrap()
+            .into_iter()
+            .find(|b| b.kind == BackupKind::PreRestore)
+            .unwrap();
+        assert_eq!(tag_names(&replaced.path).await, vec!["after", "before"]);
+    }
+
+    #[tokio::test]
+    async fn test_backup_leaves_the_pool_available() {
+        let dir = tempfile::tempdir().unwrap();
+        let database = dir.path().join("db.sqlite");
+        let backups = dir.path().join("backups");
+
+        let pool = file_pool(&database).await;
+        add_tag(&pool, "committed").await;
+        // The pool's only connection stays busy for the whole backup
+        let mut tx = pool.begin().await.unwrap();
+        sqlx::query("INSERT INTO tags (id, tag_name, content) VALUES ($1, $2, $3)")
+            .bind(uuid::Uuid::new_v4())
+            .bind("uncommitted")
+            .bind("content")
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+        let backup = create_backup(&pool, &backups, BackupKind::Scheduled)
+            .await
+            .unwrap();
+        tx.commit().await.unwrap();
+        pool.close().await;
+
+        assert_eq!(tag_names(&backup.path).await, vec!["committed"]);
+    }
+
+    #[tokio::test]
+    async fn test_validate_rejects_unknown_databases() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let garbage = dir.path().join("garbage.sqlite");
+        fs::write(&garbage, b"not a database at all, just some bytes").unwrap();
+        assert!(matches!(
+            validate_backup(&garbage).await,
+            Err(BackupError::Invalid(_))
+        ));
+
+        let newer = dir.path().join("newer.sqlite");
+        let pool = file_pool(&newer).await;
+        sqlx::query(
+            "INSERT INTO _sqlx_migrations (version, description, success, checksum, execution_time)
+             VALUES (99990101000000, 'from the future', 1, x'00', 0)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        pool.close().await;
+        let error = validate_backup(&newer).await.unwrap_err();
+        assert!(error.to_string().contains("newer version"), "{error}");
+
+        let database = dir.path().join("db.sqlite");
+        assert!(
+            restore_backup(&newer, &database, &dir.path().join("backups"))
+                .await
+                .is_err()
+        );
+        assert!(!database.exists());
+    }
+
+    #[test]
+    fn test_parse_file_name() {
+        let created_at = DateTime::parse_from_rfc3339("2026-03-01T14:05:09Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let name = BackupKind::PreMigration.file_name(created_at);
+        assert_eq!(name, "pre-migration-20260301T140509Z.sqlite");
+        assert_eq!(
+            parse_file_name(&name),
+            Some((BackupKind::PreMigration, created_at))
+        );
+        assert_eq!(
+            parse_file_name("pre-migration-20260301T140509Z.sqlite.partial"),
+            None
+        );
+        assert_eq!(parse_file_name("db.sqlite"), None);
+    }
+}
@@ -1,18 +1,27 @@
-use std::{str::FromStr, sync::Arc};
+use std::{path::PathBuf, str::FromStr, sync::Arc};
 
 use sqlx::{
     Error, Pool, Sqlite, SqlitePool,
-    migrate::MigrateError,
+    migrate::{MigrateError, Migrator},
     sqlite::{SqliteConnectOptions, SqliteConnection, SqliteJournalMode, SqlitePoolOptions},
 };
 use utils::assets::asset_dir;
 
+pub mod backup;
 pub mod models;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
+
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+pub fn database_path() -> PathBuf {
+    asset_dir().join("db.sqlite")
+}
 
 async fn run_migrations(pool: &Pool<Sqlite>) -> Result<(), Error> {
     use std::collections::HashSet;
 
-    let migrator = sqlx::migrate!("./migrations");
+    let migrator = &MIGRATOR;
     let mut processed_versions: HashSet<i64> = HashSet::new();
 
     loop {
@@ -74,14 +83,12 @@ pub struct DBService {
 
 impl DBService {
     pub async fn new() -> Result<DBService, Error> {
-        let database_url = format!(
-            "sqlite://{}",
-            asset_dir().join("db.sqlite").to_string_lossy()
-        );
+        let database_url = format!("sqlite://{}", database_path().to_string_lossy());
         let options = SqliteConnectOptions::from_str(&database_url)?
             .create_if_missing(true)
             .journal_mode(SqliteJournalMode::Delete);
         let pool = SqlitePool::connect_with(options).await?;
+        backup::snapshot_before_migrations(&pool).await?;
         run_migrations(&pool).await?;
         Ok(DBService { pool })
     }
@@ -110,10 +117,7 @@ impl DBService {
             + Sync
             + 'static,
     {
-        let database_url = format!(
-            "sqlite://{}",
-            asset_dir().join("db.sqlite").to_string_lossy()
-        );
+        let database_url = format!("sqlite://{}", database_path().to_string_lossy());
         let options = SqliteConnectOptions::from_str(&database_url)?
             .create_if_missing(true)
             .journal_mode(SqliteJournalMode::Delete);
@@ -133,6 +137,7 @@ impl DBService {
             SqlitePool::connect_with(options).await?
         };
 
+        backup::snapshot_before_migrations(&pool).await?;
         run_migrations(&pool).await?;
         Ok(pool)
     }
@@ -0,0 +1,100 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// What a timed out approval request resolved to
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "approval_timeout_resolution", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(use_ts_enum)]
+pub enum ApprovalTimeoutResolution {
+    Denied,
+    Approved,
+    /// The run stays paused until someone responds
+    Waiting,
+}
+
+/// An approval request nobody responded to in time
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ApprovalTimeout {
+    pub approval_id: String,
+    pub execution_process_id: Uuid,
+    pub tool_name: String,
+    #[ts(type = "JsonValue")]
+    pub tool_input: Json<serde_json::Value>,
+    pub tool_call_id: String,
+    pub resolution: ApprovalTimeoutResolution,
+    pub requested_at: DateTime<Utc>,
+    pub timed_out_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateApprovalTimeout<'a> {
+    pub approval_id: &'a str,
+    pub execution_process_id: Uuid,
+    pub tool_name: &'a str,
+    pub tool_input: &'a serde_json::Value,
+    pub tool_call_id: &'a str,
+    pub resolution: ApprovalTimeoutResolution,
+    pub requested_at: DateTime<Utc>,
+}
+
+impl ApprovalTimeout {
+    pub async fn create(
+        pool: &SqlitePool,
+        data: CreateApprovalTimeout<'_>,
+    ) -> Result<Self, sqlx::Error> {
+        let tool_input = Json(data.tool_input);
+        sqlx::query_as!(
+            ApprovalTimeout,
+            r#"INSERT INTO approval_timeouts (approval_id, execution_process_id, tool_name, tool_input, tool_call_id, resolution, requested_at)
+               VALUES ($1, $2, $3, $4, $5, $6, $7)
+               RETURNING approval_id as "approval_id!",
+                         execution_process_id as "execution_process_id!: Uuid",
+                         tool_name,
+                         tool_input as "tool_input!: Json<serde_json::Value>",
+                         tool_call_id,
+                         resolution as "resolution!: ApprovalTimeoutResolution",
+                         requested_at as "requested_at!: DateTime<Utc>",
+                         timed_out_at as "timed_out_at!: DateTime<Utc>""#,
+            data.approval_id,
+            data.execution_process_id,
+            data.tool_name,
+            tool_input,
+            data.tool_call_id,
+            data.resolution,
+            data.requested_at
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Most recent timeouts first, optionally only those of one execution process
+    pub async fn find_recent(
+        pool: &SqlitePool,
+        execution_process_id: Option<Uuid>,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ApprovalTimeout,
+            r#"SELECT approval_id as "approval_id!",
+                      execution_process_id as "execution_process_id!: Uuid",
+                      tool_name,
+                      tool_input as "tool_input!: Json<serde_json::Value>",
+                      tool_call_id,
+                      resolution as "resolution!: ApprovalTimeoutResolution",
+                      requested_at as "requested_at!: DateTime<Utc>",
+                      timed_out_at as "timed_out_at!: DateTime<Utc>"
+               FROM approval_timeouts
+               WHERE $1 IS NULL OR execution_process_id = $1
+               ORDER BY timed_out_at DESC
+               LIMIT $2"#,
+            execution_process_id,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
@@ -0,0 +1,141 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{SqlitePool, Type};
+use strum_macros::Display;
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, TS, Display)]
+#[sqlx(type_name = "conversation_entry_type", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum ConversationEntryType {
+    UserMessage,
+    AssistantMessage,
+    ToolUse,
+}
+
+/// Restricts which conversation entries a search looks at
+#[derive(Debug, Clone)]
+pub struct ConversationSearchFilter {
+    pub project_id: Option<Uuid>,
+    pub task_id: Option<Uuid>,
+    /// Only entries last written at or after this time
+    pub since: Option<DateTime<Utc>>,
+    pub limit: i64,
+}
+
+/// A conversation entry matching a search, with the task and workspace it belongs to
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ConversationSearchHit {
+    pub project_id: Uuid,
+    pub task_id: Uuid,
+    pub task_title: String,
+    pub workspace_id: Uuid,
+    pub workspace_branch: String,
+    pub session_id: Uuid,
+    pub execution_process_id: Uuid,
+    /// Index of the entry in the execution process's normalized conversation
+    pub entry_index: i64,
+    pub entry_type: ConversationEntryType,
+    /// Excerpt of the entry with matched terms wrapped in `<mark>` tags
+    pub snippet: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Search index over the normalized conversation of each execution process
+pub struct ConversationEntry;
+
+impl ConversationEntry {
+    /// Insert or replace the indexed text of an entry. Entries are patched in place while the
+    /// agent streams, so unchanged content is skipped to avoid churning the FTS index.
+    pub async fn upsert(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        entry_index: i64,
+        entry_type: ConversationEntryType,
+        content: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO conversation_entries (execution_process_id, entry_index, entry_type, content)
+               VALUES ($1, $2, $3, $4)
+               ON CONFLICT(execution_process_id, entry_index) DO UPDATE SET
+                   entry_type = excluded.entry_type,
+                   content = excluded.content,
+                   updated_at = datetime('now', 'subsec')
+               WHERE conversation_entries.content != excluded.content
+                  OR conversation_entries.entry_type != excluded.entry_type"#,
+            execution_process_id,
+            entry_index,
+            entry_type,
+            content
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Execution processes from before conversation search that still need to be indexed
+    pub async fn find_backfill_pending(pool: &SqlitePool) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT execution_process_id as "execution_process_id!: Uuid"
+               FROM conversation_search_backfill"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn complete_backfill(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "DELETE FROM conversation_search_backfill WHERE execution_process_id = $1",
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Run an FTS5 `MATCH` expression against the index, best matches first
+    pub async fn search(
+        pool: &SqlitePool,
+        match_query: &str,
+        filter: &ConversationSearchFilter,
+    ) -> Result<Vec<ConversationSearchHit>, sqlx::Error> {
+        sqlx::query_as!(
+            ConversationSearchHit,
+            r#"SELECT t.project_id as "project_id!: Uuid",
+                      t.id as "task_id!: Uuid",
+                      t.title as "task_title!: String",
+                      w.id as "workspace_id!: Uuid",
+                      w.branch as "workspace_branch!: String",
+                      s.id as "session_id!: Uuid",
+                      ce.execution_process_id as "execution_process_id!: Uuid",
+                      ce.entry_index as "entry_index!: i64",
+                      ce.entry_type as "entry_type!: ConversationEntryType",
+                      snippet(conversation_entries_fts, 0, '<mark>', '</mark>', '…', 24) as "snippet!: String",
+                      ce.updated_at as "updated_at!: DateTime<Utc>"
+               FROM conversation_entries_fts
+               JOIN conversation_entries ce ON ce.id = conversation_entries_fts.rowid
+               JOIN execution_processes ep ON ep.id = ce.execution_process_id
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               JOIN tasks t ON t.id = w.task_id
+               WHERE conversation_entries_fts MATCH $1
+                 AND ($2 IS NULL OR t.project_id = $2)
+                 AND ($3 IS NULL OR t.id = $3)
+                 AND ($4 IS NULL OR datetime(ce.updated_at) >= datetime($4))
+               ORDER BY bm25(conversation_entries_fts)
+               LIMIT $5"#,
+            match_query,
+            filter.project_id,
+            filter.task_id,
+            filter.since,
+            filter.limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
@@ -49,6 +49,16 @@ pub enum ExecutionProcessStatus {
     Killed,
 }
 
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, TS)]
+#[sqlx(type_name = "execution_process_stop_reason", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(use_ts_enum)]
+pub enum ExecutionProcessStopReason {
+    TokenBudgetExceeded,
+    TimeBudgetExceeded,
+    ToolCallBudgetExceeded,
+}
+
 #[derive(Debug, Clone, Type, Serialize, Deserialize, PartialEq, TS)]
 #[sqlx(type_name = "execution_process_run_reason", rename_all = "lowercase")]
 #[serde(rename_all = "lowercase")]
@@ -57,6 +67,7 @@ pub enum ExecutionProcessRunReason {
     CleanupScript,
     CodingAgent,
     DevServer,
+    VerifyScript,
 }
 
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
@@ -68,6 +79,8 @@ pub struct ExecutionProcess {
     pub executor_action: sqlx::types::Json<ExecutorActionField>,
     pub status: ExecutionProcessStatus,
     pub exit_code: Option<i64>,
+    /// Set when the process was stopped automatically rather than by the user
+    pub stop_reason: Option<ExecutionProcessStopReason>,
     /// dropped: true if this process is excluded from the current
     /// history view (due to restore/trimming). Hidden from logs/timeline;
     /// still listed in the Processes tab.
@@ -85,6 +98,18 @@ pub struct CreateExecutionProcess {
     pub run_reason: ExecutionProcessRunReason,
 }
 
+/// An execution process that already finished elsewhere, e.g. one imported from a project bundle
+#[derive(Debug, Clone)]
+pub struct CreateFinishedExecutionProcess {
+    pub session_id: Uuid,
+    pub run_reason: ExecutionProcessRunReason,
+    pub executor_action: Value,
+    pub status: ExecutionProcessStatus,
+    pub exit_code: Option<i64>,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
 #[derive(Debug, Deserialize, TS)]
 #[allow(dead_code)]
 pub struct UpdateExecutionProcess {
@@ -143,6 +168,7 @@ impl ExecutionProcess {
                     ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                     ep.status as "status!: ExecutionProcessStatus",
                     ep.exit_code,
+                    ep.stop_reason as "stop_reason: ExecutionProcessStopReason",
                     ep.dropped as "dropped!: bool",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
@@ -217,6 +243,7 @@ impl ExecutionProcess {
                     ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                     ep.status as "status!: ExecutionProcessStatus",
                     ep.exit_code,
+                    ep.stop_reason as "stop_reason: ExecutionProcessStopReason",
                     ep.dropped as "dropped!: bool",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
@@ -244,6 +271,7 @@ impl ExecutionProcess {
                       ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                       ep.status          as "status!: ExecutionProcessStatus",
                       ep.exit_code,
+                      ep.stop_reason     as "stop_reason: ExecutionProcessStopReason",
                       ep.dropped as "dropped!: bool",
                       ep.started_at      as "started_at!: DateTime<Utc>",
                       ep.completed_at    as "completed_at?: DateTime<Utc>",
@@ -271,6 +299,7 @@ impl ExecutionProcess {
                     ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                     ep.status as "status!: ExecutionProcessStatus",
                     ep.exit_code,
+                    ep.stop_reason as "stop_reason: ExecutionProcessStopReason",
                     ep.dropped as "dropped!: bool",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
@@ -290,7 +319,7 @@ impl ExecutionProcess {
         sqlx::query_as!(
             ExecutionProcess,
             r#"SELECT ep.id as "id!: Uuid", ep.session_id as "session_id!: Uuid", ep.run_reason as "run_reason!: ExecutionProcessRunReason", ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
-                      ep.status as "status!: ExecutionProcessStatus", ep.exit_code,
+                      ep.status as "status!: ExecutionProcessStatus", ep.exit_code, ep.stop_reason as "stop_reason: ExecutionProcessStopReason",
                       ep.dropped as "dropped!: bool", ep.started_at as "started_at!: DateTime<Utc>", ep.completed_at as "completed_at?: DateTime<Utc>", ep.created_at as "created_at!: DateTime<Utc>", ep.updated_at as "updated_at!: DateTime<Utc>"
                FROM execution_processes ep
                JOIN sessions s ON ep.session_id = s.id
@@ -338,6 +367,7 @@ impl ExecutionProcess {
             ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
             ep.status as "status!: ExecutionProcessStatus",
             ep.exit_code,
+            ep.stop_reason as "stop_reason: ExecutionProcessStopReason",
             ep.dropped as "dropped!: bool",
             ep.started_at as "started_at!: DateTime<Utc>",
             ep.completed_at as "completed_at?: DateTime<Utc>",
@@ -400,6 +430,7 @@ impl ExecutionProcess {
                     ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                     ep.status as "status!: ExecutionProcessStatus",
                     ep.exit_code,
+                    ep.stop_reason as "stop_reason: ExecutionProcessStopReason",
                     ep.dropped as "dropped!: bool",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
@@ -430,6 +461,7 @@ impl ExecutionProcess {
                     ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                     ep.status as "status!: ExecutionProcessStatus",
                     ep.exit_code,
+                    ep.stop_reason as "stop_reason: ExecutionProcessStopReason",
                     ep.dropped as "dropped!: bool",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
@@ -488,6 +520,39 @@ impl ExecutionProcess {
             .ok_or(sqlx::Error::RowNotFound)
     }
 
+    /// Record a process that already finished, keeping its status and timestamps
+    pub async fn create_finished(
+        pool: &SqlitePool,
+        data: &CreateFinishedExecutionProcess,
+        process_id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        let now = Utc::now();
+        let executor_action_json = sqlx::types::Json(&data.executor_action);
+
+        sqlx::query!(
+            r#"INSERT INTO execution_processes (
+                    id, session_id, run_reason, executor_action,
+                    status, exit_code, started_at, completed_at, created_at, updated_at
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
+            process_id,
+            data.session_id,
+            data.run_reason,
+            executor_action_json,
+            data.status,
+            data.exit_code,
+            data.started_at,
+            data.completed_at,
+            now,
+            now
+        )
+        .execute(pool)
+        .await?;
+
+        Self::find_by_id(pool, process_id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+
     pub async fn was_stopped(pool: &SqlitePool, id: Uuid) -> bool {
         if let Ok(exp_process) = Self::find_by_id(pool, id).await
             && exp_process.is_some_and(|ep| {
@@ -500,6 +565,23 @@ impl ExecutionProcess {
         false
     }
 
+    pub async fn update_stop_reason(
+        pool: &SqlitePool,
+        id: Uuid,
+        stop_reason: ExecutionProcessStopReason,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE execution_processes
+               SET stop_reason = $1, updated_at = datetime('now', 'subsec')
+               WHERE id = $2"#,
+            stop_reason,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
     /// Update execution process status and completion info
     pub async fn update_completion(
         pool: &SqlitePool,
@@ -657,6 +739,7 @@ impl ExecutionProcess {
                     ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                     ep.status as "status!: ExecutionProcessStatus",
                     ep.exit_code,
+                    ep.stop_reason as "stop_reason: ExecutionProcessStopReason",
                     ep.dropped as "dropped!: bool",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
@@ -671,24 +754,63 @@ impl ExecutionProcess {
         .fetch_optional(pool)
         .await?;
 
-        let Some(latest_execution_process) = latest_execution_process else {
-            return Ok(None);
-        };
+        latest_execution_process
+            .map(|process| process.coding_agent_profile())
+            .transpose()
+    }
 
-        let action = latest_execution_process
+    /// Fetch the executor profile of the latest CodingAgent run in any of the project's tasks.
+    /// Returns None if the project has no CodingAgent execution process yet.
+    pub async fn latest_executor_profile_for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<ExecutorProfileId>, ExecutionProcessError> {
+        let latest_execution_process = sqlx::query_as!(
+            ExecutionProcess,
+            r#"SELECT
+                    ep.id as "id!: Uuid",
+                    ep.session_id as "session_id!: Uuid",
+                    ep.run_reason as "run_reason!: ExecutionProcessRunReason",
+                    ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
+                    ep.status as "status!: ExecutionProcessStatus",
+                    ep.exit_code,
+                    ep.stop_reason as "stop_reason: ExecutionProcessStopReason",
+                    ep.dropped as "dropped!: bool",
+                    ep.started_at as "started_at!: DateTime<Utc>",
+                    ep.completed_at as "completed_at?: DateTime<Utc>",
+                    ep.created_at as "created_at!: DateTime<Utc>",
+                    ep.updated_at as "updated_at!: DateTime<Utc>"
+               FROM execution_processes ep
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               JOIN tasks t ON t.id = w.task_id
+               WHERE t.project_id = ? AND ep.run_reason = ? AND ep.dropped = FALSE
+               ORDER BY ep.created_at DESC LIMIT 1"#,
+            project_id,
+            ExecutionProcessRunReason::CodingAgent
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        latest_execution_process
+            .map(|process| process.coding_agent_profile())
+            .transpose()
+    }
+
+    /// Executor profile a CodingAgent execution process ran with
+    fn coding_agent_profile(&self) -> Result<ExecutorProfileId, ExecutionProcessError> {
+        let action = self
             .executor_action()
             .map_err(|e| ExecutionProcessError::ValidationError(e.to_string()))?;
 
         match &action.typ {
             ExecutorActionType::CodingAgentInitialRequest(request) => {
-                Ok(Some(request.executor_profile_id.clone()))
+                Ok(request.executor_profile_id.clone())
             }
             ExecutorActionType::CodingAgentFollowUpRequest(request) => {
-                Ok(Some(request.executor_profile_id.clone()))
-            }
-            ExecutorActionType::ReviewRequest(request) => {
-                Ok(Some(request.executor_profile_id.clone()))
+                Ok(request.executor_profile_id.clone())
             }
+            ExecutorActionType::ReviewRequest(request) => Ok(request.executor_profile_id.clone()),
             _ => Err(ExecutionProcessError::ValidationError(
                 "Couldn't find profile from initial request".to_string(),
             )),
@@ -0,0 +1,335 @@
+use chrono::{DateTime, Utc};
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use utils::log_msg::ModelTokenUsage;
+use uuid::Uuid;
+
+/// Tokens consumed by one model during a single execution process
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ExecutionProcessUsage {
+    pub id: Uuid,
+    pub execution_process_id: Uuid,
+    /// Executor profile that ran the process, formatted as `EXECUTOR` or `EXECUTOR:VARIANT`
+    pub executor_profile: String,
+    pub model: String,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cache_read_tokens: i64,
+    pub cache_write_tokens: i64,
+    pub cost_usd: Option<f64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum UsageGroupBy {
+    Task,
+    Project,
+    ExecutorProfile,
+    Day,
+}
+
+/// Restricts which usage records are aggregated. Bounds apply to when usage was first recorded.
+#[derive(Debug, Clone, Default)]
+pub struct UsageFilter {
+    pub project_id: Option<Uuid>,
+    pub task_id: Option<Uuid>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// Usage summed over one group. Only the key fields of the requested grouping are set:
+/// `task_id` and `project_id` for tasks, `project_id` for projects, `executor_profile` for
+/// executor profiles and `day` (`YYYY-MM-DD`, UTC) for days.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct UsageSummary {
+    pub task_id: Option<Uuid>,
+    pub project_id: Option<Uuid>,
+    pub executor_profile: Option<String>,
+    pub day: Option<String>,
+    /// Task title or project name
+    pub label: Option<String>,
+    pub execution_count: i64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cache_read_tokens: i64,
+    pub cache_write_tokens: i64,
+    /// Sum of agent reported costs, null when no execution in the group reported one
+    pub cost_usd: Option<f64>,
+}
+
+impl ExecutionProcessUsage {
+    pub async fn find_by_execution_process_id(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionProcessUsage,
+            r#"SELECT id as "id!: Uuid",
+                      execution_process_id as "execution_process_id!: Uuid",
+                      executor_profile,
+                      model,
+                      input_tokens as "input_tokens!: i64",
+                      output_tokens as "output_tokens!: i64",
+                      cache_read_tokens as "cache_read_tokens!: i64",
+                      cache_write_tokens as "cache_write_tokens!: i64",
+                      cost_usd,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM execution_process_usage
+               WHERE execution_process_id = $1
+               ORDER BY created_at ASC"#,
+            execution_process_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Usage of every execution process run in a workspace
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionProcessUsage,
+            r#"SELECT u.id as "id!: Uuid",
+                      u.execution_process_id as "execution_process_id!: Uuid",
+                      u.executor_profile,
+                      u.model,
+                      u.input_tokens as "input_tokens!: i64",
+                      u.output_tokens as "output_tokens!: i64",
+                      u.cache_read_tokens as "cache_read_tokens!: i64",
+                      u.cache_write_tokens as "cache_write_tokens!: i64",
+                      u.cost_usd,
+                      u.created_at as "created_at!: DateTime<Utc>",
+                      u.updated_at as "updated_at!: DateTime<Utc>"
+               FROM execution_process_usage u
+               JOIN execution_processes ep ON ep.id = u.execution_process_id
+               JOIN sessions s ON s.id = ep.session_id
+               WHERE s.workspace_id = $1
+               ORDER BY u.created_at ASC"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Record the latest cumulative usage of a model, replacing any earlier report for it
+    pub async fn upsert(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        executor_profile_id: &ExecutorProfileId,
+        usage: &ModelTokenUsage,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let executor_profile = executor_profile_id.to_string();
+        let input_tokens = usage.input_tokens as i64;
+        let output_tokens = usage.output_tokens as i64;
+        let cache_read_tokens = usage.cache_read_tokens as i64;
+        let cache_write_tokens = usage.cache_write_tokens as i64;
+        sqlx::query_as!(
+            ExecutionProcessUsage,
+            r#"INSERT INTO execution_process_usage (
+                   id, execution_process_id, executor_profile, model, input_tokens,
+                   output_tokens, cache_read_tokens, cache_write_tokens, cost_usd
+               )
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+               ON CONFLICT(execution_process_id, model) DO UPDATE SET
+                   input_tokens = excluded.input_tokens,
+                   output_tokens = excluded.output_tokens,
+                   cache_read_tokens = excluded.cache_read_tokens,
+                   cache_write_tokens = excluded.cache_write_tokens,
+                   cost_usd = excluded.cost_usd,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING id as "id!: Uuid",
+                         execution_process_id as "execution_process_id!: Uuid",
+                         executor_profile,
+                         model,
+                         input_tokens as "input_tokens!: i64",
+                         output_tokens as "output_tokens!: i64",
+                         cache_read_tokens as "cache_read_tokens!: i64",
+                         cache_write_tokens as "cache_write_tokens!: i64",
+                         cost_usd,
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            execution_process_id,
+            executor_profile,
+            usage.model,
+            input_tokens,
+            output_tokens,
+            cache_read_tokens,
+            cache_write_tokens,
+            usage.cost_usd
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn summarize(
+        pool: &SqlitePool,
+        group_by: UsageGroupBy,
+        filter: &UsageFilter,
+    ) -> Result<Vec<UsageSummary>, sqlx::Error> {
+        match group_by {
+            UsageGroupBy::Task => Self::summarize_by_task(pool, filter).await,
+            UsageGroupBy::Project => Self::summarize_by_project(pool, filter).await,
+            UsageGroupBy::ExecutorProfile => {
+                Self::summarize_by_executor_profile(pool, filter).await
+            }
+            UsageGroupBy::Day => Self::summarize_by_day(pool, filter).await,
+        }
+    }
+
+    async fn summarize_by_task(
+        pool: &SqlitePool,
+        filter: &UsageFilter,
+    ) -> Result<Vec<UsageSummary>, sqlx::Error> {
+        sqlx::query_as!(
+            UsageSummary,
+            r#"SELECT t.id as "task_id: Uuid",
+                      t.project_id as "project_id: Uuid",
+                      NULL as "executor_profile: String",
+                      NULL as "day: String",
+                      t.title as "label: String",
+                      COUNT(DISTINCT u.execution_process_id) as "execution_count!: i64",
+                      SUM(u.input_tokens) as "input_tokens!: i64",
+                      SUM(u.output_tokens) as "output_tokens!: i64",
+                      SUM(u.cache_read_tokens) as "cache_read_tokens!: i64",
+                      SUM(u.cache_write_tokens) as "cache_write_tokens!: i64",
+                      SUM(u.cost_usd) as "cost_usd: f64"
+               FROM execution_process_usage u
+               JOIN execution_processes ep ON ep.id = u.execution_process_id
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               JOIN tasks t ON t.id = w.task_id
+               WHERE ($1 IS NULL OR t.project_id = $1)
+                 AND ($2 IS NULL OR t.id = $2)
+                 AND ($3 IS NULL OR datetime(u.created_at) >= datetime($3))
+                 AND ($4 IS NULL OR datetime(u.created_at) < datetime($4))
+               GROUP BY t.id
+               ORDER BY SUM(u.input_tokens + u.output_tokens) DESC"#,
+            filter.project_id,
+            filter.task_id,
+            filter.since,
+            filter.until
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    async fn summarize_by_project(
+        pool: &SqlitePool,
+        filter: &UsageFilter,
+    ) -> Result<Vec<UsageSummary>, sqlx::Error> {
+        sqlx::query_as!(
+            UsageSummary,
+            r#"SELECT NULL as "task_id: Uuid",
+                      p.id as "project_id: Uuid",
+                      NULL as "executor_profile: String",
+                      NULL as "day: String",
+                      p.name as "label: String",
+                      COUNT(DISTINCT u.execution_process_id) as "execution_count!: i64",
+                      SUM(u.input_tokens) as "input_tokens!: i64",
+                      SUM(u.output_tokens) as "output_tokens!: i64",
+                      SUM(u.cache_read_tokens) as "cache_read_tokens!: i64",
+                      SUM(u.cache_write_tokens) as "cache_write_tokens!: i64",
+                      SUM(u.cost_usd) as "cost_usd: f64"
+               FROM execution_process_usage u
+               JOIN execution_processes ep ON ep.id = u.execution_process_id
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               JOIN tasks t ON t.id = w.task_id
+               JOIN projects p ON p.id = t.project_id
+               WHERE ($1 IS NULL OR t.project_id = $1)
+                 AND ($2 IS NULL OR t.id = $2)
+                 AND ($3 IS NULL OR datetime(u.created_at) >= datetime($3))
+                 AND ($4 IS NULL OR datetime(u.created_at) < datetime($4))
+               GROUP BY p.id
+               ORDER BY SUM(u.input_tokens + u.output_tokens) DESC"#,
+            filter.project_id,
+            filter.task_id,
+            filter.since,
+            filter.until
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    async fn summarize_by_executor_profile(
+        pool: &SqlitePool,
+        filter: &UsageFilter,
+    ) -> Result<Vec<UsageSummary>, sqlx::Error> {
+        sqlx::query_as!(
+            UsageSummary,
+            r#"SELECT NULL as "task_id: Uuid",
+                      NULL as "project_id: Uuid",
+                      u.executor_profile as "executor_profile: String",
+                      NULL as "day: String",
+                      NULL as "label: String",
+                      COUNT(DISTINCT u.execution_process_id) as "execution_count!: i64",
+                      SUM(u.input_tokens) as "input_tokens!: i64",
+                      SUM(u.output_tokens) as "output_tokens!: i64",
+                      SUM(u.cache_read_tokens) as "cache_read_tokens!: i64",
+                      SUM(u.cache_write_tokens) as "cache_write_tokens!: i64",
+                      SUM(u.cost_usd) as "cost_usd: f64"
+               FROM execution_process_usage u
+               JOIN execution_processes ep ON ep.id = u.execution_process_id
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               JOIN tasks t ON t.id = w.task_id
+               WHERE ($1 IS NULL OR t.project_id = $1)
+                 AND ($2 IS NULL OR t.id = $2)
+                 AND ($3 IS NULL OR datetime(u.created_at) >= datetime($3))
+                 AND ($4 IS NULL OR datetime(u.created_at) < datetime($4))
+               GROUP BY u.executor_profile
+               ORDER BY SUM(u.input_tokens + u.output_tokens) DESC"#,
+            filter.project_id,
+            filter.task_id,
+            filter.since,
+            filter.until
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    async fn summarize_by_day(
+        pool: &SqlitePool,
+        filter: &UsageFilter,
+    ) -> Result<Vec<UsageSummary>, sqlx::Error> {
+        sqlx::query_as!(
+            UsageSummary,
+            r#"SELECT NULL as "task_id: Uuid",
+                      NULL as "project_id: Uuid",
+                      NULL as "executor_profile: String",
+                      date(u.created_at) as "day: String",
+                      NULL as "label: String",
+                      COUNT(DISTINCT u.execution_process_id) as "execution_count!: i64",
+                      SUM(u.input_tokens) as "input_tokens!: i64",
+                      SUM(u.output_tokens) as "output_tokens!: i64",
+                      SUM(u.cache_read_tokens) as "cache_read_tokens!: i64",
+                      SUM(u.cache_write_tokens) as "cache_write_tokens!: i64",
+                      SUM(u.cost_usd) as "cost_usd: f64"
+               FROM execution_process_usage u
+               JOIN execution_processes ep ON ep.id = u.execution_process_id
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               JOIN tasks t ON t.id = w.task_id
+               WHERE ($1 IS NULL OR t.project_id = $1)
+                 AND ($2 IS NULL OR t.id = $2)
+                 AND ($3 IS NULL OR datetime(u.created_at) >= datetime($3))
+                 AND ($4 IS NULL OR datetime(u.created_at) < datetime($4))
+               GROUP BY date(u.created_at)
+               ORDER BY date(u.created_at) ASC"#,
+            filter.project_id,
+            filter.task_id,
+            filter.since,
+            filter.until
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool, Type};
+use sqlx::{FromRow, SqlitePool, Type, types::Json};
 use ts_rs::TS;
 use uuid::Uuid;
 
@@ -42,6 +42,8 @@ pub struct PrMerge {
     pub created_at: DateTime<Utc>,
     pub target_branch_name: String,
     pub pr_info: PullRequestInfo,
+    /// CI checks as last reported by the git host; empty until they are first fetched
+    pub checks: Vec<PrCheck>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -53,6 +55,28 @@ pub struct PullRequestInfo {
     pub merge_commit_sha: Option<String>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(use_ts_enum)]
+pub enum PrCheckStatus {
+    /// Queued or running
+    Pending,
+    Passed,
+    Failed,
+    /// Skipped, cancelled or neutral
+    Skipped,
+}
+
+/// A CI check run or commit status reported on a PR
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct PrCheck {
+    pub name: String,
+    pub status: PrCheckStatus,
+    /// Page of the check on the git host or CI service
+    pub url: Option<String>,
+    pub description: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 #[sqlx(type_name = "TEXT", rename_all = "snake_case")]
 pub enum MergeType {
@@ -73,10 +97,25 @@ struct MergeRow {
     pr_status: Option<MergeStatus>,
     pr_merged_at: Option<DateTime<Utc>>,
     pr_merge_commit_sha: Option<String>,
+    pr_checks: Option<Json<Vec<PrCheck>>>,
     created_at: DateTime<Utc>,
 }
 
 impl Merge {
+    pub fn id(&self) -> Uuid {
+        match self {
+            Merge::Direct(direct) => direct.id,
+            Merge::Pr(pr) => pr.id,
+        }
+    }
+
+    pub fn workspace_id(&self) -> Uuid {
+        match self {
+            Merge::Direct(direct) => direct.workspace_id,
+            Merge::Pr(pr) => pr.workspace_id,
+        }
+    }
+
     pub fn merge_commit(&self) -> Option<String> {
         match self {
             Merge::Direct(direct) => Some(direct.merge_commit.clone()),
@@ -111,6 +150,7 @@ impl Merge {
                 pr_status as "pr_status?: MergeStatus",
                 pr_merged_at as "pr_merged_at?: DateTime<Utc>",
                 pr_merge_commit_sha,
+                pr_checks as "pr_checks: Json<Vec<PrCheck>>",
                 created_at as "created_at!: DateTime<Utc>",
                 target_branch_name as "target_branch_name!: String"
             "#,
@@ -153,6 +193,7 @@ impl Merge {
                 pr_status as "pr_status?: MergeStatus",
                 pr_merged_at as "pr_merged_at?: DateTime<Utc>",
                 pr_merge_commit_sha,
+                pr_checks as "pr_checks: Json<Vec<PrCheck>>",
                 created_at as "created_at!: DateTime<Utc>",
                 target_branch_name as "target_branch_name!: String"
             "#,
@@ -184,6 +225,7 @@ impl Merge {
                 pr_status as "pr_status?: MergeStatus",
                 pr_merged_at as "pr_merged_at?: DateTime<Utc>",
                 pr_merge_commit_sha,
+                pr_checks as "pr_checks: Json<Vec<PrCheck>>",
                 created_at as "created_at!: DateTime<Utc>",
                 target_branch_name as "target_branch_name!: String"
                FROM merges
@@ -225,6 +267,99 @@ impl Merge {
 
         Ok(())
     }
+
+    /// Record the CI checks last reported for a PR
+    pub async fn update_checks(
+        pool: &SqlitePool,
+        merge_id: Uuid,
+        checks: &[PrCheck],
+    ) -> Result<(), sqlx::Error> {
+        let checks = Json(checks);
+        sqlx::query!(
+            "UPDATE merges SET pr_checks = $1 WHERE id = $2",
+            checks,
+            merge_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Key of the failing checks of a PR that were last handled
+    pub async fn checks_failure_key(
+        pool: &SqlitePool,
+        merge_id: Uuid,
+    ) -> Result<Option<String>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT pr_checks_failure_key FROM merges WHERE id = $1"#,
+            merge_id
+        )
+        .fetch_optional(pool)
+        .await
+        .map(Option::flatten)
+    }
+
+    pub async fn set_checks_failure_key(
+        pool: &SqlitePool,
+        merge_id: Uuid,
+        failure_key: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE merges SET pr_checks_failure_key = $1 WHERE id = $2",
+            failure_key,
+            merge_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Record the new base branch of a PR that was retargeted
+    pub async fn update_target_branch_name(
+        pool: &SqlitePool,
+        merge_id: Uuid,
+        target_branch_name: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE merges SET target_branch_name = $1 WHERE id = $2",
+            target_branch_name,
+            merge_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn find_by_rowid(pool: &SqlitePool, rowid: i64) -> Result<Option<Self>, sqlx::Error> {
+        let row = sqlx::query_as!(
+            MergeRow,
+            r#"SELECT
+                id as "id!: Uuid",
+                workspace_id as "workspace_id!: Uuid",
+                repo_id as "repo_id!: Uuid",
+                merge_type as "merge_type!: MergeType",
+                merge_commit,
+                pr_number,
+                pr_url,
+                pr_status as "pr_status?: MergeStatus",
+                pr_merged_at as "pr_merged_at?: DateTime<Utc>",
+                pr_merge_commit_sha,
+                pr_checks as "pr_checks: Json<Vec<PrCheck>>",
+                target_branch_name as "target_branch_name!: String",
+                created_at as "created_at!: DateTime<Utc>"
+            FROM merges
+            WHERE rowid = $1"#,
+            rowid
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(row.map(Into::into))
+    }
+
     /// Find all merges for a workspace (returns both direct and PR merges)
     pub async fn find_by_workspace_id(
         pool: &SqlitePool,
@@ -244,6 +379,7 @@ impl Merge {
                 pr_status as "pr_status?: MergeStatus",
                 pr_merged_at as "pr_merged_at?: DateTime<Utc>",
                 pr_merge_commit_sha,
+                pr_checks as "pr_checks: Json<Vec<PrCheck>>",
                 target_branch_name as "target_branch_name!: String",
                 created_at as "created_at!: DateTime<Utc>"
             FROM merges
@@ -277,6 +413,7 @@ impl Merge {
                 pr_status as "pr_status?: MergeStatus",
                 pr_merged_at as "pr_merged_at?: DateTime<Utc>",
                 pr_merge_commit_sha,
+                pr_checks as "pr_checks: Json<Vec<PrCheck>>",
                 target_branch_name as "target_branch_name!: String",
                 created_at as "created_at!: DateTime<Utc>"
             FROM merges
@@ -361,6 +498,7 @@ impl From<MergeRow> for PrMerge {
                 merged_at: row.pr_merged_at,
                 merge_commit_sha: row.pr_merge_commit_sha,
             },
+            checks: row.pr_checks.map(|checks| checks.0).unwrap_or_default(),
             created_at: row.created_at,
         }
     }
@@ -0,0 +1,267 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// How a queued workspace lands on its target branch once it is rebased and checked
+#[derive(Debug, Clone, Copy, Default, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "merge_queue_strategy", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(use_ts_enum)]
+pub enum MergeQueueStrategy {
+    #[default]
+    Squash,
+    FastForward,
+}
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "merge_queue_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(use_ts_enum)]
+pub enum MergeQueueStatus {
+    Queued,
+    Running,
+    Merged,
+    /// Rebasing, checking or merging failed; see `failure_reason`
+    Ejected,
+    Cancelled,
+}
+
+/// A workspace waiting in a repo's merge queue
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct MergeQueueEntry {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub target_branch_name: String,
+    pub strategy: MergeQueueStrategy,
+    pub status: MergeQueueStatus,
+    /// Why the entry was ejected
+    pub failure_reason: Option<String>,
+    /// The direct merge created when the entry was merged
+    pub merge_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateMergeQueueEntry {
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub target_branch_name: String,
+    pub strategy: MergeQueueStrategy,
+}
+
+impl MergeQueueEntry {
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateMergeQueueEntry,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            MergeQueueEntry,
+            r#"INSERT INTO merge_queue_entries (id, workspace_id, repo_id, target_branch_name, strategy)
+               VALUES ($1, $2, $3, $4, $5)
+               RETURNING id as "id!: Uuid",
+                         workspace_id as "workspace_id!: Uuid",
+                         repo_id as "repo_id!: Uuid",
+                         target_branch_name,
+                         strategy as "strategy!: MergeQueueStrategy",
+                         status as "status!: MergeQueueStatus",
+                         failure_reason,
+                         merge_id as "merge_id: Uuid",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.workspace_id,
+            data.repo_id,
+            data.target_branch_name,
+            data.strategy
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            MergeQueueEntry,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      target_branch_name,
+                      strategy as "strategy!: MergeQueueStrategy",
+                      status as "status!: MergeQueueStatus",
+                      failure_reason,
+                      merge_id as "merge_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM merge_queue_entries
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Entries of a repo's queue in queue order. Finished entries are only included when
+    /// `include_finished` is set.
+    pub async fn find_by_repo_id(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        include_finished: bool,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            MergeQueueEntry,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      target_branch_name,
+                      strategy as "strategy!: MergeQueueStrategy",
+                      status as "status!: MergeQueueStatus",
+                      failure_reason,
+                      merge_id as "merge_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM merge_queue_entries
+               WHERE repo_id = $1
+                 AND ($2 OR status IN ('queued', 'running'))
+               ORDER BY created_at ASC"#,
+            repo_id,
+            include_finished
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// All entries of a workspace, newest first
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            MergeQueueEntry,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      target_branch_name,
+                      strategy as "strategy!: MergeQueueStrategy",
+                      status as "status!: MergeQueueStatus",
+                      failure_reason,
+                      merge_id as "merge_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM merge_queue_entries
+               WHERE workspace_id = $1
+               ORDER BY created_at DESC"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// The oldest queued entry of every repo whose queue is not already being worked on
+    pub async fn find_queue_heads(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            MergeQueueEntry,
+            r#"SELECT q.id as "id!: Uuid",
+                      q.workspace_id as "workspace_id!: Uuid",
+                      q.repo_id as "repo_id!: Uuid",
+                      q.target_branch_name,
+                      q.strategy as "strategy!: MergeQueueStrategy",
+                      q.status as "status!: MergeQueueStatus",
+                      q.failure_reason,
+                      q.merge_id as "merge_id: Uuid",
+                      q.created_at as "created_at!: DateTime<Utc>",
+                      q.updated_at as "updated_at!: DateTime<Utc>"
+               FROM merge_queue_entries q
+               WHERE q.status = 'queued'
+                 AND NOT EXISTS (
+                     SELECT 1 FROM merge_queue_entries r
+                     WHERE r.repo_id = q.repo_id AND r.status = 'running'
+                 )
+                 AND NOT EXISTS (
+                     SELECT 1 FROM merge_queue_entries o
+                     WHERE o.repo_id = q.repo_id
+                       AND o.status = 'queued'
+                       AND o.created_at < q.created_at
+                 )
+               ORDER BY q.created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn set_status(
+        pool: &SqlitePool,
+        id: Uuid,
+        status: MergeQueueStatus,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE merge_queue_entries
+               SET status = $2, updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            status
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn mark_merged(
+        pool: &SqlitePool,
+        id: Uuid,
+        merge_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE merge_queue_entries
+               SET status = 'merged', merge_id = $2, failure_reason = NULL,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            merge_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn eject(pool: &SqlitePool, id: Uuid, reason: &str) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE merge_queue_entries
+               SET status = 'ejected', failure_reason = $2, updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            reason
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Take a queued entry out of the queue. Returns false when it is no longer queued.
+    pub async fn cancel(pool: &SqlitePool, id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE merge_queue_entries
+               SET status = 'cancelled', updated_at = datetime('now', 'subsec')
+               WHERE id = $1 AND status = 'queued'"#,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Put entries interrupted by a shutdown back in the queue, keeping their position
+    pub async fn requeue_running(pool: &SqlitePool) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE merge_queue_entries
+               SET status = 'queued', updated_at = datetime('now', 'subsec')
+               WHERE status = 'running'"#
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
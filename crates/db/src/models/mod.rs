@@ -1,15 +1,29 @@
+pub mod approval_timeout;
 pub mod coding_agent_turn;
+pub mod conversation_entry;
 pub mod execution_process;
 pub mod execution_process_logs;
 pub mod execution_process_repo_state;
+pub mod execution_process_usage;
 pub mod image;
 pub mod merge;
+pub mod merge_queue;
+pub mod pr_review_comment;
 pub mod project;
+pub mod project_approval_policy;
+pub mod project_bundle;
+pub mod project_pr_review_setting;
 pub mod project_repo;
 pub mod repo;
+pub mod review_comment;
 pub mod scratch;
 pub mod session;
 pub mod tag;
 pub mod task;
+pub mod task_budget;
+pub mod task_dependency;
+pub mod task_issue_link;
+pub mod task_schedule;
 pub mod workspace;
+pub mod workspace_group;
 pub mod workspace_repo;
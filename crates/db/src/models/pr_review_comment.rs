@@ -0,0 +1,232 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "pr_review_comment_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(use_ts_enum)]
+pub enum PrReviewCommentStatus {
+    /// Not sent to the agent yet
+    Pending,
+    /// Sent to the agent; its turn has not finished
+    Sent,
+    /// The agent's turn finished, the branch was pushed and the PR was replied to
+    Addressed,
+    /// The agent's turn failed, or pushing or replying did
+    Failed,
+    /// Never sent to the agent: posted before auto follow-up was turned on, or its thread was
+    /// resolved first
+    Skipped,
+}
+
+/// An inline review comment found on a workspace's open PR
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct PrReviewComment {
+    pub id: Uuid,
+    pub merge_id: Uuid,
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    /// The git host's id of the comment
+    pub comment_id: String,
+    pub author: String,
+    pub body: String,
+    pub path: String,
+    pub line: Option<i64>,
+    pub diff_hunk: Option<String>,
+    pub url: Option<String>,
+    pub status: PrReviewCommentStatus,
+    /// The coding agent turn the comment was sent in
+    pub execution_process_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreatePrReviewComment {
+    pub merge_id: Uuid,
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub comment_id: String,
+    pub author: String,
+    pub body: String,
+    pub path: String,
+    pub line: Option<i64>,
+    pub diff_hunk: Option<String>,
+    pub url: Option<String>,
+    pub status: PrReviewCommentStatus,
+}
+
+impl PrReviewComment {
+    /// Record a comment unless it was recorded before; returns it only when it is new
+    pub async fn create_if_new(
+        pool: &SqlitePool,
+        data: &CreatePrReviewComment,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            PrReviewComment,
+            r#"INSERT INTO pr_review_comments (id, merge_id, workspace_id, repo_id, comment_id, author, body, path, line, diff_hunk, url, status)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+               ON CONFLICT(merge_id, comment_id) DO NOTHING
+               RETURNING id as "id!: Uuid",
+                         merge_id as "merge_id!: Uuid",
+                         workspace_id as "workspace_id!: Uuid",
+                         repo_id as "repo_id!: Uuid",
+                         comment_id,
+                         author,
+                         body,
+                         path,
+                         line,
+                         diff_hunk,
+                         url,
+                         status as "status!: PrReviewCommentStatus",
+                         execution_process_id as "execution_process_id: Uuid",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.merge_id,
+            data.workspace_id,
+            data.repo_id,
+            data.comment_id,
+            data.author,
+            data.body,
+            data.path,
+            data.line,
+            data.diff_hunk,
+            data.url,
+            data.status
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PrReviewComment,
+            r#"SELECT id as "id!: Uuid",
+                      merge_id as "merge_id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      comment_id,
+                      author,
+                      body,
+                      path,
+                      line,
+                      diff_hunk,
+                      url,
+                      status as "status!: PrReviewCommentStatus",
+                      execution_process_id as "execution_process_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM pr_review_comments
+               WHERE workspace_id = $1
+               ORDER BY created_at ASC"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Comments of a PR not sent to the agent yet, oldest first
+    pub async fn find_pending_by_merge_id(
+        pool: &SqlitePool,
+        merge_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PrReviewComment,
+            r#"SELECT id as "id!: Uuid",
+                      merge_id as "merge_id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      comment_id,
+                      author,
+                      body,
+                      path,
+                      line,
+                      diff_hunk,
+                      url,
+                      status as "status!: PrReviewCommentStatus",
+                      execution_process_id as "execution_process_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM pr_review_comments
+               WHERE merge_id = $1 AND status = 'pending'
+               ORDER BY created_at ASC"#,
+            merge_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Comments sent in a coding agent turn whose outcome is not known yet
+    pub async fn find_sent_by_execution_process_id(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PrReviewComment,
+            r#"SELECT id as "id!: Uuid",
+                      merge_id as "merge_id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      comment_id,
+                      author,
+                      body,
+                      path,
+                      line,
+                      diff_hunk,
+                      url,
+                      status as "status!: PrReviewCommentStatus",
+                      execution_process_id as "execution_process_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM pr_review_comments
+               WHERE execution_process_id = $1 AND status = 'sent'
+               ORDER BY created_at ASC"#,
+            execution_process_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn set_status(
+        pool: &SqlitePool,
+        id: Uuid,
+        status: PrReviewCommentStatus,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE pr_review_comments
+               SET status = $2, updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            status
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn mark_sent(
+        pool: &SqlitePool,
+        id: Uuid,
+        execution_process_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE pr_review_comments
+               SET status = 'sent', execution_process_id = $2,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,71 @@
+use chrono::{DateTime, Utc};
+use executors::approval_policy::ApprovalRule;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Approval rules for the coding agent runs of a project. They are evaluated before the rules
+/// configured on the executor profile variant.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectApprovalPolicy {
+    pub project_id: Uuid,
+    #[ts(type = "Array<ApprovalRule>")]
+    pub rules: Json<Vec<ApprovalRule>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ProjectApprovalPolicy {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectApprovalPolicy,
+            r#"SELECT project_id as "project_id!: Uuid",
+                      rules as "rules!: Json<Vec<ApprovalRule>>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_approval_policies
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        rules: &[ApprovalRule],
+    ) -> Result<Self, sqlx::Error> {
+        let rules = Json(rules);
+        sqlx::query_as!(
+            ProjectApprovalPolicy,
+            r#"INSERT INTO project_approval_policies (project_id, rules)
+               VALUES ($1, $2)
+               ON CONFLICT(project_id) DO UPDATE SET
+                   rules = excluded.rules,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING project_id as "project_id!: Uuid",
+                         rules as "rules!: Json<Vec<ApprovalRule>>",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            rules
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, project_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM project_approval_policies WHERE project_id = $1",
+            project_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,946 @@
+//! Portable snapshots of a project for moving it to another machine: the settings of its repos,
+//! its tasks, the prompt tags and, optionally, the history of its workspaces.
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::SqlitePool;
+use thiserror::Error;
+use utils::log_msg::LogMsg;
+use uuid::Uuid;
+
+use super::{
+    coding_agent_turn::{CodingAgentTurn, CreateCodingAgentTurn},
+    execution_process::{
+        CreateFinishedExecutionProcess, ExecutionProcess, ExecutionProcessRunReason,
+        ExecutionProcessStatus,
+    },
+    execution_process_logs::ExecutionProcessLogs,
+    project::{CreateProject, Project},
+    project_repo::ProjectRepo,
+    repo::{Repo, RepoError, UpdateRepo},
+    session::{CreateSession, Session, SessionError},
+    tag::{CreateTag, Tag},
+    task::{CreateTask, Task, TaskStatus},
+    task_dependency::{TaskDependency, TaskDependencyError},
+    workspace::{CreateWorkspace, Workspace, WorkspaceError},
+    workspace_repo::{CreateWorkspaceRepo, WorkspaceRepo},
+};
+
+/// Version of the bundle format written by this build. Bundles with a newer version are
+/// rejected on import.
+pub const PROJECT_BUNDLE_VERSION: u32 = 1;
+
+#[derive(Debug, Error)]
+pub enum ProjectBundleError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Repo(#[from] RepoError),
+    #[error(transparent)]
+    TaskDependency(#[from] TaskDependencyError),
+    #[error(transparent)]
+    Workspace(#[from] WorkspaceError),
+    #[error(transparent)]
+    Session(#[from] SessionError),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("Project not found")]
+    ProjectNotFound,
+    #[error("Bundle version {0} is not supported by this version of Vibe Kanban")]
+    UnsupportedVersion(u32),
+    #[error("Invalid project bundle: {0}")]
+    Invalid(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectBundle {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub project_name: String,
+    pub repos: Vec<BundleRepo>,
+    /// Oldest first, so imported tasks keep their order
+    pub tasks: Vec<BundleTask>,
+    pub tags: Vec<BundleTag>,
+    /// Empty unless workspace histories were exported
+    #[serde(default)]
+    pub workspaces: Vec<BundleWorkspace>,
+}
+
+/// A repo of the project with its scripts and settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleRepo {
+    /// Id on the exporting machine, referenced by workspaces of the bundle
+    pub id: Uuid,
+    /// Where the repo was checked out on the exporting machine
+    pub path: PathBuf,
+    pub display_name: String,
+    pub setup_script: Option<String>,
+    pub cleanup_script: Option<String>,
+    pub copy_files: Option<String>,
+    pub parallel_setup_script: bool,
+    pub dev_server_script: Option<String>,
+    pub git_host_provider: Option<String>,
+    #[serde(default)]
+    pub git_host_base_url: Option<String>,
+    pub verify_script: Option<String>,
+    pub verify_max_retries: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleTask {
+    /// Id on the exporting machine, referenced by dependencies and workspaces of the bundle
+    pub id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    pub status: TaskStatus,
+    /// Tasks of the bundle this task is blocked by
+    #[serde(default)]
+    pub depends_on: Vec<Uuid>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleTag {
+    pub tag_name: String,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleWorkspace {
+    pub task_id: Uuid,
+    pub branch: String,
+    pub name: Option<String>,
+    pub agent_working_dir: Option<String>,
+    pub repos: Vec<BundleWorkspaceRepo>,
+    /// Oldest first
+    pub sessions: Vec<BundleSession>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleWorkspaceRepo {
+    pub repo_id: Uuid,
+    pub target_branch: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleSession {
+    pub executor: Option<String>,
+    pub processes: Vec<BundleExecutionProcess>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleExecutionProcess {
+    /// Id on the exporting machine
+    pub id: Uuid,
+    pub run_reason: ExecutionProcessRunReason,
+    pub executor_action: Value,
+    pub status: ExecutionProcessStatus,
+    pub exit_code: Option<i64>,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    /// Prompt of a coding agent turn
+    pub prompt: Option<String>,
+    /// Final message of a coding agent turn
+    pub summary: Option<String>,
+    /// Output of the process: conversation patches of coding agent runs once normalized, stdout
+    /// and stderr of scripts
+    pub logs: Vec<LogMsg>,
+}
+
+impl ProjectBundle {
+    /// Snapshot a project. Workspace histories leave out dev servers and processes that are
+    /// still running.
+    pub async fn export(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        include_histories: bool,
+    ) -> Result<Self, ProjectBundleError> {
+        let project = Project::find_by_id(pool, project_id)
+            .await?
+            .ok_or(ProjectBundleError::ProjectNotFound)?;
+
+        let repos = ProjectRepo::find_repos_for_project(pool, project_id)
+            .await?
+            .into_iter()
+            .map(|repo| BundleRepo {
+                id: repo.id,
+                path: repo.path,
+                display_name: repo.display_name,
+                setup_script: repo.setup_script,
+                cleanup_script: repo.cleanup_script,
+                copy_files: repo.copy_files,
+                parallel_setup_script: repo.parallel_setup_script,
+                dev_server_script: repo.dev_server_script,
+                git_host_provider: repo.git_host_provider,
+                git_host_base_url: repo.git_host_base_url,
+                verify_script: repo.verify_script,
+                verify_max_retries: repo.verify_max_retries,
+            })
+            .collect::<Vec<_>>();
+
+        let project_tasks = Task::find_by_project_id(pool, project_id).await?;
+        let mut tasks = Vec::with_capacity(project_tasks.len());
+        for task in &project_tasks {
+            let depends_on = TaskDependency::find_by_task_id(pool, task.id)
+                .await?
+                .into_iter()
+                .map(|dependency| dependency.depends_on_task_id)
+                .collect();
+            tasks.push(BundleTask {
+                id: task.id,
+                title: task.title.clone(),
+                description: task.description.clone(),
+                status: task.status.clone(),
+                depends_on,
+            });
+        }
+
+        let tags = Tag::find_all(pool)
+            .await?
+            .into_iter()
+            .map(|tag| BundleTag {
+                tag_name: tag.tag_name,
+                content: tag.content,
+            })
+            .collect();
+
+        let mut workspaces = Vec::new();
+        if include_histories {
+            let repo_ids: HashSet<Uuid> = repos.iter().map(|repo| repo.id).collect();
+            for task in &project_tasks {
+                let mut task_workspaces = Workspace::fetch_all(pool, Some(task.id)).await?;
+                task_workspaces.reverse();
+                for workspace in task_workspaces {
+                    workspaces.push(export_workspace(pool, &workspace, &repo_ids).await?);
+                }
+            }
+        }
+
+        Ok(Self {
+            version: PROJECT_BUNDLE_VERSION,
+            exported_at: Utc::now(),
+            project_name: project.name,
+            repos,
+            tasks,
+            tags,
+            workspaces,
+        })
+    }
+
+    /// Recreate the bundled project. `repo_paths` maps ids of bundled repos to where they are
+    /// checked out on this machine; repos left out are expected at their original path. Repos
+    /// already known here keep their settings. Tags named like an existing tag are skipped, and
+    /// imported workspaces are archived since their worktrees do not exist here.
+    pub async fn import(
+        &self,
+        pool: &SqlitePool,
+        repo_paths: &HashMap<Uuid, PathBuf>,
+    ) -> Result<Project, ProjectBundleError> {
+        self.validate(repo_paths)?;
+
+        let project = Project::create(
+            pool,
+            &CreateProject {
+                name: self.project_name.clone(),
+                repositories: Vec::new(),
+            },
+            Uuid::new_v4(),
+        )
+        .await?;
+
+        if let Err(e) = self.restore(pool, project.id, repo_paths).await {
+            // Tasks and workspaces go with the project
+            if let Err(delete_err) = Project::delete(pool, project.id).await {
+                tracing::warn!(
+                    "Failed to remove partially imported project {}: {}",
+                    project.id,
+                    delete_err
+                );
+            }
+            return Err(e);
+        }
+
+        Ok(project)
+    }
+
+    /// Path the bundled repo is imported from
+    pub fn repo_path(&self, repo: &BundleRepo, repo_paths: &HashMap<Uuid, PathBuf>) -> PathBuf {
+        repo_paths
+            .get(&repo.id)
+            .cloned()
+            .unwrap_or_else(|| repo.path.clone())
+    }
+
+    /// Check the bundle version and that every reference within the bundle resolves
+    fn validate(&self, repo_paths: &HashMap<Uuid, PathBuf>) -> Result<(), ProjectBundleError> {
+        if self.version > PROJECT_BUNDLE_VERSION {
+            return Err(ProjectBundleError::UnsupportedVersion(self.version));
+        }
+
+        let mut paths = HashSet::new();
+        for repo in &self.repos {
+            let path = self.repo_path(repo, repo_paths);
+            if !paths.insert(path.clone()) {
+                return Err(ProjectBundleError::Invalid(format!(
+                    "more than one repo would be imported from {}",
+                    path.display()
+                )));
+            }
+        }
+
+        let task_ids: HashSet<Uuid> = self.tasks.iter().map(|task| task.id).collect();
+        if let Some(missing) = self
+            .tasks
+            .iter()
+            .flat_map(|task| &task.depends_on)
+            .find(|id| !task_ids.contains(id))
+        {
+            return Err(ProjectBundleError::Invalid(format!(
+                "dependency on unknown task {missing}"
+            )));
+        }
+
+        let repo_ids: HashSet<Uuid> = self.repos.iter().map(|repo| repo.id).collect();
+        for workspace in &self.workspaces {
+            if !task_ids.contains(&workspace.task_id) {
+                return Err(ProjectBundleError::Invalid(format!(
+                    "workspace {} belongs to unknown task {}",
+                    workspace.branch, workspace.task_id
+                )));
+            }
+            if let Some(repo) = workspace
+                .repos
+                .iter()
+                .find(|repo| !repo_ids.contains(&repo.repo_id))
+            {
+                return Err(ProjectBundleError::Invalid(format!(
+                    "workspace {} uses unknown repo {}",
+                    workspace.branch, repo.repo_id
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    async fn restore(
+        &self,
+        pool: &SqlitePool,
+        project_id: Uuid,
+        repo_paths: &HashMap<Uuid, PathBuf>,
+    ) -> Result<(), ProjectBundleError> {
+        let mut repo_ids = HashMap::new();
+        for bundle_repo in &self.repos {
+            let path = self.repo_path(bundle_repo, repo_paths);
+            // Repos already known here may be shared with other projects, so they keep their
+            // settings
+            let repo = match Repo::find_by_path(pool, &path).await? {
+                Some(repo) => repo,
+                None => {
+                    let repo = Repo::find_or_create(pool, &path, &bundle_repo.display_name).await?;
+                    Repo::update(
+                        pool,
+                        repo.id,
+                        &UpdateRepo {
+                            display_name: Some(Some(bundle_repo.display_name.clone())),
+                            setup_script: Some(bundle_repo.setup_script.clone()),
+                            cleanup_script: Some(bundle_repo.cleanup_script.clone()),
+                            copy_files: Some(bundle_repo.copy_files.clone()),
+                            parallel_setup_script: Some(Some(bundle_repo.parallel_setup_script)),
+                            dev_server_script: Some(bundle_repo.dev_server_script.clone()),
+                            git_host_provider: Some(bundle_repo.git_host_provider.clone()),
+                            git_host_base_url: Some(bundle_repo.git_host_base_url.clone()),
+                            verify_script: Some(bundle_repo.verify_script.clone()),
+                            verify_max_retries: Some(Some(bundle_repo.verify_max_retries)),
+                        },
+                    )
+                    .await?
+                }
+            };
+            ProjectRepo::create(pool, project_id, repo.id).await?;
+            repo_ids.insert(bundle_repo.id, repo.id);
+        }
+
+        let mut tasks: HashMap<Uuid, Task> = HashMap::new();
+        for bundle_task in &self.tasks {
+            let task = Task::create(
+                pool,
+                &CreateTask {
+                    project_id,
+                    title: bundle_task.title.clone(),
+                    description: bundle_task.description.clone(),
+                    status: Some(bundle_task.status.clone()),
+                    parent_workspace_id: None,
+                    image_ids: None,
+                    shared_task_id: None,
+                },
+                Uuid::new_v4(),
+            )
+            .await?;
+            tasks.insert(bundle_task.id, task);
+        }
+        for bundle_task in &self.tasks {
+            for depends_on in &bundle_task.depends_on {
+                TaskDependency::create(pool, &tasks[&bundle_task.id], &tasks[depends_on]).await?;
+            }
+        }
+
+        let existing_tags: HashSet<String> = Tag::find_all(pool)
+            .await?
+            .into_iter()
+            .map(|tag| tag.tag_name)
+            .collect();
+        for tag in &self.tags {
+            if !existing_tags.contains(&tag.tag_name) {
+                Tag::create(
+                    pool,
+                    &CreateTag {
+                        tag_name: tag.tag_name.clone(),
+                        content: tag.content.clone(),
+                    },
+                )
+                .await?;
+            }
+        }
+
+        for bundle_workspace in &self.workspaces {
+            restore_workspace(
+                pool,
+                bundle_workspace,
+                tasks[&bundle_workspace.task_id].id,
+                &repo_ids,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+}
+
+async fn export_workspace(
+    pool: &SqlitePool,
+    workspace: &Workspace,
+    repo_ids: &HashSet<Uuid>,
+) -> Result<BundleWorkspace, ProjectBundleError> {
+    let repos = WorkspaceRepo::find_by_workspace_id(pool, workspace.id)
+        .await?
+        .into_iter()
+        .filter(|workspace_repo| repo_ids.contains(&workspace_repo.repo_id))
+        .map(|workspace_repo| BundleWorkspaceRepo {
+            repo_id: workspace_repo.repo_id,
+            target_branch: workspace_repo.target_branch,
+        })
+        .collect();
+
+    let mut workspace_sessions = Session::find_by_workspace_id(pool, workspace.id).await?;
+    workspace_sessions.sort_by_key(|session| session.created_at);
+    let mut sessions = Vec::with_capacity(workspace_sessions.len());
+    for session in workspace_sessions {
+        let mut processes = Vec::new();
+        for process in ExecutionProcess::find_by_session_id(pool, session.id, false).await? {
+            if process.run_reason == ExecutionProcessRunReason::DevServer
+                || process.status == ExecutionProcessStatus::Running
+            {
+                continue;
+            }
+            let records = ExecutionProcessLogs::find_by_execution_id(pool, process.id).await?;
+            let logs = ExecutionProcessLogs::parse_logs(&records)?
+                .into_iter()
+                .filter(|msg| {
+                    matches!(
+                        msg,
+                        LogMsg::Stdout(_) | LogMsg::Stderr(_) | LogMsg::JsonPatch(_)
+                    )
+                })
+                .collect();
+            let turn = CodingAgentTurn::find_by_execution_process_id(pool, process.id).await?;
+            processes.push(BundleExecutionProcess {
+                id: process.id,
+                run_reason: process.run_reason,
+                executor_action: serde_json::to_value(&process.executor_action.0)?,
+                status: process.status,
+                exit_code: process.exit_code,
+                started_at: process.started_at,
+                completed_at: process.completed_at,
+                prompt: turn.as_ref().and_then(|turn| turn.prompt.clone()),
+                summary: turn.and_then(|turn| turn.summary),
+                logs,
+            });
+        }
+        sessions.push(BundleSession {
+            executor: session.executor,
+            processes,
+        });
+    }
+
+    Ok(BundleWorkspace {
+        task_id: workspace.task_id,
+        branch: workspace.branch.clone(),
+        name: workspace.name.clone(),
+        agent_working_dir: workspace.agent_working_dir.clone(),
+        repos,
+        sessions,
+    })
+}
+
+async fn restore_workspace(
+    pool: &SqlitePool,
+    bundle_workspace: &BundleWorkspace,
+    task_id: Uuid,
+    repo_ids: &HashMap<Uuid, Uuid>,
+) -> Result<(), ProjectBundleError> {
+    let workspace = Workspace::create(
+        pool,
+        &CreateWorkspace {
+            branch: bundle_workspace.branch.clone(),
+            agent_working_dir: bundle_workspace.agent_working_dir.clone(),
+            base_workspace_id: None,
+        },
+        Uuid::new_v4(),
+        task_id,
+    )
+    .await?;
+    Workspace::update(
+        pool,
+        workspace.id,
+        Some(true),
+        None,
+        bundle_workspace.name.as_deref(),
+    )
+    .await?;
+
+    let workspace_repos: Vec<CreateWorkspaceRepo> = bundle_workspace
+        .repos
+        .iter()
+        .map(|repo| CreateWorkspaceRepo {
+            repo_id: repo_ids[&repo.repo_id],
+            target_branch: repo.target_branch.clone(),
+        })
+        .collect();
+    WorkspaceRepo::create_many(pool, workspace.id, &workspace_repos).await?;
+
+    for bundle_session in &bundle_workspace.sessions {
+        let session = Session::create(
+            pool,
+            &CreateSession {
+                executor: bundle_session.executor.clone(),
+            },
+            Uuid::new_v4(),
+            workspace.id,
+        )
+        .await?;
+
+        for bundle_process in &bundle_session.processes {
+            let process = ExecutionProcess::create_finished(
+                pool,
+                &CreateFinishedExecutionProcess {
+                    session_id: session.id,
+                    run_reason: bundle_process.run_reason.clone(),
+                    executor_action: bundle_process.executor_action.clone(),
+                    status: bundle_process.status.clone(),
+                    exit_code: bundle_process.exit_code,
+                    started_at: bundle_process.started_at,
+                    completed_at: bundle_process.completed_at,
+                },
+                Uuid::new_v4(),
+            )
+            .await?;
+
+            if bundle_process.run_reason == ExecutionProcessRunReason::CodingAgent {
+                CodingAgentTurn::create(
+                    pool,
+                    &CreateCodingAgentTurn {
+                        execution_process_id: process.id,
+                        prompt: bundle_process.prompt.clone(),
+                    },
+                    Uuid::new_v4(),
+                )
+                .await?;
+                if let Some(summary) = &bundle_process.summary {
+                    CodingAgentTurn::update_summary(pool, process.id, summary).await?;
+                }
+            }
+
+            if !bundle_process.logs.is_empty() {
+                let mut jsonl = String::new();
+                for msg in &bundle_process.logs {
+                    jsonl.push_str(&serde_json::to_string(msg)?);
+                    jsonl.push('\n');
+                }
+                ExecutionProcessLogs::append_log_line(pool, process.id, &jsonl).await?;
+            }
+        }
+    }
+
+    // Nothing new happened in an imported workspace
+    CodingAgentTurn::mark_seen_by_workspace_id(pool, workspace.id).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::test_utils::memory_pool;
+
+    async fn add_process(
+        pool: &SqlitePool,
+        session_id: Uuid,
+        run_reason: ExecutionProcessRunReason,
+        status: ExecutionProcessStatus,
+        logs: &[LogMsg],
+    ) -> ExecutionProcess {
+        let started_at = DateTime::parse_from_rfc3339("2026-01-05T10:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let process = ExecutionProcess::create_finished(
+            pool,
+            &CreateFinishedExecutionProcess {
+                session_id,
+                run_reason,
+                executor_action: json!({ "typ": { "type": "ScriptRequest", "script": "npm i" } }),
+                status,
+                exit_code: Some(0),
+                started_at,
+                completed_at: Some(started_at + chrono::Duration::minutes(3)),
+            },
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap();
+        for msg in logs {
+            let line = format!("{}\n", serde_json::to_string(msg).unwrap());
+            ExecutionProcessLogs::append_log_line(pool, process.id, &line)
+                .await
+                .unwrap();
+        }
+        process
+    }
+
+    /// A project with a configured repo, dependent tasks, a tag and one workspace
+    async fn seed_project(pool: &SqlitePool) -> Project {
+        let project = Project::create(
+            pool,
+            &CreateProject {
+                name: "Storefront".to_string(),
+                repositories: Vec::new(),
+            },
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap();
+        let repo = Repo::find_or_create(pool, Path::new("/work/storefront"), "Storefront")
+            .await
+            .unwrap();
+        Repo::update(
+            pool,
+            repo.id,
+            &UpdateRepo {
+                display_name: None,
+                setup_script: Some(Some("npm install".to_string())),
+                cleanup_script: None,
+                copy_files: Some(Some(".env".to_string())),
+                parallel_setup_script: Some(Some(true)),
+                dev_server_script: Some(Some("npm run dev".to_string())),
+                git_host_provider: None,
+                git_host_base_url: None,
+                verify_script: Some(Some("npm test".to_string())),
+                verify_max_retries: Some(Some(5)),
+            },
+        )
+        .await
+        .unwrap();
+        ProjectRepo::create(pool, project.id, repo.id)
+            .await
+            .unwrap();
+
+        let create_task = |title: &str, status| CreateTask {
+            status: Some(status),
+            ..CreateTask::from_title_description(
+                project.id,
+                title.to_string(),
+                Some(format!("{title} details")),
+            )
+        };
+        let schema = Task::create(
+            pool,
+            &create_task("Add cart schema", TaskStatus::Done),
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap();
+        let checkout = Task::create(
+            pool,
+            &create_task("Build checkout", TaskStatus::InProgress),
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap();
+        TaskDependency::create(pool, &checkout, &schema)
+            .await
+            .unwrap();
+        Tag::create(
+            pool,
+            &CreateTag {
+                tag_name: "tests".to_string(),
+                content: "Add tests for every change".to_string(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let workspace = Workspace::create(
+            pool,
+            &CreateWorkspace {
+                branch: "vk/checkout".to_string(),
+                agent_working_dir: None,
+                base_workspace_id: None,
+            },
+            Uuid::new_v4(),
+            checkout.id,
+        )
+        .await
+        .unwrap();
+        WorkspaceRepo::create_many(
+            pool,
+            workspace.id,
+            &[CreateWorkspaceRepo {
+                repo_id: repo.id,
+                target_branch: "main".to_string(),
+            }],
+        )
+        .await
+        .unwrap();
+        let session = Session::create(
+            pool,
+            &CreateSession {
+                executor: Some("CLAUDE_CODE".to_string()),
+            },
+            Uuid::new_v4(),
+            workspace.id,
+        )
+        .await
+        .unwrap();
+        add_process(
+            pool,
+            session.id,
+            ExecutionProcessRunReason::SetupScript,
+            ExecutionProcessStatus::Completed,
+            &[
+                LogMsg::Stdout("added 12 packages\n".to_string()),
+                LogMsg::Stderr("npm warn deprecated\n".to_string()),
+            ],
+        )
+        .await;
+        let agent = add_process(
+            pool,
+            session.id,
+            ExecutionProcessRunReason::CodingAgent,
+            ExecutionProcessStatus::Failed,
+            &[LogMsg::Stdout("{\"type\":\"assistant\"}\n".to_string())],
+        )
+        .await;
+        CodingAgentTurn::create(
+            pool,
+            &CreateCodingAgentTurn {
+                execution_process_id: agent.id,
+                prompt: Some("Build checkout".to_string()),
+            },
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap();
+        CodingAgentTurn::update_summary(pool, agent.id, "Checkout page added")
+            .await
+            .unwrap();
+        add_process(
+            pool,
+            session.id,
+            ExecutionProcessRunReason::DevServer,
+            ExecutionProcessStatus::Killed,
+            &[LogMsg::Stdout("listening on :3000\n".to_string())],
+        )
+        .await;
+
+        project
+    }
+
+    /// The bundle without machine-specific ids, paths and export time
+    fn portable(bundle: &ProjectBundle) -> Value {
+        fn strip(value: &mut Value) {
+            match value {
+                Value::Object(map) => {
+                    for key in [
+                        "id",
+                        "task_id",
+                        "repo_id",
+                        "depends_on",
+                        "path",
+                        "exported_at",
+                    ] {
+                        map.remove(key);
+                    }
+                    map.values_mut().for_each(strip);
+                }
+                Value::Array(items) => items.iter_mut().for_each(strip),
+                _ => {}
+            }
+        }
+        let mut value = serde_json::to_value(bundle).unwrap();
+        strip(&mut value);
+        value
+    }
+
+    #[tokio::test]
+    async fn test_export_import_round_trip() {
+        let source = memory_pool().await;
+        let project = seed_project(&source).await;
+        let bundle = ProjectBundle::export(&source, project.id, true)
+            .await
+            .unwrap();
+        assert_eq!(bundle.tasks.len(), 2);
+        assert_eq!(bundle.tasks[1].depends_on, vec![bundle.tasks[0].id]);
+        // The dev server is left out of the history
+        let processes = &bundle.workspaces[0].sessions[0].processes;
+        assert_eq!(processes.len(), 2);
+        assert_eq!(processes[1].summary.as_deref(), Some("Checkout page added"));
+
+        // Bundles travel as JSON
+        let bundle: ProjectBundle =
+            serde_json::from_str(&serde_json::to_string(&bundle).unwrap()).unwrap();
+
+        let target = memory_pool().await;
+        let repo_paths =
+            HashMap::from([(bundle.repos[0].id, PathBuf::from("/home/me/storefront"))]);
+        let imported = bundle.import(&target, &repo_paths).await.unwrap();
+        assert_eq!(imported.name, "Storefront");
+
+        let repos = ProjectRepo::find_repos_for_project(&target, imported.id)
+            .await
+            .unwrap();
+        assert_eq!(repos.len(), 1);
+        assert_eq!(repos[0].path, PathBuf::from("/home/me/storefront"));
+        assert_eq!(repos[0].setup_script.as_deref(), Some("npm install"));
+        assert_eq!(repos[0].verify_max_retries, 5);
+
+        let workspaces = Workspace::fetch_all(&target, None).await.unwrap();
+        assert_eq!(workspaces.len(), 1);
+        assert!(workspaces[0].archived);
+        assert!(workspaces[0].container_ref.is_none());
+
+        let reexported = ProjectBundle::export(&target, imported.id, true)
+            .await
+            .unwrap();
+        assert_eq!(portable(&reexported), portable(&bundle));
+        let task_ids: Vec<Uuid> = reexported.tasks.iter().map(|task| task.id).collect();
+        assert_eq!(reexported.tasks[1].depends_on, vec![task_ids[0]]);
+        assert_eq!(reexported.workspaces[0].task_id, task_ids[1]);
+        assert_eq!(
+            reexported.workspaces[0].repos[0].repo_id,
+            reexported.repos[0].id
+        );
+    }
+
+    #[tokio::test]
+    async fn test_import_keeps_existing_tags_and_skips_histories_when_not_exported() {
+        let source = memory_pool().await;
+        let project = seed_project(&source).await;
+        let bundle = ProjectBundle::export(&source, project.id, false)
+            .await
+            .unwrap();
+        assert!(bundle.workspaces.is_empty());
+
+        // Importing next to the original project reuses its repo and tag
+        let imported = bundle.import(&source, &HashMap::new()).await.unwrap();
+        let tags = Tag::find_all(&source).await.unwrap();
+        assert_eq!(tags.len(), 1);
+        assert_eq!(
+            ProjectRepo::find_repos_for_project(&source, imported.id)
+                .await
+                .unwrap()[0]
+                .path,
+            PathBuf::from("/work/storefront")
+        );
+        assert_eq!(
+            Task::find_by_project_id(&source, imported.id)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_import_keeps_settings_of_existing_repos() {
+        let source = memory_pool().await;
+        let project = seed_project(&source).await;
+        let bundle = ProjectBundle::export(&source, project.id, false)
+            .await
+            .unwrap();
+
+        // The repo is shared with the original project, which changed it since the export
+        let repo = ProjectRepo::find_repos_for_project(&source, project.id)
+            .await
+            .unwrap()
+            .remove(0);
+        Repo::update(
+            &source,
+            repo.id,
+            &UpdateRepo {
+                display_name: None,
+                setup_script: Some(Some("pnpm install".to_string())),
+                cleanup_script: None,
+                copy_files: None,
+                parallel_setup_script: None,
+                dev_server_script: None,
+                git_host_provider: None,
+                git_host_base_url: None,
+                verify_script: None,
+                verify_max_retries: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        let imported = bundle.import(&source, &HashMap::new()).await.unwrap();
+        let repos = ProjectRepo::find_repos_for_project(&source, imported.id)
+            .await
+            .unwrap();
+        assert_eq!(repos[0].id, repo.id);
+        assert_eq!(repos[0].setup_script.as_deref(), Some("pnpm install"));
+    }
+
+    #[tokio::test]
+    async fn test_import_rejects_invalid_bundles() {
+        let source = memory_pool().await;
+        let project = seed_project(&source).await;
+        let bundle = ProjectBundle::export(&source, project.id, true)
+            .await
+            .unwrap();
+        let target = memory_pool().await;
+
+        let newer = ProjectBundle {
+            version: PROJECT_BUNDLE_VERSION + 1,
+            ..bundle.clone()
+        };
+        assert!(matches!(
+            newer.import(&target, &HashMap::new()).await,
+            Err(ProjectBundleError::UnsupportedVersion(_))
+        ));
+
+        let mut dangling = bundle.clone();
+        dangling.tasks.remove(0);
+        assert!(matches!(
+            dangling.import(&target, &HashMap::new()).await,
+            Err(ProjectBundleError::Invalid(_))
+        ));
+
+        assert_eq!(Project::count(&target).await.unwrap(), 0);
+    }
+}
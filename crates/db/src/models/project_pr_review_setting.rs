@@ -0,0 +1,119 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// How review comments and CI checks on the PRs of a project's workspaces are handled
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectPrReviewSetting {
+    pub project_id: Uuid,
+    /// Send new review comments to the workspace's latest session as a follow-up, then push the
+    /// result and reply on the PR
+    pub auto_follow_up: bool,
+    /// Send failing CI checks, with an excerpt of their logs, to the workspace's latest session
+    /// as a follow-up
+    pub auto_fix_checks: bool,
+    /// When auto follow-up was last turned on; review comments posted before then are not sent
+    pub auto_follow_up_enabled_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Settings left out keep their current value
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdateProjectPrReviewSetting {
+    #[ts(optional)]
+    pub auto_follow_up: Option<bool>,
+    #[ts(optional)]
+    pub auto_fix_checks: Option<bool>,
+}
+
+impl ProjectPrReviewSetting {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectPrReviewSetting,
+            r#"SELECT project_id as "project_id!: Uuid",
+                      auto_follow_up as "auto_follow_up!: bool",
+                      auto_fix_checks as "auto_fix_checks!: bool",
+                      auto_follow_up_enabled_at as "auto_follow_up_enabled_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_pr_review_settings
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &UpdateProjectPrReviewSetting,
+    ) -> Result<Self, sqlx::Error> {
+        let existing = Self::find_by_project_id(pool, project_id).await?;
+        let auto_follow_up = data
+            .auto_follow_up
+            .or(existing.as_ref().map(|setting| setting.auto_follow_up))
+            .unwrap_or(false);
+        let auto_fix_checks = data
+            .auto_fix_checks
+            .or(existing.as_ref().map(|setting| setting.auto_fix_checks))
+            .unwrap_or(false);
+        let auto_follow_up_enabled_at = match &existing {
+            Some(setting) if setting.auto_follow_up && auto_follow_up => {
+                setting.auto_follow_up_enabled_at
+            }
+            _ if auto_follow_up => Some(Utc::now()),
+            _ => None,
+        };
+        sqlx::query_as!(
+            ProjectPrReviewSetting,
+            r#"INSERT INTO project_pr_review_settings (project_id, auto_follow_up, auto_fix_checks, auto_follow_up_enabled_at)
+               VALUES ($1, $2, $3, $4)
+               ON CONFLICT(project_id) DO UPDATE SET
+                   auto_follow_up = excluded.auto_follow_up,
+                   auto_fix_checks = excluded.auto_fix_checks,
+                   auto_follow_up_enabled_at = excluded.auto_follow_up_enabled_at,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING project_id as "project_id!: Uuid",
+                         auto_follow_up as "auto_follow_up!: bool",
+                         auto_fix_checks as "auto_fix_checks!: bool",
+                         auto_follow_up_enabled_at as "auto_follow_up_enabled_at: DateTime<Utc>",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            auto_follow_up,
+            auto_fix_checks,
+            auto_follow_up_enabled_at
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// When review comments on the project's PRs started being sent to the agent automatically;
+    /// `None` while they are not
+    pub async fn auto_follow_up_enabled_at(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+        Ok(Self::find_by_project_id(pool, project_id)
+            .await?
+            .filter(|setting| setting.auto_follow_up)
+            .and_then(|setting| setting.auto_follow_up_enabled_at))
+    }
+
+    /// Whether failing CI checks of the project's PRs are sent to the agent automatically
+    pub async fn auto_fix_checks_enabled(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        Ok(Self::find_by_project_id(pool, project_id)
+            .await?
+            .is_some_and(|setting| setting.auto_fix_checks))
+    }
+}
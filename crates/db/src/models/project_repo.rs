@@ -82,6 +82,10 @@ impl ProjectRepo {
                       r.copy_files,
                       r.parallel_setup_script as "parallel_setup_script!: bool",
                       r.dev_server_script,
+                      r.git_host_provider,
+                      r.git_host_base_url,
+                      r.verify_script,
+                      r.verify_max_retries as "verify_max_retries!: i64",
                       r.created_at as "created_at!: DateTime<Utc>",
                       r.updated_at as "updated_at!: DateTime<Utc>"
                FROM repos r
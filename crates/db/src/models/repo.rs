@@ -16,6 +16,9 @@ pub enum RepoError {
     NotFound,
 }
 
+/// Follow-ups a failing verify script may queue unless the repo sets its own limit
+pub const DEFAULT_VERIFY_MAX_RETRIES: i64 = 2;
+
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
 pub struct Repo {
     pub id: Uuid,
@@ -27,6 +30,16 @@ pub struct Repo {
     pub copy_files: Option<String>,
     pub parallel_setup_script: bool,
     pub dev_server_script: Option<String>,
+    /// Overrides provider detection from the remote URL, e.g. for self-hosted GitLab or Gitea
+    #[ts(type = "ProviderKind | null")]
+    pub git_host_provider: Option<String>,
+    /// Web base URL of a self-hosted instance when the remote URL doesn't point at it, e.g. an
+    /// SSH remote on another host or an instance served under a sub-path
+    pub git_host_base_url: Option<String>,
+    /// Checks (tests, lint, typecheck) run after every coding agent turn
+    pub verify_script: Option<String>,
+    /// Follow-ups queued automatically when the verify script keeps failing
+    pub verify_max_retries: i64,
     #[ts(type = "Date")]
     pub created_at: DateTime<Utc>,
     #[ts(type = "Date")]
@@ -83,6 +96,38 @@ pub struct UpdateRepo {
     )]
     #[ts(optional, type = "string | null")]
     pub dev_server_script: Option<Option<String>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "ProviderKind | null")]
+    pub git_host_provider: Option<Option<String>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "string | null")]
+    pub git_host_base_url: Option<Option<String>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "string | null")]
+    pub verify_script: Option<Option<String>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "number | null")]
+    pub verify_max_retries: Option<Option<i64>>,
 }
 
 impl Repo {
@@ -100,6 +145,10 @@ impl Repo {
                       copy_files,
                       parallel_setup_script as "parallel_setup_script!: bool",
                       dev_server_script,
+                      git_host_provider,
+                      git_host_base_url,
+                      verify_script,
+                      verify_max_retries as "verify_max_retries!: i64",
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM repos
@@ -138,6 +187,10 @@ impl Repo {
                       copy_files,
                       parallel_setup_script as "parallel_setup_script!: bool",
                       dev_server_script,
+                      git_host_provider,
+                      git_host_base_url,
+                      verify_script,
+                      verify_max_retries as "verify_max_retries!: i64",
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM repos
@@ -148,6 +201,33 @@ impl Repo {
         .await
     }
 
+    pub async fn find_by_path(pool: &SqlitePool, path: &Path) -> Result<Option<Self>, sqlx::Error> {
+        let path_str = path.to_string_lossy().to_string();
+        sqlx::query_as!(
+            Repo,
+            r#"SELECT id as "id!: Uuid",
+                      path,
+                      name,
+                      display_name,
+                      setup_script,
+                      cleanup_script,
+                      copy_files,
+                      parallel_setup_script as "parallel_setup_script!: bool",
+                      dev_server_script,
+                      git_host_provider,
+                      git_host_base_url,
+                      verify_script,
+                      verify_max_retries as "verify_max_retries!: i64",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM repos
+               WHERE path = $1"#,
+            path_str
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
     pub async fn find_by_ids(pool: &SqlitePool, ids: &[Uuid]) -> Result<Vec<Self>, sqlx::Error> {
         if ids.is_empty() {
             return Ok(Vec::new());
@@ -193,6 +273,10 @@ impl Repo {
                          copy_files,
                          parallel_setup_script as "parallel_setup_script!: bool",
                          dev_server_script,
+                         git_host_provider,
+                         git_host_base_url,
+                         verify_script,
+                         verify_max_retries as "verify_max_retries!: i64",
                          created_at as "created_at!: DateTime<Utc>",
                          updated_at as "updated_at!: DateTime<Utc>""#,
             id,
@@ -227,6 +311,10 @@ impl Repo {
                       copy_files,
                       parallel_setup_script as "parallel_setup_script!: bool",
                       dev_server_script,
+                      git_host_provider,
+                      git_host_base_url,
+                      verify_script,
+                      verify_max_retries as "verify_max_retries!: i64",
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM repos
@@ -272,6 +360,22 @@ impl Repo {
             None => existing.dev_server_script,
             Some(v) => v.clone(),
         };
+        let git_host_provider = match &payload.git_host_provider {
+            None => existing.git_host_provider,
+            Some(v) => v.clone(),
+        };
+        let git_host_base_url = match &payload.git_host_base_url {
+            None => existing.git_host_base_url,
+            Some(v) => v.clone(),
+        };
+        let verify_script = match &payload.verify_script {
+            None => existing.verify_script,
+            Some(v) => v.clone(),
+        };
+        let verify_max_retries = match &payload.verify_max_retries {
+            None => existing.verify_max_retries,
+            Some(v) => v.unwrap_or(DEFAULT_VERIFY_MAX_RETRIES).max(0),
+        };
 
         sqlx::query_as!(
             Repo,
@@ -282,8 +386,12 @@ impl Repo {
                    copy_files = $4,
                    parallel_setup_script = $5,
                    dev_server_script = $6,
+                   git_host_provider = $7,
+                   git_host_base_url = $8,
+                   verify_script = $9,
+                   verify_max_retries = $10,
                    updated_at = datetime('now', 'subsec')
-               WHERE id = $7
+               WHERE id = $11
                RETURNING id as "id!: Uuid",
                          path,
                          name,
@@ -293,6 +401,10 @@ impl Repo {
                          copy_files,
                          parallel_setup_script as "parallel_setup_script!: bool",
                          dev_server_script,
+                         git_host_provider,
+                         git_host_base_url,
+                         verify_script,
+                         verify_max_retries as "verify_max_retries!: i64",
                          created_at as "created_at!: DateTime<Utc>",
                          updated_at as "updated_at!: DateTime<Utc>""#,
             display_name,
@@ -301,6 +413,10 @@ impl Repo {
             copy_files,
             parallel_setup_script,
             dev_server_script,
+            git_host_provider,
+            git_host_base_url,
+            verify_script,
+            verify_max_retries,
             id
         )
         .fetch_one(pool)
@@ -0,0 +1,221 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "review_comment_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(use_ts_enum)]
+pub enum ReviewCommentStatus {
+    /// Not sent to the agent yet
+    Open,
+    /// Sent to the agent; its turn has not changed the commented lines
+    Sent,
+    /// The commented lines changed before the comment was sent
+    Outdated,
+    /// The agent's turn the comment was sent in changed the commented lines
+    Resolved,
+}
+
+/// A comment on a line range of a file in a workspace's diff
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ReviewComment {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub file_path: String,
+    /// First commented line, 1-based, in the workspace's version of the file
+    pub line_start: i64,
+    /// Last commented line, inclusive
+    pub line_end: i64,
+    pub body: String,
+    /// HEAD of the workspace branch the comment was made against
+    pub commit_sha: String,
+    /// The commented lines as they were when the comment was made
+    pub anchor_text: String,
+    pub status: ReviewCommentStatus,
+    /// The coding agent turn the comment was sent in
+    pub execution_process_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateReviewComment {
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub file_path: String,
+    pub line_start: i64,
+    pub line_end: i64,
+    pub body: String,
+    pub commit_sha: String,
+    pub anchor_text: String,
+}
+
+impl ReviewComment {
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateReviewComment,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            ReviewComment,
+            r#"INSERT INTO review_comments (id, workspace_id, repo_id, file_path, line_start, line_end, body, commit_sha, anchor_text)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+               RETURNING id as "id!: Uuid",
+                         workspace_id as "workspace_id!: Uuid",
+                         repo_id as "repo_id!: Uuid",
+                         file_path,
+                         line_start,
+                         line_end,
+                         body,
+                         commit_sha,
+                         anchor_text,
+                         status as "status!: ReviewCommentStatus",
+                         execution_process_id as "execution_process_id: Uuid",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.workspace_id,
+            data.repo_id,
+            data.file_path,
+            data.line_start,
+            data.line_end,
+            data.body,
+            data.commit_sha,
+            data.anchor_text
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ReviewComment,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      file_path,
+                      line_start,
+                      line_end,
+                      body,
+                      commit_sha,
+                      anchor_text,
+                      status as "status!: ReviewCommentStatus",
+                      execution_process_id as "execution_process_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM review_comments
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Comments of a workspace ordered by file and line
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ReviewComment,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      file_path,
+                      line_start,
+                      line_end,
+                      body,
+                      commit_sha,
+                      anchor_text,
+                      status as "status!: ReviewCommentStatus",
+                      execution_process_id as "execution_process_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM review_comments
+               WHERE workspace_id = $1
+               ORDER BY file_path ASC, line_start ASC, created_at ASC"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn update_body(pool: &SqlitePool, id: Uuid, body: &str) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE review_comments
+               SET body = $2, updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            body
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn set_status(
+        pool: &SqlitePool,
+        id: Uuid,
+        status: ReviewCommentStatus,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE review_comments
+               SET status = $2, updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            status
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Follow the commented lines to where they moved in the file
+    pub async fn move_anchor(
+        pool: &SqlitePool,
+        id: Uuid,
+        line_start: i64,
+        line_end: i64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE review_comments
+               SET line_start = $2, line_end = $3, updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            line_start,
+            line_end
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn mark_sent(
+        pool: &SqlitePool,
+        id: Uuid,
+        execution_process_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE review_comments
+               SET status = 'sent', execution_process_id = $2,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM review_comments WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -130,6 +130,23 @@ impl Task {
         }
     }
 
+    /// Message of the squash commit that merges the task's work
+    pub fn merge_commit_message(&self) -> String {
+        let task_uuid_str = self.id.to_string();
+        let first_uuid_section = task_uuid_str.split('-').next().unwrap_or(&task_uuid_str);
+
+        let mut commit_message = format!("{} (vibe-kanban {})", self.title, first_uuid_section);
+
+        // Add description on next line if it exists
+        if let Some(description) = &self.description
+            && !description.trim().is_empty()
+        {
+            commit_message.push_str("\n\n");
+            commit_message.push_str(description);
+        }
+        commit_message
+    }
+
     pub async fn parent_project(&self, pool: &SqlitePool) -> Result<Option<Project>, sqlx::Error> {
         Project::find_by_id(pool, self.project_id).await
     }
@@ -157,7 +174,7 @@ impl Task {
       JOIN execution_processes ep ON ep.session_id = s.id
      WHERE w.task_id       = t.id
        AND ep.status        = 'running'
-       AND ep.run_reason IN ('setupscript','cleanupscript','codingagent')
+       AND ep.run_reason IN ('setupscript','cleanupscript','codingagent','verifyscript')
      LIMIT 1
   ) THEN 1 ELSE 0 END            AS "has_in_progress_attempt!: i64",
 
@@ -212,6 +229,23 @@ ORDER BY t.created_at DESC"#,
         Ok(tasks)
     }
 
+    /// Tasks of a project, oldest first
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Task,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", shared_task_id as "shared_task_id: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM tasks
+               WHERE project_id = $1
+               ORDER BY created_at ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
     pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             Task,
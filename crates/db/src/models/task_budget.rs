@@ -0,0 +1,87 @@
+use chrono::{DateTime, Utc};
+use executors::budget::RunBudget;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Run limits for the coding agent runs of a task. Limits set here take precedence over the
+/// ones configured on the executor profile variant.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskBudget {
+    pub task_id: Uuid,
+    pub budget_max_tokens: Option<i64>,
+    pub budget_max_minutes: Option<i64>,
+    pub budget_max_tool_calls: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TaskBudget {
+    pub fn run_budget(&self) -> RunBudget {
+        RunBudget {
+            budget_max_tokens: self.budget_max_tokens.map(|v| v.max(0) as u64),
+            budget_max_minutes: self.budget_max_minutes.map(|v| v.max(0) as u32),
+            budget_max_tool_calls: self.budget_max_tool_calls.map(|v| v.max(0) as u32),
+        }
+    }
+
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskBudget,
+            r#"SELECT task_id as "task_id!: Uuid",
+                      budget_max_tokens,
+                      budget_max_minutes,
+                      budget_max_tool_calls,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_budgets
+               WHERE task_id = $1"#,
+            task_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        budget: &RunBudget,
+    ) -> Result<Self, sqlx::Error> {
+        let budget_max_tokens = budget.budget_max_tokens.map(|v| v as i64);
+        let budget_max_minutes = budget.budget_max_minutes.map(i64::from);
+        let budget_max_tool_calls = budget.budget_max_tool_calls.map(i64::from);
+        sqlx::query_as!(
+            TaskBudget,
+            r#"INSERT INTO task_budgets (task_id, budget_max_tokens, budget_max_minutes, budget_max_tool_calls)
+               VALUES ($1, $2, $3, $4)
+               ON CONFLICT(task_id) DO UPDATE SET
+                   budget_max_tokens = excluded.budget_max_tokens,
+                   budget_max_minutes = excluded.budget_max_minutes,
+                   budget_max_tool_calls = excluded.budget_max_tool_calls,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING task_id as "task_id!: Uuid",
+                         budget_max_tokens,
+                         budget_max_minutes,
+                         budget_max_tool_calls,
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            task_id,
+            budget_max_tokens,
+            budget_max_minutes,
+            budget_max_tool_calls
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, task_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM task_budgets WHERE task_id = $1", task_id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,311 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::task::{Task, TaskStatus};
+
+#[derive(Debug, Error)]
+pub enum TaskDependencyError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("A task cannot depend on itself")]
+    SelfDependency,
+    #[error("Tasks must belong to the same project")]
+    ProjectMismatch,
+    #[error("Dependency already exists")]
+    AlreadyExists,
+    #[error("Adding this dependency would create a cycle")]
+    CycleDetected,
+    #[error("Dependency not found")]
+    NotFound,
+}
+
+/// Edge in the task dependency graph: `task_id` is blocked until `depends_on_task_id` is done.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskDependency {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub depends_on_task_id: Uuid,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct CreateTaskDependency {
+    pub depends_on_task_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct TaskDependencies {
+    pub blocked_by: Vec<Task>, // Tasks that must be done before this one can start
+    pub blocking: Vec<Task>,   // Tasks waiting on this one
+}
+
+impl TaskDependency {
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskDependency,
+            r#"SELECT id as "id!: Uuid",
+                      task_id as "task_id!: Uuid",
+                      depends_on_task_id as "depends_on_task_id!: Uuid",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM task_dependencies
+               WHERE task_id = $1
+               ORDER BY created_at ASC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Tasks that `task_id` depends on
+    pub async fn find_blocked_by(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<Task>, sqlx::Error> {
+        sqlx::query_as!(
+            Task,
+            r#"SELECT t.id as "id!: Uuid", t.project_id as "project_id!: Uuid", t.title, t.description, t.status as "status!: TaskStatus", t.parent_workspace_id as "parent_workspace_id: Uuid", t.shared_task_id as "shared_task_id: Uuid", t.created_at as "created_at!: DateTime<Utc>", t.updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_dependencies td
+               JOIN tasks t ON t.id = td.depends_on_task_id
+               WHERE td.task_id = $1
+               ORDER BY td.created_at ASC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Tasks that depend on `task_id`
+    pub async fn find_blocking(pool: &SqlitePool, task_id: Uuid) -> Result<Vec<Task>, sqlx::Error> {
+        sqlx::query_as!(
+            Task,
+            r#"SELECT t.id as "id!: Uuid", t.project_id as "project_id!: Uuid", t.title, t.description, t.status as "status!: TaskStatus", t.parent_workspace_id as "parent_workspace_id: Uuid", t.shared_task_id as "shared_task_id: Uuid", t.created_at as "created_at!: DateTime<Utc>", t.updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_dependencies td
+               JOIN tasks t ON t.id = td.task_id
+               WHERE td.depends_on_task_id = $1
+               ORDER BY td.created_at ASC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_for_task(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<TaskDependencies, sqlx::Error> {
+        Ok(TaskDependencies {
+            blocked_by: Self::find_blocked_by(pool, task_id).await?,
+            blocking: Self::find_blocking(pool, task_id).await?,
+        })
+    }
+
+    /// Returns true if `depends_on_task_id` already (transitively) depends on `task_id`,
+    /// i.e. adding the edge `task_id -> depends_on_task_id` would close a cycle.
+    pub async fn would_create_cycle(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        depends_on_task_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let exists = sqlx::query_scalar!(
+            r#"WITH RECURSIVE upstream(id) AS (
+                   SELECT depends_on_task_id FROM task_dependencies WHERE task_id = $1
+                   UNION
+                   SELECT td.depends_on_task_id
+                     FROM task_dependencies td
+                     JOIN upstream u ON td.task_id = u.id
+               )
+               SELECT EXISTS(SELECT 1 FROM upstream WHERE id = $2) as "exists!: bool""#,
+            depends_on_task_id,
+            task_id
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(exists)
+    }
+
+    /// Validate and insert a new dependency edge
+    pub async fn create(
+        pool: &SqlitePool,
+        task: &Task,
+        depends_on: &Task,
+    ) -> Result<Self, TaskDependencyError> {
+        if task.id == depends_on.id {
+            return Err(TaskDependencyError::SelfDependency);
+        }
+        if task.project_id != depends_on.project_id {
+            return Err(TaskDependencyError::ProjectMismatch);
+        }
+        if Self::find_by_task_id(pool, task.id)
+            .await?
+            .iter()
+            .any(|d| d.depends_on_task_id == depends_on.id)
+        {
+            return Err(TaskDependencyError::AlreadyExists);
+        }
+        if Self::would_create_cycle(pool, task.id, depends_on.id).await? {
+            return Err(TaskDependencyError::CycleDetected);
+        }
+
+        let id = Uuid::new_v4();
+        Ok(sqlx::query_as!(
+            TaskDependency,
+            r#"INSERT INTO task_dependencies (id, task_id, depends_on_task_id)
+               VALUES ($1, $2, $3)
+               RETURNING id as "id!: Uuid",
+                         task_id as "task_id!: Uuid",
+                         depends_on_task_id as "depends_on_task_id!: Uuid",
+                         created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            task.id,
+            depends_on.id
+        )
+        .fetch_one(pool)
+        .await?)
+    }
+
+    pub async fn delete(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        depends_on_task_id: Uuid,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM task_dependencies WHERE task_id = $1 AND depends_on_task_id = $2",
+            task_id,
+            depends_on_task_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Todo tasks that have at least one dependency, whose dependencies are all done,
+    /// and that have never been started (no workspaces).
+    pub async fn find_unblocked_tasks(pool: &SqlitePool) -> Result<Vec<Task>, sqlx::Error> {
+        sqlx::query_as!(
+            Task,
+            r#"SELECT t.id as "id!: Uuid", t.project_id as "project_id!: Uuid", t.title, t.description, t.status as "status!: TaskStatus", t.parent_workspace_id as "parent_workspace_id: Uuid", t.shared_task_id as "shared_task_id: Uuid", t.created_at as "created_at!: DateTime<Utc>", t.updated_at as "updated_at!: DateTime<Utc>"
+               FROM tasks t
+               WHERE t.status = 'todo'
+                 AND EXISTS (SELECT 1 FROM task_dependencies td WHERE td.task_id = t.id)
+                 AND NOT EXISTS (
+                     SELECT 1
+                       FROM task_dependencies td
+                       JOIN tasks dep ON dep.id = td.depends_on_task_id
+                      WHERE td.task_id = t.id
+                        AND dep.status != 'done'
+                 )
+                 AND NOT EXISTS (SELECT 1 FROM workspaces w WHERE w.task_id = t.id)
+               ORDER BY t.created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        models::{
+            project::{CreateProject, Project},
+            task::CreateTask,
+        },
+        test_utils::memory_pool,
+    };
+
+    async fn create_tasks(pool: &SqlitePool, titles: &[&str]) -> Vec<Task> {
+        let project = Project::create(
+            pool,
+            &CreateProject {
+                name: "Storefront".to_string(),
+                repositories: Vec::new(),
+            },
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap();
+        let mut tasks = Vec::new();
+        for title in titles {
+            let data = CreateTask::from_title_description(project.id, title.to_string(), None);
+            tasks.push(Task::create(pool, &data, Uuid::new_v4()).await.unwrap());
+        }
+        tasks
+    }
+
+    #[tokio::test]
+    async fn test_create_rejects_cycles() {
+        let pool = memory_pool().await;
+        let tasks = create_tasks(&pool, &["schema", "api", "ui"]).await;
+        let (schema, api, ui) = (&tasks[0], &tasks[1], &tasks[2]);
+
+        assert!(matches!(
+            TaskDependency::create(&pool, schema, schema).await,
+            Err(TaskDependencyError::SelfDependency)
+        ));
+
+        TaskDependency::create(&pool, api, schema).await.unwrap();
+        assert!(
+            TaskDependency::would_create_cycle(&pool, schema.id, api.id)
+                .await
+                .unwrap()
+        );
+        assert!(matches!(
+            TaskDependency::create(&pool, schema, api).await,
+            Err(TaskDependencyError::CycleDetected)
+        ));
+
+        TaskDependency::create(&pool, ui, api).await.unwrap();
+        assert!(matches!(
+            TaskDependency::create(&pool, schema, ui).await,
+            Err(TaskDependencyError::CycleDetected)
+        ));
+        assert!(
+            !TaskDependency::would_create_cycle(&pool, ui.id, schema.id)
+                .await
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_find_unblocked_tasks_once_last_dependency_is_done() {
+        let pool = memory_pool().await;
+        let tasks = create_tasks(&pool, &["schema", "api", "ui"]).await;
+        let (schema, api, ui) = (&tasks[0], &tasks[1], &tasks[2]);
+        TaskDependency::create(&pool, ui, schema).await.unwrap();
+        TaskDependency::create(&pool, ui, api).await.unwrap();
+
+        assert!(
+            TaskDependency::find_unblocked_tasks(&pool)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        Task::update_status(&pool, schema.id, TaskStatus::Done)
+            .await
+            .unwrap();
+        assert!(
+            TaskDependency::find_unblocked_tasks(&pool)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        Task::update_status(&pool, api.id, TaskStatus::Done)
+            .await
+            .unwrap();
+        let unblocked = TaskDependency::find_unblocked_tasks(&pool).await.unwrap();
+        assert_eq!(
+            unblocked.iter().map(|t| t.id).collect::<Vec<_>>(),
+            vec![ui.id]
+        );
+    }
+}
@@ -0,0 +1,110 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// The git host issue a task was imported from
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskIssueLink {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub repo_id: Uuid,
+    /// Remote the issue was imported through
+    pub remote_url: String,
+    /// Issue number on GitHub, work item id on Azure Boards
+    pub issue_number: i64,
+    pub issue_url: String,
+    /// When the issue was closed after a PR of the task merged
+    pub closed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateTaskIssueLink {
+    pub task_id: Uuid,
+    pub repo_id: Uuid,
+    pub remote_url: String,
+    pub issue_number: i64,
+    pub issue_url: String,
+}
+
+impl TaskIssueLink {
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateTaskIssueLink,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            TaskIssueLink,
+            r#"INSERT INTO task_issue_links (id, task_id, repo_id, remote_url, issue_number, issue_url)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               RETURNING id as "id!: Uuid",
+                         task_id as "task_id!: Uuid",
+                         repo_id as "repo_id!: Uuid",
+                         remote_url,
+                         issue_number,
+                         issue_url,
+                         closed_at as "closed_at: DateTime<Utc>",
+                         created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            data.task_id,
+            data.repo_id,
+            data.remote_url,
+            data.issue_number,
+            data.issue_url
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskIssueLink,
+            r#"SELECT id as "id!: Uuid",
+                      task_id as "task_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      remote_url,
+                      issue_number,
+                      issue_url,
+                      closed_at as "closed_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM task_issue_links
+               WHERE task_id = $1"#,
+            task_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// URLs of the issues already imported into a project
+    pub async fn find_issue_urls_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT l.issue_url
+               FROM task_issue_links l
+               JOIN tasks t ON t.id = l.task_id
+               WHERE t.project_id = $1"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn mark_closed(pool: &SqlitePool, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE task_issue_links
+               SET closed_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
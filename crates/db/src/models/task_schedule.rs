@@ -0,0 +1,297 @@
+use chrono::{DateTime, Utc};
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::workspace_repo::CreateWorkspaceRepo;
+
+/// Template for a task that is created and started automatically, either once at `run_at`
+/// or on every tick of `cron_expression`.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskSchedule {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    #[ts(type = "ExecutorProfileId")]
+    pub executor_profile_id: Json<ExecutorProfileId>,
+    #[ts(type = "Array<CreateWorkspaceRepo>")]
+    pub repos: Json<Vec<CreateWorkspaceRepo>>,
+    pub cron_expression: Option<String>,
+    pub run_at: Option<DateTime<Utc>>,
+    /// Start one run on startup if any fire times passed while the app was closed
+    pub catch_up_missed: bool,
+    pub enabled: bool,
+    pub next_run_at: Option<DateTime<Utc>>,
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub last_task_id: Option<Uuid>,
+    /// Number of fire times skipped because the app was not running
+    pub missed_runs: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct CreateTaskSchedule {
+    pub project_id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    pub executor_profile_id: ExecutorProfileId,
+    pub repos: Vec<CreateWorkspaceRepo>,
+    pub cron_expression: Option<String>,
+    pub run_at: Option<DateTime<Utc>>,
+    pub catch_up_missed: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct UpdateTaskSchedule {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub executor_profile_id: Option<ExecutorProfileId>,
+    pub repos: Option<Vec<CreateWorkspaceRepo>>,
+    /// Setting either `cron_expression` or `run_at` replaces the current timing
+    pub cron_expression: Option<String>,
+    pub run_at: Option<DateTime<Utc>>,
+    pub catch_up_missed: Option<bool>,
+    pub enabled: Option<bool>,
+}
+
+impl TaskSchedule {
+    pub async fn find_all(
+        pool: &SqlitePool,
+        project_id: Option<Uuid>,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskSchedule,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      title,
+                      description,
+                      executor_profile_id as "executor_profile_id!: Json<ExecutorProfileId>",
+                      repos as "repos!: Json<Vec<CreateWorkspaceRepo>>",
+                      cron_expression,
+                      run_at as "run_at: DateTime<Utc>",
+                      catch_up_missed as "catch_up_missed!: bool",
+                      enabled as "enabled!: bool",
+                      next_run_at as "next_run_at: DateTime<Utc>",
+                      last_run_at as "last_run_at: DateTime<Utc>",
+                      last_task_id as "last_task_id: Uuid",
+                      missed_runs as "missed_runs!: i64",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_schedules
+               WHERE $1 IS NULL OR project_id = $1
+               ORDER BY created_at ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskSchedule,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      title,
+                      description,
+                      executor_profile_id as "executor_profile_id!: Json<ExecutorProfileId>",
+                      repos as "repos!: Json<Vec<CreateWorkspaceRepo>>",
+                      cron_expression,
+                      run_at as "run_at: DateTime<Utc>",
+                      catch_up_missed as "catch_up_missed!: bool",
+                      enabled as "enabled!: bool",
+                      next_run_at as "next_run_at: DateTime<Utc>",
+                      last_run_at as "last_run_at: DateTime<Utc>",
+                      last_task_id as "last_task_id: Uuid",
+                      missed_runs as "missed_runs!: i64",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_schedules
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Enabled schedules whose next fire time is at or before `now`
+    pub async fn find_due(pool: &SqlitePool, now: DateTime<Utc>) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskSchedule,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      title,
+                      description,
+                      executor_profile_id as "executor_profile_id!: Json<ExecutorProfileId>",
+                      repos as "repos!: Json<Vec<CreateWorkspaceRepo>>",
+                      cron_expression,
+                      run_at as "run_at: DateTime<Utc>",
+                      catch_up_missed as "catch_up_missed!: bool",
+                      enabled as "enabled!: bool",
+                      next_run_at as "next_run_at: DateTime<Utc>",
+                      last_run_at as "last_run_at: DateTime<Utc>",
+                      last_task_id as "last_task_id: Uuid",
+                      missed_runs as "missed_runs!: i64",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_schedules
+               WHERE enabled = 1
+                 AND next_run_at IS NOT NULL
+                 AND datetime(next_run_at) <= datetime($1)
+               ORDER BY next_run_at ASC"#,
+            now
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateTaskSchedule,
+        id: Uuid,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<Self, sqlx::Error> {
+        let executor_profile_id = Json(&data.executor_profile_id);
+        let repos = Json(&data.repos);
+        let catch_up_missed = data.catch_up_missed.unwrap_or(false);
+        sqlx::query_as!(
+            TaskSchedule,
+            r#"INSERT INTO task_schedules (id, project_id, title, description, executor_profile_id, repos, cron_expression, run_at, catch_up_missed, next_run_at)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         title,
+                         description,
+                         executor_profile_id as "executor_profile_id!: Json<ExecutorProfileId>",
+                         repos as "repos!: Json<Vec<CreateWorkspaceRepo>>",
+                         cron_expression,
+                         run_at as "run_at: DateTime<Utc>",
+                         catch_up_missed as "catch_up_missed!: bool",
+                         enabled as "enabled!: bool",
+                         next_run_at as "next_run_at: DateTime<Utc>",
+                         last_run_at as "last_run_at: DateTime<Utc>",
+                         last_task_id as "last_task_id: Uuid",
+                         missed_runs as "missed_runs!: i64",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.project_id,
+            data.title,
+            data.description,
+            executor_profile_id,
+            repos,
+            data.cron_expression,
+            data.run_at,
+            catch_up_missed,
+            next_run_at
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Replace the template and timing of a schedule. `data` must already be merged with
+    /// the existing values; `project_id` is ignored.
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &CreateTaskSchedule,
+        enabled: bool,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<Self, sqlx::Error> {
+        let executor_profile_id = Json(&data.executor_profile_id);
+        let repos = Json(&data.repos);
+        let catch_up_missed = data.catch_up_missed.unwrap_or(false);
+        sqlx::query_as!(
+            TaskSchedule,
+            r#"UPDATE task_schedules
+               SET title = $2, description = $3, executor_profile_id = $4, repos = $5,
+                   cron_expression = $6, run_at = $7, catch_up_missed = $8, enabled = $9,
+                   next_run_at = $10, updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         title,
+                         description,
+                         executor_profile_id as "executor_profile_id!: Json<ExecutorProfileId>",
+                         repos as "repos!: Json<Vec<CreateWorkspaceRepo>>",
+                         cron_expression,
+                         run_at as "run_at: DateTime<Utc>",
+                         catch_up_missed as "catch_up_missed!: bool",
+                         enabled as "enabled!: bool",
+                         next_run_at as "next_run_at: DateTime<Utc>",
+                         last_run_at as "last_run_at: DateTime<Utc>",
+                         last_task_id as "last_task_id: Uuid",
+                         missed_runs as "missed_runs!: i64",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.title,
+            data.description,
+            executor_profile_id,
+            repos,
+            data.cron_expression,
+            data.run_at,
+            catch_up_missed,
+            enabled,
+            next_run_at
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Record a fire: one-off schedules pass `next_run_at = None` and are disabled
+    pub async fn record_run(
+        pool: &SqlitePool,
+        id: Uuid,
+        task_id: Uuid,
+        ran_at: DateTime<Utc>,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE task_schedules
+               SET last_run_at = $2, last_task_id = $3, next_run_at = $4,
+                   enabled = CASE WHEN $4 IS NULL THEN 0 ELSE enabled END,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            ran_at,
+            task_id,
+            next_run_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Record fire times that were skipped and move on to the next one
+    pub async fn record_missed(
+        pool: &SqlitePool,
+        id: Uuid,
+        missed: i64,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE task_schedules
+               SET missed_runs = missed_runs + $2, next_run_at = $3,
+                   enabled = CASE WHEN $3 IS NULL THEN 0 ELSE enabled END,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            missed,
+            next_run_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM task_schedules WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -9,6 +9,7 @@ use uuid::Uuid;
 const WORKSPACE_NAME_MAX_LEN: usize = 60;
 
 use super::{
+    execution_process::ExecutionProcessStatus,
     project::Project,
     task::Task,
     workspace_repo::{RepoWithTargetBranch, WorkspaceRepo},
@@ -48,6 +49,8 @@ pub struct Workspace {
     pub archived: bool,
     pub pinned: bool,
     pub name: Option<String>,
+    /// Workspace whose branch this one is stacked on, if any
+    pub base_workspace_id: Option<Uuid>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -57,6 +60,25 @@ pub struct WorkspaceWithStatus {
     pub workspace: Workspace,
     pub is_running: bool,
     pub is_errored: bool,
+    /// Outcome of the latest verify script run, if any has finished
+    pub verification_status: Option<WorkspaceVerificationStatus>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkspaceVerificationStatus {
+    Verified,
+    FailedVerification,
+}
+
+impl WorkspaceVerificationStatus {
+    fn from_latest_run(status: Option<ExecutionProcessStatus>) -> Option<Self> {
+        match status? {
+            ExecutionProcessStatus::Completed => Some(Self::Verified),
+            ExecutionProcessStatus::Failed => Some(Self::FailedVerification),
+            ExecutionProcessStatus::Running | ExecutionProcessStatus::Killed => None,
+        }
+    }
 }
 
 impl std::ops::Deref for WorkspaceWithStatus {
@@ -101,6 +123,7 @@ pub struct WorkspaceContext {
 pub struct CreateWorkspace {
     pub branch: String,
     pub agent_working_dir: Option<String>,
+    pub base_workspace_id: Option<Uuid>,
 }
 
 impl Workspace {
@@ -126,7 +149,8 @@ impl Workspace {
                               updated_at AS "updated_at!: DateTime<Utc>",
                               archived AS "archived!: bool",
                               pinned AS "pinned!: bool",
-                              name
+                              name,
+                              base_workspace_id AS "base_workspace_id: Uuid"
                        FROM workspaces
                        WHERE task_id = $1
                        ORDER BY created_at DESC"#,
@@ -147,7 +171,8 @@ impl Workspace {
                               updated_at AS "updated_at!: DateTime<Utc>",
                               archived AS "archived!: bool",
                               pinned AS "pinned!: bool",
-                              name
+                              name,
+                              base_workspace_id AS "base_workspace_id: Uuid"
                        FROM workspaces
                        ORDER BY created_at DESC"#
             )
@@ -178,7 +203,8 @@ impl Workspace {
                        w.updated_at        AS "updated_at!: DateTime<Utc>",
                        w.archived          AS "archived!: bool",
                        w.pinned            AS "pinned!: bool",
-                       w.name
+                       w.name,
+                       w.base_workspace_id AS "base_workspace_id: Uuid"
                FROM    workspaces w
                JOIN    tasks t ON w.task_id = t.id
                JOIN    projects p ON t.project_id = p.id
@@ -267,7 +293,8 @@ impl Workspace {
                        updated_at        AS "updated_at!: DateTime<Utc>",
                        archived          AS "archived!: bool",
                        pinned            AS "pinned!: bool",
-                       name
+                       name,
+                       base_workspace_id AS "base_workspace_id: Uuid"
                FROM    workspaces
                WHERE   id = $1"#,
             id
@@ -289,7 +316,8 @@ impl Workspace {
                        updated_at        AS "updated_at!: DateTime<Utc>",
                        archived          AS "archived!: bool",
                        pinned            AS "pinned!: bool",
-                       name
+                       name,
+                       base_workspace_id AS "base_workspace_id: Uuid"
                FROM    workspaces
                WHERE   rowid = $1"#,
             rowid
@@ -332,7 +360,8 @@ impl Workspace {
                 w.updated_at as "updated_at!: DateTime<Utc>",
                 w.archived as "archived!: bool",
                 w.pinned as "pinned!: bool",
-                w.name
+                w.name,
+                w.base_workspace_id as "base_workspace_id: Uuid"
             FROM workspaces w
             JOIN tasks t ON w.task_id = t.id
             LEFT JOIN sessions s ON w.id = s.workspace_id
@@ -379,15 +408,16 @@ impl Workspace {
     ) -> Result<Self, WorkspaceError> {
         Ok(sqlx::query_as!(
             Workspace,
-            r#"INSERT INTO workspaces (id, task_id, container_ref, branch, agent_working_dir, setup_completed_at)
-               VALUES ($1, $2, $3, $4, $5, $6)
-               RETURNING id as "id!: Uuid", task_id as "task_id!: Uuid", container_ref, branch, agent_working_dir, setup_completed_at as "setup_completed_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>", archived as "archived!: bool", pinned as "pinned!: bool", name"#,
+            r#"INSERT INTO workspaces (id, task_id, container_ref, branch, agent_working_dir, setup_completed_at, base_workspace_id)
+               VALUES ($1, $2, $3, $4, $5, $6, $7)
+               RETURNING id as "id!: Uuid", task_id as "task_id!: Uuid", container_ref, branch, agent_working_dir, setup_completed_at as "setup_completed_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>", archived as "archived!: bool", pinned as "pinned!: bool", name, base_workspace_id as "base_workspace_id: Uuid""#,
             id,
             task_id,
             Option::<String>::None,
             data.branch,
             data.agent_working_dir,
-            Option::<DateTime<Utc>>::None
+            Option::<DateTime<Utc>>::None,
+            data.base_workspace_id
         )
         .fetch_one(pool)
         .await?)
@@ -470,6 +500,49 @@ impl Workspace {
         Ok(())
     }
 
+    /// Workspaces stacked directly on the given workspace, oldest first
+    pub async fn find_stacked_on(
+        pool: &SqlitePool,
+        base_workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Workspace,
+            r#"SELECT  id                AS "id!: Uuid",
+                       task_id           AS "task_id!: Uuid",
+                       container_ref,
+                       branch,
+                       agent_working_dir,
+                       setup_completed_at AS "setup_completed_at: DateTime<Utc>",
+                       created_at        AS "created_at!: DateTime<Utc>",
+                       updated_at        AS "updated_at!: DateTime<Utc>",
+                       archived          AS "archived!: bool",
+                       pinned            AS "pinned!: bool",
+                       name,
+                       base_workspace_id AS "base_workspace_id: Uuid"
+               FROM    workspaces
+               WHERE   base_workspace_id = $1
+               ORDER BY created_at ASC"#,
+            base_workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn set_base_workspace(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        base_workspace_id: Option<Uuid>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE workspaces SET base_workspace_id = $1, updated_at = datetime('now', 'subsec') WHERE id = $2",
+            base_workspace_id,
+            workspace_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
     /// Update workspace fields. Only non-None values will be updated.
     /// For `name`, pass `Some("")` to clear the name, `Some("foo")` to set it, or `None` to leave unchanged.
     pub async fn update(
@@ -555,6 +628,7 @@ impl Workspace {
                 w.archived AS "archived!: bool",
                 w.pinned AS "pinned!: bool",
                 w.name,
+                w.base_workspace_id AS "base_workspace_id: Uuid",
 
                 CASE WHEN EXISTS (
                     SELECT 1
@@ -562,7 +636,7 @@ impl Workspace {
                     JOIN execution_processes ep ON ep.session_id = s.id
                     WHERE s.workspace_id = w.id
                       AND ep.status = 'running'
-                      AND ep.run_reason IN ('setupscript','cleanupscript','codingagent')
+                      AND ep.run_reason IN ('setupscript','cleanupscript','codingagent','verifyscript')
                     LIMIT 1
                 ) THEN 1 ELSE 0 END AS "is_running!: i64",
 
@@ -574,7 +648,18 @@ impl Workspace {
                       AND ep.run_reason IN ('setupscript','cleanupscript','codingagent')
                     ORDER BY ep.created_at DESC
                     LIMIT 1
-                ) IN ('failed','killed') THEN 1 ELSE 0 END AS "is_errored!: i64"
+                ) IN ('failed','killed') THEN 1 ELSE 0 END AS "is_errored!: i64",
+
+                (
+                    SELECT ep.status
+                    FROM sessions s
+                    JOIN execution_processes ep ON ep.session_id = s.id
+                    WHERE s.workspace_id = w.id
+                      AND ep.run_reason = 'verifyscript'
+                      AND ep.dropped = FALSE
+                    ORDER BY ep.created_at DESC
+                    LIMIT 1
+                ) AS "verify_status: ExecutionProcessStatus"
 
             FROM workspaces w
             ORDER BY w.updated_at DESC"#
@@ -597,9 +682,13 @@ impl Workspace {
                     archived: rec.archived,
                     pinned: rec.pinned,
                     name: rec.name,
+                    base_workspace_id: rec.base_workspace_id,
                 },
                 is_running: rec.is_running != 0,
                 is_errored: rec.is_errored != 0,
+                verification_status: WorkspaceVerificationStatus::from_latest_run(
+                    rec.verify_status,
+                ),
             })
             // Apply archived filter if provided
             .filter(|ws| archived.is_none_or(|a| ws.workspace.archived == a))
@@ -656,6 +745,7 @@ impl Workspace {
                 w.archived AS "archived!: bool",
                 w.pinned AS "pinned!: bool",
                 w.name,
+                w.base_workspace_id AS "base_workspace_id: Uuid",
 
                 CASE WHEN EXISTS (
                     SELECT 1
@@ -663,7 +753,7 @@ impl Workspace {
                     JOIN execution_processes ep ON ep.session_id = s.id
                     WHERE s.workspace_id = w.id
                       AND ep.status = 'running'
-                      AND ep.run_reason IN ('setupscript','cleanupscript','codingagent')
+                      AND ep.run_reason IN ('setupscript','cleanupscript','codingagent','verifyscript')
                     LIMIT 1
                 ) THEN 1 ELSE 0 END AS "is_running!: i64",
 
@@ -675,7 +765,18 @@ impl Workspace {
                       AND ep.run_reason IN ('setupscript','cleanupscript','codingagent')
                     ORDER BY ep.created_at DESC
                     LIMIT 1
-                ) IN ('failed','killed') THEN 1 ELSE 0 END AS "is_errored!: i64"
+                ) IN ('failed','killed') THEN 1 ELSE 0 END AS "is_errored!: i64",
+
+                (
+                    SELECT ep.status
+                    FROM sessions s
+                    JOIN execution_processes ep ON ep.session_id = s.id
+                    WHERE s.workspace_id = w.id
+                      AND ep.run_reason = 'verifyscript'
+                      AND ep.dropped = FALSE
+                    ORDER BY ep.created_at DESC
+                    LIMIT 1
+                ) AS "verify_status: ExecutionProcessStatus"
 
             FROM workspaces w
             WHERE w.id = $1"#,
@@ -701,9 +802,11 @@ impl Workspace {
                 archived: rec.archived,
                 pinned: rec.pinned,
                 name: rec.name,
+                base_workspace_id: rec.base_workspace_id,
             },
             is_running: rec.is_running != 0,
             is_errored: rec.is_errored != 0,
+            verification_status: WorkspaceVerificationStatus::from_latest_run(rec.verify_status),
         };
 
         if ws.workspace.name.is_none()
@@ -0,0 +1,142 @@
+use chrono::{DateTime, Utc};
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Workspaces started together for one task with different executor profiles ("fan-out"), so
+/// their results can be compared and a winner picked
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct WorkspaceGroup {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    /// Workspace picked as the winner; the other members are archived when it is set
+    pub winner_workspace_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct WorkspaceGroupMember {
+    pub workspace_id: Uuid,
+    pub workspace_group_id: Uuid,
+    #[ts(type = "ExecutorProfileId")]
+    pub executor_profile_id: Json<ExecutorProfileId>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl WorkspaceGroup {
+    pub async fn create(pool: &SqlitePool, id: Uuid, task_id: Uuid) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            WorkspaceGroup,
+            r#"INSERT INTO workspace_groups (id, task_id)
+               VALUES ($1, $2)
+               RETURNING id as "id!: Uuid",
+                         task_id as "task_id!: Uuid",
+                         winner_workspace_id as "winner_workspace_id: Uuid",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            task_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WorkspaceGroup,
+            r#"SELECT id as "id!: Uuid",
+                      task_id as "task_id!: Uuid",
+                      winner_workspace_id as "winner_workspace_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM workspace_groups
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WorkspaceGroup,
+            r#"SELECT id as "id!: Uuid",
+                      task_id as "task_id!: Uuid",
+                      winner_workspace_id as "winner_workspace_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM workspace_groups
+               WHERE task_id = $1
+               ORDER BY created_at DESC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn set_winner(
+        pool: &SqlitePool,
+        id: Uuid,
+        winner_workspace_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE workspace_groups
+               SET winner_workspace_id = $2, updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            winner_workspace_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn add_member(
+        pool: &SqlitePool,
+        id: Uuid,
+        workspace_id: Uuid,
+        executor_profile_id: &ExecutorProfileId,
+    ) -> Result<WorkspaceGroupMember, sqlx::Error> {
+        let executor_profile_id = Json(executor_profile_id);
+        sqlx::query_as!(
+            WorkspaceGroupMember,
+            r#"INSERT INTO workspace_group_members (workspace_id, workspace_group_id, executor_profile_id)
+               VALUES ($1, $2, $3)
+               RETURNING workspace_id as "workspace_id!: Uuid",
+                         workspace_group_id as "workspace_group_id!: Uuid",
+                         executor_profile_id as "executor_profile_id!: Json<ExecutorProfileId>",
+                         created_at as "created_at!: DateTime<Utc>""#,
+            workspace_id,
+            id,
+            executor_profile_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Members in the order they were started
+    pub async fn members(
+        &self,
+        pool: &SqlitePool,
+    ) -> Result<Vec<WorkspaceGroupMember>, sqlx::Error> {
+        sqlx::query_as!(
+            WorkspaceGroupMember,
+            r#"SELECT workspace_id as "workspace_id!: Uuid",
+                      workspace_group_id as "workspace_group_id!: Uuid",
+                      executor_profile_id as "executor_profile_id!: Json<ExecutorProfileId>",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM workspace_group_members
+               WHERE workspace_group_id = $1
+               ORDER BY rowid ASC"#,
+            self.id
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
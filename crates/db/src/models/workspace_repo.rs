@@ -20,7 +20,7 @@ pub struct WorkspaceRepo {
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Deserialize, TS)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct CreateWorkspaceRepo {
     pub repo_id: Uuid,
     pub target_branch: String,
@@ -120,6 +120,10 @@ impl WorkspaceRepo {
                       r.copy_files,
                       r.parallel_setup_script as "parallel_setup_script!: bool",
                       r.dev_server_script,
+                      r.git_host_provider,
+                      r.git_host_base_url,
+                      r.verify_script,
+                      r.verify_max_retries as "verify_max_retries!: i64",
                       r.created_at as "created_at!: DateTime<Utc>",
                       r.updated_at as "updated_at!: DateTime<Utc>"
                FROM repos r
@@ -146,6 +150,10 @@ impl WorkspaceRepo {
                       r.copy_files,
                       r.parallel_setup_script as "parallel_setup_script!: bool",
                       r.dev_server_script,
+                      r.git_host_provider,
+                      r.git_host_base_url,
+                      r.verify_script,
+                      r.verify_max_retries as "verify_max_retries!: i64",
                       r.created_at as "created_at!: DateTime<Utc>",
                       r.updated_at as "updated_at!: DateTime<Utc>",
                       wr.target_branch
@@ -171,6 +179,10 @@ impl WorkspaceRepo {
                     copy_files: row.copy_files,
                     parallel_setup_script: row.parallel_setup_script,
                     dev_server_script: row.dev_server_script,
+                    git_host_provider: row.git_host_provider,
+                    git_host_base_url: row.git_host_base_url,
+                    verify_script: row.verify_script,
+                    verify_max_retries: row.verify_max_retries,
                     created_at: row.created_at,
                     updated_at: row.updated_at,
                 },
@@ -218,6 +230,8 @@ impl WorkspaceRepo {
         Ok(())
     }
 
+    /// Retarget repos of subtask workspaces and of workspaces stacked on the given workspace
+    /// after its branch was renamed
     pub async fn update_target_branch_for_children_of_workspace(
         pool: &SqlitePool,
         parent_workspace_id: Uuid,
@@ -231,7 +245,7 @@ impl WorkspaceRepo {
                  AND workspace_id IN (
                      SELECT w.id FROM workspaces w
                      JOIN tasks t ON w.task_id = t.id
-                     WHERE t.parent_workspace_id = $3
+                     WHERE t.parent_workspace_id = $3 OR w.base_workspace_id = $3
                  )"#,
             new_branch,
             old_branch,
@@ -257,6 +271,10 @@ impl WorkspaceRepo {
                       r.copy_files,
                       r.parallel_setup_script as "parallel_setup_script!: bool",
                       r.dev_server_script,
+                      r.git_host_provider,
+                      r.git_host_base_url,
+                      r.verify_script,
+                      r.verify_max_retries as "verify_max_retries!: i64",
                       r.created_at as "created_at!: DateTime<Utc>",
                       r.updated_at as "updated_at!: DateTime<Utc>"
                FROM repos r
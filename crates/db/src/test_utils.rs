@@ -0,0 +1,21 @@
+//! Helpers for tests that need a database
+
+use std::str::FromStr;
+
+use sqlx::{
+    SqlitePool,
+    sqlite::{SqliteConnectOptions, SqlitePoolOptions},
+};
+
+/// A migrated in-memory database. The pool holds a single connection, as every connection to
+/// an in-memory database gets a database of its own.
+pub async fn memory_pool() -> SqlitePool {
+    let options = SqliteConnectOptions::from_str("sqlite::memory:").unwrap();
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect_with(options)
+        .await
+        .unwrap();
+    crate::run_migrations(&pool).await.unwrap();
+    pool
+}
@@ -21,6 +21,7 @@ use services::services::{
     auth::AuthContext,
     config::{Config, ConfigError},
     container::{ContainerError, ContainerService},
+    db_backup::DbBackupService,
     events::{EventError, EventService},
     file_search::FileSearchCache,
     filesystem::{FilesystemError, FilesystemService},
@@ -89,7 +90,7 @@ pub trait Deployment: Clone + Send + Sync + 'static {
 
     fn analytics(&self) -> &Option<AnalyticsService>;
 
-    fn container(&self) -> &impl ContainerService;
+    fn container(&self) -> &(impl ContainerService + Clone + Send + Sync + 'static);
 
     fn git(&self) -> &GitService;
 
@@ -124,7 +125,6 @@ pub trait Deployment: Clone + Send + Sync + 'static {
     }
 
     async fn spawn_pr_monitor_service(&self) -> tokio::task::JoinHandle<()> {
-        let db = self.db().clone();
         let analytics = self
             .analytics()
             .as_ref()
@@ -133,7 +133,11 @@ pub trait Deployment: Clone + Send + Sync + 'static {
                 analytics_service: analytics_service.clone(),
             });
         let publisher = self.share_publisher().ok();
-        PrMonitorService::spawn(db, analytics, publisher).await
+        PrMonitorService::spawn(self.container().clone(), analytics, publisher).await
+    }
+
+    async fn spawn_db_backup_service(&self) -> tokio::task::JoinHandle<()> {
+        DbBackupService::spawn(self.db().clone()).await
     }
 
     async fn track_if_analytics_allowed(&self, event_name: &str, properties: Value) {
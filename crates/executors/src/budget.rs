@@ -0,0 +1,75 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Limits for a single coding agent run. A run that exceeds any of them is stopped.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema, Default)]
+pub struct RunBudget {
+    #[schemars(
+        title = "Token Budget",
+        description = "Stop a run once it has used this many tokens, cached input included"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget_max_tokens: Option<u64>,
+    #[schemars(
+        title = "Time Budget (minutes)",
+        description = "Stop a run once it has been running for this many minutes"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget_max_minutes: Option<u32>,
+    #[schemars(
+        title = "Tool Call Budget",
+        description = "Stop a run once the agent has made this many tool calls"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget_max_tool_calls: Option<u32>,
+}
+
+impl RunBudget {
+    pub fn is_unlimited(&self) -> bool {
+        self.budget_max_tokens.is_none()
+            && self.budget_max_minutes.is_none()
+            && self.budget_max_tool_calls.is_none()
+    }
+
+    /// Combine with a more specific budget; limits set in `overrides` take precedence.
+    pub fn with_overrides(&self, overrides: &RunBudget) -> RunBudget {
+        RunBudget {
+            budget_max_tokens: overrides.budget_max_tokens.or(self.budget_max_tokens),
+            budget_max_minutes: overrides.budget_max_minutes.or(self.budget_max_minutes),
+            budget_max_tool_calls: overrides
+                .budget_max_tool_calls
+                .or(self.budget_max_tool_calls),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overrides_take_precedence_per_limit() {
+        let profile = RunBudget {
+            budget_max_tokens: Some(1_000_000),
+            budget_max_minutes: Some(30),
+            budget_max_tool_calls: None,
+        };
+        let task = RunBudget {
+            budget_max_tokens: None,
+            budget_max_minutes: Some(10),
+            budget_max_tool_calls: Some(50),
+        };
+
+        assert_eq!(
+            profile.with_overrides(&task),
+            RunBudget {
+                budget_max_tokens: Some(1_000_000),
+                budget_max_minutes: Some(10),
+                budget_max_tool_calls: Some(50),
+            }
+        );
+        assert!(RunBudget::default().is_unlimited());
+        assert!(!task.is_unlimited());
+    }
+}
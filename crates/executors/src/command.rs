@@ -6,7 +6,7 @@ use thiserror::Error;
 use ts_rs::TS;
 use workspace_utils::shell::resolve_executable_path;
 
-use crate::executors::ExecutorError;
+use crate::{approval_policy::ApprovalPolicy, budget::RunBudget, executors::ExecutorError};
 
 #[derive(Debug, Error)]
 pub enum CommandBuildError {
@@ -60,6 +60,12 @@ pub struct CmdOverrides {
     )]
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub env: Option<HashMap<String, String>>,
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub budget: RunBudget,
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub approval_policy: ApprovalPolicy,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
@@ -2,7 +2,7 @@ use std::{collections::HashMap, path::PathBuf};
 
 use tokio::process::Command;
 
-use crate::command::CmdOverrides;
+use crate::{command::CmdOverrides, sandbox::SandboxPolicy};
 
 /// Repository context for executor operations
 #[derive(Debug, Clone, Default)]
@@ -34,6 +34,8 @@ pub struct ExecutionEnv {
     pub vars: HashMap<String, String>,
     pub repo_context: RepoContext,
     pub commit_reminder: bool,
+    /// When set, spawned processes are confined by this sandbox
+    pub sandbox: Option<SandboxPolicy>,
 }
 
 impl ExecutionEnv {
@@ -42,6 +44,7 @@ impl ExecutionEnv {
             vars: HashMap::new(),
             repo_context,
             commit_reminder,
+            sandbox: None,
         }
     }
 
@@ -71,11 +74,20 @@ impl ExecutionEnv {
         }
     }
 
-    /// Apply all environment variables to a Command
+    /// Return a new env whose processes run inside `sandbox`
+    pub fn with_sandbox(mut self, sandbox: SandboxPolicy) -> Self {
+        self.sandbox = Some(sandbox);
+        self
+    }
+
+    /// Apply all environment variables, and the sandbox if any, to a Command
     pub fn apply_to_command(&self, command: &mut Command) {
         for (key, value) in &self.vars {
             command.env(key, value);
         }
+        if let Some(sandbox) = &self.sandbox {
+            sandbox.apply_to_command(command);
+        }
     }
 
     pub fn contains_key(&self, key: &str) -> bool {
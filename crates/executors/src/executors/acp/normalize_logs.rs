@@ -22,6 +22,7 @@ use crate::{
     },
 };
 
+/// ACP session updates carry no token usage, so none is reported for ACP agents.
 pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
     // stderr normalization
     let entry_index = EntryIndexProvider::start_from(&msg_store);
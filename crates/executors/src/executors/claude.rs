@@ -13,7 +13,10 @@ use serde::{Deserialize, Serialize};
 use tokio::process::Command;
 use ts_rs::TS;
 use workspace_utils::{
-    approvals::ApprovalStatus, diff::create_unified_diff, log_msg::LogMsg, msg_store::MsgStore,
+    approvals::ApprovalStatus,
+    diff::create_unified_diff,
+    log_msg::{LogMsg, ModelTokenUsage},
+    msg_store::MsgStore,
     path::make_path_relative,
 };
 
@@ -354,6 +357,8 @@ pub struct ClaudeLogProcessor {
     main_model_name: Option<String>,
     main_model_context_window: u32,
     context_tokens_used: u32,
+    // Latest usage reported for each assistant message id, with the model that produced it
+    message_usage: HashMap<String, (String, ClaudeUsage)>,
 }
 
 impl ClaudeLogProcessor {
@@ -372,6 +377,7 @@ impl ClaudeLogProcessor {
             streaming_message_id: None,
             main_model_context_window: DEFAULT_CLAUDE_CONTEXT_WINDOW,
             context_tokens_used: 0,
+            message_usage: HashMap::new(),
         }
     }
 
@@ -395,6 +401,7 @@ impl ClaudeLogProcessor {
                     LogMsg::Stdout(x) => x,
                     LogMsg::JsonPatch(_)
                     | LogMsg::SessionId(_)
+                    | LogMsg::TokenUsage(_)
                     | LogMsg::Stderr(_)
                     | LogMsg::Ready => continue,
                     LogMsg::Finished => break,
@@ -432,6 +439,10 @@ impl ClaudeLogProcessor {
                                 session_id_extracted = true;
                             }
 
+                            for usage in processor.extract_token_usage(&claude_json) {
+                                msg_store.push_token_usage(usage);
+                            }
+
                             let patches = processor.normalize_entries(
                                 &claude_json,
                                 &worktree_path,
@@ -480,6 +491,53 @@ impl ClaudeLogProcessor {
         });
     }
 
+    /// Per-model usage totals for the run so far. Running totals are summed from assistant
+    /// messages (which repeat their usage for every content block), until the result message
+    /// reports the final totals.
+    fn extract_token_usage(&mut self, claude_json: &ClaudeJson) -> Vec<ModelTokenUsage> {
+        match claude_json {
+            ClaudeJson::Assistant { message, .. } => {
+                let (Some(id), Some(model), Some(usage)) =
+                    (&message.id, &message.model, &message.usage)
+                else {
+                    return Vec::new();
+                };
+                let entry = (model.clone(), usage.clone());
+                if self.message_usage.get(id) == Some(&entry) {
+                    return Vec::new();
+                }
+                self.message_usage.insert(id.clone(), entry);
+
+                let mut total = ModelTokenUsage {
+                    model: model.clone(),
+                    ..Default::default()
+                };
+                for (_, usage) in self.message_usage.values().filter(|(m, _)| m == model) {
+                    total.input_tokens += usage.input_tokens.unwrap_or(0);
+                    total.output_tokens += usage.output_tokens.unwrap_or(0);
+                    total.cache_read_tokens += usage.cache_read_input_tokens.unwrap_or(0);
+                    total.cache_write_tokens += usage.cache_creation_input_tokens.unwrap_or(0);
+                }
+                vec![total]
+            }
+            ClaudeJson::Result {
+                model_usage: Some(model_usage),
+                ..
+            } => model_usage
+                .iter()
+                .map(|(model, usage)| ModelTokenUsage {
+                    model: model.clone(),
+                    input_tokens: usage.input_tokens.unwrap_or(0),
+                    output_tokens: usage.output_tokens.unwrap_or(0),
+                    cache_read_tokens: usage.cache_read_input_tokens.unwrap_or(0),
+                    cache_write_tokens: usage.cache_creation_input_tokens.unwrap_or(0),
+                    cost_usd: usage.cost_usd,
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
     /// Extract session ID from Claude JSON
     fn extract_session_id(claude_json: &ClaudeJson) -> Option<String> {
         match claude_json {
@@ -1649,6 +1707,7 @@ pub struct ClaudeMessage {
     pub model: Option<String>,
     pub content: Vec<ClaudeContentItem>,
     pub stop_reason: Option<String>,
+    pub usage: Option<ClaudeUsage>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
@@ -1741,6 +1800,16 @@ pub struct ClaudeUsage {
 pub struct ClaudeModelUsage {
     #[serde(default)]
     pub context_window: Option<u32>,
+    #[serde(default)]
+    pub input_tokens: Option<u64>,
+    #[serde(default)]
+    pub output_tokens: Option<u64>,
+    #[serde(default)]
+    pub cache_read_input_tokens: Option<u64>,
+    #[serde(default)]
+    pub cache_creation_input_tokens: Option<u64>,
+    #[serde(default, rename = "costUSD")]
+    pub cost_usd: Option<f64>,
 }
 
 /// Structured tool data for Claude tools based on real samples
@@ -2032,6 +2101,75 @@ mod tests {
         assert_eq!(entries.len(), 0); // Should be ignored like in old implementation
     }
 
+    #[test]
+    fn test_result_model_usage_extracted() {
+        let result_json = r#"{"type":"result","subtype":"success","is_error":false,"session_id":"abc123","total_cost_usd":0.0421,"modelUsage":{"claude-sonnet-4-5-20250929":{"inputTokens":12,"outputTokens":845,"cacheReadInputTokens":40210,"cacheCreationInputTokens":6120,"webSearchRequests":0,"costUSD":0.0398,"contextWindow":200000},"claude-haiku-4-5-20251001":{"inputTokens":310,"outputTokens":25,"costUSD":0.0023}}}"#;
+        let parsed: ClaudeJson = serde_json::from_str(result_json).unwrap();
+
+        let mut processor = ClaudeLogProcessor::new();
+        let mut usage = processor.extract_token_usage(&parsed);
+        usage.sort_by(|a, b| a.model.cmp(&b.model));
+        assert_eq!(
+            usage,
+            vec![
+                ModelTokenUsage {
+                    model: "claude-haiku-4-5-20251001".to_string(),
+                    input_tokens: 310,
+                    output_tokens: 25,
+                    cache_read_tokens: 0,
+                    cache_write_tokens: 0,
+                    cost_usd: Some(0.0023),
+                },
+                ModelTokenUsage {
+                    model: "claude-sonnet-4-5-20250929".to_string(),
+                    input_tokens: 12,
+                    output_tokens: 845,
+                    cache_read_tokens: 40210,
+                    cache_write_tokens: 6120,
+                    cost_usd: Some(0.0398),
+                },
+            ]
+        );
+
+        let assistant_json = r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"Hello world"}]},"session_id":"abc123"}"#;
+        let parsed: ClaudeJson = serde_json::from_str(assistant_json).unwrap();
+        assert!(processor.extract_token_usage(&parsed).is_empty());
+    }
+
+    #[test]
+    fn test_assistant_usage_accumulates_per_message() {
+        let mut processor = ClaudeLogProcessor::new();
+        let message = |id: &str, output_tokens: u64| {
+            let json = format!(
+                r#"{{"type":"assistant","message":{{"id":"{id}","role":"assistant","model":"claude-sonnet-4-5","content":[{{"type":"text","text":"hi"}}],"usage":{{"input_tokens":10,"output_tokens":{output_tokens},"cache_read_input_tokens":1000,"cache_creation_input_tokens":50}}}}}}"#
+            );
+            serde_json::from_str::<ClaudeJson>(&json).unwrap()
+        };
+
+        let usage = processor.extract_token_usage(&message("msg_1", 5));
+        assert_eq!(usage[0].output_tokens, 5);
+
+        // Another content block of the same message repeats its usage
+        assert!(
+            processor
+                .extract_token_usage(&message("msg_1", 5))
+                .is_empty()
+        );
+
+        let usage = processor.extract_token_usage(&message("msg_2", 7));
+        assert_eq!(
+            usage,
+            vec![ModelTokenUsage {
+                model: "claude-sonnet-4-5".to_string(),
+                input_tokens: 20,
+                output_tokens: 12,
+                cache_read_tokens: 2000,
+                cache_write_tokens: 100,
+                cost_usd: None,
+            }]
+        );
+    }
+
     #[test]
     fn test_thinking_content() {
         let thinking_json = r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"thinking","thinking":"Let me think about this..."}]}}"#;
@@ -2147,6 +2285,8 @@ mod tests {
                 base_command_override: None,
                 additional_params: None,
                 env: None,
+                budget: Default::default(),
+                approval_policy: Default::default(),
             },
             approvals_service: None,
             disable_api_key: None,
@@ -17,8 +17,8 @@ use codex_protocol::{
         ErrorEvent, EventMsg, ExecApprovalRequestEvent, ExecCommandBeginEvent, ExecCommandEndEvent,
         ExecCommandOutputDeltaEvent, ExecOutputStream, FileChange as CodexProtoFileChange,
         McpInvocation, McpToolCallBeginEvent, McpToolCallEndEvent, PatchApplyBeginEvent,
-        PatchApplyEndEvent, StreamErrorEvent, ViewImageToolCallEvent, WarningEvent,
-        WebSearchBeginEvent, WebSearchEndEvent,
+        PatchApplyEndEvent, StreamErrorEvent, TokenUsage, TokenUsageInfo, ViewImageToolCallEvent,
+        WarningEvent, WebSearchBeginEvent, WebSearchEndEvent,
     },
 };
 use futures::StreamExt;
@@ -26,8 +26,8 @@ use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use workspace_utils::{
-    approvals::ApprovalStatus, diff::normalize_unified_diff, msg_store::MsgStore,
-    path::make_path_relative,
+    approvals::ApprovalStatus, diff::normalize_unified_diff, log_msg::ModelTokenUsage,
+    msg_store::MsgStore, path::make_path_relative,
 };
 
 use crate::{
@@ -215,6 +215,9 @@ struct LogState {
     mcp_tools: HashMap<String, McpToolState>,
     patches: HashMap<String, PatchState>,
     web_searches: HashMap<String, WebSearchState>,
+    model: Option<String>,
+    // Conversation totals already spent before this execution started
+    usage_baseline: Option<TokenUsage>,
 }
 
 enum StreamingTextKind {
@@ -232,6 +235,39 @@ impl LogState {
             mcp_tools: HashMap::new(),
             patches: HashMap::new(),
             web_searches: HashMap::new(),
+            model: None,
+            usage_baseline: None,
+        }
+    }
+
+    /// Codex reports running totals for the whole conversation, which carry over when a
+    /// session is resumed, so the first report of a process marks where its usage starts.
+    fn token_usage(&mut self, info: &TokenUsageInfo) -> ModelTokenUsage {
+        let total = &info.total_token_usage;
+        let baseline = self.usage_baseline.get_or_insert_with(|| {
+            let last = &info.last_token_usage;
+            TokenUsage {
+                input_tokens: (total.input_tokens - last.input_tokens).max(0),
+                cached_input_tokens: (total.cached_input_tokens - last.cached_input_tokens).max(0),
+                output_tokens: (total.output_tokens - last.output_tokens).max(0),
+                reasoning_output_tokens: (total.reasoning_output_tokens
+                    - last.reasoning_output_tokens)
+                    .max(0),
+                total_tokens: (total.total_tokens - last.total_tokens).max(0),
+            }
+        });
+
+        let input_tokens = (total.input_tokens - baseline.input_tokens).max(0) as u64;
+        let cached_input_tokens =
+            (total.cached_input_tokens - baseline.cached_input_tokens).max(0) as u64;
+        ModelTokenUsage {
+            model: self.model.clone().unwrap_or_else(|| "unknown".to_string()),
+            // Codex counts cached input as part of the input tokens
+            input_tokens: input_tokens.saturating_sub(cached_input_tokens),
+            output_tokens: (total.output_tokens - baseline.output_tokens).max(0) as u64,
+            cache_read_tokens: cached_input_tokens,
+            cache_write_tokens: 0,
+            cost_usd: None,
         }
     }
 
@@ -378,7 +414,9 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
             }
 
             if let Ok(response) = serde_json::from_str::<JSONRPCResponse>(&line) {
-                handle_jsonrpc_response(response, &msg_store, &entry_index);
+                if let Some(model) = handle_jsonrpc_response(response, &msg_store, &entry_index) {
+                    state.model = Some(model);
+                }
                 continue;
             }
 
@@ -387,6 +425,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                     server_notification
                 {
                     msg_store.push_session_id(session_configured.session_id.to_string());
+                    state.model = Some(session_configured.model.clone());
                     handle_model_params(
                         session_configured.model,
                         session_configured.reasoning_effort,
@@ -425,6 +464,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
             match event {
                 EventMsg::SessionConfigured(payload) => {
                     msg_store.push_session_id(payload.session_id.to_string());
+                    state.model = Some(payload.model.clone());
                     handle_model_params(
                         payload.model,
                         payload.reasoning_effort,
@@ -969,6 +1009,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                 }
                 EventMsg::TokenCount(payload) => {
                     if let Some(info) = payload.info {
+                        msg_store.push_token_usage(state.token_usage(&info));
                         add_normalized_entry(
                             &msg_store,
                             &entry_index,
@@ -1040,10 +1081,10 @@ fn handle_jsonrpc_response(
     response: JSONRPCResponse,
     msg_store: &Arc<MsgStore>,
     entry_index: &EntryIndexProvider,
-) {
+) -> Option<String> {
     let Ok(response) = serde_json::from_value::<NewConversationResponse>(response.result.clone())
     else {
-        return;
+        return None;
     };
 
     match SessionHandler::extract_session_id_from_rollout_path(response.rollout_path) {
@@ -1052,11 +1093,12 @@ fn handle_jsonrpc_response(
     }
 
     handle_model_params(
-        response.model,
+        response.model.clone(),
         response.reasoning_effort,
         msg_store,
         entry_index,
     );
+    Some(response.model)
 }
 
 fn handle_model_params(
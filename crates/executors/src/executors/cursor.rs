@@ -10,6 +10,7 @@ use tokio::{io::AsyncWriteExt, process::Command};
 use ts_rs::TS;
 use workspace_utils::{
     diff::{create_unified_diff, normalize_unified_diff},
+    log_msg::ModelTokenUsage,
     msg_store::MsgStore,
     path::make_path_relative,
     shell::resolve_executable_path_blocking,
@@ -203,6 +204,7 @@ impl StandardCodingAgentExecutor for CursorAgent {
             // Assistant streaming coalescer state
             let mut model_reported = false;
             let mut session_id_reported = false;
+            let mut model: Option<String> = None;
 
             let mut current_assistant_message_buffer = String::new();
             let mut current_assistant_message_index: Option<usize> = None;
@@ -256,8 +258,14 @@ impl StandardCodingAgentExecutor for CursorAgent {
                 }
 
                 match &cursor_json {
-                    CursorJson::System { model, .. } => {
-                        if !model_reported && let Some(model) = model.as_ref() {
+                    CursorJson::System {
+                        model: system_model,
+                        ..
+                    } => {
+                        if system_model.is_some() {
+                            model = system_model.clone();
+                        }
+                        if !model_reported && let Some(model) = system_model.as_ref() {
                             let entry = NormalizedEntry {
                                 timestamp: None,
                                 entry_type: NormalizedEntryType::SystemMessage,
@@ -467,8 +475,11 @@ impl StandardCodingAgentExecutor for CursorAgent {
                         }
                     }
 
-                    CursorJson::Result { .. } => {
-                        // no-op; metadata-only events not surfaced
+                    CursorJson::Result { usage, .. } => {
+                        // Otherwise metadata-only, not surfaced
+                        if let Some(usage) = usage {
+                            msg_store.push_token_usage(usage.to_model_usage(model.as_deref()));
+                        }
                     }
 
                     CursorJson::Unknown => {
@@ -573,11 +584,40 @@ pub enum CursorJson {
         result: Option<serde_json::Value>,
         #[serde(default)]
         session_id: Option<String>,
+        /// Totals for the run, only reported by recent cursor-agent versions
+        #[serde(default)]
+        usage: Option<CursorUsage>,
     },
     #[serde(other)]
     Unknown,
 }
 
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CursorUsage {
+    #[serde(default)]
+    pub input_tokens: u64,
+    #[serde(default)]
+    pub output_tokens: u64,
+    #[serde(default)]
+    pub cache_read_tokens: u64,
+    #[serde(default)]
+    pub cache_write_tokens: u64,
+}
+
+impl CursorUsage {
+    fn to_model_usage(&self, model: Option<&str>) -> ModelTokenUsage {
+        ModelTokenUsage {
+            model: model.unwrap_or("unknown").to_string(),
+            input_tokens: self.input_tokens,
+            output_tokens: self.output_tokens,
+            cache_read_tokens: self.cache_read_tokens,
+            cache_write_tokens: self.cache_write_tokens,
+            cost_usd: None,
+        }
+    }
+}
+
 impl CursorJson {
     pub fn extract_session_id(&self) -> Option<String> {
         match self {
@@ -1270,6 +1310,53 @@ mod tests {
         assert_eq!(parsed.extract_session_id().as_deref(), None);
     }
 
+    #[tokio::test]
+    async fn test_result_usage_is_reported_for_the_session_model() {
+        let executor = CursorAgent {
+            append_prompt: AppendPrompt::default(),
+            force: None,
+            model: None,
+            cmd: Default::default(),
+        };
+        let msg_store = Arc::new(MsgStore::new());
+
+        msg_store.push_stdout(format!(
+            "{}\n",
+            r#"{"type":"system","subtype":"init","session_id":"sess-123","model":"Claude 4 Sonnet"}"#
+        ));
+        msg_store.push_stdout(format!(
+            "{}\n",
+            r#"{"type":"result","subtype":"success","is_error":false,"session_id":"sess-123","usage":{"inputTokens":1200,"outputTokens":300,"cacheReadTokens":800,"cacheWriteTokens":50}}"#
+        ));
+        msg_store.push_finished();
+
+        executor.normalize_logs(
+            msg_store.clone(),
+            std::path::Path::new("/tmp/test-worktree"),
+        );
+        tokio::time::sleep(tokio::time::Duration::from_millis(150)).await;
+
+        let usage: Vec<ModelTokenUsage> = msg_store
+            .get_history()
+            .into_iter()
+            .filter_map(|m| match m {
+                workspace_utils::log_msg::LogMsg::TokenUsage(usage) => Some(usage),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            usage,
+            vec![ModelTokenUsage {
+                model: "Claude 4 Sonnet".to_string(),
+                input_tokens: 1200,
+                output_tokens: 300,
+                cache_read_tokens: 800,
+                cache_write_tokens: 50,
+                cost_usd: None,
+            }]
+        );
+    }
+
     #[test]
     fn test_cursor_tool_call_parsing() {
         // Test known variant (from reference JSONL)
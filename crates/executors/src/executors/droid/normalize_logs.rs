@@ -8,7 +8,8 @@ use futures::{StreamExt, future::ready};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use workspace_utils::{
-    diff::normalize_unified_diff, msg_store::MsgStore, path::make_path_relative,
+    diff::normalize_unified_diff, log_msg::ModelTokenUsage, msg_store::MsgStore,
+    path::make_path_relative,
 };
 
 use crate::logs::{
@@ -87,6 +88,7 @@ pub fn normalize_logs(
                         && let Some(model) = model
                     {
                         state.model_reported = true;
+                        state.model = Some(model.clone());
                         let entry = NormalizedEntry {
                             timestamp: None,
                             entry_type: NormalizedEntryType::SystemMessage,
@@ -641,7 +643,12 @@ pub fn normalize_logs(
                     }
                 }
 
-                DroidJson::Completion { final_text, .. } => {
+                DroidJson::Completion {
+                    final_text, usage, ..
+                } => {
+                    if let Some(usage) = usage {
+                        msg_store.push_token_usage(usage.to_model_usage(state.model.as_deref()));
+                    }
                     let entry = NormalizedEntry {
                         timestamp: None,
                         entry_type: NormalizedEntryType::AssistantMessage,
@@ -861,9 +868,41 @@ pub enum DroidJson {
         #[serde(default)]
         timestamp: Option<u64>,
         session_id: String,
+        /// Totals for the run, when the droid version reports them
+        #[serde(default)]
+        usage: Option<DroidUsage>,
     },
 }
 
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct DroidUsage {
+    #[serde(default, alias = "inputTokens")]
+    pub input_tokens: u64,
+    #[serde(default, alias = "outputTokens")]
+    pub output_tokens: u64,
+    #[serde(default, alias = "cacheReadInputTokens", alias = "cacheReadTokens")]
+    pub cache_read_input_tokens: u64,
+    #[serde(
+        default,
+        alias = "cacheCreationInputTokens",
+        alias = "cacheWriteTokens"
+    )]
+    pub cache_creation_input_tokens: u64,
+}
+
+impl DroidUsage {
+    fn to_model_usage(&self, model: Option<&str>) -> ModelTokenUsage {
+        ModelTokenUsage {
+            model: model.unwrap_or("unknown").to_string(),
+            input_tokens: self.input_tokens,
+            output_tokens: self.output_tokens,
+            cache_read_tokens: self.cache_read_input_tokens,
+            cache_write_tokens: self.cache_creation_input_tokens,
+            cost_usd: None,
+        }
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 struct DroidErrorLog {
     pub level: String,
@@ -1236,6 +1275,7 @@ struct ToolCallStates {
     generic_tools: HashMap<String, GenericToolState>,
     pending_fifo: VecDeque<PendingToolCall>,
     model_reported: bool,
+    model: Option<String>,
 }
 
 impl ToolCallStates {
@@ -1251,6 +1291,7 @@ impl ToolCallStates {
             generic_tools: HashMap::new(),
             pending_fifo: VecDeque::new(),
             model_reported: false,
+            model: None,
         }
     }
 }
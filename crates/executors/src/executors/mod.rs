@@ -16,7 +16,9 @@ use workspace_utils::msg_store::MsgStore;
 use crate::executors::qa_mock::QaMockExecutor;
 use crate::{
     actions::{ExecutorAction, review::RepoReviewContext},
+    approval_policy::ApprovalPolicy,
     approvals::ExecutorApprovalService,
+    budget::RunBudget,
     command::CommandBuildError,
     env::ExecutionEnv,
     executors::{
@@ -159,6 +161,40 @@ impl CodingAgent {
         self.default_mcp_config_path().is_some()
     }
 
+    /// Run limits configured on this profile variant
+    pub fn budget(&self) -> RunBudget {
+        match self {
+            Self::ClaudeCode(executor) => executor.cmd.budget.clone(),
+            Self::Amp(executor) => executor.cmd.budget.clone(),
+            Self::Gemini(executor) => executor.cmd.budget.clone(),
+            Self::Codex(executor) => executor.cmd.budget.clone(),
+            Self::Opencode(executor) => executor.cmd.budget.clone(),
+            Self::CursorAgent(executor) => executor.cmd.budget.clone(),
+            Self::QwenCode(executor) => executor.cmd.budget.clone(),
+            Self::Copilot(executor) => executor.cmd.budget.clone(),
+            Self::Droid(executor) => executor.cmd.budget.clone(),
+            #[cfg(feature = "qa-mode")]
+            Self::QaMock(_) => RunBudget::default(),
+        }
+    }
+
+    /// Approval settings configured on this profile variant
+    pub fn approval_policy(&self) -> ApprovalPolicy {
+        match self {
+            Self::ClaudeCode(executor) => executor.cmd.approval_policy.clone(),
+            Self::Amp(executor) => executor.cmd.approval_policy.clone(),
+            Self::Gemini(executor) => executor.cmd.approval_policy.clone(),
+            Self::Codex(executor) => executor.cmd.approval_policy.clone(),
+            Self::Opencode(executor) => executor.cmd.approval_policy.clone(),
+            Self::CursorAgent(executor) => executor.cmd.approval_policy.clone(),
+            Self::QwenCode(executor) => executor.cmd.approval_policy.clone(),
+            Self::Copilot(executor) => executor.cmd.approval_policy.clone(),
+            Self::Droid(executor) => executor.cmd.approval_policy.clone(),
+            #[cfg(feature = "qa-mode")]
+            Self::QaMock(_) => ApprovalPolicy::default(),
+        }
+    }
+
     pub fn capabilities(&self) -> Vec<BaseAgentCapability> {
         match self {
             Self::ClaudeCode(_)
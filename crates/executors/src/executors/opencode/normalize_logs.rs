@@ -3,7 +3,10 @@ use std::{collections::HashMap, path::Path, sync::Arc};
 use futures::StreamExt;
 use serde::Deserialize;
 use serde_json::Value;
-use workspace_utils::{approvals::ApprovalStatus, msg_store::MsgStore, path::make_path_relative};
+use workspace_utils::{
+    approvals::ApprovalStatus, log_msg::ModelTokenUsage, msg_store::MsgStore,
+    path::make_path_relative,
+};
 
 use super::types::{
     MessageInfo, MessageRole, OpencodeExecutorEvent, Part, PermissionAskedEvent, SdkEvent, SdkTodo,
@@ -128,6 +131,8 @@ struct LogState {
     todo_update_entry: Option<usize>,
     todo_update_fingerprint: Option<String>,
     retry_status_fingerprint: Option<String>,
+    /// Latest usage of each assistant message, keyed by message id
+    message_usage: HashMap<String, ModelTokenUsage>,
 }
 
 impl LogState {
@@ -144,6 +149,7 @@ impl LogState {
             todo_update_entry: None,
             todo_update_fingerprint: None,
             retry_status_fingerprint: None,
+            message_usage: HashMap::new(),
         }
     }
 
@@ -162,6 +168,9 @@ impl LogState {
             SdkEvent::MessageUpdated(event) => {
                 let info = event.info;
                 self.maybe_emit_model_system_message(&info);
+                if let Some(usage) = self.token_usage(&info) {
+                    msg_store.push_token_usage(usage);
+                }
                 self.message_roles.insert(info.id, info.role);
             }
             SdkEvent::MessagePartUpdated(event) => {
@@ -286,6 +295,44 @@ impl LogState {
         }
     }
 
+    /// Usage of the message's model summed over the assistant messages of this run, when the
+    /// message reports new token counts
+    fn token_usage(&mut self, info: &MessageInfo) -> Option<ModelTokenUsage> {
+        if info.role != MessageRole::Assistant {
+            return None;
+        }
+        let tokens = info.tokens.as_ref()?;
+        let model = info.model_id().unwrap_or("unknown").to_string();
+        let usage = ModelTokenUsage {
+            model: model.clone(),
+            input_tokens: tokens.input,
+            // Reasoning is billed as output but counted separately
+            output_tokens: tokens.output + tokens.reasoning,
+            cache_read_tokens: tokens.cache.read,
+            cache_write_tokens: tokens.cache.write,
+            cost_usd: info.cost,
+        };
+        if self.message_usage.get(&info.id) == Some(&usage) {
+            return None;
+        }
+        self.message_usage.insert(info.id.clone(), usage);
+
+        let mut total = ModelTokenUsage {
+            model: model.clone(),
+            ..Default::default()
+        };
+        for usage in self.message_usage.values().filter(|u| u.model == model) {
+            total.input_tokens += usage.input_tokens;
+            total.output_tokens += usage.output_tokens;
+            total.cache_read_tokens += usage.cache_read_tokens;
+            total.cache_write_tokens += usage.cache_write_tokens;
+            if let Some(cost) = usage.cost_usd {
+                *total.cost_usd.get_or_insert(0.0) += cost;
+            }
+        }
+        Some(total)
+    }
+
     fn maybe_emit_model_system_message(&mut self, info: &MessageInfo) {
         if self.model_system_message_emitted {
             return;
@@ -112,6 +112,11 @@ pub(super) struct MessageInfo {
     pub(super) provider_id: Option<String>,
     #[serde(rename = "modelID", default)]
     pub(super) model_id: Option<String>,
+    /// Set on assistant messages, growing as the message streams
+    #[serde(default)]
+    pub(super) tokens: Option<MessageTokens>,
+    #[serde(default)]
+    pub(super) cost: Option<f64>,
 }
 
 impl MessageInfo {
@@ -130,6 +135,26 @@ impl MessageInfo {
     }
 }
 
+#[derive(Debug, Clone, Default, Deserialize)]
+pub(super) struct MessageTokens {
+    #[serde(default)]
+    pub(super) input: u64,
+    #[serde(default)]
+    pub(super) output: u64,
+    #[serde(default)]
+    pub(super) reasoning: u64,
+    #[serde(default)]
+    pub(super) cache: MessageCacheTokens,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub(super) struct MessageCacheTokens {
+    #[serde(default)]
+    pub(super) read: u64,
+    #[serde(default)]
+    pub(super) write: u64,
+}
+
 #[derive(Debug, Deserialize)]
 pub(super) struct MessageModelInfo {
     #[serde(rename = "providerID", alias = "providerId")]
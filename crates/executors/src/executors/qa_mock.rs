@@ -202,6 +202,7 @@ fn generate_mock_logs(prompt: &str) -> Vec<String> {
                     thinking: "Analyzing the QA task and preparing mock execution...".to_string(),
                 }],
                 stop_reason: None,
+                usage: None,
             },
             session_id: Some(session_id.clone()),
         },
@@ -219,6 +220,7 @@ fn generate_mock_logs(prompt: &str) -> Vec<String> {
                     },
                 }],
                 stop_reason: None,
+                usage: None,
             },
             session_id: Some(session_id.clone()),
         },
@@ -237,6 +239,7 @@ fn generate_mock_logs(prompt: &str) -> Vec<String> {
                     is_error: Some(false),
                 }],
                 stop_reason: None,
+                usage: None,
             },
             is_synthetic: false,
             session_id: Some(session_id.clone()),
@@ -256,6 +259,7 @@ fn generate_mock_logs(prompt: &str) -> Vec<String> {
                     },
                 }],
                 stop_reason: None,
+                usage: None,
             },
             session_id: Some(session_id.clone()),
         },
@@ -272,6 +276,7 @@ fn generate_mock_logs(prompt: &str) -> Vec<String> {
                     is_error: Some(false),
                 }],
                 stop_reason: None,
+                usage: None,
             },
             session_id: Some(session_id.clone()),
             is_synthetic: false,
@@ -291,6 +296,7 @@ fn generate_mock_logs(prompt: &str) -> Vec<String> {
                     },
                 }],
                 stop_reason: None,
+                usage: None,
             },
             session_id: Some(session_id.clone()),
         },
@@ -307,6 +313,7 @@ fn generate_mock_logs(prompt: &str) -> Vec<String> {
                     is_error: Some(false),
                 }],
                 stop_reason: None,
+                usage: None,
             },
             is_synthetic: false,
             session_id: Some(session_id.clone()),
@@ -325,6 +332,7 @@ fn generate_mock_logs(prompt: &str) -> Vec<String> {
                     ),
                 }],
                 stop_reason: Some("end_turn".to_string()),
+                usage: None,
             },
             session_id: Some(session_id.clone()),
         },
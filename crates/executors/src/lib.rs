@@ -1,9 +1,12 @@
 pub mod actions;
+pub mod approval_policy;
 pub mod approvals;
+pub mod budget;
 pub mod command;
 pub mod env;
 pub mod executors;
 pub mod logs;
 pub mod mcp_config;
 pub mod profile;
+pub mod sandbox;
 pub mod stdout_dup;
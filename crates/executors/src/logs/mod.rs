@@ -4,6 +4,7 @@ use ts_rs::TS;
 use workspace_utils::approvals::ApprovalStatus;
 
 pub mod plain_text_processor;
+pub mod replay;
 pub mod stderr_processor;
 pub mod utils;
 
@@ -149,7 +150,8 @@ pub enum ToolStatus {
     PendingApproval {
         approval_id: String,
         requested_at: DateTime<Utc>,
-        timeout_at: DateTime<Utc>,
+        /// `None` once the request has timed out and waits for a response indefinitely
+        timeout_at: Option<DateTime<Utc>>,
     },
     TimedOut,
 }
@@ -0,0 +1,182 @@
+//! Replay captured raw agent output through a log normalizer.
+//!
+//! A [`ReplayCapture`] holds the stdout/stderr of a real run together with the executor profile
+//! and worktree it ran in. Replaying it feeds the output back through the executor's
+//! `normalize_logs` and collects everything the normalizer emits, so changes to a normalizer can
+//! be checked against real runs. Snapshots of the result are compared against golden files with
+//! [`check_golden`].
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use json_patch::Patch;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::sync::broadcast::error::RecvError;
+use workspace_utils::{
+    diff::create_unified_diff,
+    log_msg::{LogMsg, ModelTokenUsage},
+    msg_store::MsgStore,
+};
+
+use crate::{
+    executors::{CodingAgent, StandardCodingAgentExecutor},
+    logs::{NormalizedEntry, utils::patch::apply_conversation_patches},
+    profile::ExecutorProfileId,
+};
+
+/// Normalizers finish as soon as they see the end of the captured output; anything slower than
+/// this is stuck
+const REPLAY_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Error)]
+pub enum ReplayError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("Normalizer did not finish within {0:?}")]
+    Timeout(Duration),
+    #[error("Replay fell behind the normalizer and dropped {0} messages")]
+    Lagged(u64),
+}
+
+/// Raw output of a coding agent run, as stored in `execution_process_logs`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayCapture {
+    pub executor_profile_id: ExecutorProfileId,
+    /// Directory the agent ran in; normalizers use it to make paths relative
+    pub worktree_path: PathBuf,
+    pub logs: Vec<LogMsg>,
+}
+
+impl ReplayCapture {
+    pub fn load(path: &Path) -> Result<Self, ReplayError> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+}
+
+/// Everything a normalizer emitted while replaying a capture, in order
+#[derive(Debug, Clone, Default)]
+pub struct ReplayOutput {
+    pub patches: Vec<Patch>,
+    pub session_id: Option<String>,
+    pub token_usage: Vec<ModelTokenUsage>,
+}
+
+impl ReplayOutput {
+    /// The conversation the patch stream builds up
+    pub fn entries(&self) -> Vec<NormalizedEntry> {
+        apply_conversation_patches(&self.patches)
+    }
+
+    /// Stable view of the output for golden files. Entry metadata is dropped since it mostly
+    /// echoes the raw agent output.
+    pub fn snapshot(&self) -> ReplaySnapshot {
+        ReplaySnapshot {
+            session_id: self.session_id.clone(),
+            token_usage: self.token_usage.clone(),
+            entries: self
+                .entries()
+                .into_iter()
+                .map(|entry| NormalizedEntry {
+                    metadata: None,
+                    ..entry
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplaySnapshot {
+    pub session_id: Option<String>,
+    pub token_usage: Vec<ModelTokenUsage>,
+    pub entries: Vec<NormalizedEntry>,
+}
+
+impl ReplaySnapshot {
+    pub fn to_pretty_json(&self) -> Result<String, ReplayError> {
+        let mut json = serde_json::to_string_pretty(self)?;
+        json.push('\n');
+        Ok(json)
+    }
+}
+
+/// Feed the stdout/stderr of a capture through `executor`'s log normalizer and collect what it
+/// emits. Patches already present in the capture are ignored so the normalizer starts from an
+/// empty conversation.
+pub async fn replay(
+    executor: &CodingAgent,
+    capture: &ReplayCapture,
+) -> Result<ReplayOutput, ReplayError> {
+    let msg_store = Arc::new(MsgStore::new());
+    for msg in &capture.logs {
+        if matches!(msg, LogMsg::Stdout(_) | LogMsg::Stderr(_)) {
+            msg_store.push(msg.clone());
+        }
+    }
+    msg_store.push_finished();
+
+    // Subscribe before the normalizer starts so nothing it pushes is missed. The channel closes
+    // once every normalizer task has finished and dropped its handle on the store.
+    let mut receiver = msg_store.get_receiver();
+    executor.normalize_logs(msg_store.clone(), &capture.worktree_path);
+    drop(msg_store);
+
+    let collect = async move {
+        let mut output = ReplayOutput::default();
+        loop {
+            match receiver.recv().await {
+                Ok(LogMsg::JsonPatch(patch)) => output.patches.push(patch),
+                Ok(LogMsg::SessionId(session_id)) => output.session_id = Some(session_id),
+                Ok(LogMsg::TokenUsage(usage)) => output.token_usage.push(usage),
+                Ok(_) => {}
+                Err(RecvError::Closed) => return Ok(output),
+                Err(RecvError::Lagged(skipped)) => return Err(ReplayError::Lagged(skipped)),
+            }
+        }
+    };
+
+    tokio::time::timeout(REPLAY_TIMEOUT, collect)
+        .await
+        .map_err(|_| ReplayError::Timeout(REPLAY_TIMEOUT))?
+}
+
+#[derive(Debug)]
+pub enum GoldenOutcome {
+    Matched,
+    /// The golden file was missing or an update was requested, and it has been (re)written
+    Written,
+    /// The snapshot differs from the golden file
+    Mismatch {
+        diff: String,
+    },
+}
+
+/// Compare a snapshot against the golden file at `path`. When `update` is set, or the golden
+/// file does not exist yet, the snapshot is written there instead.
+pub fn check_golden(
+    snapshot: &ReplaySnapshot,
+    path: &Path,
+    update: bool,
+) -> Result<GoldenOutcome, ReplayError> {
+    let actual = snapshot.to_pretty_json()?;
+    if update || !path.exists() {
+        std::fs::write(path, actual)?;
+        return Ok(GoldenOutcome::Written);
+    }
+
+    let expected = std::fs::read_to_string(path)?;
+    if expected == actual {
+        Ok(GoldenOutcome::Matched)
+    } else {
+        Ok(GoldenOutcome::Mismatch {
+            diff: create_unified_diff(&path.to_string_lossy(), &expected, &actual),
+        })
+    }
+}
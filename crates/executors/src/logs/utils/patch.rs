@@ -142,6 +142,30 @@ pub fn extract_normalized_entry_from_patch(patch: &Patch) -> Option<(usize, Norm
     })
 }
 
+/// Apply conversation patches to an empty conversation the way the frontend does, returning
+/// the final normalized entries. Patches that do not apply are skipped.
+pub fn apply_conversation_patches<'a>(
+    patches: impl IntoIterator<Item = &'a Patch>,
+) -> Vec<NormalizedEntry> {
+    let mut document = json!({ "entries": [] });
+    for patch in patches {
+        if let Err(e) = json_patch::patch(&mut document, &patch.0) {
+            tracing::debug!("Skipping conversation patch that does not apply: {e}");
+        }
+    }
+
+    document["entries"]
+        .as_array()
+        .map(|entries| {
+            entries
+                .iter()
+                .filter(|entry| entry["type"] == "NORMALIZED_ENTRY")
+                .filter_map(|entry| from_value(entry["content"].clone()).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 pub fn upsert_normalized_entry(
     msg_store: &Arc<MsgStore>,
     index: usize,
@@ -0,0 +1,708 @@
+//! Optional sandbox for coding agent processes.
+//!
+//! On Linux the agent is confined with Landlock right before it execs: the whole filesystem stays
+//! readable, but writes are only allowed below the workspace, the parts of its repositories' git
+//! directories that commits write to, the temp directories and any extra configured paths. Network
+//! access can either be limited to outgoing TCP connections on a set of ports (UDP, and so DNS,
+//! stays open), or blocked entirely by running the process in its own user and network
+//! namespaces, which have no interface besides an unconfigured loopback. Both are inherited by
+//! every process the agent starts.
+//!
+//! Denied operations fail with `EACCES` inside the agent, which shows up in its tool output as
+//! "Permission denied"; [`SandboxPolicy::violation_messages`] picks out the lines that name a
+//! path the sandbox kept read-only, so they can be surfaced in the conversation.
+
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::process::Command;
+use ts_rs::TS;
+use workspace_utils::shell::resolve_executable_path;
+
+/// Longest excerpt of an offending log line kept in a violation message
+const MAX_VIOLATION_LINE_CHARS: usize = 300;
+
+const VIOLATION_PREFIX: &str = "Sandbox may have blocked an operation: ";
+
+/// How failed connections read without a network namespace's interfaces
+const NO_NETWORK_ERRORS: &[&str] = &[
+    "network is unreachable",
+    "temporary failure in name resolution",
+    "could not resolve host",
+];
+
+#[derive(Debug, Error)]
+pub enum SandboxError {
+    #[error("Sandboxing agents requires Linux")]
+    UnsupportedPlatform,
+    #[error("Landlock is not available on this kernel (Linux 5.13+ with Landlock enabled)")]
+    LandlockUnavailable,
+    #[error("Restricting network ports requires Landlock ABI 4 (Linux 6.7+), found ABI {0}")]
+    NetworkUnsupported(i32),
+    #[error("Blocking network access requires unprivileged user namespaces, which are disabled")]
+    NamespacesUnavailable,
+    #[error(
+        "Coding agents cannot run with network access blocked, as they reach their model API over \
+         the network; allow the ports they need instead"
+    )]
+    NetworkRequired,
+    #[error("Failed to set up sandbox: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Outgoing network access for sandboxed processes. `AllowedPorts` permits TCP connections only
+/// to the configured ports, on any host including loopback, and leaves UDP (including DNS) open.
+/// `Blocked` gives the process a network namespace of its own, so no traffic leaves at all, not
+/// even to the host's loopback; coding agents refuse to start under it since they could not reach
+/// their model API.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(use_ts_enum)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SandboxNetworkAccess {
+    #[default]
+    Unrestricted,
+    AllowedPorts,
+    Blocked,
+}
+
+/// What a sandboxed agent process may do
+#[derive(Debug, Clone)]
+pub struct SandboxPolicy {
+    pub writable_paths: Vec<PathBuf>,
+    pub network: SandboxNetworkAccess,
+    pub allowed_ports: Vec<u16>,
+}
+
+impl SandboxPolicy {
+    /// Writable access to `workspace_root`, the temp directories and `/dev`, plus `extra_paths`
+    pub fn new(workspace_root: &Path, extra_paths: impl IntoIterator<Item = PathBuf>) -> Self {
+        let mut writable_paths = vec![
+            workspace_root.to_path_buf(),
+            std::env::temp_dir(),
+            PathBuf::from("/tmp"),
+            PathBuf::from("/var/tmp"),
+            PathBuf::from("/dev"),
+        ];
+        for path in extra_paths {
+            if !writable_paths.contains(&path) {
+                writable_paths.push(path);
+            }
+        }
+        Self {
+            writable_paths,
+            network: SandboxNetworkAccess::Unrestricted,
+            allowed_ports: Vec::new(),
+        }
+    }
+
+    pub fn with_network(mut self, network: SandboxNetworkAccess, allowed_ports: Vec<u16>) -> Self {
+        self.network = network;
+        self.allowed_ports = allowed_ports;
+        self
+    }
+
+    /// Lines in a chunk of agent output that look like an operation refused by the sandbox: a
+    /// permission error naming a path outside the writable paths, or a refused connection when
+    /// the network is restricted. JSON lines are searched through their string values, so tool
+    /// output embedded in agent events is found too. Lines this produced are never matched again.
+    pub fn violation_messages(&self, chunk: &str) -> Vec<String> {
+        let mut seen = HashSet::new();
+        let mut messages = Vec::new();
+        for line in chunk.lines() {
+            let texts = match serde_json::from_str::<serde_json::Value>(line) {
+                Ok(value) => {
+                    let mut texts = Vec::new();
+                    collect_strings(&value, &mut texts);
+                    texts
+                }
+                Err(_) => vec![line.to_string()],
+            };
+            for text in texts {
+                for text_line in text.lines() {
+                    let text_line = text_line.trim();
+                    if !self.is_violation(text_line) || !seen.insert(text_line.to_string()) {
+                        continue;
+                    }
+                    let excerpt: String =
+                        text_line.chars().take(MAX_VIOLATION_LINE_CHARS).collect();
+                    messages.push(format!("{VIOLATION_PREFIX}{excerpt}"));
+                }
+            }
+        }
+        messages
+    }
+
+    fn is_violation(&self, line: &str) -> bool {
+        if line.starts_with(VIOLATION_PREFIX) {
+            return false;
+        }
+        let lower = line.to_ascii_lowercase();
+        if self.network == SandboxNetworkAccess::Blocked
+            && NO_NETWORK_ERRORS.iter().any(|error| lower.contains(error))
+        {
+            return true;
+        }
+        if !lower.contains("permission denied") && !lower.contains("eacces") {
+            return false;
+        }
+
+        let mut paths = mentioned_paths(line).peekable();
+        if paths.peek().is_none() {
+            // Connections refused by the port filter name no path
+            return self.network == SandboxNetworkAccess::AllowedPorts;
+        }
+        paths.any(|path| !self.is_writable(path))
+    }
+
+    fn is_writable(&self, path: &Path) -> bool {
+        self.writable_paths
+            .iter()
+            .any(|writable| path.starts_with(writable))
+    }
+
+    /// Fail early when the running system cannot enforce this policy
+    pub fn check_supported(&self) -> Result<(), SandboxError> {
+        landlock::check_supported(self)
+    }
+
+    /// Confine the process `command` spawns. If the sandbox cannot be prepared the spawn fails
+    /// rather than running the agent unconfined.
+    pub fn apply_to_command(&self, command: &mut Command) {
+        landlock::apply_to_command(self, command);
+    }
+}
+
+/// Parts of a worktree's git directories that commits write to: the objects, refs, reflogs and
+/// `packed-refs` shared with the main repository, and the worktree's own git directory (index,
+/// `HEAD` and their lock files). The shared `config` and `hooks/` stay read-only, since the
+/// server runs git in the worktree outside the sandbox and would pick up whatever the agent put
+/// there.
+pub async fn git_writable_paths(worktree: &Path) -> Vec<PathBuf> {
+    let (Some(common_dir), Some(git_dir)) = (
+        rev_parse_dir(worktree, "--git-common-dir").await,
+        rev_parse_dir(worktree, "--git-dir").await,
+    ) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = ["objects", "refs", "logs", "packed-refs"]
+        .into_iter()
+        .map(|name| common_dir.join(name))
+        .collect();
+    // The main checkout keeps its index in the shared directory, which stays read-only
+    if git_dir != common_dir {
+        paths.push(git_dir);
+    }
+    paths
+}
+
+/// A directory `git rev-parse` reports for `worktree`
+async fn rev_parse_dir(worktree: &Path, flag: &str) -> Option<PathBuf> {
+    let git = resolve_executable_path("git").await?;
+    let output = Command::new(git)
+        .args(["rev-parse", flag])
+        .current_dir(worktree)
+        .output()
+        .await
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let dir = PathBuf::from(String::from_utf8(output.stdout).ok()?.trim());
+    // Relative to the worktree when it is the main checkout itself
+    Some(if dir.is_relative() {
+        worktree.join(dir)
+    } else {
+        dir
+    })
+}
+
+/// Absolute paths in a line, without the quotes and punctuation tools put around them
+fn mentioned_paths(line: &str) -> impl Iterator<Item = &Path> {
+    line.split_whitespace()
+        .map(|token| {
+            token.trim_matches(|c: char| {
+                matches!(
+                    c,
+                    '\'' | '"' | '`' | ',' | ':' | ';' | '(' | ')' | '[' | ']'
+                )
+            })
+        })
+        .filter(|token| token.starts_with('/'))
+        .map(Path::new)
+}
+
+fn collect_strings(value: &serde_json::Value, out: &mut Vec<String>) {
+    match value {
+        serde_json::Value::String(s) => out.push(s.clone()),
+        serde_json::Value::Array(items) => items.iter().for_each(|item| collect_strings(item, out)),
+        serde_json::Value::Object(map) => map.values().for_each(|item| collect_strings(item, out)),
+        _ => {}
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod landlock {
+    //! Thin wrappers around the Landlock syscalls. See `include/uapi/linux/landlock.h`.
+
+    use std::{
+        ffi::CString,
+        io,
+        os::{
+            fd::{AsRawFd, FromRawFd, OwnedFd},
+            unix::ffi::OsStrExt,
+        },
+    };
+
+    use tokio::process::Command;
+
+    use super::{SandboxError, SandboxNetworkAccess, SandboxPolicy};
+
+    const CREATE_RULESET_VERSION: u32 = 1 << 0;
+    const RULE_PATH_BENEATH: libc::c_int = 1;
+    const RULE_NET_PORT: libc::c_int = 2;
+
+    const ACCESS_FS_EXECUTE: u64 = 1 << 0;
+    const ACCESS_FS_WRITE_FILE: u64 = 1 << 1;
+    const ACCESS_FS_READ_FILE: u64 = 1 << 2;
+    const ACCESS_FS_READ_DIR: u64 = 1 << 3;
+    /// Every filesystem right of ABI 1, from EXECUTE up to MAKE_SYM
+    const ACCESS_FS_ABI_1: u64 = (1 << 13) - 1;
+    const ACCESS_FS_REFER: u64 = 1 << 13;
+    const ACCESS_FS_TRUNCATE: u64 = 1 << 14;
+    const ACCESS_FS_READ: u64 = ACCESS_FS_EXECUTE | ACCESS_FS_READ_FILE | ACCESS_FS_READ_DIR;
+    /// The rights that apply to a file itself rather than to a directory's entries
+    const ACCESS_FS_FILE: u64 =
+        ACCESS_FS_EXECUTE | ACCESS_FS_WRITE_FILE | ACCESS_FS_READ_FILE | ACCESS_FS_TRUNCATE;
+
+    const ACCESS_NET_CONNECT_TCP: u64 = 1 << 1;
+
+    /// Sysctls that turn off unprivileged user namespaces when set to 0 (Debian, upstream) or
+    /// restrict them to profiled programs when set to 1 (Ubuntu's AppArmor)
+    const USERNS_DISABLED: &[(&str, &str)] = &[
+        ("/proc/sys/kernel/unprivileged_userns_clone", "0"),
+        ("/proc/sys/user/max_user_namespaces", "0"),
+        (
+            "/proc/sys/kernel/apparmor_restrict_unprivileged_userns",
+            "1",
+        ),
+    ];
+
+    #[repr(C)]
+    struct RulesetAttr {
+        handled_access_fs: u64,
+        handled_access_net: u64,
+    }
+
+    #[repr(C, packed)]
+    struct PathBeneathAttr {
+        allowed_access: u64,
+        parent_fd: i32,
+    }
+
+    #[repr(C)]
+    struct NetPortAttr {
+        allowed_access: u64,
+        port: u64,
+    }
+
+    /// Landlock ABI version of the running kernel, if Landlock is enabled
+    fn abi_version() -> Option<i32> {
+        // SAFETY: querying the version takes no attribute struct
+        let version = unsafe {
+            libc::syscall(
+                libc::SYS_landlock_create_ruleset,
+                std::ptr::null::<RulesetAttr>(),
+                0usize,
+                CREATE_RULESET_VERSION,
+            )
+        };
+        (version > 0).then_some(version as i32)
+    }
+
+    fn user_namespaces_available() -> bool {
+        USERNS_DISABLED.iter().all(|(path, disabled)| {
+            std::fs::read_to_string(path).map_or(true, |value| value.trim() != *disabled)
+        })
+    }
+
+    /// ABI version to build `policy` against, if the kernel can enforce all of it
+    fn supported_abi(policy: &SandboxPolicy) -> Result<i32, SandboxError> {
+        let abi = abi_version().ok_or(SandboxError::LandlockUnavailable)?;
+        match policy.network {
+            SandboxNetworkAccess::AllowedPorts if abi < 4 => {
+                return Err(SandboxError::NetworkUnsupported(abi));
+            }
+            SandboxNetworkAccess::Blocked if !user_namespaces_available() => {
+                return Err(SandboxError::NamespacesUnavailable);
+            }
+            _ => {}
+        }
+        Ok(abi)
+    }
+
+    pub(super) fn check_supported(policy: &SandboxPolicy) -> Result<(), SandboxError> {
+        supported_abi(policy).map(|_| ())
+    }
+
+    fn handled_access_fs(abi: i32) -> u64 {
+        let mut access = ACCESS_FS_ABI_1;
+        if abi >= 2 {
+            access |= ACCESS_FS_REFER;
+        }
+        if abi >= 3 {
+            access |= ACCESS_FS_TRUNCATE;
+        }
+        access
+    }
+
+    fn add_rule<T>(ruleset: &OwnedFd, rule_type: libc::c_int, attr: &T) -> io::Result<()> {
+        // SAFETY: `attr` is the struct the kernel expects for `rule_type`
+        let result = unsafe {
+            libc::syscall(
+                libc::SYS_landlock_add_rule,
+                ruleset.as_raw_fd(),
+                rule_type,
+                attr as *const T,
+                0u32,
+            )
+        };
+        if result < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    /// Allow `access` below `path`. Rules on a file only keep the rights that apply to files.
+    fn add_path_rule(ruleset: &OwnedFd, path: &std::path::Path, access: u64) -> io::Result<()> {
+        let access = if std::fs::metadata(path)?.is_dir() {
+            access
+        } else {
+            access & ACCESS_FS_FILE
+        };
+        let c_path = CString::new(path.as_os_str().as_bytes()).map_err(io::Error::other)?;
+        // SAFETY: `c_path` is a valid NUL-terminated string
+        let fd = unsafe { libc::open(c_path.as_ptr(), libc::O_PATH | libc::O_CLOEXEC) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: `fd` was just opened and is owned here
+        let parent = unsafe { OwnedFd::from_raw_fd(fd) };
+        let attr = PathBeneathAttr {
+            allowed_access: access,
+            parent_fd: parent.as_raw_fd(),
+        };
+        add_rule(ruleset, RULE_PATH_BENEATH, &attr)
+    }
+
+    /// Create the ruleset for `policy`. This runs in the parent, so the forked child only has to
+    /// enforce it.
+    fn build_ruleset(policy: &SandboxPolicy) -> Result<OwnedFd, SandboxError> {
+        let abi = supported_abi(policy)?;
+        let handled_fs = handled_access_fs(abi);
+        // Blocked networks are isolated with a namespace instead
+        let filter_ports = policy.network == SandboxNetworkAccess::AllowedPorts;
+        let attr = RulesetAttr {
+            handled_access_fs: handled_fs,
+            handled_access_net: if filter_ports {
+                ACCESS_NET_CONNECT_TCP
+            } else {
+                0
+            },
+        };
+        // Kernels before ABI 4 only know the filesystem field
+        let attr_size = if abi >= 4 {
+            std::mem::size_of::<RulesetAttr>()
+        } else {
+            std::mem::size_of::<u64>()
+        };
+
+        // SAFETY: `attr` outlives the call and `attr_size` does not exceed it
+        let fd = unsafe {
+            libc::syscall(
+                libc::SYS_landlock_create_ruleset,
+                &attr as *const RulesetAttr,
+                attr_size,
+                0u32,
+            )
+        };
+        if fd < 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+        // SAFETY: the kernel returned a new close-on-exec file descriptor
+        let ruleset = unsafe { OwnedFd::from_raw_fd(fd as i32) };
+
+        add_path_rule(&ruleset, std::path::Path::new("/"), ACCESS_FS_READ)?;
+        for path in &policy.writable_paths {
+            match add_path_rule(&ruleset, path, handled_fs) {
+                Ok(()) => {}
+                // Paths that do not exist yet cannot be written to anyway
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                    tracing::debug!("Skipping missing sandbox path {}", path.display());
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        if filter_ports {
+            for port in &policy.allowed_ports {
+                let attr = NetPortAttr {
+                    allowed_access: ACCESS_NET_CONNECT_TCP,
+                    port: u64::from(*port),
+                };
+                add_rule(&ruleset, RULE_NET_PORT, &attr)?;
+            }
+        }
+
+        Ok(ruleset)
+    }
+
+    /// Write `contents` to a `/proc` file, using only async-signal-safe calls
+    fn write_proc_file(path: &std::ffi::CStr, contents: &[u8]) -> io::Result<()> {
+        // SAFETY: `path` is NUL-terminated and `contents` outlives the write
+        unsafe {
+            let fd = libc::open(path.as_ptr(), libc::O_WRONLY | libc::O_CLOEXEC);
+            if fd < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let written = libc::write(fd, contents.as_ptr().cast(), contents.len());
+            let result = if written < 0 {
+                Err(io::Error::last_os_error())
+            } else {
+                Ok(())
+            };
+            libc::close(fd);
+            result
+        }
+    }
+
+    /// Move the calling process into new user and network namespaces, keeping its own uid and gid
+    /// so file ownership checks (such as git's `safe.directory`) still pass. The new network
+    /// namespace only has a loopback interface that is down.
+    fn isolate_network(uid_map: &[u8], gid_map: &[u8]) -> io::Result<()> {
+        // SAFETY: plain syscall on the calling (single-threaded, just forked) process
+        if unsafe { libc::unshare(libc::CLONE_NEWUSER | libc::CLONE_NEWNET) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // Unprivileged processes may only map their gid after giving up setgroups
+        write_proc_file(c"/proc/self/setgroups", b"deny")?;
+        write_proc_file(c"/proc/self/uid_map", uid_map)?;
+        write_proc_file(c"/proc/self/gid_map", gid_map)
+    }
+
+    pub(super) fn apply_to_command(policy: &SandboxPolicy, command: &mut Command) {
+        let ruleset = match build_ruleset(policy) {
+            Ok(ruleset) => ruleset,
+            Err(e) => {
+                tracing::error!("Refusing to start agent without its sandbox: {e}");
+                // SAFETY: the hook only builds an error value without allocating
+                unsafe {
+                    command.pre_exec(|| Err(io::Error::from(io::ErrorKind::PermissionDenied)));
+                }
+                return;
+            }
+        };
+
+        // The id maps are formatted here since the child must not allocate
+        let block_network = policy.network == SandboxNetworkAccess::Blocked;
+        // SAFETY: getuid and getgid cannot fail
+        let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+        let uid_map = format!("{uid} {uid} 1").into_bytes();
+        let gid_map = format!("{gid} {gid} 1").into_bytes();
+
+        // SAFETY: runs between fork and exec and only makes async-signal-safe syscalls
+        unsafe {
+            command.pre_exec(move || {
+                // Before Landlock, which would refuse the writes to /proc
+                if block_network {
+                    isolate_network(&uid_map, &gid_map)?;
+                }
+                if libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+                if libc::syscall(libc::SYS_landlock_restrict_self, ruleset.as_raw_fd(), 0u32) != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod landlock {
+    use tokio::process::Command;
+
+    use super::{SandboxError, SandboxPolicy};
+
+    pub(super) fn check_supported(_policy: &SandboxPolicy) -> Result<(), SandboxError> {
+        Err(SandboxError::UnsupportedPlatform)
+    }
+
+    pub(super) fn apply_to_command(_policy: &SandboxPolicy, _command: &mut Command) {
+        tracing::error!("Sandboxing agents requires Linux; running unconfined");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> SandboxPolicy {
+        SandboxPolicy::new(Path::new("/work/space"), [])
+    }
+
+    #[test]
+    fn finds_denials_in_plain_output() {
+        let chunk = "ok\ntouch: cannot touch '/etc/passwd': Permission denied\nmore\n";
+        assert_eq!(
+            policy().violation_messages(chunk),
+            vec![
+                "Sandbox may have blocked an operation: touch: cannot touch '/etc/passwd': Permission denied"
+                    .to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_denials_inside_json_events() {
+        let chunk = r#"{"type":"user","message":{"content":[{"type":"tool_result","content":"npm ERR! EACCES: permission denied, mkdir '/usr/lib/node_modules'\nnpm ERR! done"}]}}"#;
+        let messages = policy().violation_messages(chunk);
+        assert_eq!(messages.len(), 1);
+        assert!(
+            messages[0]
+                .ends_with("npm ERR! EACCES: permission denied, mkdir '/usr/lib/node_modules'")
+        );
+    }
+
+    #[test]
+    fn repeated_denials_are_reported_once() {
+        let chunk = "rm: cannot remove '/x': Permission denied\nrm: cannot remove '/x': Permission denied\n";
+        assert_eq!(policy().violation_messages(chunk).len(), 1);
+        assert!(policy().violation_messages("all good\n").is_empty());
+    }
+
+    #[test]
+    fn denials_the_sandbox_did_not_cause_are_ignored() {
+        let chunk = "\
+bash: /work/space/run.sh: Permission denied
+I got a permission denied error, let me check the file mode
+Sandbox may have blocked an operation: touch: cannot touch '/etc/passwd': Permission denied
+";
+        assert!(policy().violation_messages(chunk).is_empty());
+
+        let filtered = policy().with_network(SandboxNetworkAccess::AllowedPorts, vec![443]);
+        assert_eq!(
+            filtered
+                .violation_messages("connect(80) failed: Permission denied")
+                .len(),
+            1
+        );
+        let blocked = policy().with_network(SandboxNetworkAccess::Blocked, Vec::new());
+        assert_eq!(
+            blocked
+                .violation_messages("curl: (6) Could not resolve host: example.com")
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn policy_always_allows_workspace_and_temp() {
+        let policy = SandboxPolicy::new(Path::new("/work/space"), [PathBuf::from("/work/space")]);
+        assert_eq!(policy.writable_paths[0], PathBuf::from("/work/space"));
+        assert!(policy.writable_paths.contains(&PathBuf::from("/tmp")));
+        assert_eq!(
+            policy
+                .writable_paths
+                .iter()
+                .filter(|path| *path == Path::new("/work/space"))
+                .count(),
+            1
+        );
+    }
+
+    async fn git(dir: &Path, args: &[&str], sandbox: Option<&SandboxPolicy>) -> bool {
+        let mut command = Command::new("git");
+        command
+            .args(args)
+            .current_dir(dir)
+            .env("GIT_CONFIG_GLOBAL", "/dev/null")
+            .env("GIT_AUTHOR_NAME", "Test")
+            .env("GIT_AUTHOR_EMAIL", "test@example.com")
+            .env("GIT_COMMITTER_NAME", "Test")
+            .env("GIT_COMMITTER_EMAIL", "test@example.com");
+        if let Some(sandbox) = sandbox {
+            sandbox.apply_to_command(&mut command);
+        }
+        command
+            .output()
+            .await
+            .is_ok_and(|output| output.status.success())
+    }
+
+    async fn touch(path: &Path, sandbox: &SandboxPolicy) -> bool {
+        let mut command = Command::new("touch");
+        command.arg(path);
+        sandbox.apply_to_command(&mut command);
+        command
+            .output()
+            .await
+            .is_ok_and(|output| output.status.success())
+    }
+
+    #[tokio::test]
+    async fn commits_from_a_sandboxed_worktree() {
+        let root = std::env::temp_dir().join(format!("vk-sandbox-{}", uuid::Uuid::new_v4()));
+        let repo = root.join("repo");
+        let worktree = root.join("worktree");
+        std::fs::create_dir_all(&repo).unwrap();
+        assert!(git(&repo, &["init", "-q"], None).await);
+        assert!(
+            git(
+                &repo,
+                &["commit", "-q", "--allow-empty", "-m", "init"],
+                None
+            )
+            .await
+        );
+        assert!(
+            git(
+                &repo,
+                &["worktree", "add", "-q", worktree.to_str().unwrap()],
+                None
+            )
+            .await
+        );
+
+        // Built by hand, as the temp dir holding both checkouts is always writable otherwise
+        let sandbox = |writable_paths: Vec<PathBuf>| SandboxPolicy {
+            writable_paths,
+            network: SandboxNetworkAccess::Unrestricted,
+            allowed_ports: Vec::new(),
+        };
+        let without_git_dirs = sandbox(vec![worktree.clone(), PathBuf::from("/dev")]);
+        let mut paths = vec![worktree.clone(), PathBuf::from("/dev")];
+        paths.extend(git_writable_paths(&worktree).await);
+        let with_git_dirs = sandbox(paths);
+
+        if with_git_dirs.check_supported().is_ok() {
+            std::fs::write(worktree.join("file.txt"), "change").unwrap();
+            assert!(git(&worktree, &["add", "file.txt"], Some(&with_git_dirs)).await);
+            let commit = ["commit", "-q", "-m", "change"];
+            assert!(!git(&worktree, &commit, Some(&without_git_dirs)).await);
+            assert!(git(&worktree, &commit, Some(&with_git_dirs)).await);
+
+            // Hooks and config run outside the sandbox later, so they stay out of reach
+            let git_dir = repo.join(".git");
+            assert!(!touch(&git_dir.join("hooks/pre-commit"), &with_git_dirs).await);
+            assert!(!git_dir.join("hooks/pre-commit").exists());
+            let set_hooks_path = ["config", "core.hooksPath", "/tmp"];
+            assert!(!git(&worktree, &set_hooks_path, Some(&with_git_dirs)).await);
+        }
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
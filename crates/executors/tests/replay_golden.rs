@@ -0,0 +1,66 @@
+//! Replays captured agent runs through their log normalizers and compares the result with the
+//! golden snapshots next to them.
+//!
+//! Each `tests/replay/<name>.capture.json` is replayed and checked against
+//! `tests/replay/<name>.golden.json`. After an intended normalizer change, regenerate the
+//! snapshots with `UPDATE_GOLDEN=1 cargo test -p executors --test replay_golden` and review the
+//! diff. New captures can be downloaded from `/api/execution-processes/{id}/replay-capture`.
+
+use std::path::{Path, PathBuf};
+
+use executors::{
+    logs::replay::{GoldenOutcome, ReplayCapture, check_golden, replay},
+    profile::ExecutorConfigs,
+};
+
+fn captures() -> Vec<PathBuf> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/replay");
+    let mut captures: Vec<PathBuf> = std::fs::read_dir(&dir)
+        .expect("replay fixture directory")
+        .map(|entry| entry.expect("fixture entry").path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.ends_with(".capture.json"))
+        })
+        .collect();
+    captures.sort();
+    captures
+}
+
+#[tokio::test]
+async fn replayed_captures_match_golden_snapshots() {
+    let update = std::env::var_os("UPDATE_GOLDEN").is_some();
+    let configs = ExecutorConfigs::from_defaults();
+    let captures = captures();
+    assert!(!captures.is_empty(), "no replay captures found");
+
+    let mut failures = Vec::new();
+    for capture_path in captures {
+        let capture = ReplayCapture::load(&capture_path).expect("valid capture");
+        let executor = configs.get_coding_agent_or_default(&capture.executor_profile_id);
+        let output = replay(&executor, &capture)
+            .await
+            .unwrap_or_else(|e| panic!("replaying {}: {e}", capture_path.display()));
+
+        let golden_path = capture_path
+            .to_string_lossy()
+            .replace(".capture.json", ".golden.json");
+        match check_golden(&output.snapshot(), Path::new(&golden_path), update)
+            .expect("golden file readable")
+        {
+            GoldenOutcome::Matched => {}
+            GoldenOutcome::Written if update => {}
+            GoldenOutcome::Written => failures.push(format!(
+                "{golden_path} did not exist and has been written; review and commit it"
+            )),
+            GoldenOutcome::Mismatch { diff } => failures.push(diff),
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "replayed output differs from golden snapshots (rerun with UPDATE_GOLDEN=1 if intended):\n{}",
+        failures.join("\n")
+    );
+}
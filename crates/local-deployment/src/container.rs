@@ -1,8 +1,7 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     io,
     path::{Path, PathBuf},
-    str::FromStr,
     sync::Arc,
     time::Duration,
 };
@@ -16,41 +15,49 @@ use db::{
         coding_agent_turn::CodingAgentTurn,
         execution_process::{
             ExecutionContext, ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus,
+            ExecutionProcessStopReason,
         },
         execution_process_repo_state::ExecutionProcessRepoState,
+        project_approval_policy::ProjectApprovalPolicy,
         repo::Repo,
         scratch::{DraftFollowUpData, Scratch, ScratchType},
         task::{Task, TaskStatus},
+        task_budget::TaskBudget,
         workspace::Workspace,
         workspace_repo::WorkspaceRepo,
     },
 };
 use deployment::{DeploymentError, RemoteClientNotConfigured};
 use executors::{
-    actions::{
-        Executable, ExecutorAction, ExecutorActionType,
-        coding_agent_follow_up::CodingAgentFollowUpRequest,
-        coding_agent_initial::CodingAgentInitialRequest,
-    },
+    actions::{Executable, ExecutorAction, ExecutorActionType},
     approvals::{ExecutorApprovalService, NoopExecutorApprovalService},
+    budget::RunBudget,
     env::{ExecutionEnv, RepoContext},
     executors::{BaseCodingAgent, ExecutorExitResult, ExecutorExitSignal, InterruptSender},
     logs::{NormalizedEntryType, utils::patch::extract_normalized_entry_from_patch},
-    profile::ExecutorProfileId,
+    profile::ExecutorConfigs,
+    sandbox::{self, SandboxError, SandboxNetworkAccess, SandboxPolicy},
 };
-use futures::{FutureExt, TryStreamExt, stream::select};
+use futures::{FutureExt, StreamExt, TryStreamExt, stream::select};
 use serde_json::json;
 use services::services::{
     analytics::AnalyticsContext,
     approvals::{Approvals, executor_approvals::ExecutorApprovalBridge},
+    auto_rebase::AutoRebaseService,
+    budget::BudgetTracker,
     config::Config,
     container::{ContainerError, ContainerRef, ContainerService},
     diff_stream::{self, DiffStreamHandle},
     git::{GitCli, GitService},
     image::ImageService,
+    merge_queue::MergeQueueService,
     notification::NotificationService,
+    pr_review,
     queued_message::QueuedMessageService,
+    review_comments,
     share::SharePublisher,
+    task_scheduler::TaskSchedulerService,
+    verification,
     workspace_manager::{RepoWorkspaceInput, WorkspaceManager},
 };
 use tokio::{sync::RwLock, task::JoinHandle};
@@ -58,6 +65,8 @@ use tokio_util::io::ReaderStream;
 use utils::{
     log_msg::LogMsg,
     msg_store::MsgStore,
+    path::expand_tilde,
+    port_file::read_port_file,
     text::{git_branch_id, short_uuid, truncate_to_char_boundary},
 };
 use uuid::Uuid;
@@ -76,6 +85,9 @@ pub struct LocalContainerService {
     analytics: Option<AnalyticsContext>,
     approvals: Approvals,
     queued_message_service: QueuedMessageService,
+    /// Sessions whose verify scripts queued a follow-up during the scripts still running after
+    /// an agent turn, and whether it carries a message the user had queued
+    verification_follow_ups: Arc<RwLock<HashMap<Uuid, bool>>>,
     publisher: Result<SharePublisher, RemoteClientNotConfigured>,
     notification_service: NotificationService,
 }
@@ -95,6 +107,7 @@ impl LocalContainerService {
     ) -> Self {
         let child_store = Arc::new(RwLock::new(HashMap::new()));
         let interrupt_senders = Arc::new(RwLock::new(HashMap::new()));
+        let verification_follow_ups = Arc::new(RwLock::new(HashMap::new()));
         let notification_service = NotificationService::new(config.clone());
 
         let container = LocalContainerService {
@@ -108,11 +121,15 @@ impl LocalContainerService {
             analytics,
             approvals,
             queued_message_service,
+            verification_follow_ups,
             publisher,
             notification_service,
         };
 
         container.spawn_workspace_cleanup();
+        TaskSchedulerService::spawn(container.clone());
+        AutoRebaseService::spawn(container.clone(), container.config.clone());
+        MergeQueueService::spawn(container.clone());
 
         container
     }
@@ -459,7 +476,10 @@ impl LocalContainerService {
                     ExecutionProcessStatus::Running
                 );
 
-                if success || cleanup_done {
+                if success
+                    || cleanup_done
+                    || verification::failed_verify_continues(&ctx.execution_process)
+                {
                     // Commit changes (if any) and get feedback about whether changes were made
                     let changes_committed = match container.try_commit_changes(&ctx).await {
                         Ok(committed) => committed,
@@ -491,7 +511,7 @@ impl LocalContainerService {
                         }
                     } else {
                         tracing::info!(
-                            "Skipping cleanup script for workspace {} - no changes made by coding agent",
+                            "Skipping verify and cleanup scripts for workspace {} - no changes made by coding agent",
                             ctx.workspace.id
                         );
 
@@ -500,13 +520,52 @@ impl LocalContainerService {
                     }
                 }
 
-                if container.should_finalize(&ctx) {
-                    // Only execute queued messages if the execution succeeded
-                    // If it failed or was killed, just clear the queue and finalize
-                    let should_execute_queued = !matches!(
-                        ctx.execution_process.status,
-                        ExecutionProcessStatus::Failed | ExecutionProcessStatus::Killed
+                if ctx.execution_process.run_reason == ExecutionProcessRunReason::CodingAgent
+                    && let Err(e) =
+                        review_comments::refresh_statuses(&db.pool, &ctx.workspace, Some(exec_id))
+                            .await
+                {
+                    tracing::warn!("Failed to update review comments after agent turn: {}", e);
+                }
+
+                // PR review comments sent in this turn: push the result and reply on the PR
+                if ctx.execution_process.run_reason == ExecutionProcessRunReason::CodingAgent
+                    && let Err(e) = pr_review::complete_follow_up(
+                        &db.pool,
+                        &container.git,
+                        &ctx.workspace,
+                        &ctx.execution_process,
+                    )
+                    .await
+                {
+                    tracing::warn!(
+                        "Failed to settle PR review comments after agent turn: {}",
+                        e
                     );
+                }
+
+                // A failed verify script sends its output back to the agent through the queue,
+                // once the remaining verify and cleanup scripts have run
+                if ctx.execution_process.run_reason == ExecutionProcessRunReason::VerifyScript
+                    && ctx.execution_process.status == ExecutionProcessStatus::Failed
+                {
+                    container.queue_verification_follow_up(&ctx).await;
+                }
+
+                if container.should_finalize(&ctx) {
+                    let verification_retry = container
+                        .verification_follow_ups
+                        .write()
+                        .await
+                        .remove(&ctx.session.id);
+
+                    // Only execute queued messages if the execution succeeded or verification
+                    // queued a retry. If it was killed, just clear the queue and finalize
+                    let should_execute_queued = match ctx.execution_process.status {
+                        ExecutionProcessStatus::Killed => false,
+                        ExecutionProcessStatus::Failed => verification_retry.is_some(),
+                        _ => true,
+                    };
 
                     if let Some(queued_msg) =
                         container.queued_message_service.take_queued(ctx.session.id)
@@ -517,13 +576,15 @@ impl LocalContainerService {
                                 ctx.session.id
                             );
 
-                            // Delete the scratch since we're consuming the queued message
-                            if let Err(e) = Scratch::delete(
-                                &db.pool,
-                                ctx.session.id,
-                                &ScratchType::DraftFollowUp,
-                            )
-                            .await
+                            // Delete the scratch since we're consuming the queued message,
+                            // unless the message was queued by verification alone
+                            if verification_retry.is_none_or(|user_queued| user_queued)
+                                && let Err(e) = Scratch::delete(
+                                    &db.pool,
+                                    ctx.session.id,
+                                    &ScratchType::DraftFollowUp,
+                                )
+                                .await
                             {
                                 tracing::warn!(
                                     "Failed to delete scratch after consuming queued message: {}",
@@ -533,7 +594,12 @@ impl LocalContainerService {
 
                             // Execute the queued follow-up
                             if let Err(e) = container
-                                .start_queued_follow_up(&ctx, &queued_msg.data)
+                                .start_follow_up_with_variant(
+                                    &ctx.workspace,
+                                    &ctx.session,
+                                    &queued_msg.data.message,
+                                    queued_msg.data.variant.clone(),
+                                )
                                 .await
                             {
                                 tracing::error!("Failed to start queued follow-up: {}", e);
@@ -633,6 +699,91 @@ impl LocalContainerService {
         rx
     }
 
+    /// Watch a coding agent run and stop it once it goes over its budget
+    pub fn spawn_budget_monitor(&self, exec_id: Uuid, budget: RunBudget) -> JoinHandle<()> {
+        let container = self.clone();
+        tokio::spawn(async move {
+            let Some(store) = container.get_msg_store_by_id(&exec_id).await else {
+                return;
+            };
+            let mut stream = store.history_plus_stream();
+            let mut tracker = BudgetTracker::new(budget);
+
+            let deadline = tracker.budget().budget_max_minutes.map(|minutes| {
+                tokio::time::Instant::now() + Duration::from_secs(u64::from(minutes) * 60)
+            });
+            let mut deadline_future = match deadline {
+                Some(deadline) => tokio::time::sleep_until(deadline).boxed(),
+                None => std::future::pending().boxed(),
+            };
+
+            let reason = loop {
+                tokio::select! {
+                    _ = &mut deadline_future => {
+                        break ExecutionProcessStopReason::TimeBudgetExceeded;
+                    }
+                    msg = stream.next() => {
+                        match msg {
+                            Some(Ok(LogMsg::Finished)) | None => return,
+                            Some(Ok(msg)) => {
+                                tracker.record(&msg);
+                                if let Some(reason) = tracker.exceeded() {
+                                    break reason;
+                                }
+                            }
+                            Some(Err(_)) => {}
+                        }
+                    }
+                }
+            };
+
+            let process = match ExecutionProcess::find_by_id(&container.db.pool, exec_id).await {
+                Ok(Some(process)) if process.status == ExecutionProcessStatus::Running => process,
+                Ok(_) => return,
+                Err(e) => {
+                    tracing::error!("Failed to load execution process {exec_id}: {e}");
+                    return;
+                }
+            };
+
+            tracing::info!(
+                "Stopping execution process {exec_id}: {reason:?} (tokens: {}, tool calls: {})",
+                tracker.total_tokens(),
+                tracker.tool_calls()
+            );
+            if let Err(e) = container.stop_execution_with_reason(&process, reason).await {
+                tracing::error!("Failed to stop execution process {exec_id} over budget: {e}");
+            }
+        })
+    }
+
+    /// Report operations the sandbox refused as errors in the conversation. Both output streams
+    /// are scanned; the messages go to stderr so the agent's stderr normalizer turns them into
+    /// `ErrorMessage` entries.
+    pub fn spawn_sandbox_monitor(&self, exec_id: Uuid, policy: SandboxPolicy) -> JoinHandle<()> {
+        let container = self.clone();
+        tokio::spawn(async move {
+            let Some(store) = container.get_msg_store_by_id(&exec_id).await else {
+                return;
+            };
+            let mut stream = store.history_plus_stream();
+            let mut reported = HashSet::new();
+            while let Some(msg) = stream.next().await {
+                match msg {
+                    Ok(LogMsg::Stdout(chunk) | LogMsg::Stderr(chunk)) => {
+                        for message in policy.violation_messages(&chunk) {
+                            if reported.insert(message.clone()) {
+                                store.push_stderr(format!("{message}\n"));
+                            }
+                        }
+                    }
+                    Ok(LogMsg::Finished) => break,
+                    _ => {}
+                }
+            }
+        })
+    }
+
     pub fn dir_name_from_workspace(workspace_id: &Uuid, task_title: &str) -> String {
         let task_title_id = git_branch_id(task_title);
         format!("{}-{}", short_uuid(workspace_id), task_title_id)
@@ -820,82 +971,96 @@ impl LocalContainerService {
         Ok(())
     }
 
-    /// Start a follow-up execution from a queued message
-    async fn start_queued_follow_up(
-        &self,
-        ctx: &ExecutionContext,
-        queued_data: &DraftFollowUpData,
-    ) -> Result<ExecutionProcess, ContainerError> {
-        // Get executor from the latest CodingAgent process, or fall back to session's executor
-        let base_executor = match ExecutionProcess::latest_executor_profile_for_session(
+    /// Queue a follow-up with the output of a failed verify script so the agent can fix what
+    /// it reported, unless the repo's retries are used up. Failures of several repos in one turn
+    /// are combined into one follow-up.
+    async fn queue_verification_follow_up(&self, ctx: &ExecutionContext) {
+        let Ok(action) = ctx.execution_process.executor_action() else {
+            return;
+        };
+        let ExecutorActionType::ScriptRequest(request) = action.typ() else {
+            return;
+        };
+        let Some(repo) = ctx
+            .repos
+            .iter()
+            .find(|repo| request.working_dir.as_deref() == Some(repo.name.as_str()))
+        else {
+            return;
+        };
+
+        let processes = match ExecutionProcess::find_by_session_id(
             &self.db.pool,
             ctx.session.id,
+            false,
         )
         .await
-        .map_err(|e| ContainerError::Other(anyhow!("Failed to get executor profile: {e}")))?
         {
-            Some(profile) => profile.executor,
-            None => {
-                // No prior execution - use session's executor field
-                let executor_str = ctx.session.executor.as_ref().ok_or_else(|| {
-                    ContainerError::Other(anyhow!(
-                        "No prior execution and no executor configured on session"
-                    ))
-                })?;
-                BaseCodingAgent::from_str(&executor_str.replace('-', "_").to_ascii_uppercase())
-                    .map_err(|_| {
-                        ContainerError::Other(anyhow!("Invalid executor: {}", executor_str))
-                    })?
+            Ok(processes) => processes,
+            Err(e) => {
+                tracing::error!("Failed to load processes for verification retry: {}", e);
+                return;
             }
         };
+        let attempt = verification::failed_attempts(&processes);
+        if attempt > repo.verify_max_retries {
+            tracing::info!(
+                "Verification of workspace {} still failing after {} retries, giving up",
+                ctx.workspace.id,
+                repo.verify_max_retries
+            );
+            return;
+        }
 
-        let executor_profile_id = ExecutorProfileId {
-            executor: base_executor,
-            variant: queued_data.variant.clone(),
-        };
-
-        // Get latest agent session ID for session continuity (from coding agent turns)
-        let latest_agent_session_id = ExecutionProcess::find_latest_coding_agent_turn_session_id(
-            &self.db.pool,
-            ctx.session.id,
-        )
-        .await?;
-
-        let repos =
-            WorkspaceRepo::find_repos_for_workspace(&self.db.pool, ctx.workspace.id).await?;
-        let cleanup_action = self.cleanup_actions_for_repos(&repos);
-
-        let working_dir = ctx
-            .workspace
-            .agent_working_dir
-            .as_ref()
-            .filter(|dir| !dir.is_empty())
-            .cloned();
-
-        let action_type = if let Some(agent_session_id) = latest_agent_session_id {
-            ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
-                prompt: queued_data.message.clone(),
-                session_id: agent_session_id,
-                executor_profile_id: executor_profile_id.clone(),
-                working_dir: working_dir.clone(),
-            })
-        } else {
-            ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
-                prompt: queued_data.message.clone(),
-                executor_profile_id: executor_profile_id.clone(),
-                working_dir,
+        let output = self
+            .get_msg_store_by_id(&ctx.execution_process.id)
+            .await
+            .map(|store| {
+                store
+                    .get_history()
+                    .into_iter()
+                    .filter_map(|msg| match msg {
+                        LogMsg::Stdout(chunk) | LogMsg::Stderr(chunk) => Some(chunk),
+                        _ => None,
+                    })
+                    .collect::<String>()
             })
-        };
+            .unwrap_or_default();
 
-        let action = ExecutorAction::new(action_type, cleanup_action.map(Box::new));
+        let mut message = verification::follow_up_prompt(
+            &repo.name,
+            &request.script,
+            &verification::trim_output(&output),
+            attempt,
+            repo.verify_max_retries,
+        );
+        let mut variant =
+            ExecutionProcess::latest_executor_profile_for_session(&self.db.pool, ctx.session.id)
+                .await
+                .ok()
+                .flatten()
+                .and_then(|profile| profile.variant);
+        // Keep a message the user queued while the checks were running, and the failures of
+        // other repos in this turn
+        if let Some(queued) = self.queued_message_service.get_queued(ctx.session.id) {
+            message = format!("{message}\n\n{}", queued.data.message);
+            variant = queued.data.variant;
+        }
 
-        self.start_execution(
-            &ctx.workspace,
-            &ctx.session,
-            &action,
-            &ExecutionProcessRunReason::CodingAgent,
-        )
-        .await
+        tracing::info!(
+            "Verification failed for workspace {}, queuing follow-up (attempt {} of {})",
+            ctx.workspace.id,
+            attempt,
+            repo.verify_max_retries
+        );
+        let mut follow_ups = self.verification_follow_ups.write().await;
+        let user_queued = match follow_ups.get(&ctx.session.id) {
+            Some(user_queued) => *user_queued,
+            None => self.queued_message_service.has_queued(ctx.session.id),
+        };
+        follow_ups.insert(ctx.session.id, user_queued);
+        self.queued_message_service
+            .queue_message(ctx.session.id, DraftFollowUpData { message, variant });
     }
 }
 
@@ -1094,23 +1259,6 @@ impl ContainerService for LocalContainerService {
             )))?;
         let current_dir = PathBuf::from(container_ref);
 
-        let approvals_service: Arc<dyn ExecutorApprovalService> =
-            match executor_action.base_executor() {
-                Some(
-                    BaseCodingAgent::Codex
-                    | BaseCodingAgent::ClaudeCode
-                    | BaseCodingAgent::Gemini
-                    | BaseCodingAgent::QwenCode
-                    | BaseCodingAgent::Opencode,
-                ) => ExecutorApprovalBridge::new(
-                    self.approvals.clone(),
-                    self.db.clone(),
-                    self.notification_service.clone(),
-                    execution_process.id,
-                ),
-                _ => Arc::new(NoopExecutorApprovalService {}),
-            };
-
         let repos = WorkspaceRepo::find_repos_for_workspace(&self.db.pool, workspace.id).await?;
         let repo_names: Vec<String> = repos.iter().map(|r| r.name.clone()).collect();
         let repo_context = RepoContext::new(current_dir.clone(), repo_names);
@@ -1118,6 +1266,44 @@ impl ContainerService for LocalContainerService {
         let commit_reminder = self.config.read().await.commit_reminder;
         let mut env = ExecutionEnv::new(repo_context, commit_reminder);
 
+        // Confine coding agents when the sandbox is enabled; scripts run unconfined
+        let sandbox_config = self.config.read().await.sandbox.clone();
+        let mut sandbox_policy = None;
+        if sandbox_config.enabled && executor_action.base_executor().is_some() {
+            if sandbox_config.network == SandboxNetworkAccess::Blocked {
+                return Err(ContainerError::Other(anyhow!(
+                    SandboxError::NetworkRequired
+                )));
+            }
+            // Worktree commits write to the main repository's git directory
+            let mut git_paths = Vec::new();
+            for repo in &repos {
+                git_paths.extend(sandbox::git_writable_paths(&current_dir.join(&repo.name)).await);
+            }
+            // Agents reach this app's MCP server through its HTTP port
+            let mut allowed_ports = sandbox_config.allowed_ports.clone();
+            if let Ok(port) = read_port_file("vibe-kanban").await
+                && !allowed_ports.contains(&port)
+            {
+                allowed_ports.push(port);
+            }
+            let sandbox = SandboxPolicy::new(
+                &current_dir,
+                git_paths.into_iter().chain(
+                    sandbox_config
+                        .writable_paths
+                        .iter()
+                        .map(|path| expand_tilde(path)),
+                ),
+            )
+            .with_network(sandbox_config.network, allowed_ports);
+            sandbox
+                .check_supported()
+                .map_err(|e| ContainerError::Other(anyhow!(e)))?;
+            env = env.with_sandbox(sandbox.clone());
+            sandbox_policy = Some(sandbox);
+        }
+
         // Load task and project context for environment variables
         let task = workspace
             .parent_task(&self.db.pool)
@@ -1130,6 +1316,39 @@ impl ContainerService for LocalContainerService {
             .await?
             .ok_or(ContainerError::Other(anyhow!("Project not found for task")))?;
 
+        let approvals_service: Arc<dyn ExecutorApprovalService> =
+            match executor_action.base_executor() {
+                Some(
+                    BaseCodingAgent::Codex
+                    | BaseCodingAgent::ClaudeCode
+                    | BaseCodingAgent::Gemini
+                    | BaseCodingAgent::QwenCode
+                    | BaseCodingAgent::Opencode,
+                ) => {
+                    let mut approval_policy = executor_action
+                        .executor_profile_id()
+                        .and_then(|id| ExecutorConfigs::get_cached().get_coding_agent(id))
+                        .map(|agent| agent.approval_policy())
+                        .unwrap_or_default();
+                    // Project rules take precedence over the profile variant's
+                    if let Some(project_policy) =
+                        ProjectApprovalPolicy::find_by_project_id(&self.db.pool, project.id).await?
+                    {
+                        approval_policy
+                            .approval_rules
+                            .splice(0..0, project_policy.rules.0);
+                    }
+                    ExecutorApprovalBridge::new(
+                        self.approvals.clone(),
+                        self.db.clone(),
+                        self.notification_service.clone(),
+                        execution_process.id,
+                        approval_policy,
+                    )
+                }
+                _ => Arc::new(NoopExecutorApprovalService {}),
+            };
+
         env.insert("VK_PROJECT_NAME", &project.name);
         env.insert("VK_PROJECT_ID", project.id.to_string());
         env.insert("VK_TASK_ID", task.id.to_string());
@@ -1163,6 +1382,26 @@ impl ContainerService for LocalContainerService {
         // Spawn unified exit monitor: watches OS exit and optional executor signal
         let _hn = self.spawn_exit_monitor(&execution_process.id, spawned.exit_signal);
 
+        if let Some(policy) = sandbox_policy {
+            self.spawn_sandbox_monitor(execution_process.id, policy);
+        }
+
+        // Enforce run budgets for coding agents; task limits override the profile variant's
+        if let Some(executor_profile_id) = executor_action.executor_profile_id() {
+            let profile_budget = ExecutorConfigs::get_cached()
+                .get_coding_agent(executor_profile_id)
+                .map(|agent| agent.budget())
+                .unwrap_or_default();
+            let task_budget = TaskBudget::find_by_task_id(&self.db.pool, task.id)
+                .await?
+                .map(|budget| budget.run_budget())
+                .unwrap_or_default();
+            let budget = profile_budget.with_overrides(&task_budget);
+            if !budget.is_unlimited() {
+                self.spawn_budget_monitor(execution_process.id, budget);
+            }
+        }
+
         Ok(())
     }
 
@@ -21,6 +21,7 @@ use services::services::{
     remote_client::{RemoteClient, RemoteClientError},
     repo::RepoService,
     share::{ShareConfig, SharePublisher},
+    task_dependencies::TaskDependencyService,
 };
 use tokio::sync::RwLock;
 use utils::{
@@ -187,6 +188,7 @@ impl Deployment for LocalDeployment {
         )
         .await;
 
+        TaskDependencyService::spawn(container.clone(), config.clone(), events_msg_store.clone());
         let events = EventService::new(db.clone(), events_msg_store, events_entry_count);
 
         let file_search_cache = Arc::new(FileSearchCache::new());
@@ -235,7 +237,7 @@ impl Deployment for LocalDeployment {
         &self.analytics
     }
 
-    fn container(&self) -> &impl ContainerService {
+    fn container(&self) -> &(impl ContainerService + Clone + Send + Sync + 'static) {
         &self.container
     }
 
@@ -27,6 +27,8 @@ fn generate_types_content() -> String {
         db::models::workspace_repo::WorkspaceRepo::decl(),
         db::models::workspace_repo::CreateWorkspaceRepo::decl(),
         db::models::workspace_repo::RepoWithTargetBranch::decl(),
+        db::models::workspace_group::WorkspaceGroup::decl(),
+        db::models::workspace_group::WorkspaceGroupMember::decl(),
         db::models::tag::Tag::decl(),
         db::models::tag::CreateTag::decl(),
         db::models::tag::UpdateTag::decl(),
@@ -36,6 +38,24 @@ fn generate_types_content() -> String {
         db::models::task::TaskRelationships::decl(),
         db::models::task::CreateTask::decl(),
         db::models::task::UpdateTask::decl(),
+        db::models::task_dependency::TaskDependency::decl(),
+        db::models::task_dependency::CreateTaskDependency::decl(),
+        db::models::task_dependency::TaskDependencies::decl(),
+        db::models::task_budget::TaskBudget::decl(),
+        db::models::task_issue_link::TaskIssueLink::decl(),
+        executors::budget::RunBudget::decl(),
+        db::models::project_approval_policy::ProjectApprovalPolicy::decl(),
+        db::models::project_pr_review_setting::ProjectPrReviewSetting::decl(),
+        db::models::project_pr_review_setting::UpdateProjectPrReviewSetting::decl(),
+        db::models::approval_timeout::ApprovalTimeoutResolution::decl(),
+        db::models::approval_timeout::ApprovalTimeout::decl(),
+        executors::approval_policy::ApprovalRuleDecision::decl(),
+        executors::approval_policy::ApprovalRule::decl(),
+        executors::approval_policy::ApprovalTimeoutOutcome::decl(),
+        executors::approval_policy::ApprovalPolicy::decl(),
+        db::models::task_schedule::TaskSchedule::decl(),
+        db::models::task_schedule::CreateTaskSchedule::decl(),
+        db::models::task_schedule::UpdateTaskSchedule::decl(),
         db::models::scratch::DraftFollowUpData::decl(),
         db::models::scratch::DraftWorkspaceData::decl(),
         db::models::scratch::DraftWorkspaceRepo::decl(),
@@ -49,16 +69,32 @@ fn generate_types_content() -> String {
         db::models::image::CreateImage::decl(),
         db::models::workspace::Workspace::decl(),
         db::models::workspace::WorkspaceWithStatus::decl(),
+        db::models::workspace::WorkspaceVerificationStatus::decl(),
         db::models::session::Session::decl(),
         db::models::execution_process::ExecutionProcess::decl(),
         db::models::execution_process::ExecutionProcessStatus::decl(),
         db::models::execution_process::ExecutionProcessRunReason::decl(),
+        db::models::execution_process::ExecutionProcessStopReason::decl(),
         db::models::execution_process_repo_state::ExecutionProcessRepoState::decl(),
+        db::models::execution_process_usage::ExecutionProcessUsage::decl(),
+        db::models::execution_process_usage::UsageGroupBy::decl(),
+        db::models::execution_process_usage::UsageSummary::decl(),
+        db::models::conversation_entry::ConversationEntryType::decl(),
+        db::models::conversation_entry::ConversationSearchHit::decl(),
         db::models::merge::Merge::decl(),
         db::models::merge::DirectMerge::decl(),
         db::models::merge::PrMerge::decl(),
         db::models::merge::MergeStatus::decl(),
         db::models::merge::PullRequestInfo::decl(),
+        db::models::merge::PrCheckStatus::decl(),
+        db::models::merge::PrCheck::decl(),
+        db::models::merge_queue::MergeQueueStrategy::decl(),
+        db::models::merge_queue::MergeQueueStatus::decl(),
+        db::models::merge_queue::MergeQueueEntry::decl(),
+        db::models::review_comment::ReviewCommentStatus::decl(),
+        db::models::review_comment::ReviewComment::decl(),
+        db::models::pr_review_comment::PrReviewCommentStatus::decl(),
+        db::models::pr_review_comment::PrReviewComment::decl(),
         utils::approvals::ApprovalStatus::decl(),
         utils::approvals::CreateApprovalRequest::decl(),
         utils::approvals::ApprovalResponse::decl(),
@@ -122,6 +158,9 @@ fn generate_types_content() -> String {
         server::routes::shared_tasks::AssignSharedTaskRequest::decl(),
         server::routes::tasks::ShareTaskResponse::decl(),
         server::routes::tasks::CreateAndStartTaskRequest::decl(),
+        server::routes::tasks::ImportTasksSource::decl(),
+        server::routes::tasks::ImportTasksRequest::decl(),
+        server::routes::tasks::ImportTasksResponse::decl(),
         server::routes::task_attempts::pr::CreatePrApiRequest::decl(),
         server::routes::images::ImageResponse::decl(),
         server::routes::images::ImageMetadata::decl(),
@@ -132,7 +171,20 @@ fn generate_types_content() -> String {
         server::routes::task_attempts::gh_cli_setup::GhCliSetupError::decl(),
         server::routes::task_attempts::RebaseTaskAttemptRequest::decl(),
         server::routes::task_attempts::AbortConflictsRequest::decl(),
+        server::routes::task_attempts::conflicts::ConflictsQuery::decl(),
+        server::routes::task_attempts::conflicts::WorkspaceConflicts::decl(),
+        server::routes::task_attempts::conflicts::ResolveConflictRequest::decl(),
+        server::routes::task_attempts::conflicts::ContinueConflictsRequest::decl(),
+        server::routes::task_attempts::selection::ChangeHunksQuery::decl(),
+        server::routes::task_attempts::selection::SelectionDestination::decl(),
+        server::routes::task_attempts::selection::ApplySelectionRequest::decl(),
+        server::routes::task_attempts::selection::AppliedSelection::decl(),
+        server::routes::task_attempts::selection::DiscardSelectionRequest::decl(),
+        server::routes::task_attempts::review_comments::CreateReviewCommentRequest::decl(),
+        server::routes::task_attempts::review_comments::UpdateReviewCommentRequest::decl(),
         server::routes::task_attempts::GitOperationError::decl(),
+        server::routes::merge_queue::MergeQueueQuery::decl(),
+        server::routes::merge_queue::EnqueueMergeRequest::decl(),
         server::routes::task_attempts::PushError::decl(),
         server::routes::task_attempts::pr::PrError::decl(),
         server::routes::task_attempts::BranchStatus::decl(),
@@ -150,11 +202,20 @@ fn generate_types_content() -> String {
         server::routes::task_attempts::workspace_summary::WorkspaceSummary::decl(),
         server::routes::task_attempts::workspace_summary::WorkspaceSummaryResponse::decl(),
         server::routes::task_attempts::workspace_summary::DiffStats::decl(),
+        server::routes::workspace_groups::WorkspaceGroupAttempt::decl(),
+        server::routes::workspace_groups::WorkspaceGroupComparison::decl(),
+        server::routes::workspace_groups::PickWorkspaceGroupWinner::decl(),
         services::services::filesystem::DirectoryEntry::decl(),
         services::services::filesystem::DirectoryListResponse::decl(),
         services::services::file_search::SearchMode::decl(),
         services::services::config::Config::decl(),
         services::services::config::NotificationConfig::decl(),
+        services::services::config::WebhookConfig::decl(),
+        services::services::config::WebhookFormat::decl(),
+        services::services::config::NotificationEvent::decl(),
+        services::services::config::SandboxConfig::decl(),
+        services::services::config::AutoRebaseConfig::decl(),
+        executors::sandbox::SandboxNetworkAccess::decl(),
         services::services::config::ThemeMode::decl(),
         services::services::config::EditorConfig::decl(),
         services::services::config::EditorType::decl(),
@@ -164,10 +225,21 @@ fn generate_types_content() -> String {
         services::services::config::UiLanguage::decl(),
         services::services::config::ShowcaseState::decl(),
         services::services::git::GitBranch::decl(),
+        services::services::session_export::SessionExportFormat::decl(),
+        services::services::session_export::SessionTranscript::decl(),
+        services::services::session_export::ProcessTranscript::decl(),
+        services::services::session_export::RepoDiffTranscript::decl(),
+        services::services::session_export::FileDiffTranscript::decl(),
+        services::services::project_bundle::ProjectBundleSummary::decl(),
+        services::services::project_bundle::ProjectBundleRepoSummary::decl(),
         services::services::share::SharedTaskDetails::decl(),
         services::services::queued_message::QueuedMessage::decl(),
         services::services::queued_message::QueueStatus::decl(),
         services::services::git::ConflictOp::decl(),
+        services::services::git::ConflictFile::decl(),
+        services::services::git::ConflictResolution::decl(),
+        services::services::change_selection::FileSelection::decl(),
+        services::services::change_selection::FileHunks::decl(),
         executors::actions::ExecutorAction::decl(),
         executors::mcp_config::McpConfig::decl(),
         executors::actions::ExecutorActionType::decl(),
@@ -205,6 +277,7 @@ fn generate_types_content() -> String {
         executors::actions::review::RepoReviewContext::decl(),
         executors::logs::CommandExitStatus::decl(),
         executors::logs::CommandRunResult::decl(),
+        executors::logs::NormalizedConversation::decl(),
         executors::logs::NormalizedEntry::decl(),
         executors::logs::NormalizedEntryType::decl(),
         executors::logs::TokenUsageInfo::decl(),
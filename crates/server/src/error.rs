@@ -6,7 +6,8 @@ use axum::{
 };
 use db::models::{
     execution_process::ExecutionProcessError, project::ProjectError,
-    project_repo::ProjectRepoError, repo::RepoError, scratch::ScratchError, session::SessionError,
+    project_bundle::ProjectBundleError, project_repo::ProjectRepoError, repo::RepoError,
+    scratch::ScratchError, session::SessionError, task_dependency::TaskDependencyError,
     workspace::WorkspaceError,
 };
 use deployment::{DeploymentError, RemoteClientNotConfigured};
@@ -14,15 +15,19 @@ use executors::{command::CommandBuildError, executors::ExecutorError};
 use git2::Error as Git2Error;
 use local_deployment::pty::PtyError;
 use services::services::{
+    change_selection::ChangeSelectionError,
     config::{ConfigError, EditorOpenError},
     container::ContainerError,
     git::GitServiceError,
     git_host::GitHostError,
     image::ImageError,
     project::ProjectServiceError,
+    project_bundle::ProjectBundleServiceError,
     remote_client::RemoteClientError,
     repo::RepoError as RepoServiceError,
     share::ShareError,
+    task_import::TaskImportError,
+    task_scheduler::ScheduleError,
     worktree_manager::WorktreeError,
 };
 use thiserror::Error;
@@ -44,6 +49,10 @@ pub enum ApiError {
     #[error(transparent)]
     ExecutionProcess(#[from] ExecutionProcessError),
     #[error(transparent)]
+    TaskDependency(#[from] TaskDependencyError),
+    #[error(transparent)]
+    Schedule(#[from] ScheduleError),
+    #[error(transparent)]
     GitService(#[from] GitServiceError),
     #[error(transparent)]
     GitHost(#[from] GitHostError),
@@ -115,6 +124,19 @@ impl IntoResponse for ApiError {
                 }
                 _ => (StatusCode::INTERNAL_SERVER_ERROR, "ExecutionProcessError"),
             },
+            ApiError::TaskDependency(err) => match err {
+                TaskDependencyError::Database(_) => {
+                    (StatusCode::INTERNAL_SERVER_ERROR, "TaskDependencyError")
+                }
+                TaskDependencyError::SelfDependency | TaskDependencyError::ProjectMismatch => {
+                    (StatusCode::BAD_REQUEST, "TaskDependencyError")
+                }
+                TaskDependencyError::AlreadyExists | TaskDependencyError::CycleDetected => {
+                    (StatusCode::CONFLICT, "TaskDependencyError")
+                }
+                TaskDependencyError::NotFound => (StatusCode::NOT_FOUND, "TaskDependencyError"),
+            },
+            ApiError::Schedule(_) => (StatusCode::BAD_REQUEST, "ScheduleError"),
             // Promote certain GitService errors to conflict status with concise messages
             ApiError::GitService(git_err) => match git_err {
                 services::services::git::GitServiceError::MergeConflicts(_) => {
@@ -271,6 +293,40 @@ impl IntoResponse for ApiError {
     }
 }
 
+impl From<ChangeSelectionError> for ApiError {
+    fn from(err: ChangeSelectionError) -> Self {
+        match err {
+            ChangeSelectionError::GitService(err) => ApiError::GitService(err),
+            ChangeSelectionError::Io(err) => ApiError::Io(err),
+            err => ApiError::BadRequest(err.to_string()),
+        }
+    }
+}
+
+impl From<TaskImportError> for ApiError {
+    fn from(err: TaskImportError) -> Self {
+        match err {
+            TaskImportError::GitService(err) => ApiError::GitService(err),
+            TaskImportError::GitHost(err) => ApiError::GitHost(err),
+            TaskImportError::Sqlx(err) => ApiError::Database(err),
+            err => ApiError::BadRequest(err.to_string()),
+        }
+    }
+}
+
+impl From<ProjectBundleServiceError> for ApiError {
+    fn from(err: ProjectBundleServiceError) -> Self {
+        match err {
+            ProjectBundleServiceError::Bundle(ProjectBundleError::Database(err)) => {
+                ApiError::Database(err)
+            }
+            ProjectBundleServiceError::Container(err) => ApiError::Container(err),
+            ProjectBundleServiceError::Io(err) => ApiError::Io(err),
+            err => ApiError::BadRequest(err.to_string()),
+        }
+    }
+}
+
 impl From<ShareError> for ApiError {
     fn from(err: ShareError) -> Self {
         match err {
@@ -1,4 +1,10 @@
+use std::path::PathBuf;
+
 use anyhow::{self, Error as AnyhowError};
+use db::{
+    backup::{self, BackupError},
+    database_path,
+};
 use deployment::{Deployment, DeploymentError};
 use server::{DeploymentImpl, routes};
 use services::services::container::ContainerService;
@@ -9,7 +15,7 @@ use tracing_subscriber::{EnvFilter, prelude::*};
 use utils::{
     assets::asset_dir,
     browser::open_browser,
-    port_file::write_port_file,
+    port_file::{read_port_file, write_port_file},
     sentry::{self as sentry_utils, SentrySource, sentry_layer},
 };
 
@@ -22,6 +28,8 @@ pub enum VibeKanbanError {
     #[error(transparent)]
     Deployment(#[from] DeploymentError),
     #[error(transparent)]
+    Backup(#[from] BackupError),
+    #[error(transparent)]
     Other(#[from] AnyhowError),
 }
 
@@ -50,6 +58,11 @@ async fn main() -> Result<(), VibeKanbanError> {
         std::fs::create_dir_all(asset_dir())?;
     }
 
+    let mut args = std::env::args().skip(1);
+    if args.next().as_deref() == Some("restore-db") {
+        return restore_database(args.next()).await;
+    }
+
     let deployment = DeploymentImpl::new().await?;
     deployment.update_sentry_scope().await?;
     deployment
@@ -68,6 +81,7 @@ async fn main() -> Result<(), VibeKanbanError> {
         .await
         .map_err(DeploymentError::from)?;
     deployment.spawn_pr_monitor_service().await;
+    deployment.spawn_db_backup_service().await;
     deployment
         .track_if_analytics_allowed("session_start", serde_json::json!({}))
         .await;
@@ -93,6 +107,18 @@ async fn main() -> Result<(), VibeKanbanError> {
         }
     });
 
+    // Index conversations that predate conversation search in background
+    let deployment_for_search = deployment.clone();
+    tokio::spawn(async move {
+        if let Err(e) = deployment_for_search
+            .container()
+            .backfill_conversation_search()
+            .await
+        {
+            tracing::warn!("Failed to backfill conversation search: {}", e);
+        }
+    });
+
     let app_router = routes::router(deployment.clone());
 
     let port = std::env::var("BACKEND_PORT")
@@ -142,6 +168,65 @@ async fn main() -> Result<(), VibeKanbanError> {
     Ok(())
 }
 
+/// `restore-db` lists the database backups; `restore-db <backup>` replaces the database with one,
+/// given as a path or as a file name in the backup directory
+async fn restore_database(backup: Option<String>) -> Result<(), VibeKanbanError> {
+    let dir = backup::backup_dir();
+    let Some(backup) = backup else {
+        let backups = backup::list_backups(&dir)?;
+        if backups.is_empty() {
+            println!("No backups in {}", dir.display());
+            return Ok(());
+        }
+        println!("Backups in {}, newest first:", dir.display());
+        for backup in backups {
+            println!(
+                "  {}  {} ({} KiB)",
+                backup
+                    .path
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy(),
+                backup.kind,
+                backup.size / 1024
+            );
+        }
+        println!("Restore one with: vibe-kanban restore-db <backup>");
+        return Ok(());
+    };
+
+    if let Ok(port) = read_port_file("vibe-kanban").await
+        && tokio::net::TcpStream::connect(("127.0.0.1", port))
+            .await
+            .is_ok()
+    {
+        return Err(anyhow::anyhow!(
+            "vibe-kanban is running on port {port}, stop it before restoring a backup"
+        )
+        .into());
+    }
+
+    let path = PathBuf::from(&backup);
+    let path = if path.exists() {
+        path
+    } else {
+        dir.join(backup)
+    };
+    let info = backup::restore_backup(&path, &database_path(), &dir).await?;
+    println!(
+        "Restored {}; the replaced database was kept in {}",
+        path.display(),
+        dir.display()
+    );
+    if info.pending_migrations > 0 {
+        println!(
+            "{} migrations will run the next time vibe-kanban starts",
+            info.pending_migrations
+        );
+    }
+    Ok(())
+}
+
 pub async fn shutdown_signal() {
     // Always wait for Ctrl+C
     let ctrl_c = async {
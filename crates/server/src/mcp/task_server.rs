@@ -1,10 +1,12 @@
 use std::{future::Future, str::FromStr};
 
 use db::models::{
+    conversation_entry::ConversationSearchHit,
     project::Project,
     repo::Repo,
     tag::Tag,
     task::{CreateTask, Task, TaskStatus, TaskWithAttemptStatus, UpdateTask},
+    task_dependency::{CreateTaskDependency, TaskDependencies, TaskDependency},
     workspace::{Workspace, WorkspaceContext},
 };
 use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
@@ -251,6 +253,88 @@ pub struct GetTaskResponse {
     pub task: TaskDetails,
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct AddTaskDependencyRequest {
+    #[schemars(description = "The ID of the task that should wait")]
+    pub task_id: Uuid,
+    #[schemars(description = "The ID of the task that must be done first")]
+    pub depends_on_task_id: Uuid,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct AddTaskDependencyResponse {
+    pub task_id: String,
+    pub depends_on_task_id: String,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct RemoveTaskDependencyRequest {
+    #[schemars(description = "The ID of the waiting task")]
+    pub task_id: Uuid,
+    #[schemars(description = "The ID of the task it no longer depends on")]
+    pub depends_on_task_id: Uuid,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct RemoveTaskDependencyResponse {
+    pub task_id: String,
+    pub removed_depends_on_task_id: String,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListTaskDependenciesRequest {
+    #[schemars(description = "The ID of the task to list dependencies for")]
+    pub task_id: Uuid,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ListTaskDependenciesResponse {
+    #[schemars(description = "Tasks that must be done before this task starts")]
+    pub blocked_by: Vec<TaskDetails>,
+    #[schemars(description = "Tasks waiting on this task")]
+    pub blocking: Vec<TaskDetails>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct SearchConversationsRequest {
+    #[schemars(
+        description = "Words to search for in past agent conversations: messages, file paths and commands. All words must match; end a word with `*` to match it as a prefix."
+    )]
+    pub query: String,
+    #[schemars(description = "Optional project ID to limit the search to")]
+    pub project_id: Option<Uuid>,
+    #[schemars(description = "Optional task ID to limit the search to")]
+    pub task_id: Option<Uuid>,
+    #[schemars(description = "Maximum number of matches to return (default: 20)")]
+    pub limit: Option<i32>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ConversationMatch {
+    #[schemars(description = "The ID of the task the conversation belongs to")]
+    pub task_id: String,
+    #[schemars(description = "The title of the task")]
+    pub task_title: String,
+    #[schemars(description = "The ID of the workspace (attempt) the conversation ran in")]
+    pub workspace_id: String,
+    #[schemars(description = "The branch of the workspace")]
+    pub workspace_branch: String,
+    #[schemars(
+        description = "The kind of entry: 'user_message', 'assistant_message' or 'tool_use'"
+    )]
+    pub entry_type: String,
+    #[schemars(description = "Excerpt of the entry with matched words wrapped in <mark> tags")]
+    pub snippet: String,
+    #[schemars(description = "When the entry was last written")]
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct SearchConversationsResponse {
+    pub matches: Vec<ConversationMatch>,
+    pub count: usize,
+}
+
 #[derive(Debug, Clone)]
 pub struct TaskServer {
     client: reqwest::Client,
@@ -732,6 +816,8 @@ impl TaskServer {
             task_id,
             executor_profile_id,
             repos: workspace_repos,
+            fan_out_executor_profile_ids: None,
+            base_workspace_id: None,
         };
 
         let url = self.url("/api/task-attempts");
@@ -834,12 +920,142 @@ impl TaskServer {
 
         TaskServer::success(&response)
     }
+
+    #[tool(
+        description = "Make a task wait for another task. When every task it depends on is done, the task is started automatically with the default coding agent. `task_id` and `depends_on_task_id` are required and must belong to the same project."
+    )]
+    async fn add_task_dependency(
+        &self,
+        Parameters(AddTaskDependencyRequest {
+            task_id,
+            depends_on_task_id,
+        }): Parameters<AddTaskDependencyRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = self.url(&format!("/api/tasks/{}/dependencies", task_id));
+        let payload = CreateTaskDependency { depends_on_task_id };
+        let dependency: TaskDependency =
+            match self.send_json(self.client.post(&url).json(&payload)).await {
+                Ok(d) => d,
+                Err(e) => return Ok(e),
+            };
+
+        TaskServer::success(&AddTaskDependencyResponse {
+            task_id: dependency.task_id.to_string(),
+            depends_on_task_id: dependency.depends_on_task_id.to_string(),
+        })
+    }
+
+    #[tool(
+        description = "Remove a dependency between two tasks. `task_id` and `depends_on_task_id` are required."
+    )]
+    async fn remove_task_dependency(
+        &self,
+        Parameters(RemoveTaskDependencyRequest {
+            task_id,
+            depends_on_task_id,
+        }): Parameters<RemoveTaskDependencyRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = self.url(&format!(
+            "/api/tasks/{}/dependencies/{}",
+            task_id, depends_on_task_id
+        ));
+        if let Err(e) = self.send_empty_json(self.client.delete(&url)).await {
+            return Ok(e);
+        }
+
+        TaskServer::success(&RemoveTaskDependencyResponse {
+            task_id: task_id.to_string(),
+            removed_depends_on_task_id: depends_on_task_id.to_string(),
+        })
+    }
+
+    #[tool(
+        description = "List the tasks a task is blocked by and the tasks it is blocking. `task_id` is required."
+    )]
+    async fn list_task_dependencies(
+        &self,
+        Parameters(ListTaskDependenciesRequest { task_id }): Parameters<
+            ListTaskDependenciesRequest,
+        >,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = self.url(&format!("/api/tasks/{}/dependencies", task_id));
+        let dependencies: TaskDependencies = match self.send_json(self.client.get(&url)).await {
+            Ok(d) => d,
+            Err(e) => return Ok(e),
+        };
+
+        let response = ListTaskDependenciesResponse {
+            blocked_by: dependencies
+                .blocked_by
+                .into_iter()
+                .map(TaskDetails::from_task)
+                .collect(),
+            blocking: dependencies
+                .blocking
+                .into_iter()
+                .map(TaskDetails::from_task)
+                .collect(),
+        };
+
+        TaskServer::success(&response)
+    }
+
+    #[tool(
+        description = "Search the conversation history of past agent runs, e.g. to find which task or workspace touched a file or ran a command. `query` is required; `project_id` and `task_id` narrow the search."
+    )]
+    async fn search_conversations(
+        &self,
+        Parameters(SearchConversationsRequest {
+            query,
+            project_id,
+            task_id,
+            limit,
+        }): Parameters<SearchConversationsRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let mut params = vec![
+            ("q", query),
+            ("limit", limit.unwrap_or(20).max(1).to_string()),
+        ];
+        if let Some(project_id) = project_id {
+            params.push(("project_id", project_id.to_string()));
+        }
+        if let Some(task_id) = task_id {
+            params.push(("task_id", task_id.to_string()));
+        }
+
+        let url = self.url("/api/search/conversations");
+        let hits: Vec<ConversationSearchHit> =
+            match self.send_json(self.client.get(&url).query(&params)).await {
+                Ok(h) => h,
+                Err(e) => return Ok(e),
+            };
+
+        let matches: Vec<ConversationMatch> = hits
+            .into_iter()
+            .map(|hit| ConversationMatch {
+                task_id: hit.task_id.to_string(),
+                task_title: hit.task_title,
+                workspace_id: hit.workspace_id.to_string(),
+                workspace_branch: hit.workspace_branch,
+                entry_type: hit.entry_type.to_string(),
+                snippet: hit.snippet,
+                updated_at: hit.updated_at.to_rfc3339(),
+            })
+            .collect();
+
+        let response = SearchConversationsResponse {
+            count: matches.len(),
+            matches,
+        };
+
+        TaskServer::success(&response)
+    }
 }
 
 #[tool_handler]
 impl ServerHandler for TaskServer {
     fn get_info(&self) -> ServerInfo {
-        let mut instruction = "A task and project management server. If you need to create or update tickets or tasks then use these tools. Most of them absolutely require that you pass the `project_id` of the project that you are currently working on. You can get project ids by using `list projects`. Call `list_tasks` to fetch the `task_ids` of all the tasks in a project`.. TOOLS: 'list_projects', 'list_tasks', 'create_task', 'start_workspace_session', 'get_task', 'update_task', 'delete_task', 'list_repos'. Make sure to pass `project_id` or `task_id` where required. You can use list tools to get the available ids.".to_string();
+        let mut instruction = "A task and project management server. If you need to create or update tickets or tasks then use these tools. Most of them absolutely require that you pass the `project_id` of the project that you are currently working on. You can get project ids by using `list projects`. Call `list_tasks` to fetch the `task_ids` of all the tasks in a project`.. TOOLS: 'list_projects', 'list_tasks', 'create_task', 'start_workspace_session', 'get_task', 'update_task', 'delete_task', 'list_repos', 'add_task_dependency', 'remove_task_dependency', 'list_task_dependencies', 'search_conversations'. Make sure to pass `project_id` or `task_id` where required. You can use list tools to get the available ids.".to_string();
         if self.context.is_some() {
             let context_instruction = "Use 'get_context' to fetch project/task/workspace metadata for the active Vibe Kanban workspace session when available.";
             instruction = format!("{} {}", context_instruction, instruction);
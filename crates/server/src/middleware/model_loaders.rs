@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use axum::{
     extract::{Path, Request, State},
     http::StatusCode,
@@ -6,7 +8,7 @@ use axum::{
 };
 use db::models::{
     execution_process::ExecutionProcess, project::Project, session::Session, tag::Tag, task::Task,
-    workspace::Workspace,
+    task_schedule::TaskSchedule, workspace::Workspace, workspace_group::WorkspaceGroup,
 };
 use deployment::Deployment;
 use uuid::Uuid;
@@ -42,10 +44,15 @@ pub async fn load_project_middleware(
 
 pub async fn load_task_middleware(
     State(deployment): State<DeploymentImpl>,
-    Path(task_id): Path<Uuid>,
+    // By name, as routes below a task may take ids of their own
+    Path(params): Path<HashMap<String, Uuid>>,
     request: Request,
     next: Next,
 ) -> Result<Response, StatusCode> {
+    let Some(&task_id) = params.get("task_id") else {
+        return Err(StatusCode::BAD_REQUEST);
+    };
+
     // Load the task and validate it belongs to the project
     let task = match Task::find_by_id(&deployment.db().pool, task_id).await {
         Ok(Some(task)) => task,
@@ -148,6 +155,28 @@ pub async fn load_tag_middleware(
     Ok(next.run(request).await)
 }
 
+pub async fn load_task_schedule_middleware(
+    State(deployment): State<DeploymentImpl>,
+    Path(schedule_id): Path<Uuid>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let schedule = match TaskSchedule::find_by_id(&deployment.db().pool, schedule_id).await {
+        Ok(Some(schedule)) => schedule,
+        Ok(None) => {
+            tracing::warn!("Task schedule {} not found", schedule_id);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch task schedule {}: {}", schedule_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    request.extensions_mut().insert(schedule);
+    Ok(next.run(request).await)
+}
+
 pub async fn load_session_middleware(
     State(deployment): State<DeploymentImpl>,
     Path(session_id): Path<Uuid>,
@@ -169,3 +198,25 @@ pub async fn load_session_middleware(
     request.extensions_mut().insert(session);
     Ok(next.run(request).await)
 }
+
+pub async fn load_workspace_group_middleware(
+    State(deployment): State<DeploymentImpl>,
+    Path(group_id): Path<Uuid>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let group = match WorkspaceGroup::find_by_id(&deployment.db().pool, group_id).await {
+        Ok(Some(group)) => group,
+        Ok(None) => {
+            tracing::warn!("Workspace group {} not found", group_id);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch workspace group {}: {}", group_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    request.extensions_mut().insert(group);
+    Ok(next.run(request).await)
+}
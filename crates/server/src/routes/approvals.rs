@@ -1,17 +1,28 @@
 use axum::{
     Router,
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     response::Json as ResponseJson,
-    routing::post,
+    routing::{get, post},
 };
+use db::models::approval_timeout::ApprovalTimeout;
 use deployment::Deployment;
+use serde::Deserialize;
 use utils::{
     approvals::{ApprovalResponse, ApprovalStatus},
     response::ApiResponse,
 };
+use uuid::Uuid;
 
-use crate::DeploymentImpl;
+use crate::{DeploymentImpl, error::ApiError};
+
+const DEFAULT_TIMED_OUT_LIMIT: i64 = 100;
+
+#[derive(Debug, Deserialize)]
+pub struct TimedOutApprovalsQuery {
+    pub execution_process_id: Option<Uuid>,
+    pub limit: Option<i64>,
+}
 
 pub async fn respond_to_approval(
     State(deployment): State<DeploymentImpl>,
@@ -43,6 +54,23 @@ pub async fn respond_to_approval(
     }
 }
 
+/// Approval requests nobody responded to in time, most recent first
+pub async fn get_timed_out_approvals(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<TimedOutApprovalsQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<ApprovalTimeout>>>, ApiError> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_TIMED_OUT_LIMIT)
+        .clamp(1, 1000);
+    let timeouts =
+        ApprovalTimeout::find_recent(&deployment.db().pool, query.execution_process_id, limit)
+            .await?;
+    Ok(ResponseJson(ApiResponse::success(timeouts)))
+}
+
 pub fn router() -> Router<DeploymentImpl> {
-    Router::new().route("/approvals/{id}/respond", post(respond_to_approval))
+    Router::new()
+        .route("/approvals/timed-out", get(get_timed_out_approvals))
+        .route("/approvals/{id}/respond", post(respond_to_approval))
 }
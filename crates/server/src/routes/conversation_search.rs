@@ -0,0 +1,53 @@
+use axum::{
+    Router,
+    extract::{Query, State},
+    response::Json as ResponseJson,
+    routing::get,
+};
+use chrono::{DateTime, Utc};
+use db::models::conversation_entry::{
+    ConversationEntry, ConversationSearchFilter, ConversationSearchHit,
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::conversation_search::fts_match_query;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 200;
+
+#[derive(Debug, Deserialize)]
+pub struct ConversationSearchQuery {
+    pub q: String,
+    pub project_id: Option<Uuid>,
+    pub task_id: Option<Uuid>,
+    pub since: Option<DateTime<Utc>>,
+    pub limit: Option<i64>,
+}
+
+pub async fn search_conversations(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ConversationSearchQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<ConversationSearchHit>>>, ApiError> {
+    let Some(match_query) = fts_match_query(&query.q) else {
+        return Err(ApiError::BadRequest(
+            "Search query must contain at least one word".to_string(),
+        ));
+    };
+
+    let filter = ConversationSearchFilter {
+        project_id: query.project_id,
+        task_id: query.task_id,
+        since: query.since,
+        limit: query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT),
+    };
+    let hits = ConversationEntry::search(&deployment.db().pool, &match_query, &filter).await?;
+    Ok(ResponseJson(ApiResponse::success(hits)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new().route("/search/conversations", get(search_conversations))
+}
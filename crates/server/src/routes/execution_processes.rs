@@ -1,22 +1,25 @@
 use anyhow;
 use axum::{
     Extension, Router,
+    body::Body,
     extract::{
         Path, Query, State,
         ws::{WebSocket, WebSocketUpgrade},
     },
+    http::{StatusCode, header},
     middleware::from_fn_with_state,
-    response::{IntoResponse, Json as ResponseJson},
+    response::{IntoResponse, Json as ResponseJson, Response},
     routing::{get, post},
 };
 use db::models::{
     execution_process::{ExecutionProcess, ExecutionProcessError, ExecutionProcessStatus},
     execution_process_repo_state::ExecutionProcessRepoState,
+    execution_process_usage::ExecutionProcessUsage,
 };
 use deployment::Deployment;
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
 use serde::Deserialize;
-use services::services::container::ContainerService;
+use services::services::{container::ContainerService, session_export};
 use utils::{log_msg::LogMsg, response::ApiResponse};
 use uuid::Uuid;
 
@@ -243,11 +246,51 @@ pub async fn get_execution_process_repo_states(
     Ok(ResponseJson(ApiResponse::success(repo_states)))
 }
 
+pub async fn get_execution_process_usage(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ExecutionProcessUsage>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let usage =
+        ExecutionProcessUsage::find_by_execution_process_id(pool, execution_process.id).await?;
+    Ok(ResponseJson(ApiResponse::success(usage)))
+}
+
+/// Download the raw output of a coding agent process for replaying it through its log
+/// normalizer, e.g. as a regression fixture for the executors' golden tests
+pub async fn get_execution_process_replay_capture(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<Response, ApiError> {
+    let capture = session_export::replay_capture(deployment.container(), &execution_process)
+        .await?
+        .ok_or_else(|| {
+            ApiError::BadRequest("Only coding agent processes can be replayed".to_string())
+        })?;
+    let body =
+        serde_json::to_string_pretty(&capture).map_err(|e| ApiError::BadRequest(e.to_string()))?;
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!(
+                "attachment; filename=\"{}.capture.json\"",
+                execution_process.id
+            ),
+        )
+        .body(Body::from(body))
+        .map_err(|e| ApiError::BadRequest(e.to_string()))
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let workspace_id_router = Router::new()
         .route("/", get(get_execution_process_by_id))
         .route("/stop", post(stop_execution_process))
         .route("/repo-states", get(get_execution_process_repo_states))
+        .route("/usage", get(get_execution_process_usage))
+        .route("/replay-capture", get(get_execution_process_replay_capture))
         .route("/raw-logs/ws", get(stream_raw_logs_ws))
         .route("/normalized-logs/ws", get(stream_normalized_logs_ws))
         .layer(from_fn_with_state(
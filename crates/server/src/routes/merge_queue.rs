@@ -0,0 +1,129 @@
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use db::models::{
+    merge_queue::{CreateMergeQueueEntry, MergeQueueEntry, MergeQueueStatus, MergeQueueStrategy},
+    workspace_repo::WorkspaceRepo,
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct MergeQueueQuery {
+    pub repo_id: Uuid,
+    /// Also return merged, ejected and cancelled entries
+    #[serde(default)]
+    pub include_finished: bool,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct EnqueueMergeRequest {
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    /// Defaults to squash
+    pub strategy: Option<MergeQueueStrategy>,
+}
+
+pub async fn get_merge_queue(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<MergeQueueQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<MergeQueueEntry>>>, ApiError> {
+    let entries = MergeQueueEntry::find_by_repo_id(
+        &deployment.db().pool,
+        query.repo_id,
+        query.include_finished,
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(entries)))
+}
+
+/// Add a workspace to the end of its repo's queue. It is merged into the workspace's current
+/// target branch for that repo.
+pub async fn enqueue_merge(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<EnqueueMergeRequest>,
+) -> Result<ResponseJson<ApiResponse<MergeQueueEntry>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let workspace_repo =
+        WorkspaceRepo::find_by_workspace_and_repo_id(pool, payload.workspace_id, payload.repo_id)
+            .await?
+            .ok_or(ApiError::BadRequest(
+                "Repository is not part of this workspace".to_string(),
+            ))?;
+
+    let already_queued = MergeQueueEntry::find_by_workspace_id(pool, payload.workspace_id)
+        .await?
+        .iter()
+        .any(|entry| {
+            entry.repo_id == payload.repo_id
+                && matches!(
+                    entry.status,
+                    MergeQueueStatus::Queued | MergeQueueStatus::Running
+                )
+        });
+    if already_queued {
+        return Err(ApiError::Conflict(
+            "This workspace is already in the merge queue".to_string(),
+        ));
+    }
+
+    let entry = MergeQueueEntry::create(
+        pool,
+        &CreateMergeQueueEntry {
+            workspace_id: payload.workspace_id,
+            repo_id: payload.repo_id,
+            target_branch_name: workspace_repo.target_branch,
+            strategy: payload.strategy.unwrap_or_default(),
+        },
+    )
+    .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "merge_queue_entry_created",
+            serde_json::json!({
+                "workspace_id": entry.workspace_id.to_string(),
+                "repo_id": entry.repo_id.to_string(),
+                "strategy": entry.strategy,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(entry)))
+}
+
+/// Take a queued entry out of the queue. Entries already being merged cannot be cancelled.
+pub async fn cancel_merge_queue_entry(
+    State(deployment): State<DeploymentImpl>,
+    Path(entry_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<MergeQueueEntry>>, ApiError> {
+    let pool = &deployment.db().pool;
+    if !MergeQueueEntry::cancel(pool, entry_id).await? {
+        let message = match MergeQueueEntry::find_by_id(pool, entry_id).await? {
+            Some(_) => "Only queued entries can be cancelled",
+            None => "Merge queue entry not found",
+        };
+        return Err(ApiError::BadRequest(message.to_string()));
+    }
+
+    let entry = MergeQueueEntry::find_by_id(pool, entry_id)
+        .await?
+        .ok_or(ApiError::Database(sqlx::Error::RowNotFound))?;
+    Ok(ResponseJson(ApiResponse::success(entry)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    let inner = Router::new()
+        .route("/", get(get_merge_queue).post(enqueue_merge))
+        .route("/{entry_id}/cancel", post(cancel_merge_queue_entry));
+
+    Router::new().nest("/merge-queue", inner)
+}
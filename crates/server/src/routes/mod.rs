@@ -8,6 +8,7 @@ use crate::DeploymentImpl;
 pub mod approvals;
 pub mod config;
 pub mod containers;
+pub mod conversation_search;
 pub mod filesystem;
 // pub mod github;
 pub mod events;
@@ -15,6 +16,7 @@ pub mod execution_processes;
 pub mod frontend;
 pub mod health;
 pub mod images;
+pub mod merge_queue;
 pub mod oauth;
 pub mod organizations;
 pub mod projects;
@@ -24,8 +26,11 @@ pub mod sessions;
 pub mod shared_tasks;
 pub mod tags;
 pub mod task_attempts;
+pub mod task_schedules;
 pub mod tasks;
 pub mod terminal;
+pub mod usage;
+pub mod workspace_groups;
 
 pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
     // Create routers with different middleware layers
@@ -37,6 +42,9 @@ pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
         .merge(tasks::router(&deployment))
         .merge(shared_tasks::router())
         .merge(task_attempts::router(&deployment))
+        .merge(workspace_groups::router(&deployment))
+        .merge(task_schedules::router(&deployment))
+        .merge(merge_queue::router())
         .merge(execution_processes::router(&deployment))
         .merge(tags::router(&deployment))
         .merge(oauth::router())
@@ -48,6 +56,8 @@ pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
         .merge(scratch::router(&deployment))
         .merge(sessions::router(&deployment))
         .merge(terminal::router())
+        .merge(usage::router())
+        .merge(conversation_search::router())
         .nest("/images", images::routes())
         .with_state(deployment);
 
@@ -1,10 +1,12 @@
+pub mod bundle;
+
 use std::path::PathBuf;
 
 use anyhow;
 use axum::{
     Extension, Json, Router,
     extract::{
-        Path, Query, State,
+        DefaultBodyLimit, Path, Query, State,
         ws::{WebSocket, WebSocketUpgrade},
     },
     http::StatusCode,
@@ -14,10 +16,13 @@ use axum::{
 };
 use db::models::{
     project::{CreateProject, Project, ProjectError, SearchResult, UpdateProject},
+    project_approval_policy::ProjectApprovalPolicy,
+    project_pr_review_setting::{ProjectPrReviewSetting, UpdateProjectPrReviewSetting},
     project_repo::{CreateProjectRepo, ProjectRepo},
     repo::Repo,
 };
 use deployment::Deployment;
+use executors::approval_policy::ApprovalRule;
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
 use serde::Deserialize;
 use services::services::{
@@ -568,6 +573,78 @@ pub async fn get_project_repository(
     }
 }
 
+pub async fn get_project_approval_policy(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<ProjectApprovalPolicy>>>, ApiError> {
+    let policy =
+        ProjectApprovalPolicy::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(policy)))
+}
+
+pub async fn set_project_approval_policy(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(rules): Json<Vec<ApprovalRule>>,
+) -> Result<ResponseJson<ApiResponse<ProjectApprovalPolicy>>, ApiError> {
+    for rule in &rules {
+        rule.validate().map_err(ApiError::BadRequest)?;
+    }
+
+    let policy = ProjectApprovalPolicy::upsert(&deployment.db().pool, project.id, &rules).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "project_approval_policy_set",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "rule_count": rules.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(policy)))
+}
+
+pub async fn delete_project_approval_policy(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    ProjectApprovalPolicy::delete(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub async fn get_project_pr_review_setting(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<ProjectPrReviewSetting>>>, ApiError> {
+    let setting =
+        ProjectPrReviewSetting::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(setting)))
+}
+
+pub async fn set_project_pr_review_setting(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateProjectPrReviewSetting>,
+) -> Result<ResponseJson<ApiResponse<ProjectPrReviewSetting>>, ApiError> {
+    let setting =
+        ProjectPrReviewSetting::upsert(&deployment.db().pool, project.id, &payload).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "project_pr_review_setting_set",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "auto_follow_up": setting.auto_follow_up,
+                "auto_fix_checks": setting.auto_fix_checks,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(setting)))
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let project_id_router = Router::new()
         .route(
@@ -586,6 +663,17 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
             "/repositories",
             get(get_project_repositories).post(add_project_repository),
         )
+        .route(
+            "/approval-policy",
+            get(get_project_approval_policy)
+                .put(set_project_approval_policy)
+                .delete(delete_project_approval_policy),
+        )
+        .route(
+            "/pr-review-setting",
+            get(get_project_pr_review_setting).put(set_project_pr_review_setting),
+        )
+        .route("/export", get(bundle::export_project))
         .layer(from_fn_with_state(
             deployment.clone(),
             load_project_middleware,
@@ -593,6 +681,16 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
 
     let projects_router = Router::new()
         .route("/", get(get_projects).post(create_project))
+        .route(
+            "/import",
+            post(bundle::import_project_bundle)
+                .layer(DefaultBodyLimit::max(bundle::BUNDLE_UPLOAD_LIMIT)),
+        )
+        .route(
+            "/import/preview",
+            post(bundle::preview_project_bundle)
+                .layer(DefaultBodyLimit::max(bundle::BUNDLE_UPLOAD_LIMIT)),
+        )
         .route(
             "/{project_id}/repositories/{repo_id}",
             get(get_project_repository).delete(delete_project_repository),
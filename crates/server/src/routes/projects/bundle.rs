@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+
+use axum::{
+    Extension,
+    body::Body,
+    extract::{Multipart, Query, State},
+    http::{StatusCode, header},
+    response::{Json as ResponseJson, Response},
+};
+use db::models::{project::Project, project_bundle::ProjectBundle};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::project_bundle::{self, ProjectBundleSummary};
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// Largest bundle accepted for import; bundles with workspace histories can get big
+pub const BUNDLE_UPLOAD_LIMIT: usize = 200 * 1024 * 1024;
+
+#[derive(Debug, Deserialize)]
+pub struct ExportProjectQuery {
+    /// Also export workspaces with the normalized logs of their sessions
+    #[serde(default)]
+    pub include_histories: bool,
+}
+
+/// Download the project as a bundle that can be imported on another machine
+pub async fn export_project(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ExportProjectQuery>,
+) -> Result<Response, ApiError> {
+    let bundle =
+        project_bundle::export_project(deployment.container(), project.id, query.include_histories)
+            .await?;
+    let archive = project_bundle::write_archive(&bundle)?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "project_exported",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "task_count": bundle.tasks.len(),
+                "workspace_count": bundle.workspaces.len(),
+                "include_histories": query.include_histories,
+            }),
+        )
+        .await;
+
+    let filename = format!(
+        "project-{}.{}",
+        project.id,
+        project_bundle::ARCHIVE_EXTENSION
+    );
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, project_bundle::ARCHIVE_CONTENT_TYPE)
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{filename}\""),
+        )
+        .body(Body::from(archive))
+        .map_err(|e| ApiError::BadRequest(e.to_string()))
+}
+
+/// Read the `bundle` file of a multipart upload, along with the optional `repo_paths` field:
+/// a JSON object mapping ids of bundled repos to paths on this machine
+async fn read_upload(
+    mut multipart: Multipart,
+) -> Result<(ProjectBundle, HashMap<Uuid, String>), ApiError> {
+    let mut bundle = None;
+    let mut repo_paths = HashMap::new();
+    while let Some(field) = multipart.next_field().await? {
+        match field.name() {
+            Some("bundle") => {
+                let data = field.bytes().await?;
+                bundle = Some(project_bundle::read_archive(&data)?);
+            }
+            Some("repo_paths") => {
+                let text = field.text().await?;
+                repo_paths = serde_json::from_str(&text)
+                    .map_err(|e| ApiError::BadRequest(format!("Invalid repo_paths: {e}")))?;
+            }
+            _ => {}
+        }
+    }
+    let bundle = bundle.ok_or(ApiError::BadRequest("No bundle uploaded".to_string()))?;
+    Ok((bundle, repo_paths))
+}
+
+/// Describe an uploaded bundle without importing it
+pub async fn preview_project_bundle(
+    multipart: Multipart,
+) -> Result<ResponseJson<ApiResponse<ProjectBundleSummary>>, ApiError> {
+    let (bundle, _) = read_upload(multipart).await?;
+    Ok(ResponseJson(ApiResponse::success(
+        ProjectBundleSummary::from(&bundle),
+    )))
+}
+
+/// Create a project from an uploaded bundle
+pub async fn import_project_bundle(
+    State(deployment): State<DeploymentImpl>,
+    multipart: Multipart,
+) -> Result<ResponseJson<ApiResponse<Project>>, ApiError> {
+    let (bundle, repo_paths) = read_upload(multipart).await?;
+    let project = project_bundle::import_project(
+        &deployment.db().pool,
+        deployment.repo(),
+        &bundle,
+        &repo_paths,
+    )
+    .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "project_imported",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "bundle_version": bundle.version,
+                "repository_count": bundle.repos.len(),
+                "task_count": bundle.tasks.len(),
+                "workspace_count": bundle.workspaces.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(project)))
+}
@@ -11,7 +11,7 @@ use db::models::{
 };
 use deployment::Deployment;
 use serde::Deserialize;
-use services::services::{file_search::SearchQuery, git::GitBranch};
+use services::services::{file_search::SearchQuery, git::GitBranch, git_host::ProviderKind};
 use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
@@ -119,6 +119,25 @@ pub async fn update_repo(
     Path(repo_id): Path<Uuid>,
     ResponseJson(payload): ResponseJson<UpdateRepo>,
 ) -> Result<ResponseJson<ApiResponse<Repo>>, ApiError> {
+    if let Some(Some(provider)) = &payload.git_host_provider
+        && !matches!(
+            ProviderKind::from_setting(provider),
+            Some(kind) if kind != ProviderKind::Unknown
+        )
+    {
+        return Err(ApiError::BadRequest(format!(
+            "Unsupported git host provider: {provider}"
+        )));
+    }
+
+    if let Some(Some(base_url)) = &payload.git_host_base_url
+        && !url::Url::parse(base_url).is_ok_and(|url| matches!(url.scheme(), "http" | "https"))
+    {
+        return Err(ApiError::BadRequest(format!(
+            "Git host base URL must be an http(s) URL: {base_url}"
+        )));
+    }
+
     let repo = Repo::update(&deployment.db().pool, repo_id, &payload).await?;
     Ok(ResponseJson(ApiResponse::success(repo)))
 }
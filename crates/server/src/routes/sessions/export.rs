@@ -0,0 +1,53 @@
+use axum::{
+    Extension,
+    body::Body,
+    extract::{Query, State},
+    http::{StatusCode, header},
+    response::Response,
+};
+use db::models::session::Session;
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::session_export::{self, SessionExportFormat};
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize)]
+pub struct ExportSessionQuery {
+    #[serde(default)]
+    pub format: SessionExportFormat,
+}
+
+/// Download a transcript of the session as Markdown, standalone HTML or JSON
+pub async fn export_session(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ExportSessionQuery>,
+) -> Result<Response, ApiError> {
+    let transcript = session_export::build_transcript(deployment.container(), &session).await?;
+    let body = session_export::render(&transcript, query.format);
+
+    deployment
+        .track_if_analytics_allowed(
+            "session_exported",
+            serde_json::json!({
+                "session_id": session.id.to_string(),
+                "workspace_id": session.workspace_id.to_string(),
+                "format": query.format,
+            }),
+        )
+        .await;
+
+    let filename = format!("session-{}.{}", session.id, query.format.file_extension());
+    let response = Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, query.format.content_type())
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{filename}\""),
+        )
+        .body(Body::from(body))
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+
+    Ok(response)
+}
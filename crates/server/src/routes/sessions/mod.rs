@@ -1,7 +1,8 @@
+pub mod export;
 pub mod queue;
 pub mod review;
 
-use std::str::FromStr;
+use std::{path::PathBuf, str::FromStr};
 
 use axum::{
     Extension, Json, Router,
@@ -12,6 +13,7 @@ use axum::{
 };
 use db::models::{
     execution_process::{ExecutionProcess, ExecutionProcessRunReason},
+    review_comment::{ReviewComment, ReviewCommentStatus},
     scratch::{Scratch, ScratchType},
     session::{CreateSession, Session},
     workspace::{Workspace, WorkspaceError},
@@ -26,7 +28,7 @@ use executors::{
     profile::ExecutorProfileId,
 };
 use serde::Deserialize;
-use services::services::container::ContainerService;
+use services::services::{container::ContainerService, review_comments};
 use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
@@ -95,6 +97,9 @@ pub struct CreateFollowUpAttempt {
     pub retry_process_id: Option<Uuid>,
     pub force_when_dirty: Option<bool>,
     pub perform_git_reset: Option<bool>,
+    /// Open review comments of the workspace to send along with the prompt
+    #[ts(optional)]
+    pub review_comment_ids: Option<Vec<Uuid>>,
 }
 
 pub async fn follow_up(
@@ -144,6 +149,11 @@ pub async fn follow_up(
         variant: payload.variant,
     };
 
+    let comments = match &payload.review_comment_ids {
+        Some(ids) if !ids.is_empty() => open_review_comments(&workspace, pool, ids).await?,
+        _ => Vec::new(),
+    };
+
     // If retry settings provided, perform replace-logic before proceeding
     if let Some(proc_id) = payload.retry_process_id {
         // Validate process belongs to this session
@@ -182,10 +192,14 @@ pub async fn follow_up(
     let latest_agent_session_id =
         ExecutionProcess::find_latest_coding_agent_turn_session_id(pool, session.id).await?;
 
-    let prompt = payload.prompt;
+    let prompt = if comments.is_empty() {
+        payload.prompt
+    } else {
+        review_comments::follow_up_prompt(&payload.prompt, &comments)
+    };
 
     let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
-    let cleanup_action = deployment.container().cleanup_actions_for_repos(&repos);
+    let post_agent_action = deployment.container().post_agent_actions_for_repos(&repos);
 
     let working_dir = workspace
         .agent_working_dir
@@ -210,7 +224,7 @@ pub async fn follow_up(
         )
     };
 
-    let action = ExecutorAction::new(action_type, cleanup_action.map(Box::new));
+    let action = ExecutorAction::new(action_type, post_agent_action.map(Box::new));
 
     let execution_process = deployment
         .container()
@@ -222,6 +236,10 @@ pub async fn follow_up(
         )
         .await?;
 
+    for (comment, _) in &comments {
+        ReviewComment::mark_sent(pool, comment.id, execution_process.id).await?;
+    }
+
     // Clear the draft follow-up scratch on successful spawn
     // This ensures the scratch is wiped even if the user navigates away quickly
     if let Err(e) = Scratch::delete(pool, session.id, &ScratchType::DraftFollowUp).await {
@@ -236,11 +254,58 @@ pub async fn follow_up(
     Ok(ResponseJson(ApiResponse::success(execution_process)))
 }
 
+/// The requested review comments, which must be open comments of the workspace, each with the
+/// current content of its file
+async fn open_review_comments(
+    workspace: &Workspace,
+    pool: &sqlx::SqlitePool,
+    ids: &[Uuid],
+) -> Result<Vec<(ReviewComment, Option<String>)>, ApiError> {
+    review_comments::refresh_statuses(pool, workspace, None).await?;
+    let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
+
+    let mut comments = Vec::new();
+    for comment in ReviewComment::find_by_workspace_id(pool, workspace.id).await? {
+        if !ids.contains(&comment.id) {
+            continue;
+        }
+        if comment.status != ReviewCommentStatus::Open {
+            return Err(ApiError::BadRequest(format!(
+                "Review comment on {} is no longer open",
+                comment.file_path
+            )));
+        }
+        let content = workspace
+            .container_ref
+            .as_deref()
+            .zip(repos.iter().find(|repo| repo.id == comment.repo_id))
+            .and_then(|(container_ref, repo)| {
+                std::fs::read_to_string(
+                    PathBuf::from(container_ref)
+                        .join(&repo.name)
+                        .join(&comment.file_path),
+                )
+                .ok()
+            });
+        comments.push((comment, content));
+    }
+    if ids
+        .iter()
+        .any(|id| !comments.iter().any(|(comment, _)| comment.id == *id))
+    {
+        return Err(ApiError::BadRequest(
+            "Review comment not found in this workspace".to_string(),
+        ));
+    }
+    Ok(comments)
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let session_id_router = Router::new()
         .route("/", get(get_session))
         .route("/follow-up", post(follow_up))
         .route("/review", post(review::start_review))
+        .route("/export", get(export::export_session))
         .layer(from_fn_with_state(
             deployment.clone(),
             load_session_middleware,
@@ -1,8 +1,11 @@
 pub mod codex_setup;
+pub mod conflicts;
 pub mod cursor_setup;
 pub mod gh_cli_setup;
 pub mod images;
 pub mod pr;
+pub mod review_comments;
+pub mod selection;
 pub mod util;
 pub mod workspace_summary;
 
@@ -26,11 +29,13 @@ use db::models::{
     coding_agent_turn::CodingAgentTurn,
     execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
     merge::{Merge, MergeStatus, PrMerge, PullRequestInfo},
+    merge_queue::MergeQueueEntry,
     project::SearchResult,
     repo::{Repo, RepoError},
     session::{CreateSession, Session},
-    task::{Task, TaskRelationships, TaskStatus},
-    workspace::{CreateWorkspace, Workspace, WorkspaceError},
+    task::{Task, TaskRelationships},
+    workspace::{Workspace, WorkspaceError},
+    workspace_group::WorkspaceGroup,
     workspace_repo::{CreateWorkspaceRepo, RepoWithTargetBranch, WorkspaceRepo},
 };
 use deployment::Deployment;
@@ -152,6 +157,16 @@ pub struct CreateTaskAttemptBody {
     pub task_id: Uuid,
     pub executor_profile_id: ExecutorProfileId,
     pub repos: Vec<WorkspaceRepoInput>,
+    /// Further executor profiles to run the task with in parallel. Each one gets its own
+    /// workspace from the same base branches, and all of them are grouped for comparison.
+    #[serde(default)]
+    #[ts(optional)]
+    pub fan_out_executor_profile_ids: Option<Vec<ExecutorProfileId>>,
+    /// Stack the new workspace on this workspace: its repos target that workspace's branch
+    /// instead of `target_branch`. With no repos given, all of that workspace's repos are used.
+    #[serde(default)]
+    #[ts(optional)]
+    pub base_workspace_id: Option<Uuid>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ts_rs::TS)]
@@ -168,6 +183,44 @@ pub struct RunAgentSetupRequest {
 #[derive(Debug, Serialize, TS)]
 pub struct RunAgentSetupResponse {}
 
+/// Repos of a workspace stacked on `base_workspace_id`, each targeting that workspace's branch.
+/// The requested repos must all belong to the base workspace; with none, all of its repos are used.
+async fn stacked_workspace_repos(
+    deployment: &DeploymentImpl,
+    base_workspace_id: Uuid,
+    repos: &[WorkspaceRepoInput],
+) -> Result<Vec<CreateWorkspaceRepo>, ApiError> {
+    let pool = &deployment.db().pool;
+    let base = Workspace::find_by_id(pool, base_workspace_id)
+        .await?
+        .ok_or(ApiError::BadRequest("Base workspace not found".to_string()))?;
+    let base_repo_ids: Vec<Uuid> = WorkspaceRepo::find_by_workspace_id(pool, base.id)
+        .await?
+        .into_iter()
+        .map(|wr| wr.repo_id)
+        .collect();
+
+    let repo_ids = if repos.is_empty() {
+        base_repo_ids
+    } else {
+        if let Some(missing) = repos.iter().find(|r| !base_repo_ids.contains(&r.repo_id)) {
+            return Err(ApiError::BadRequest(format!(
+                "Repository {} is not part of the base workspace",
+                missing.repo_id
+            )));
+        }
+        repos.iter().map(|r| r.repo_id).collect()
+    };
+
+    Ok(repo_ids
+        .into_iter()
+        .map(|repo_id| CreateWorkspaceRepo {
+            repo_id,
+            target_branch: base.branch.clone(),
+        })
+        .collect())
+}
+
 #[axum::debug_handler]
 pub async fn create_task_attempt(
     State(deployment): State<DeploymentImpl>,
@@ -175,79 +228,101 @@ pub async fn create_task_attempt(
 ) -> Result<ResponseJson<ApiResponse<Workspace>>, ApiError> {
     let executor_profile_id = payload.executor_profile_id.clone();
 
-    if payload.repos.is_empty() {
+    let workspace_repos: Vec<CreateWorkspaceRepo> = match payload.base_workspace_id {
+        Some(base_workspace_id) => {
+            stacked_workspace_repos(&deployment, base_workspace_id, &payload.repos).await?
+        }
+        None => payload
+            .repos
+            .iter()
+            .map(|r| CreateWorkspaceRepo {
+                repo_id: r.repo_id,
+                target_branch: r.target_branch.clone(),
+            })
+            .collect(),
+    };
+
+    if workspace_repos.is_empty() {
         return Err(ApiError::BadRequest(
             "At least one repository is required".to_string(),
         ));
     }
 
-    let pool = &deployment.db().pool;
     let task = Task::find_by_id(&deployment.db().pool, payload.task_id)
         .await?
         .ok_or(SqlxError::RowNotFound)?;
 
-    // Compute agent_working_dir based on repo count:
-    // - Single repo: use repo name as working dir (agent runs in repo directory)
-    // - Multiple repos: use None (agent runs in workspace root)
-    let agent_working_dir = if payload.repos.len() == 1 {
-        let repo = Repo::find_by_id(pool, payload.repos[0].repo_id)
-            .await?
-            .ok_or(RepoError::NotFound)?;
-        Some(repo.name)
+    let fan_out_profiles = payload
+        .fan_out_executor_profile_ids
+        .clone()
+        .unwrap_or_default();
+    let mut executor_profile_ids = vec![executor_profile_id];
+    for profile in fan_out_profiles {
+        if executor_profile_ids.contains(&profile) {
+            return Err(ApiError::BadRequest(format!(
+                "Executor profile {profile} is listed more than once"
+            )));
+        }
+        executor_profile_ids.push(profile);
+    }
+
+    let group = if executor_profile_ids.len() > 1 {
+        Some(WorkspaceGroup::create(&deployment.db().pool, Uuid::new_v4(), task.id).await?)
     } else {
         None
     };
 
-    let attempt_id = Uuid::new_v4();
-    let git_branch_name = deployment
-        .container()
-        .git_branch_from_workspace(&attempt_id, &task.title)
-        .await;
+    let mut workspaces = Vec::with_capacity(executor_profile_ids.len());
+    for executor_profile_id in &executor_profile_ids {
+        let workspace = deployment
+            .container()
+            .create_workspace_for_task(&task, &workspace_repos, payload.base_workspace_id)
+            .await?;
+        if let Some(group) = &group {
+            WorkspaceGroup::add_member(
+                &deployment.db().pool,
+                group.id,
+                workspace.id,
+                executor_profile_id,
+            )
+            .await?;
+        }
 
-    let workspace = Workspace::create(
-        pool,
-        &CreateWorkspace {
-            branch: git_branch_name.clone(),
-            agent_working_dir,
-        },
-        attempt_id,
-        payload.task_id,
-    )
-    .await?;
+        if let Err(err) = deployment
+            .container()
+            .start_workspace(&workspace, executor_profile_id.clone())
+            .await
+        {
+            tracing::error!("Failed to start task attempt: {}", err);
+        }
 
-    let workspace_repos: Vec<CreateWorkspaceRepo> = payload
-        .repos
-        .iter()
-        .map(|r| CreateWorkspaceRepo {
-            repo_id: r.repo_id,
-            target_branch: r.target_branch.clone(),
-        })
-        .collect();
+        deployment
+            .track_if_analytics_allowed(
+                "task_attempt_started",
+                serde_json::json!({
+                    "task_id": workspace.task_id.to_string(),
+                    "variant": &executor_profile_id.variant,
+                    "executor": &executor_profile_id.executor,
+                    "workspace_id": workspace.id.to_string(),
+                    "repository_count": workspace_repos.len(),
+                    "workspace_group_id": group.as_ref().map(|g| g.id.to_string()),
+                    "stacked": payload.base_workspace_id.is_some(),
+                }),
+            )
+            .await;
 
-    WorkspaceRepo::create_many(pool, workspace.id, &workspace_repos).await?;
-    if let Err(err) = deployment
-        .container()
-        .start_workspace(&workspace, executor_profile_id.clone())
-        .await
-    {
-        tracing::error!("Failed to start task attempt: {}", err);
+        workspaces.push(workspace);
     }
 
-    deployment
-        .track_if_analytics_allowed(
-            "task_attempt_started",
-            serde_json::json!({
-                "task_id": workspace.task_id.to_string(),
-                "variant": &executor_profile_id.variant,
-                "executor": &executor_profile_id.executor,
-                "workspace_id": workspace.id.to_string(),
-                "repository_count": payload.repos.len(),
-            }),
-        )
-        .await;
-
-    tracing::info!("Created attempt for task {}", task.id);
+    tracing::info!(
+        "Created {} attempt(s) for task {}",
+        workspaces.len(),
+        task.id
+    );
 
+    // The attempt for the primary executor profile; fanned-out attempts are reachable through
+    // the workspace group
+    let workspace = workspaces.swap_remove(0);
     Ok(ResponseJson(ApiResponse::success(workspace)))
 }
 
@@ -347,6 +422,60 @@ async fn handle_task_attempt_diff_ws(
     Ok(())
 }
 
+/// Stream the workspace's merges and PRs, including PR status and CI check updates
+pub async fn stream_task_attempt_merges_ws(
+    ws: WebSocketUpgrade,
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| async move {
+        if let Err(e) = handle_task_attempt_merges_ws(socket, deployment, workspace.id).await {
+            tracing::warn!("merges WS closed: {}", e);
+        }
+    })
+}
+
+async fn handle_task_attempt_merges_ws(
+    socket: WebSocket,
+    deployment: DeploymentImpl,
+    workspace_id: Uuid,
+) -> anyhow::Result<()> {
+    use futures_util::{SinkExt, StreamExt, TryStreamExt};
+
+    let mut stream = deployment
+        .events()
+        .stream_merges_for_workspace_raw(workspace_id)
+        .await?
+        .map_ok(|msg| msg.to_ws_message_unchecked());
+
+    let (mut sender, mut receiver) = socket.split();
+
+    loop {
+        tokio::select! {
+            item = stream.next() => {
+                match item {
+                    Some(Ok(msg)) => {
+                        if sender.send(msg).await.is_err() {
+                            break;
+                        }
+                    }
+                    Some(Err(e)) => {
+                        tracing::error!("stream error: {}", e);
+                        break;
+                    }
+                    None => break,
+                }
+            }
+            msg = receiver.next() => {
+                if msg.is_none() {
+                    break;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
 pub async fn stream_workspaces_ws(
     ws: WebSocketUpgrade,
     Query(query): Query<WorkspaceStreamQuery>,
@@ -440,80 +569,24 @@ pub async fn merge_task_attempt(
         .parent_task(pool)
         .await?
         .ok_or(ApiError::Workspace(WorkspaceError::TaskNotFound))?;
-    let task_uuid_str = task.id.to_string();
-    let first_uuid_section = task_uuid_str.split('-').next().unwrap_or(&task_uuid_str);
-
-    let mut commit_message = format!("{} (vibe-kanban {})", task.title, first_uuid_section);
-
-    // Add description on next line if it exists
-    if let Some(description) = &task.description
-        && !description.trim().is_empty()
-    {
-        commit_message.push_str("\n\n");
-        commit_message.push_str(description);
-    }
 
     let merge_commit_id = deployment.git().merge_changes(
         &repo.path,
         &worktree_path,
         &workspace.branch,
         &workspace_repo.target_branch,
-        &commit_message,
+        &task.merge_commit_message(),
     )?;
 
-    Merge::create_direct(
-        pool,
-        workspace.id,
-        workspace_repo.repo_id,
-        &workspace_repo.target_branch,
-        &merge_commit_id,
-    )
-    .await?;
-    Task::update_status(pool, task.id, TaskStatus::Done).await?;
-    if !workspace.pinned {
-        Workspace::set_archived(pool, workspace.id, true).await?;
-    }
-
-    // Stop any running dev servers for this workspace
-    let dev_servers =
-        ExecutionProcess::find_running_dev_servers_by_workspace(pool, workspace.id).await?;
-
-    for dev_server in dev_servers {
-        tracing::info!(
-            "Stopping dev server {} for completed task attempt {}",
-            dev_server.id,
-            workspace.id
-        );
-
-        if let Err(e) = deployment
-            .container()
-            .stop_execution(&dev_server, ExecutionProcessStatus::Killed)
-            .await
-        {
-            tracing::error!(
-                "Failed to stop dev server {} for task attempt {}: {}",
-                dev_server.id,
-                workspace.id,
-                e
-            );
-        }
-    }
-
-    // Try broadcast update to other users in organization
-    if let Ok(publisher) = deployment.share_publisher() {
-        if let Err(err) = publisher.update_shared_task_by_id(task.id).await {
-            tracing::warn!(
-                ?err,
-                "Failed to propagate shared task update for {}",
-                task.id
-            );
-        }
-    } else {
-        tracing::debug!(
-            "Share publisher unavailable; skipping remote update for {}",
-            task.id
-        );
-    }
+    deployment
+        .container()
+        .complete_direct_merge(
+            &workspace,
+            workspace_repo.repo_id,
+            &workspace_repo.target_branch,
+            &merge_commit_id,
+        )
+        .await?;
 
     deployment
         .track_if_analytics_allowed(
@@ -693,6 +766,9 @@ pub struct BranchStatus {
     pub remote_commits_behind: Option<usize>,
     pub remote_commits_ahead: Option<usize>,
     pub merges: Vec<Merge>,
+    /// Latest merge queue entry of the workspace for this repo; an ejected entry carries the
+    /// reason it was not merged
+    pub merge_queue_entry: Option<MergeQueueEntry>,
     /// True if a `git rebase` is currently in progress in this worktree
     pub is_rebase_in_progress: bool,
     /// Current conflict operation if any
@@ -741,6 +817,8 @@ pub async fn get_task_attempt_branch_status(
                 acc.entry(repo_id).or_insert_with(Vec::new).push(merge);
                 acc
             });
+    // Newest first
+    let queue_entries = MergeQueueEntry::find_by_workspace_id(pool, workspace.id).await?;
 
     let mut results = Vec::with_capacity(repositories.len());
 
@@ -843,6 +921,10 @@ pub async fn get_task_attempt_branch_status(
                 remote_commits_ahead: remote_ahead,
                 remote_commits_behind: remote_behind,
                 merges: repo_merges,
+                merge_queue_entry: queue_entries
+                    .iter()
+                    .find(|entry| entry.repo_id == repo.id)
+                    .cloned(),
                 target_branch_name: target_branch,
                 is_rebase_in_progress,
                 conflict_op,
@@ -1767,10 +1849,27 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/push", post(push_task_attempt_branch))
         .route("/push/force", post(force_push_task_attempt_branch))
         .route("/rebase", post(rebase_task_attempt))
+        .route("/changes/hunks", get(selection::get_change_hunks))
+        .route("/changes/apply", post(selection::apply_selection))
+        .route("/changes/discard", post(selection::discard_selection))
+        .route(
+            "/review-comments",
+            get(review_comments::get_review_comments).post(review_comments::create_review_comment),
+        )
+        .route(
+            "/review-comments/{comment_id}",
+            put(review_comments::update_review_comment)
+                .delete(review_comments::delete_review_comment),
+        )
+        .route("/conflicts", get(conflicts::get_conflicts))
+        .route("/conflicts/resolve", post(conflicts::resolve_conflict))
+        .route("/conflicts/continue", post(conflicts::continue_conflicts))
         .route("/conflicts/abort", post(abort_conflicts_task_attempt))
         .route("/pr", post(pr::create_pr))
         .route("/pr/attach", post(pr::attach_existing_pr))
         .route("/pr/comments", get(pr::get_pr_comments))
+        .route("/pr/review-comments", get(pr::get_pr_review_comments))
+        .route("/merges/ws", get(stream_task_attempt_merges_ws))
         .route("/open-editor", post(open_task_attempt_in_editor))
         .route("/children", get(get_task_attempt_children))
         .route("/stop", post(stop_task_attempt_execution))
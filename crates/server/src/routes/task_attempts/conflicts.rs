@@ -0,0 +1,157 @@
+use std::path::PathBuf;
+
+use axum::{
+    Extension, Json,
+    extract::{Query, State},
+    response::Json as ResponseJson,
+};
+use db::models::{
+    repo::{Repo, RepoError},
+    workspace::Workspace,
+};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use services::services::{
+    container::ContainerService,
+    git::{ConflictFile, ConflictOp, ConflictResolution, GitServiceError},
+};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct ConflictsQuery {
+    pub repo_id: Uuid,
+}
+
+/// The conflicted operation of a repo in a workspace and the files it left unmerged
+#[derive(Debug, Serialize, TS)]
+pub struct WorkspaceConflicts {
+    /// Null when no rebase, merge, cherry-pick or revert is in progress
+    pub op: Option<ConflictOp>,
+    pub files: Vec<ConflictFile>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct ResolveConflictRequest {
+    pub repo_id: Uuid,
+    pub path: String,
+    pub resolution: ConflictResolution,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct ContinueConflictsRequest {
+    pub repo_id: Uuid,
+}
+
+async fn worktree_path(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+    repo_id: Uuid,
+) -> Result<PathBuf, ApiError> {
+    let repo = Repo::find_by_id(&deployment.db().pool, repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(workspace)
+        .await?;
+    Ok(PathBuf::from(container_ref).join(&repo.name))
+}
+
+fn conflicts_at(
+    deployment: &DeploymentImpl,
+    worktree_path: &std::path::Path,
+) -> Result<WorkspaceConflicts, ApiError> {
+    let op = deployment.git().detect_conflict_op(worktree_path)?;
+    let files = deployment.git().get_conflict_files(worktree_path)?;
+    Ok(WorkspaceConflicts { op, files })
+}
+
+pub async fn get_conflicts(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ConflictsQuery>,
+) -> Result<ResponseJson<ApiResponse<WorkspaceConflicts>>, ApiError> {
+    let worktree_path = worktree_path(&deployment, &workspace, query.repo_id).await?;
+    Ok(ResponseJson(ApiResponse::success(conflicts_at(
+        &deployment,
+        &worktree_path,
+    )?)))
+}
+
+/// Resolve one conflicted file with ours, theirs or the given content, and stage it
+pub async fn resolve_conflict(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ResolveConflictRequest>,
+) -> Result<ResponseJson<ApiResponse<WorkspaceConflicts>>, ApiError> {
+    let worktree_path = worktree_path(&deployment, &workspace, payload.repo_id).await?;
+
+    // Only files git reports as unmerged can be written, which also keeps paths inside the worktree
+    if !deployment
+        .git()
+        .get_conflicted_files(&worktree_path)?
+        .contains(&payload.path)
+    {
+        return Err(ApiError::BadRequest(format!(
+            "{} has no unresolved conflicts",
+            payload.path
+        )));
+    }
+
+    deployment
+        .git()
+        .resolve_conflict(&worktree_path, &payload.path, &payload.resolution)?;
+
+    Ok(ResponseJson(ApiResponse::success(conflicts_at(
+        &deployment,
+        &worktree_path,
+    )?)))
+}
+
+/// Continue the rebase or merge once every file is resolved. When it stops on conflicts again
+/// (e.g. the next commit of a rebase), the new conflicts are returned.
+pub async fn continue_conflicts(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ContinueConflictsRequest>,
+) -> Result<ResponseJson<ApiResponse<WorkspaceConflicts>>, ApiError> {
+    let worktree_path = worktree_path(&deployment, &workspace, payload.repo_id).await?;
+
+    let Some(op) = deployment.git().detect_conflict_op(&worktree_path)? else {
+        return Err(ApiError::BadRequest(
+            "No rebase or merge is in progress".to_string(),
+        ));
+    };
+    let unresolved = deployment.git().get_conflicted_files(&worktree_path)?;
+    if !unresolved.is_empty() {
+        return Err(ApiError::BadRequest(format!(
+            "Resolve all conflicted files first: {}",
+            unresolved.join(", ")
+        )));
+    }
+
+    match deployment.git().continue_conflicts(&worktree_path) {
+        Ok(()) | Err(GitServiceError::MergeConflicts(_)) => {}
+        Err(e) => return Err(e.into()),
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_attempt_conflicts_continued",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "repo_id": payload.repo_id.to_string(),
+                "op": op,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(conflicts_at(
+        &deployment,
+        &worktree_path,
+    )?)))
+}
@@ -8,6 +8,7 @@ use axum::{
 use db::models::{
     execution_process::{ExecutionProcess, ExecutionProcessRunReason},
     merge::{Merge, MergeStatus},
+    pr_review_comment::PrReviewComment,
     repo::{Repo, RepoError},
     session::{CreateSession, Session},
     task::{Task, TaskStatus},
@@ -192,6 +193,20 @@ async fn trigger_pr_description_follow_up(
     Ok(())
 }
 
+fn push_error_response(
+    e: GitServiceError,
+) -> Result<ResponseJson<ApiResponse<String, PrError>>, ApiError> {
+    match e {
+        GitServiceError::GitCLI(GitCliError::AuthFailed(_)) => Ok(ResponseJson(
+            ApiResponse::error_with_data(PrError::GitCliNotLoggedIn),
+        )),
+        GitServiceError::GitCLI(GitCliError::NotAvailable) => Ok(ResponseJson(
+            ApiResponse::error_with_data(PrError::GitCliNotInstalled),
+        )),
+        _ => Err(ApiError::GitService(e)),
+    }
+}
+
 pub async fn create_pr(
     Extension(workspace): Extension<Workspace>,
     State(deployment): State<DeploymentImpl>,
@@ -238,6 +253,23 @@ pub async fn create_pr(
             Err(_) => (push_remote.clone(), target_branch.clone()),
         };
 
+    // A stacked workspace's PR targets its base workspace's branch, which may not be pushed yet
+    if let Some(base_workspace_id) = workspace.base_workspace_id
+        && let Some(base) = Workspace::find_by_id(pool, base_workspace_id).await?
+        && base.branch == target_branch
+    {
+        let base_path = base
+            .container_ref
+            .as_deref()
+            .map(|container_ref| PathBuf::from(container_ref).join(&repo.name))
+            .filter(|path| path.exists())
+            .unwrap_or_else(|| repo_path.clone());
+        if let Err(e) = git.push_to_remote(&base_path, &base.branch, false) {
+            tracing::error!("Failed to push base workspace branch to remote: {}", e);
+            return push_error_response(e);
+        }
+    }
+
     let push_remote_url = git.get_remote_url(&repo_path, &push_remote)?;
     let target_remote_url = git.get_remote_url(&repo_path, &target_remote)?;
 
@@ -265,22 +297,10 @@ pub async fn create_pr(
 
     if let Err(e) = git.push_to_remote(&worktree_path, &workspace.branch, false) {
         tracing::error!("Failed to push branch to remote: {}", e);
-        match e {
-            GitServiceError::GitCLI(GitCliError::AuthFailed(_)) => {
-                return Ok(ResponseJson(ApiResponse::error_with_data(
-                    PrError::GitCliNotLoggedIn,
-                )));
-            }
-            GitServiceError::GitCLI(GitCliError::NotAvailable) => {
-                return Ok(ResponseJson(ApiResponse::error_with_data(
-                    PrError::GitCliNotInstalled,
-                )));
-            }
-            _ => return Err(ApiError::GitService(e)),
-        }
+        return push_error_response(e);
     }
 
-    let git_host = match git_host::GitHostService::from_url(&target_remote_url) {
+    let git_host = match git_host::GitHostService::for_repo(&repo, &target_remote_url) {
         Ok(host) => host,
         Err(GitHostError::UnsupportedProvider) => {
             return Ok(ResponseJson(ApiResponse::error_with_data(
@@ -420,7 +440,7 @@ pub async fn attach_existing_pr(
         &git.resolve_remote_name_for_branch(&repo.path, &workspace_repo.target_branch)?,
     )?;
 
-    let git_host = match git_host::GitHostService::from_url(&remote_url) {
+    let git_host = match git_host::GitHostService::for_repo(&repo, &remote_url) {
         Ok(host) => host,
         Err(GitHostError::UnsupportedProvider) => {
             return Ok(ResponseJson(ApiResponse::error_with_data(
@@ -556,7 +576,7 @@ pub async fn get_pr_comments(
         &git.resolve_remote_name_for_branch(&repo.path, &workspace_repo.target_branch)?,
     )?;
 
-    let git_host = match git_host::GitHostService::from_url(&remote_url) {
+    let git_host = match git_host::GitHostService::for_repo(&repo, &remote_url) {
         Ok(host) => host,
         Err(GitHostError::CliNotInstalled { provider }) => {
             return Ok(ResponseJson(ApiResponse::error_with_data(
@@ -596,3 +616,14 @@ pub async fn get_pr_comments(
         }
     }
 }
+
+/// Review comments picked up from the workspace's open PRs, and whether they were sent to the
+/// agent and addressed
+pub async fn get_pr_review_comments(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<PrReviewComment>>>, ApiError> {
+    let comments =
+        PrReviewComment::find_by_workspace_id(&deployment.db().pool, workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(comments)))
+}
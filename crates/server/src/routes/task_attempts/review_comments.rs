@@ -0,0 +1,157 @@
+use std::path::{Component, Path as FsPath, PathBuf};
+
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+};
+use db::models::{
+    repo::{Repo, RepoError},
+    review_comment::{CreateReviewComment, ReviewComment},
+    workspace::Workspace,
+    workspace_repo::WorkspaceRepo,
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::{container::ContainerService, review_comments};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateReviewCommentRequest {
+    pub repo_id: Uuid,
+    /// Path of the file within the repo
+    pub file_path: String,
+    /// First commented line, 1-based, in the workspace's version of the file
+    pub line_start: i64,
+    /// Last commented line, inclusive
+    pub line_end: i64,
+    pub body: String,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateReviewCommentRequest {
+    pub body: String,
+}
+
+/// Comments of the workspace, after following their lines to where they are now
+pub async fn get_review_comments(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ReviewComment>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    review_comments::refresh_statuses(pool, &workspace, None).await?;
+    let comments = ReviewComment::find_by_workspace_id(pool, workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(comments)))
+}
+
+/// Comment on lines of a file as it is in the workspace now
+pub async fn create_review_comment(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateReviewCommentRequest>,
+) -> Result<ResponseJson<ApiResponse<ReviewComment>>, ApiError> {
+    let pool = &deployment.db().pool;
+    if payload.body.trim().is_empty() {
+        return Err(ApiError::BadRequest("Comment is empty".to_string()));
+    }
+    // Keep the path inside the worktree
+    if !FsPath::new(&payload.file_path)
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)))
+    {
+        return Err(ApiError::BadRequest(format!(
+            "Invalid file path: {}",
+            payload.file_path
+        )));
+    }
+    WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, payload.repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+    let repo = Repo::find_by_id(pool, payload.repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+    let worktree_path = PathBuf::from(container_ref).join(&repo.name);
+    let content = std::fs::read_to_string(worktree_path.join(&payload.file_path))?;
+    let anchor_text = review_comments::anchor_text(&content, payload.line_start, payload.line_end)
+        .ok_or_else(|| {
+            ApiError::BadRequest(format!(
+                "{} has no lines {}-{}",
+                payload.file_path, payload.line_start, payload.line_end
+            ))
+        })?;
+    let commit_sha = deployment.git().get_head_info(&worktree_path)?.oid;
+
+    let comment = ReviewComment::create(
+        pool,
+        &CreateReviewComment {
+            workspace_id: workspace.id,
+            repo_id: repo.id,
+            file_path: payload.file_path,
+            line_start: payload.line_start,
+            line_end: payload.line_end,
+            body: payload.body,
+            commit_sha,
+            anchor_text,
+        },
+    )
+    .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "review_comment_created",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "line_count": comment.line_end - comment.line_start + 1,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(comment)))
+}
+
+async fn workspace_comment(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+    comment_id: Uuid,
+) -> Result<ReviewComment, ApiError> {
+    ReviewComment::find_by_id(&deployment.db().pool, comment_id)
+        .await?
+        .filter(|comment| comment.workspace_id == workspace.id)
+        .ok_or(ApiError::BadRequest("Review comment not found".to_string()))
+}
+
+pub async fn update_review_comment(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Path((_id, comment_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<UpdateReviewCommentRequest>,
+) -> Result<ResponseJson<ApiResponse<ReviewComment>>, ApiError> {
+    let pool = &deployment.db().pool;
+    if payload.body.trim().is_empty() {
+        return Err(ApiError::BadRequest("Comment is empty".to_string()));
+    }
+    let comment = workspace_comment(&deployment, &workspace, comment_id).await?;
+    ReviewComment::update_body(pool, comment.id, &payload.body).await?;
+
+    let comment = workspace_comment(&deployment, &workspace, comment_id).await?;
+    Ok(ResponseJson(ApiResponse::success(comment)))
+}
+
+pub async fn delete_review_comment(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Path((_id, comment_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let comment = workspace_comment(&deployment, &workspace, comment_id).await?;
+    ReviewComment::delete(&deployment.db().pool, comment.id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
@@ -0,0 +1,250 @@
+use std::path::PathBuf;
+
+use axum::{
+    Extension, Json,
+    extract::{Query, State},
+    response::Json as ResponseJson,
+};
+use db::models::{
+    repo::{Repo, RepoError},
+    workspace::{Workspace, WorkspaceError},
+    workspace_repo::{CreateWorkspaceRepo, WorkspaceRepo},
+};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use services::services::{
+    change_selection::{self, FileHunks, FileSelection},
+    container::ContainerService,
+    git::{Commit, DiffTarget},
+};
+use ts_rs::TS;
+use utils::{diff::Diff, response::ApiResponse};
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct ChangeHunksQuery {
+    pub repo_id: Uuid,
+}
+
+/// Where accepted changes are committed: onto the repo's target branch, or in a new workspace
+/// of the same task branched from it
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum SelectionDestination {
+    TargetBranch,
+    NewWorkspace,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct ApplySelectionRequest {
+    pub repo_id: Uuid,
+    pub files: Vec<FileSelection>,
+    pub destination: SelectionDestination,
+    /// Defaults to the task's merge commit message
+    #[ts(optional)]
+    pub commit_message: Option<String>,
+}
+
+#[derive(Debug, Serialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(tag = "type", rename_all = "snake_case")]
+pub enum AppliedSelection {
+    TargetBranch { commit_sha: String },
+    NewWorkspace { workspace: Workspace },
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct DiscardSelectionRequest {
+    pub repo_id: Uuid,
+    pub files: Vec<FileSelection>,
+}
+
+/// A repo of a workspace with its changes against the merge base
+struct RepoChanges {
+    repo: Repo,
+    target_branch: String,
+    worktree_path: PathBuf,
+    base_commit: Commit,
+    diffs: Vec<Diff>,
+}
+
+async fn repo_changes(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+    repo_id: Uuid,
+) -> Result<RepoChanges, ApiError> {
+    let pool = &deployment.db().pool;
+    let workspace_repo = WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+    let repo = Repo::find_by_id(pool, repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(workspace)
+        .await?;
+    let worktree_path = PathBuf::from(container_ref).join(&repo.name);
+
+    let base_commit = deployment.git().get_base_commit(
+        &repo.path,
+        &workspace.branch,
+        &workspace_repo.target_branch,
+    )?;
+    let diffs = deployment.git().get_diffs(
+        DiffTarget::Worktree {
+            worktree_path: &worktree_path,
+            base_commit: &base_commit,
+        },
+        None,
+    )?;
+
+    Ok(RepoChanges {
+        repo,
+        target_branch: workspace_repo.target_branch,
+        worktree_path,
+        base_commit,
+        diffs,
+    })
+}
+
+/// Changed files of a repo in the workspace, with the hunks that can be selected in each
+pub async fn get_change_hunks(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ChangeHunksQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<FileHunks>>>, ApiError> {
+    let changes = repo_changes(&deployment, &workspace, query.repo_id).await?;
+    Ok(ResponseJson(ApiResponse::success(
+        change_selection::file_hunks(&changes.diffs),
+    )))
+}
+
+/// Apply only the selected files and hunks of the workspace's changes, including uncommitted
+/// ones, to the target branch or to a new workspace. The workspace itself is left unchanged.
+pub async fn apply_selection(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ApplySelectionRequest>,
+) -> Result<ResponseJson<ApiResponse<AppliedSelection>>, ApiError> {
+    if payload.files.is_empty() {
+        return Err(ApiError::BadRequest("Select at least one file".to_string()));
+    }
+    let pool = &deployment.db().pool;
+    let changes = repo_changes(&deployment, &workspace, payload.repo_id).await?;
+
+    // The selected changes are relative to the merge base, so they only apply cleanly while
+    // the target branch is still there
+    let (_, behind) = deployment.git().get_branch_status(
+        &changes.repo.path,
+        &workspace.branch,
+        &changes.target_branch,
+    )?;
+    if behind > 0 {
+        return Err(ApiError::Conflict(format!(
+            "{} has moved on since this workspace branched off; rebase the workspace first",
+            changes.target_branch
+        )));
+    }
+
+    let writes =
+        change_selection::accepted_writes(&changes.worktree_path, &changes.diffs, &payload.files)?;
+    let task = workspace
+        .parent_task(pool)
+        .await?
+        .ok_or(ApiError::Workspace(WorkspaceError::TaskNotFound))?;
+    let commit_message = payload
+        .commit_message
+        .filter(|message| !message.trim().is_empty())
+        .unwrap_or_else(|| task.merge_commit_message());
+
+    let applied = match payload.destination {
+        SelectionDestination::TargetBranch => {
+            let commit_sha = deployment.git().commit_files_to_branch(
+                &changes.repo.path,
+                &changes.target_branch,
+                &writes,
+                &commit_message,
+            )?;
+            AppliedSelection::TargetBranch { commit_sha }
+        }
+        SelectionDestination::NewWorkspace => {
+            let new_workspace = deployment
+                .container()
+                .create_workspace_for_task(
+                    &task,
+                    &[CreateWorkspaceRepo {
+                        repo_id: changes.repo.id,
+                        target_branch: changes.target_branch.clone(),
+                    }],
+                    None,
+                )
+                .await?;
+            let container_ref = deployment.container().create(&new_workspace).await?;
+            let new_worktree_path = PathBuf::from(container_ref).join(&changes.repo.name);
+            change_selection::write_to_worktree(&new_worktree_path, &writes)?;
+            deployment
+                .git()
+                .commit(&new_worktree_path, &commit_message)?;
+            // Pick up the container ref set by `create`
+            let new_workspace = Workspace::find_by_id(pool, new_workspace.id)
+                .await?
+                .ok_or(ApiError::Database(sqlx::Error::RowNotFound))?;
+            AppliedSelection::NewWorkspace {
+                workspace: new_workspace,
+            }
+        }
+    };
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_attempt_selection_applied",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "repo_id": payload.repo_id.to_string(),
+                "destination": payload.destination,
+                "file_count": payload.files.len(),
+                "partial_file_count": payload.files.iter().filter(|f| f.hunks.is_some()).count(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(applied)))
+}
+
+/// Revert the selected files and hunks in the worktree. The result is left uncommitted; the
+/// remaining changes are returned.
+pub async fn discard_selection(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<DiscardSelectionRequest>,
+) -> Result<ResponseJson<ApiResponse<Vec<FileHunks>>>, ApiError> {
+    let changes = repo_changes(&deployment, &workspace, payload.repo_id).await?;
+    let writes = change_selection::discarded_writes(
+        deployment.git(),
+        &changes.worktree_path,
+        &changes.base_commit,
+        &changes.diffs,
+        &payload.files,
+    )?;
+    change_selection::write_to_worktree(&changes.worktree_path, &writes)?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_attempt_selection_discarded",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "repo_id": payload.repo_id.to_string(),
+                "file_count": payload.files.len(),
+            }),
+        )
+        .await;
+
+    let changes = repo_changes(&deployment, &workspace, payload.repo_id).await?;
+    Ok(ResponseJson(ApiResponse::success(
+        change_selection::file_hunks(&changes.diffs),
+    )))
+}
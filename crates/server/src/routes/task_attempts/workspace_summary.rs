@@ -165,7 +165,7 @@ pub async fn get_workspace_summaries(
 }
 
 /// Compute diff stats for a workspace.
-async fn compute_workspace_diff_stats(
+pub async fn compute_workspace_diff_stats(
     deployment: &DeploymentImpl,
     workspace: &Workspace,
 ) -> Result<DiffStats, ApiError> {
@@ -0,0 +1,174 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Query, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::get,
+};
+use chrono::Utc;
+use db::models::{
+    project::Project,
+    task_schedule::{CreateTaskSchedule, TaskSchedule, UpdateTaskSchedule},
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::task_scheduler;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::load_task_schedule_middleware};
+
+#[derive(Debug, Deserialize)]
+pub struct TaskScheduleQuery {
+    pub project_id: Option<Uuid>,
+}
+
+pub async fn get_task_schedules(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<TaskScheduleQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<TaskSchedule>>>, ApiError> {
+    let schedules = TaskSchedule::find_all(&deployment.db().pool, query.project_id).await?;
+    Ok(ResponseJson(ApiResponse::success(schedules)))
+}
+
+pub async fn get_task_schedule(
+    Extension(schedule): Extension<TaskSchedule>,
+) -> Result<ResponseJson<ApiResponse<TaskSchedule>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(schedule)))
+}
+
+fn validate_template(data: &CreateTaskSchedule) -> Result<(), ApiError> {
+    if data.title.trim().is_empty() {
+        return Err(ApiError::BadRequest("Title is required".to_string()));
+    }
+    if data.repos.is_empty() {
+        return Err(ApiError::BadRequest(
+            "At least one repository is required".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub async fn create_task_schedule(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateTaskSchedule>,
+) -> Result<ResponseJson<ApiResponse<TaskSchedule>>, ApiError> {
+    validate_template(&payload)?;
+
+    let pool = &deployment.db().pool;
+    Project::find_by_id(pool, payload.project_id)
+        .await?
+        .ok_or(ApiError::BadRequest("Project not found".to_string()))?;
+
+    let next_run_at = task_scheduler::next_run_at(
+        payload.cron_expression.as_deref(),
+        payload.run_at,
+        Utc::now(),
+    )?;
+
+    let schedule = TaskSchedule::create(pool, &payload, Uuid::new_v4(), next_run_at).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_schedule_created",
+            serde_json::json!({
+                "schedule_id": schedule.id.to_string(),
+                "project_id": schedule.project_id.to_string(),
+                "recurring": schedule.cron_expression.is_some(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(schedule)))
+}
+
+pub async fn update_task_schedule(
+    Extension(existing): Extension<TaskSchedule>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateTaskSchedule>,
+) -> Result<ResponseJson<ApiResponse<TaskSchedule>>, ApiError> {
+    // Timing is replaced as a whole: setting either field clears the other
+    let (cron_expression, run_at) = match (payload.cron_expression, payload.run_at) {
+        (None, None) => (existing.cron_expression, existing.run_at),
+        timing => timing,
+    };
+    let description = match payload.description {
+        Some(s) if s.trim().is_empty() => None, // Empty string = clear description
+        Some(s) => Some(s),
+        None => existing.description,
+    };
+
+    let data = CreateTaskSchedule {
+        project_id: existing.project_id,
+        title: payload.title.unwrap_or(existing.title),
+        description,
+        executor_profile_id: payload
+            .executor_profile_id
+            .unwrap_or(existing.executor_profile_id.0),
+        repos: payload.repos.unwrap_or(existing.repos.0),
+        cron_expression,
+        run_at,
+        catch_up_missed: Some(payload.catch_up_missed.unwrap_or(existing.catch_up_missed)),
+    };
+    validate_template(&data)?;
+
+    let enabled = payload.enabled.unwrap_or(existing.enabled);
+    // A one-off schedule that already fired (or was skipped) only runs again at a new run_at
+    let spent = existing.cron_expression.is_none()
+        && existing.next_run_at.is_none()
+        && data.cron_expression.is_none()
+        && data.run_at == existing.run_at;
+    let next_run_at = if spent {
+        if enabled {
+            return Err(ApiError::BadRequest(
+                "This one-off schedule already ran; set a new run_at to run it again".to_string(),
+            ));
+        }
+        None
+    } else {
+        task_scheduler::next_run_at(data.cron_expression.as_deref(), data.run_at, Utc::now())?
+    };
+
+    let schedule = TaskSchedule::update(
+        &deployment.db().pool,
+        existing.id,
+        &data,
+        enabled,
+        next_run_at,
+    )
+    .await?;
+
+    Ok(ResponseJson(ApiResponse::success(schedule)))
+}
+
+pub async fn delete_task_schedule(
+    Extension(schedule): Extension<TaskSchedule>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = TaskSchedule::delete(&deployment.db().pool, schedule.id).await?;
+    if rows_affected == 0 {
+        Err(ApiError::Database(sqlx::Error::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let schedule_router = Router::new()
+        .route(
+            "/",
+            get(get_task_schedule)
+                .put(update_task_schedule)
+                .delete(delete_task_schedule),
+        )
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_task_schedule_middleware,
+        ));
+
+    let inner = Router::new()
+        .route("/", get(get_task_schedules).post(create_task_schedule))
+        .nest("/{schedule_id}", schedule_router);
+
+    Router::new().nest("/task-schedules", inner)
+}
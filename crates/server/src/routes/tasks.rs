@@ -4,7 +4,7 @@ use anyhow;
 use axum::{
     Extension, Json, Router,
     extract::{
-        Query, State,
+        Path, Query, State,
         ws::{WebSocket, WebSocketUpgrade},
     },
     http::StatusCode,
@@ -14,17 +14,24 @@ use axum::{
 };
 use db::models::{
     image::TaskImage,
+    project_repo::ProjectRepo,
     repo::{Repo, RepoError},
     task::{CreateTask, Task, TaskWithAttemptStatus, UpdateTask},
-    workspace::{CreateWorkspace, Workspace},
+    task_budget::TaskBudget,
+    task_dependency::{
+        CreateTaskDependency, TaskDependencies, TaskDependency, TaskDependencyError,
+    },
+    task_issue_link::TaskIssueLink,
+    workspace::Workspace,
     workspace_repo::{CreateWorkspaceRepo, WorkspaceRepo},
 };
 use deployment::Deployment;
-use executors::profile::ExecutorProfileId;
+use executors::{budget::RunBudget, profile::ExecutorProfileId};
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
 use serde::{Deserialize, Serialize};
 use services::services::{
-    container::ContainerService, share::ShareError, workspace_manager::WorkspaceManager,
+    container::ContainerService, git_host::IssueFilter, share::ShareError, task_import,
+    workspace_manager::WorkspaceManager,
 };
 use sqlx::Error as SqlxError;
 use ts_rs::TS;
@@ -139,6 +146,113 @@ pub async fn create_task(
     Ok(ResponseJson(ApiResponse::success(task)))
 }
 
+/// Where imported tasks are read from
+#[derive(Debug, Deserialize, TS)]
+#[serde(tag = "source", rename_all = "snake_case")]
+#[ts(tag = "source", rename_all = "snake_case")]
+pub enum ImportTasksSource {
+    /// Open issues of a project repo on GitHub, or work items on Azure Boards
+    Issues {
+        repo_id: Uuid,
+        /// Issues must have all of these labels (tags on Azure Boards)
+        #[serde(default)]
+        labels: Vec<String>,
+        /// Milestone title (iteration path on Azure Boards)
+        #[ts(optional)]
+        milestone: Option<String>,
+        #[ts(optional)]
+        query: Option<String>,
+        #[ts(optional)]
+        limit: Option<usize>,
+    },
+    /// Checklist items (`- [ ] title`) of a Markdown document
+    Markdown { content: String },
+    /// A CSV document with `title` and optional `description` and `status` columns
+    Csv { content: String },
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct ImportTasksRequest {
+    pub project_id: Uuid,
+    pub source: ImportTasksSource,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct ImportTasksResponse {
+    pub created: Vec<Task>,
+    /// Issues left out because they were imported into the project before
+    pub skipped: usize,
+}
+
+/// Issues imported when no limit is given
+const DEFAULT_ISSUE_IMPORT_LIMIT: usize = 100;
+
+/// Create tasks from git host issues, a Markdown checklist or a CSV document
+pub async fn import_tasks(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ImportTasksRequest>,
+) -> Result<ResponseJson<ApiResponse<ImportTasksResponse>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let source = match &payload.source {
+        ImportTasksSource::Issues { .. } => "issues",
+        ImportTasksSource::Markdown { .. } => "markdown",
+        ImportTasksSource::Csv { .. } => "csv",
+    };
+    let tasks = match payload.source {
+        ImportTasksSource::Issues {
+            repo_id,
+            labels,
+            milestone,
+            query,
+            limit,
+        } => {
+            ProjectRepo::find_by_project_and_repo(pool, payload.project_id, repo_id)
+                .await?
+                .ok_or(RepoError::NotFound)?;
+            let repo = Repo::find_by_id(pool, repo_id)
+                .await?
+                .ok_or(RepoError::NotFound)?;
+            let filter = IssueFilter {
+                labels,
+                milestone: milestone.filter(|m| !m.trim().is_empty()),
+                query: query.filter(|q| !q.trim().is_empty()),
+                limit: limit.unwrap_or(DEFAULT_ISSUE_IMPORT_LIMIT),
+            };
+            task_import::fetch_issues(deployment.git(), &repo, &filter).await?
+        }
+        ImportTasksSource::Markdown { content } => task_import::parse_markdown_checklist(&content),
+        ImportTasksSource::Csv { content } => task_import::parse_csv(&content)?,
+    };
+
+    let outcome = task_import::import_tasks(pool, payload.project_id, tasks).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "tasks_imported",
+            serde_json::json!({
+                "project_id": payload.project_id.to_string(),
+                "source": source,
+                "created_count": outcome.created.len(),
+                "skipped_count": outcome.skipped,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(ImportTasksResponse {
+        created: outcome.created,
+        skipped: outcome.skipped,
+    })))
+}
+
+/// The issue the task was imported from, if any
+pub async fn get_task_issue_link(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<TaskIssueLink>>>, ApiError> {
+    let link = TaskIssueLink::find_by_task_id(&deployment.db().pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(link)))
+}
+
 #[derive(Debug, Deserialize, TS)]
 pub struct CreateAndStartTaskRequest {
     pub task: CreateTask,
@@ -177,35 +291,6 @@ pub async fn create_task_and_start(
         )
         .await;
 
-    let attempt_id = Uuid::new_v4();
-    let git_branch_name = deployment
-        .container()
-        .git_branch_from_workspace(&attempt_id, &task.title)
-        .await;
-
-    // Compute agent_working_dir based on repo count:
-    // - Single repo: use repo name as working dir (agent runs in repo directory)
-    // - Multiple repos: use None (agent runs in workspace root)
-    let agent_working_dir = if payload.repos.len() == 1 {
-        let repo = Repo::find_by_id(pool, payload.repos[0].repo_id)
-            .await?
-            .ok_or(RepoError::NotFound)?;
-        Some(repo.name)
-    } else {
-        None
-    };
-
-    let workspace = Workspace::create(
-        pool,
-        &CreateWorkspace {
-            branch: git_branch_name,
-            agent_working_dir,
-        },
-        attempt_id,
-        task.id,
-    )
-    .await?;
-
     let workspace_repos: Vec<CreateWorkspaceRepo> = payload
         .repos
         .iter()
@@ -214,7 +299,10 @@ pub async fn create_task_and_start(
             target_branch: r.target_branch.clone(),
         })
         .collect();
-    WorkspaceRepo::create_many(&deployment.db().pool, workspace.id, &workspace_repos).await?;
+    let workspace = deployment
+        .container()
+        .create_workspace_for_task(&task, &workspace_repos, None)
+        .await?;
 
     let is_attempt_running = deployment
         .container()
@@ -457,6 +545,103 @@ pub async fn share_task(
     })))
 }
 
+pub async fn get_task_dependencies(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<TaskDependencies>>, ApiError> {
+    let dependencies = TaskDependency::find_for_task(&deployment.db().pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(dependencies)))
+}
+
+pub async fn add_task_dependency(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateTaskDependency>,
+) -> Result<ResponseJson<ApiResponse<TaskDependency>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let depends_on = Task::find_by_id(pool, payload.depends_on_task_id)
+        .await?
+        .ok_or(ApiError::BadRequest(
+            "Dependency task not found".to_string(),
+        ))?;
+
+    let dependency = TaskDependency::create(pool, &task, &depends_on).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_dependency_added",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "depends_on_task_id": depends_on.id.to_string(),
+                "project_id": task.project_id.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(dependency)))
+}
+
+pub async fn remove_task_dependency(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Path((_, depends_on_task_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected =
+        TaskDependency::delete(&deployment.db().pool, task.id, depends_on_task_id).await?;
+    if rows_affected == 0 {
+        return Err(TaskDependencyError::NotFound.into());
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub async fn get_task_budget(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<TaskBudget>>>, ApiError> {
+    let budget = TaskBudget::find_by_task_id(&deployment.db().pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(budget)))
+}
+
+pub async fn set_task_budget(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<RunBudget>,
+) -> Result<ResponseJson<ApiResponse<TaskBudget>>, ApiError> {
+    if payload.budget_max_tokens == Some(0)
+        || payload.budget_max_minutes == Some(0)
+        || payload.budget_max_tool_calls == Some(0)
+    {
+        return Err(ApiError::BadRequest(
+            "Budget limits must be greater than zero".to_string(),
+        ));
+    }
+
+    let budget = TaskBudget::upsert(&deployment.db().pool, task.id, &payload).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_budget_set",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "project_id": task.project_id.to_string(),
+                "has_token_limit": payload.budget_max_tokens.is_some(),
+                "has_time_limit": payload.budget_max_minutes.is_some(),
+                "has_tool_call_limit": payload.budget_max_tool_calls.is_some(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(budget)))
+}
+
+pub async fn delete_task_budget(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    TaskBudget::delete(&deployment.db().pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let task_actions_router = Router::new()
         .route("/", put(update_task))
@@ -465,6 +650,21 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
 
     let task_id_router = Router::new()
         .route("/", get(get_task))
+        .route("/issue-link", get(get_task_issue_link))
+        .route(
+            "/dependencies",
+            get(get_task_dependencies).post(add_task_dependency),
+        )
+        .route(
+            "/dependencies/{depends_on_task_id}",
+            delete(remove_task_dependency),
+        )
+        .route(
+            "/budget",
+            get(get_task_budget)
+                .put(set_task_budget)
+                .delete(delete_task_budget),
+        )
         .merge(task_actions_router)
         .layer(from_fn_with_state(deployment.clone(), load_task_middleware));
 
@@ -472,6 +672,7 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/", get(get_tasks).post(create_task))
         .route("/stream/ws", get(stream_tasks_ws))
         .route("/create-and-start", post(create_task_and_start))
+        .route("/import", post(import_tasks))
         .nest("/{task_id}", task_id_router);
 
     // mount under /projects/:project_id/tasks
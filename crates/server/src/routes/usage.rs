@@ -0,0 +1,52 @@
+use axum::{
+    Router,
+    extract::{Query, State},
+    response::Json as ResponseJson,
+    routing::get,
+};
+use chrono::{DateTime, Utc};
+use db::models::execution_process_usage::{
+    ExecutionProcessUsage, UsageFilter, UsageGroupBy, UsageSummary,
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize)]
+pub struct UsageQuery {
+    pub group_by: UsageGroupBy,
+    pub project_id: Option<Uuid>,
+    pub task_id: Option<Uuid>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+pub async fn get_usage_summary(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<UsageQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<UsageSummary>>>, ApiError> {
+    if let (Some(since), Some(until)) = (query.since, query.until)
+        && since >= until
+    {
+        return Err(ApiError::BadRequest(
+            "`since` must be earlier than `until`".to_string(),
+        ));
+    }
+
+    let filter = UsageFilter {
+        project_id: query.project_id,
+        task_id: query.task_id,
+        since: query.since,
+        until: query.until,
+    };
+    let summary =
+        ExecutionProcessUsage::summarize(&deployment.db().pool, query.group_by, &filter).await?;
+    Ok(ResponseJson(ApiResponse::success(summary)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new().route("/usage", get(get_usage_summary))
+}
@@ -0,0 +1,236 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Query, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use chrono::Utc;
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+    execution_process_usage::ExecutionProcessUsage,
+    session::Session,
+    workspace::Workspace,
+    workspace_group::{WorkspaceGroup, WorkspaceGroupMember},
+};
+use deployment::Deployment;
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use services::services::container::ContainerService;
+use sqlx::Error as SqlxError;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::load_workspace_group_middleware,
+    routes::task_attempts::workspace_summary::{DiffStats, compute_workspace_diff_stats},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct WorkspaceGroupQuery {
+    pub task_id: Uuid,
+}
+
+/// How one attempt of a fan-out did, for side by side comparison
+#[derive(Debug, Serialize, TS)]
+pub struct WorkspaceGroupAttempt {
+    pub workspace: Workspace,
+    pub executor_profile_id: ExecutorProfileId,
+    /// Changes against the target branches; null when the worktree is not available
+    pub diff_stats: Option<DiffStats>,
+    /// Status of the latest coding agent run
+    pub coding_agent_status: Option<ExecutionProcessStatus>,
+    pub setup_script_exit_code: Option<i64>,
+    pub cleanup_script_exit_code: Option<i64>,
+    /// Total wall-clock time of the coding agent runs, in seconds
+    pub agent_duration_seconds: i64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cache_read_tokens: i64,
+    pub cache_write_tokens: i64,
+    /// Sum of agent reported costs, null when no run reported one
+    pub cost_usd: Option<f64>,
+    pub is_winner: bool,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct WorkspaceGroupComparison {
+    pub group: WorkspaceGroup,
+    pub attempts: Vec<WorkspaceGroupAttempt>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct PickWorkspaceGroupWinner {
+    pub workspace_id: Uuid,
+}
+
+pub async fn get_workspace_groups(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<WorkspaceGroupQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<WorkspaceGroup>>>, ApiError> {
+    let groups = WorkspaceGroup::find_by_task_id(&deployment.db().pool, query.task_id).await?;
+    Ok(ResponseJson(ApiResponse::success(groups)))
+}
+
+pub async fn get_workspace_group_comparison(
+    Extension(group): Extension<WorkspaceGroup>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<WorkspaceGroupComparison>>, ApiError> {
+    let comparison = compare_attempts(&deployment, group).await?;
+    Ok(ResponseJson(ApiResponse::success(comparison)))
+}
+
+/// Keep one attempt of the group and archive the others, stopping anything still running in them
+pub async fn pick_workspace_group_winner(
+    Extension(group): Extension<WorkspaceGroup>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<PickWorkspaceGroupWinner>,
+) -> Result<ResponseJson<ApiResponse<WorkspaceGroupComparison>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let members = group.members(pool).await?;
+    if !members
+        .iter()
+        .any(|member| member.workspace_id == payload.workspace_id)
+    {
+        return Err(ApiError::BadRequest(
+            "Workspace is not part of this group".to_string(),
+        ));
+    }
+
+    WorkspaceGroup::set_winner(pool, group.id, payload.workspace_id).await?;
+
+    for member in members
+        .iter()
+        .filter(|member| member.workspace_id != payload.workspace_id)
+    {
+        let Some(workspace) = Workspace::find_by_id(pool, member.workspace_id).await? else {
+            continue;
+        };
+        deployment.container().try_stop(&workspace, true).await;
+        Workspace::set_archived(pool, workspace.id, true).await?;
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "workspace_group_winner_picked",
+            serde_json::json!({
+                "workspace_group_id": group.id.to_string(),
+                "workspace_id": payload.workspace_id.to_string(),
+                "attempt_count": members.len(),
+            }),
+        )
+        .await;
+
+    let group = WorkspaceGroup::find_by_id(pool, group.id)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+    let comparison = compare_attempts(&deployment, group).await?;
+    Ok(ResponseJson(ApiResponse::success(comparison)))
+}
+
+async fn compare_attempts(
+    deployment: &DeploymentImpl,
+    group: WorkspaceGroup,
+) -> Result<WorkspaceGroupComparison, ApiError> {
+    let pool = &deployment.db().pool;
+    let mut attempts = Vec::new();
+    for member in group.members(pool).await? {
+        let Some(workspace) = Workspace::find_by_id(pool, member.workspace_id).await? else {
+            continue;
+        };
+        attempts.push(compare_attempt(deployment, &group, member, workspace).await?);
+    }
+    Ok(WorkspaceGroupComparison { group, attempts })
+}
+
+async fn compare_attempt(
+    deployment: &DeploymentImpl,
+    group: &WorkspaceGroup,
+    member: WorkspaceGroupMember,
+    workspace: Workspace,
+) -> Result<WorkspaceGroupAttempt, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let diff_stats = if workspace.container_ref.is_some() {
+        compute_workspace_diff_stats(deployment, &workspace)
+            .await
+            .ok()
+    } else {
+        None
+    };
+
+    let setup_script_exit_code = ExecutionProcess::find_latest_by_workspace_and_run_reason(
+        pool,
+        workspace.id,
+        &ExecutionProcessRunReason::SetupScript,
+    )
+    .await?
+    .and_then(|process| process.exit_code);
+    let cleanup_script_exit_code = ExecutionProcess::find_latest_by_workspace_and_run_reason(
+        pool,
+        workspace.id,
+        &ExecutionProcessRunReason::CleanupScript,
+    )
+    .await?
+    .and_then(|process| process.exit_code);
+
+    let mut coding_agent_runs = Vec::new();
+    for session in Session::find_by_workspace_id(pool, workspace.id).await? {
+        coding_agent_runs.extend(
+            ExecutionProcess::find_by_session_id(pool, session.id, false)
+                .await?
+                .into_iter()
+                .filter(|process| process.run_reason == ExecutionProcessRunReason::CodingAgent),
+        );
+    }
+    coding_agent_runs.sort_by_key(|process| process.started_at);
+    let coding_agent_status = coding_agent_runs.last().map(|p| p.status.clone());
+    let agent_duration_seconds = coding_agent_runs
+        .iter()
+        .map(|process| {
+            let end = process.completed_at.unwrap_or_else(Utc::now);
+            (end - process.started_at).num_seconds().max(0)
+        })
+        .sum();
+
+    let usage = ExecutionProcessUsage::find_by_workspace_id(pool, workspace.id).await?;
+    let cost_usd = usage
+        .iter()
+        .filter_map(|u| u.cost_usd)
+        .reduce(|total, cost| total + cost);
+
+    Ok(WorkspaceGroupAttempt {
+        is_winner: group.winner_workspace_id == Some(workspace.id),
+        executor_profile_id: member.executor_profile_id.0,
+        diff_stats,
+        coding_agent_status,
+        setup_script_exit_code,
+        cleanup_script_exit_code,
+        agent_duration_seconds,
+        input_tokens: usage.iter().map(|u| u.input_tokens).sum(),
+        output_tokens: usage.iter().map(|u| u.output_tokens).sum(),
+        cache_read_tokens: usage.iter().map(|u| u.cache_read_tokens).sum(),
+        cache_write_tokens: usage.iter().map(|u| u.cache_write_tokens).sum(),
+        cost_usd,
+        workspace,
+    })
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let group_router = Router::new()
+        .route("/comparison", get(get_workspace_group_comparison))
+        .route("/winner", post(pick_workspace_group_winner))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_workspace_group_middleware,
+        ));
+
+    let inner = Router::new()
+        .route("/", get(get_workspace_groups))
+        .nest("/{group_id}", group_router);
+
+    Router::new().nest("/workspace-groups", inner)
+}
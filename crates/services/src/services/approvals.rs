@@ -8,13 +8,15 @@ use std::{
 
 use dashmap::DashMap;
 use db::models::{
+    approval_timeout::{ApprovalTimeout, ApprovalTimeoutResolution, CreateApprovalTimeout},
     execution_process::ExecutionProcess,
     task::{Task, TaskStatus},
 };
 use executors::{
+    approval_policy::{ApprovalRule, ApprovalRuleDecision, ApprovalRuleSet},
     approvals::ToolCallMetadata,
     logs::{
-        NormalizedEntry, NormalizedEntryType, ToolStatus,
+        ActionType, NormalizedEntry, NormalizedEntryType, ToolStatus,
         utils::patch::{ConversationPatch, extract_normalized_entry_from_patch},
     },
 };
@@ -78,9 +80,13 @@ impl Approvals {
         }
     }
 
+    /// Create a pending approval for a tool call. `on_timeout` is what it resolves to when
+    /// nobody responds before `request.timeout_at`.
     pub async fn create_with_waiter(
         &self,
+        pool: &SqlitePool,
         request: ApprovalRequest,
+        on_timeout: ApprovalTimeoutResolution,
     ) -> Result<(ApprovalRequest, ApprovalWaiter), ApprovalError> {
         let (tx, rx) = oneshot::channel();
         let waiter: ApprovalWaiter = rx
@@ -98,7 +104,7 @@ impl Approvals {
                     .with_tool_status(ToolStatus::PendingApproval {
                         approval_id: req_id.clone(),
                         requested_at: request.created_at,
-                        timeout_at: request.timeout_at,
+                        timeout_at: Some(request.timeout_at),
                     })
                     .ok_or(ApprovalError::NoToolUseEntry)?;
                 store.push_patch(ConversationPatch::replace(idx, approval_entry));
@@ -133,10 +139,62 @@ impl Approvals {
             );
         }
 
-        self.spawn_timeout_watcher(req_id.clone(), request.timeout_at, waiter.clone());
+        self.spawn_timeout_watcher(pool.clone(), request.clone(), on_timeout, waiter.clone());
         Ok((request, waiter))
     }
 
+    /// Decide a tool call with the first matching rule instead of asking. Returns `None` when no
+    /// rule decides it, in which case a pending approval should be created as usual. The rule is
+    /// recorded in the metadata of the tool use entry.
+    pub async fn check_policy(
+        &self,
+        execution_process_id: Uuid,
+        tool_name: &str,
+        tool_call_id: &str,
+        rules: &ApprovalRuleSet,
+    ) -> Option<ApprovalStatus> {
+        if rules.is_empty() {
+            return None;
+        }
+
+        let store = self.msg_store_by_id(&execution_process_id).await;
+        let tool_use = store
+            .as_ref()
+            .and_then(|store| find_matching_tool_use(store.clone(), tool_call_id));
+        let action = tool_use
+            .as_ref()
+            .and_then(|(_, entry)| match &entry.entry_type {
+                NormalizedEntryType::ToolUse { action_type, .. } => Some(action_type),
+                _ => None,
+            });
+
+        let rule = rules.find_match(tool_name, action)?;
+        let status = match rule.decision {
+            ApprovalRuleDecision::Allow => ApprovalStatus::Approved,
+            ApprovalRuleDecision::Deny => ApprovalStatus::Denied {
+                reason: Some(rule.denial_reason()),
+            },
+            ApprovalRuleDecision::Ask => return None,
+        };
+        tracing::debug!(
+            "Approval rule '{}' decided tool '{}' ({}): {:?}",
+            rule.name,
+            tool_name,
+            tool_call_id,
+            rule.decision
+        );
+
+        if let (Some(store), Some((idx, entry))) = (store, tool_use)
+            && let Some(tool_status) = ToolStatus::from_approval_status(&status)
+            && let Some(mut updated_entry) = entry.with_tool_status(tool_status)
+        {
+            record_approval_rule(&mut updated_entry, rule);
+            store.push_patch(ConversationPatch::replace(idx, updated_entry));
+        }
+
+        Some(status)
+    }
+
     #[tracing::instrument(skip(self, id, req))]
     pub async fn respond(
         &self,
@@ -193,35 +251,75 @@ impl Approvals {
         }
     }
 
-    #[tracing::instrument(skip(self, id, timeout_at, waiter))]
+    #[tracing::instrument(skip(self, pool, request, waiter))]
     fn spawn_timeout_watcher(
         &self,
-        id: String,
-        timeout_at: chrono::DateTime<chrono::Utc>,
+        pool: SqlitePool,
+        request: ApprovalRequest,
+        on_timeout: ApprovalTimeoutResolution,
         waiter: ApprovalWaiter,
     ) {
         let pending = self.pending.clone();
         let completed = self.completed.clone();
         let msg_stores = self.msg_stores.clone();
+        let id = request.id.clone();
 
         let now = chrono::Utc::now();
-        let to_wait = (timeout_at - now)
+        let to_wait = (request.timeout_at - now)
             .to_std()
             .unwrap_or_else(|_| StdDuration::from_secs(0));
         let deadline = tokio::time::Instant::now() + to_wait;
 
         tokio::spawn(async move {
-            let status = tokio::select! {
+            let resolved = tokio::select! {
                 biased;
 
-                resolved = waiter.clone() => resolved,
-                _ = tokio::time::sleep_until(deadline) => ApprovalStatus::TimedOut,
+                resolved = waiter.clone() => Some(resolved),
+                _ = tokio::time::sleep_until(deadline) => None,
             };
+            if let Some(status) = resolved {
+                completed.insert(id, status);
+                return;
+            }
+            record_timeout(&pool, &request, on_timeout).await;
 
-            let is_timeout = matches!(&status, ApprovalStatus::TimedOut);
+            let (status, tool_status) = match on_timeout {
+                ApprovalTimeoutResolution::Denied => {
+                    (ApprovalStatus::TimedOut, ToolStatus::TimedOut)
+                }
+                ApprovalTimeoutResolution::Approved => {
+                    (ApprovalStatus::Approved, ToolStatus::Created)
+                }
+                ApprovalTimeoutResolution::Waiting => {
+                    // Leave the request pending; the run stays paused until someone responds.
+                    // The deadline is cleared so the conversation keeps offering a response.
+                    tracing::info!("Approval '{}' timed out, still waiting for a response", id);
+                    let Some((entry_index, entry, execution_process_id)) = pending
+                        .get(&id)
+                        .map(|p| (p.entry_index, p.entry.clone(), p.execution_process_id))
+                    else {
+                        return;
+                    };
+                    let store = {
+                        let map = msg_stores.read().await;
+                        map.get(&execution_process_id).cloned()
+                    };
+                    if let Some(store) = store
+                        && let Some(waiting_entry) =
+                            entry.with_tool_status(ToolStatus::PendingApproval {
+                                approval_id: id.clone(),
+                                requested_at: request.created_at,
+                                timeout_at: None,
+                            })
+                    {
+                        store.push_patch(ConversationPatch::replace(entry_index, waiting_entry));
+                    }
+                    return;
+                }
+            };
             completed.insert(id.clone(), status.clone());
 
-            if is_timeout && let Some((_, pending_approval)) = pending.remove(&id) {
+            if let Some((_, pending_approval)) = pending.remove(&id) {
                 if pending_approval.response_tx.send(status.clone()).is_err() {
                     tracing::debug!("approval '{}' timeout notification receiver dropped", id);
                 }
@@ -232,9 +330,8 @@ impl Approvals {
                 };
 
                 if let Some(store) = store {
-                    if let Some(updated_entry) = pending_approval
-                        .entry
-                        .with_tool_status(ToolStatus::TimedOut)
+                    if let Some(updated_entry) =
+                        pending_approval.entry.with_tool_status(tool_status)
                     {
                         store.push_patch(ConversationPatch::replace(
                             pending_approval.entry_index,
@@ -256,6 +353,25 @@ impl Approvals {
         });
     }
 
+    /// Whether an approval request is still waiting for a response
+    pub fn is_pending(&self, id: &str) -> bool {
+        self.pending.contains_key(id)
+    }
+
+    /// Normalized action of a tool call that has not been decided yet, if its entry is known
+    pub async fn tool_action(
+        &self,
+        execution_process_id: Uuid,
+        tool_call_id: &str,
+    ) -> Option<ActionType> {
+        let store = self.msg_store_by_id(&execution_process_id).await?;
+        let (_, entry) = find_matching_tool_use(store, tool_call_id)?;
+        match entry.entry_type {
+            NormalizedEntryType::ToolUse { action_type, .. } => Some(action_type),
+            _ => None,
+        }
+    }
+
     async fn msg_store_by_id(&self, execution_process_id: &Uuid) -> Option<Arc<MsgStore>> {
         let map = self.msg_stores.read().await;
         map.get(execution_process_id).cloned()
@@ -294,6 +410,45 @@ pub(crate) async fn ensure_task_in_review(pool: &SqlitePool, execution_process_i
     }
 }
 
+async fn record_timeout(
+    pool: &SqlitePool,
+    request: &ApprovalRequest,
+    resolution: ApprovalTimeoutResolution,
+) {
+    let data = CreateApprovalTimeout {
+        approval_id: &request.id,
+        execution_process_id: request.execution_process_id,
+        tool_name: &request.tool_name,
+        tool_input: &request.tool_input,
+        tool_call_id: &request.tool_call_id,
+        resolution,
+        requested_at: request.created_at,
+    };
+    if let Err(e) = ApprovalTimeout::create(pool, data).await {
+        tracing::error!(
+            "Failed to record timeout of approval '{}': {}",
+            request.id,
+            e
+        );
+    }
+}
+
+/// Note which approval rule decided a tool call in its entry's metadata
+fn record_approval_rule(entry: &mut NormalizedEntry, rule: &ApprovalRule) {
+    let record = serde_json::json!({
+        "name": rule.name,
+        "decision": rule.decision,
+    });
+    match &mut entry.metadata {
+        Some(serde_json::Value::Object(metadata)) => {
+            metadata.insert("approval_rule".to_string(), record);
+        }
+        metadata => {
+            *metadata = Some(serde_json::json!({ "approval_rule": record }));
+        }
+    }
+}
+
 /// Find a matching tool use entry that hasn't been assigned to an approval yet
 /// Matches by tool call id from tool metadata
 fn find_matching_tool_use(
@@ -404,7 +559,7 @@ mod tests {
             ToolStatus::PendingApproval {
                 approval_id: "test-id".to_string(),
                 requested_at: chrono::Utc::now(),
-                timeout_at: chrono::Utc::now(),
+                timeout_at: Some(chrono::Utc::now()),
             },
         );
         store.push_patch(
@@ -422,4 +577,78 @@ mod tests {
             "Should not match different tool ids"
         );
     }
+
+    #[tokio::test]
+    async fn test_policy_decides_without_pending_approval() {
+        let store = Arc::new(MsgStore::new());
+        let execution_process_id = Uuid::new_v4();
+        let push = NormalizedEntry {
+            timestamp: None,
+            entry_type: NormalizedEntryType::ToolUse {
+                tool_name: "Bash".to_string(),
+                action_type: ActionType::CommandRun {
+                    command: "git push --force".to_string(),
+                    result: None,
+                },
+                status: ToolStatus::Created,
+            },
+            content: "git push --force".to_string(),
+            metadata: Some(serde_json::json!({ "tool_call_id": "push-id" })),
+        };
+        store.push_patch(ConversationPatch::add_normalized_entry(0, push));
+
+        let msg_stores = Arc::new(RwLock::new(HashMap::from([(
+            execution_process_id,
+            store.clone(),
+        )])));
+        let approvals = Approvals::new(msg_stores);
+        let rules = ApprovalRuleSet::new(&[ApprovalRule {
+            name: "no force push".to_string(),
+            decision: ApprovalRuleDecision::Deny,
+            tool_name: None,
+            command_pattern: Some("^git push .*--force".to_string()),
+            path_globs: Vec::new(),
+            domains: Vec::new(),
+            reason: None,
+            safe: false,
+        }]);
+
+        let status = approvals
+            .check_policy(execution_process_id, "Bash", "push-id", &rules)
+            .await;
+        assert!(matches!(
+            status,
+            Some(ApprovalStatus::Denied { reason: Some(ref reason) })
+                if reason == "Denied by approval rule 'no force push'"
+        ));
+        assert!(approvals.pending.is_empty());
+
+        let (_, entry) = store
+            .get_history()
+            .iter()
+            .rev()
+            .find_map(|msg| match msg {
+                LogMsg::JsonPatch(patch) => extract_normalized_entry_from_patch(patch),
+                _ => None,
+            })
+            .expect("tool use entry");
+        assert!(matches!(
+            entry.entry_type,
+            NormalizedEntryType::ToolUse {
+                status: ToolStatus::Denied { .. },
+                ..
+            }
+        ));
+        assert_eq!(
+            entry.metadata.unwrap()["approval_rule"]["name"],
+            "no force push"
+        );
+
+        assert!(
+            approvals
+                .check_policy(execution_process_id, "Edit", "other-id", &rules)
+                .await
+                .is_none()
+        );
+    }
 }
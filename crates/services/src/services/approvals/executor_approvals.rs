@@ -1,19 +1,31 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use db::{self, DBService, models::execution_process::ExecutionProcess};
-use executors::approvals::{ExecutorApprovalError, ExecutorApprovalService};
+use db::{
+    self, DBService,
+    models::{approval_timeout::ApprovalTimeoutResolution, execution_process::ExecutionProcess},
+};
+use executors::{
+    approval_policy::{ApprovalPolicy, ApprovalRuleSet, ApprovalTimeoutOutcome},
+    approvals::{ExecutorApprovalError, ExecutorApprovalService},
+};
 use serde_json::Value;
 use utils::approvals::{ApprovalRequest, ApprovalStatus, CreateApprovalRequest};
 use uuid::Uuid;
 
-use crate::services::{approvals::Approvals, notification::NotificationService};
+use crate::services::{
+    approvals::Approvals, config::NotificationEvent, notification::NotificationService,
+};
 
 pub struct ExecutorApprovalBridge {
     approvals: Approvals,
     db: DBService,
     notification_service: NotificationService,
     execution_process_id: Uuid,
+    /// Profile variant policy, with the project rules ahead of the profile rules
+    approval_policy: ApprovalPolicy,
+    /// Rules of `approval_policy`, compiled once for the whole run
+    rules: ApprovalRuleSet,
 }
 
 impl ExecutorApprovalBridge {
@@ -22,14 +34,77 @@ impl ExecutorApprovalBridge {
         db: DBService,
         notification_service: NotificationService,
         execution_process_id: Uuid,
+        approval_policy: ApprovalPolicy,
     ) -> Arc<Self> {
+        let rules = approval_policy.rule_set();
         Arc::new(Self {
             approvals,
             db,
             notification_service,
             execution_process_id,
+            approval_policy,
+            rules,
         })
     }
+
+    /// Remind before the request times out, and tell the user when a run stays paused on it
+    fn spawn_escalation(&self, request: &ApprovalRequest, task_name: String) {
+        let reminder_at = self
+            .approval_policy
+            .approval_reminder_minutes
+            .map(|minutes| request.timeout_at - chrono::Duration::minutes(i64::from(minutes)))
+            .filter(|reminder_at| *reminder_at > request.created_at);
+        let keeps_waiting =
+            self.approval_policy.approval_timeout_outcome == Some(ApprovalTimeoutOutcome::Wait);
+        if reminder_at.is_none() && !keeps_waiting {
+            return;
+        }
+
+        let approvals = self.approvals.clone();
+        let notification_service = self.notification_service.clone();
+        let approval_id = request.id.clone();
+        let tool_name = request.tool_name.clone();
+        let timeout_at = request.timeout_at;
+        tokio::spawn(async move {
+            if let Some(reminder_at) = reminder_at {
+                sleep_until(reminder_at).await;
+                if !approvals.is_pending(&approval_id) {
+                    return;
+                }
+                let minutes_left = (timeout_at - chrono::Utc::now()).num_minutes().max(1);
+                notification_service
+                    .notify_event(
+                        NotificationEvent::ApprovalPending,
+                        &format!("Approval Expiring: {task_name}"),
+                        &format!(
+                            "Tool '{tool_name}' still needs approval; the request times out in {minutes_left} min"
+                        ),
+                    )
+                    .await;
+            }
+
+            if keeps_waiting {
+                sleep_until(timeout_at).await;
+                if approvals.is_pending(&approval_id) {
+                    notification_service
+                        .notify_event(
+                            NotificationEvent::ApprovalPending,
+                            &format!("Run Paused: {task_name}"),
+                            &format!(
+                                "Approval for tool '{tool_name}' timed out; the run is paused until you respond"
+                            ),
+                        )
+                        .await;
+                }
+            }
+        });
+    }
+}
+
+async fn sleep_until(at: chrono::DateTime<chrono::Utc>) {
+    if let Ok(duration) = (at - chrono::Utc::now()).to_std() {
+        tokio::time::sleep(duration).await;
+    }
 }
 
 #[async_trait]
@@ -40,8 +115,44 @@ impl ExecutorApprovalService for ExecutorApprovalBridge {
         tool_input: Value,
         tool_call_id: &str,
     ) -> Result<ApprovalStatus, ExecutorApprovalError> {
+        if let Some(status) = self
+            .approvals
+            .check_policy(
+                self.execution_process_id,
+                tool_name,
+                tool_call_id,
+                &self.rules,
+            )
+            .await
+        {
+            return Ok(status);
+        }
+
         super::ensure_task_in_review(&self.db.pool, self.execution_process_id).await;
 
+        let on_timeout = match self
+            .approval_policy
+            .approval_timeout_outcome
+            .unwrap_or_default()
+        {
+            ApprovalTimeoutOutcome::Deny => ApprovalTimeoutResolution::Denied,
+            ApprovalTimeoutOutcome::Wait => ApprovalTimeoutResolution::Waiting,
+            ApprovalTimeoutOutcome::ApproveIfSafe => {
+                let action = self
+                    .approvals
+                    .tool_action(self.execution_process_id, tool_call_id)
+                    .await;
+                if self
+                    .approval_policy
+                    .approves_on_timeout(&self.rules, tool_name, action.as_ref())
+                {
+                    ApprovalTimeoutResolution::Approved
+                } else {
+                    ApprovalTimeoutResolution::Denied
+                }
+            }
+        };
+
         let request = ApprovalRequest::from_create(
             CreateApprovalRequest {
                 tool_name: tool_name.to_string(),
@@ -49,11 +160,12 @@ impl ExecutorApprovalService for ExecutorApprovalBridge {
                 tool_call_id: tool_call_id.to_string(),
             },
             self.execution_process_id,
-        );
+        )
+        .with_timeout_seconds(self.approval_policy.timeout_seconds());
 
-        let (_, waiter) = self
+        let (request, waiter) = self
             .approvals
-            .create_with_waiter(request)
+            .create_with_waiter(&self.db.pool, request, on_timeout)
             .await
             .map_err(ExecutorApprovalError::request_failed)?;
 
@@ -63,11 +175,13 @@ impl ExecutorApprovalService for ExecutorApprovalBridge {
             .unwrap_or_else(|_| "Unknown task".to_string());
 
         self.notification_service
-            .notify(
+            .notify_event(
+                NotificationEvent::ApprovalPending,
                 &format!("Approval Needed: {}", task_name),
                 &format!("Tool '{}' requires approval", tool_name),
             )
             .await;
+        self.spawn_escalation(&request, task_name);
 
         let status = waiter.clone().await;
 
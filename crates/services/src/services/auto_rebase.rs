@@ -0,0 +1,396 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use db::models::{
+    execution_process::ExecutionProcess,
+    merge_queue::{MergeQueueEntry, MergeQueueStatus},
+    session::Session,
+    task::TaskStatus,
+    workspace::{Workspace, WorkspaceError},
+    workspace_repo::{RepoWithTargetBranch, WorkspaceRepo},
+};
+use sqlx::error::Error as SqlxError;
+use thiserror::Error;
+use tokio::{sync::RwLock, time::interval};
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+use crate::services::{
+    config::Config,
+    container::{ContainerError, ContainerService},
+    git::GitServiceError,
+    workspace_manager::WorkspaceManager,
+};
+
+#[derive(Debug, Error)]
+enum AutoRebaseServiceError {
+    #[error(transparent)]
+    Container(#[from] ContainerError),
+    #[error(transparent)]
+    WorkspaceError(#[from] WorkspaceError),
+    #[error(transparent)]
+    Sqlx(#[from] SqlxError),
+}
+
+/// A repo of a workspace whose rebase stopped on conflicts
+#[derive(Debug, Clone)]
+pub struct RebaseConflict {
+    pub repo_name: String,
+    pub target_branch: String,
+    pub files: Vec<String>,
+}
+
+/// Follow-up asking the agent to finish rebases that stopped on conflicts
+pub fn conflict_prompt(conflicts: &[RebaseConflict]) -> String {
+    let mut prompt = String::from(
+        "The workspace was rebased automatically because its target branch moved, and the rebase stopped on conflicts.\n",
+    );
+    for conflict in conflicts {
+        prompt.push_str(&format!(
+            "\nRepository `{}`, rebasing onto `{}`:\n",
+            conflict.repo_name, conflict.target_branch
+        ));
+        if conflict.files.is_empty() {
+            prompt.push_str("- (run `git status` to list the conflicted files)\n");
+        }
+        for file in &conflict.files {
+            prompt.push_str(&format!("- {file}\n"));
+        }
+    }
+    prompt.push_str(
+        "\nResolve the conflicts keeping the intent of both sides, `git add` the resolved files and run `git rebase --continue` until the rebase completes. Do not abort the rebase.",
+    );
+    prompt
+}
+
+/// Service that rebases idle workspaces onto their target branch once it has moved. Workspaces
+/// stacked on another workspace are always kept on top of its branch; all others only when
+/// auto-rebase is enabled.
+pub struct AutoRebaseService<C> {
+    container: C,
+    config: Arc<RwLock<Config>>,
+    poll_interval: Duration,
+    /// Target branch commit each conflicted (workspace, repo) was last rebased onto, so the
+    /// same conflicts are not retried until the target moves again
+    conflicted_targets: HashMap<(Uuid, Uuid), String>,
+    /// Remote target branches fetched during the current pass, as (repo path, branch)
+    fetched_targets: HashSet<(PathBuf, String)>,
+}
+
+impl<C> AutoRebaseService<C>
+where
+    C: ContainerService + Send + Sync + 'static,
+{
+    pub fn spawn(container: C, config: Arc<RwLock<Config>>) -> tokio::task::JoinHandle<()> {
+        let service = Self {
+            container,
+            config,
+            poll_interval: Duration::from_secs(300),
+            conflicted_targets: HashMap::new(),
+            fetched_targets: HashSet::new(),
+        };
+        tokio::spawn(async move {
+            service.start().await;
+        })
+    }
+
+    async fn start(mut self) {
+        info!(
+            "Starting auto-rebase service with interval {:?}",
+            self.poll_interval
+        );
+
+        let mut interval = interval(self.poll_interval);
+
+        loop {
+            interval.tick().await;
+            let stacked_only = !self.config.read().await.auto_rebase.enabled;
+            if let Err(e) = self.rebase_idle_workspaces(stacked_only).await {
+                error!("Error rebasing idle workspaces: {}", e);
+            }
+        }
+    }
+
+    async fn rebase_idle_workspaces(
+        &mut self,
+        stacked_only: bool,
+    ) -> Result<(), AutoRebaseServiceError> {
+        self.fetched_targets.clear();
+        let pool = self.container.db().pool.clone();
+        let workspaces: Vec<Workspace> = Workspace::fetch_all(&pool, None)
+            .await?
+            .into_iter()
+            .filter(|workspace| {
+                !workspace.archived
+                    && workspace.container_ref.is_some()
+                    && (!stacked_only || workspace.base_workspace_id.is_some())
+            })
+            .collect();
+
+        for workspace in workspaces {
+            let Some(task) = workspace.parent_task(&pool).await? else {
+                continue;
+            };
+            if matches!(task.status, TaskStatus::Done | TaskStatus::Cancelled) {
+                continue;
+            }
+            if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(
+                &pool,
+                workspace.id,
+            )
+            .await?
+            {
+                debug!("Skipping auto-rebase of busy workspace {}", workspace.id);
+                continue;
+            }
+            // The merge queue rebases queued workspaces itself
+            let queued = MergeQueueEntry::find_by_workspace_id(&pool, workspace.id)
+                .await?
+                .iter()
+                .any(|entry| {
+                    matches!(
+                        entry.status,
+                        MergeQueueStatus::Queued | MergeQueueStatus::Running
+                    )
+                });
+            if queued {
+                debug!("Skipping auto-rebase of queued workspace {}", workspace.id);
+                continue;
+            }
+            let Some(_branch_lock) = WorkspaceManager::try_lock_branches(workspace.id) else {
+                debug!("Skipping auto-rebase of locked workspace {}", workspace.id);
+                continue;
+            };
+            if let Err(e) = self.rebase_workspace(&workspace).await {
+                error!("Error auto-rebasing workspace {}: {}", workspace.id, e);
+            }
+        }
+        Ok(())
+    }
+
+    async fn rebase_workspace(
+        &mut self,
+        workspace: &Workspace,
+    ) -> Result<(), AutoRebaseServiceError> {
+        let pool = self.container.db().pool.clone();
+        let Some(container_ref) = workspace.container_ref.as_deref() else {
+            return Ok(());
+        };
+
+        let mut conflicts = Vec::new();
+        for RepoWithTargetBranch {
+            repo,
+            target_branch,
+        } in
+            WorkspaceRepo::find_repos_with_target_branch_for_workspace(&pool, workspace.id).await?
+        {
+            let worktree_path = PathBuf::from(container_ref).join(&repo.name);
+            if !worktree_path.exists() {
+                continue;
+            }
+            let key = (workspace.id, repo.id);
+
+            // Compare against where a remote target is now; offline, the last fetch will do
+            if self
+                .fetched_targets
+                .insert((repo.path.clone(), target_branch.clone()))
+                && let Err(e) = self
+                    .container
+                    .git()
+                    .fetch_remote_branch(&repo.path, &target_branch)
+            {
+                warn!(
+                    "Failed to fetch target branch {} of repo {}: {}",
+                    target_branch, repo.name, e
+                );
+            }
+
+            match self.rebase_repo(
+                &repo.path,
+                &worktree_path,
+                &workspace.branch,
+                &target_branch,
+                self.conflicted_targets.get(&key).map(String::as_str),
+            ) {
+                Ok(None) => {}
+                Ok(Some(target_oid)) => {
+                    self.conflicted_targets.remove(&key);
+                    info!(
+                        "Rebased workspace {} repo {} onto {} ({})",
+                        workspace.id, repo.name, target_branch, target_oid
+                    );
+                }
+                Err(GitServiceError::MergeConflicts(_)) => {
+                    let target_oid = self
+                        .container
+                        .git()
+                        .get_branch_oid(&repo.path, &target_branch)
+                        .unwrap_or_default();
+                    self.conflicted_targets.insert(key, target_oid);
+                    let files = self
+                        .container
+                        .git()
+                        .get_conflicted_files(&worktree_path)
+                        .unwrap_or_default();
+                    warn!(
+                        "Auto-rebase of workspace {} repo {} onto {} stopped on {} conflicted file(s)",
+                        workspace.id,
+                        repo.name,
+                        target_branch,
+                        files.len()
+                    );
+                    conflicts.push(RebaseConflict {
+                        repo_name: repo.name.clone(),
+                        target_branch,
+                        files,
+                    });
+                }
+                Err(GitServiceError::WorktreeDirty(_, _) | GitServiceError::RebaseInProgress) => {
+                    debug!(
+                        "Skipping auto-rebase of workspace {} repo {}: worktree is not clean",
+                        workspace.id, repo.name
+                    );
+                }
+                Err(e) => {
+                    warn!(
+                        "Auto-rebase of workspace {} repo {} failed: {}",
+                        workspace.id, repo.name, e
+                    );
+                }
+            }
+        }
+
+        if !conflicts.is_empty() {
+            self.handle_conflicts(workspace, &conflicts).await?;
+        }
+        Ok(())
+    }
+
+    /// Rebase the workspace branch when the target has commits it lacks, unless the target is
+    /// still at `conflicted_target`, the commit an earlier rebase stopped on conflicts with.
+    /// Returns the commit of the target it was rebased onto, or `None` when nothing was done.
+    fn rebase_repo(
+        &self,
+        repo_path: &Path,
+        worktree_path: &Path,
+        branch: &str,
+        target_branch: &str,
+        conflicted_target: Option<&str>,
+    ) -> Result<Option<String>, GitServiceError> {
+        let git = self.container.git();
+        if git.is_rebase_in_progress(worktree_path)? {
+            return Err(GitServiceError::RebaseInProgress);
+        }
+
+        let (_, behind) = git.get_branch_status(repo_path, branch, target_branch)?;
+        if behind == 0 {
+            return Ok(None);
+        }
+        let target_oid = git.get_branch_oid(repo_path, target_branch)?;
+        if conflicted_target == Some(target_oid.as_str()) {
+            return Ok(None);
+        }
+
+        git.rebase_branch(
+            repo_path,
+            worktree_path,
+            target_branch,
+            target_branch,
+            branch,
+        )?;
+        Ok(Some(target_oid))
+    }
+
+    /// Hand the conflicts to the agent when configured; otherwise leave the rebase in progress,
+    /// which the workspace shows as conflicts, and tell the user
+    async fn handle_conflicts(
+        &self,
+        workspace: &Workspace,
+        conflicts: &[RebaseConflict],
+    ) -> Result<(), AutoRebaseServiceError> {
+        let pool = &self.container.db().pool;
+        let resolve_with_agent = self
+            .config
+            .read()
+            .await
+            .auto_rebase
+            .resolve_conflicts_with_agent;
+
+        if resolve_with_agent
+            && let Some(session) = Session::find_latest_by_workspace_id(pool, workspace.id).await?
+        {
+            self.container
+                .start_follow_up(workspace, &session, &conflict_prompt(conflicts))
+                .await?;
+            info!(
+                "Started agent follow-up to resolve auto-rebase conflicts in workspace {}",
+                workspace.id
+            );
+            return Ok(());
+        }
+
+        let task_title = workspace
+            .parent_task(pool)
+            .await?
+            .map(|task| task.title)
+            .unwrap_or_else(|| "Unknown task".to_string());
+        let repos = conflicts
+            .iter()
+            .map(|conflict| conflict.repo_name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        self.container
+            .notification_service()
+            .notify(
+                &format!("Rebase Conflicts: {task_title}"),
+                &format!("Automatic rebase onto the updated target branch stopped on conflicts in {repos}"),
+            )
+            .await;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conflict_prompt_lists_files_per_repo() {
+        let prompt = conflict_prompt(&[
+            RebaseConflict {
+                repo_name: "api".to_string(),
+                target_branch: "main".to_string(),
+                files: vec!["src/lib.rs".to_string(), "Cargo.toml".to_string()],
+            },
+            RebaseConflict {
+                repo_name: "web".to_string(),
+                target_branch: "origin/develop".to_string(),
+                files: Vec::new(),
+            },
+        ]);
+        assert!(
+            prompt
+                .contains("Repository `api`, rebasing onto `main`:\n- src/lib.rs\n- Cargo.toml\n")
+        );
+        assert!(
+            prompt
+                .contains("Repository `web`, rebasing onto `origin/develop`:\n- (run `git status`")
+        );
+        assert!(prompt.ends_with("Do not abort the rebase."));
+    }
+
+    #[tokio::test]
+    async fn test_workspace_held_by_merge_queue_is_not_rebased() {
+        let workspace_id = Uuid::new_v4();
+        let queue_lock = WorkspaceManager::lock_branches(workspace_id).await;
+        assert!(WorkspaceManager::try_lock_branches(workspace_id).is_none());
+        assert!(WorkspaceManager::try_lock_branches(Uuid::new_v4()).is_some());
+
+        drop(queue_lock);
+        assert!(WorkspaceManager::try_lock_branches(workspace_id).is_some());
+    }
+}
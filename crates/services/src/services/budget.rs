@@ -0,0 +1,174 @@
+use std::collections::{HashMap, HashSet};
+
+use db::models::execution_process::ExecutionProcessStopReason;
+use executors::{
+    budget::RunBudget,
+    logs::{NormalizedEntryType, utils::patch::extract_normalized_entry_from_patch},
+};
+use utils::log_msg::{LogMsg, ModelTokenUsage};
+
+/// Tracks what a coding agent run has consumed so far and checks it against a [`RunBudget`].
+///
+/// Time is not tracked here; the caller owns the deadline since it has to fire even when the
+/// agent stays silent.
+#[derive(Debug)]
+pub struct BudgetTracker {
+    budget: RunBudget,
+    /// Latest cumulative usage reported per model
+    usage_by_model: HashMap<String, ModelTokenUsage>,
+    /// Entry indices of tool calls seen so far; a tool call is patched several times as its
+    /// status changes, so it is counted by index
+    tool_call_indices: HashSet<usize>,
+}
+
+impl BudgetTracker {
+    pub fn new(budget: RunBudget) -> Self {
+        Self {
+            budget,
+            usage_by_model: HashMap::new(),
+            tool_call_indices: HashSet::new(),
+        }
+    }
+
+    pub fn budget(&self) -> &RunBudget {
+        &self.budget
+    }
+
+    pub fn record(&mut self, msg: &LogMsg) {
+        match msg {
+            LogMsg::TokenUsage(usage) => {
+                self.usage_by_model
+                    .insert(usage.model.clone(), usage.clone());
+            }
+            LogMsg::JsonPatch(patch) => {
+                if let Some((index, entry)) = extract_normalized_entry_from_patch(patch)
+                    && matches!(entry.entry_type, NormalizedEntryType::ToolUse { .. })
+                {
+                    self.tool_call_indices.insert(index);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn total_tokens(&self) -> u64 {
+        self.usage_by_model
+            .values()
+            .map(|usage| {
+                usage.input_tokens
+                    + usage.output_tokens
+                    + usage.cache_read_tokens
+                    + usage.cache_write_tokens
+            })
+            .sum()
+    }
+
+    pub fn tool_calls(&self) -> usize {
+        self.tool_call_indices.len()
+    }
+
+    /// Returns the first limit the run has gone over, if any
+    pub fn exceeded(&self) -> Option<ExecutionProcessStopReason> {
+        if let Some(max_tokens) = self.budget.budget_max_tokens
+            && self.total_tokens() > max_tokens
+        {
+            return Some(ExecutionProcessStopReason::TokenBudgetExceeded);
+        }
+        if let Some(max_tool_calls) = self.budget.budget_max_tool_calls
+            && self.tool_calls() > max_tool_calls as usize
+        {
+            return Some(ExecutionProcessStopReason::ToolCallBudgetExceeded);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::logs::{
+        ActionType, NormalizedEntry, ToolStatus, utils::patch::ConversationPatch,
+    };
+
+    use super::*;
+
+    fn usage(model: &str, input_tokens: u64, output_tokens: u64) -> LogMsg {
+        LogMsg::TokenUsage(ModelTokenUsage {
+            model: model.to_string(),
+            input_tokens,
+            output_tokens,
+            ..Default::default()
+        })
+    }
+
+    fn tool_use(index: usize, status: ToolStatus) -> LogMsg {
+        LogMsg::JsonPatch(ConversationPatch::add_normalized_entry(
+            index,
+            NormalizedEntry {
+                timestamp: None,
+                entry_type: NormalizedEntryType::ToolUse {
+                    tool_name: "bash".to_string(),
+                    action_type: ActionType::Other {
+                        description: "ls".to_string(),
+                    },
+                    status,
+                },
+                content: "ls".to_string(),
+                metadata: None,
+            },
+        ))
+    }
+
+    #[test]
+    fn test_token_usage_is_cumulative_per_model() {
+        let mut tracker = BudgetTracker::new(RunBudget {
+            budget_max_tokens: Some(1_000),
+            ..Default::default()
+        });
+
+        tracker.record(&usage("sonnet", 200, 100));
+        tracker.record(&usage("sonnet", 400, 200));
+        tracker.record(&usage("haiku", 100, 100));
+        assert_eq!(tracker.total_tokens(), 800);
+        assert_eq!(tracker.exceeded(), None);
+
+        tracker.record(&usage("haiku", 200, 100));
+        assert_eq!(tracker.total_tokens(), 900);
+        assert_eq!(tracker.exceeded(), None);
+
+        tracker.record(&usage("sonnet", 500, 250));
+        assert_eq!(
+            tracker.exceeded(),
+            Some(ExecutionProcessStopReason::TokenBudgetExceeded)
+        );
+    }
+
+    #[test]
+    fn test_tool_calls_counted_once_per_entry() {
+        let mut tracker = BudgetTracker::new(RunBudget {
+            budget_max_tool_calls: Some(2),
+            ..Default::default()
+        });
+
+        tracker.record(&tool_use(1, ToolStatus::Created));
+        tracker.record(&tool_use(1, ToolStatus::Success));
+        tracker.record(&tool_use(3, ToolStatus::Created));
+        assert_eq!(tracker.tool_calls(), 2);
+        assert_eq!(tracker.exceeded(), None);
+
+        tracker.record(&tool_use(4, ToolStatus::Created));
+        assert_eq!(
+            tracker.exceeded(),
+            Some(ExecutionProcessStopReason::ToolCallBudgetExceeded)
+        );
+    }
+
+    #[test]
+    fn test_unlimited_budget_never_exceeded() {
+        let mut tracker = BudgetTracker::new(RunBudget::default());
+        tracker.record(&usage("sonnet", u32::MAX as u64, 0));
+        for index in 0..100 {
+            tracker.record(&tool_use(index, ToolStatus::Created));
+        }
+        assert_eq!(tracker.exceeded(), None);
+    }
+}
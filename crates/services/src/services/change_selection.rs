@@ -0,0 +1,279 @@
+//! Selecting part of a workspace's changes, per file or per hunk, to accept into another
+//! branch or to discard from the worktree. Changes are those `GitService::get_diffs` reports
+//! against the merge base; hunks are numbered as `utils::diff::create_unified_diff_hunks` lists
+//! them.
+
+use std::path::Path;
+
+use git2::FileMode;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use ts_rs::TS;
+use utils::diff::{Diff, DiffChangeKind, apply_selected_hunks, create_unified_diff_hunks};
+
+use crate::services::git::{Commit, FileWrite, GitService, GitServiceError};
+
+#[derive(Debug, Error)]
+pub enum ChangeSelectionError {
+    #[error(transparent)]
+    GitService(#[from] GitServiceError),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("{0} has no changes")]
+    UnchangedFile(String),
+    #[error("Hunks of {0} cannot be selected because its contents are binary or too large")]
+    HunksUnavailable(String),
+    #[error("{path} has no hunk {hunk}")]
+    UnknownHunk { path: String, hunk: usize },
+}
+
+/// A changed file to select, or only some of its hunks
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct FileSelection {
+    pub path: String,
+    /// Indices of the selected hunks; the whole file when omitted
+    #[ts(optional)]
+    pub hunks: Option<Vec<usize>>,
+}
+
+/// A changed file and its hunks, for choosing what to select
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct FileHunks {
+    pub path: String,
+    pub old_path: Option<String>,
+    pub change: DiffChangeKind,
+    /// Empty when the file can only be selected as a whole
+    pub hunks: Vec<String>,
+}
+
+/// Path a change is selected by: its new path, or its old one when it was deleted
+fn diff_path(diff: &Diff) -> Option<&str> {
+    diff.new_path.as_deref().or(diff.old_path.as_deref())
+}
+
+/// Both sides of a change as text, when hunks can be computed for it
+fn text_sides(diff: &Diff) -> Option<(&str, &str)> {
+    if diff.content_omitted {
+        return None;
+    }
+    let old = match (&diff.old_content, &diff.old_path) {
+        (Some(content), _) => content.as_str(),
+        (None, None) => "",
+        (None, Some(_)) => return None,
+    };
+    let new = match (&diff.new_content, &diff.new_path) {
+        (Some(content), _) => content.as_str(),
+        (None, None) => "",
+        (None, Some(_)) => return None,
+    };
+    Some((old, new))
+}
+
+pub fn file_hunks(diffs: &[Diff]) -> Vec<FileHunks> {
+    diffs
+        .iter()
+        .filter_map(|diff| {
+            let path = diff_path(diff)?;
+            let hunks = text_sides(diff)
+                .map(|(old, new)| create_unified_diff_hunks(old, new))
+                .unwrap_or_default();
+            Some(FileHunks {
+                path: path.to_string(),
+                old_path: diff.old_path.clone(),
+                change: diff.change.clone(),
+                hunks,
+            })
+        })
+        .collect()
+}
+
+fn find_diff<'a>(diffs: &'a [Diff], path: &str) -> Result<&'a Diff, ChangeSelectionError> {
+    diffs
+        .iter()
+        .find(|diff| diff_path(diff) == Some(path))
+        .ok_or_else(|| ChangeSelectionError::UnchangedFile(path.to_string()))
+}
+
+/// Both sides of a change and its hunk count, checking that the given hunks exist
+fn checked_sides<'a>(
+    diff: &'a Diff,
+    path: &str,
+    hunks: &[usize],
+) -> Result<(&'a str, &'a str, usize), ChangeSelectionError> {
+    let (old, new) =
+        text_sides(diff).ok_or_else(|| ChangeSelectionError::HunksUnavailable(path.to_string()))?;
+    let hunk_count = create_unified_diff_hunks(old, new).len();
+    if let Some(&hunk) = hunks.iter().find(|&&hunk| hunk >= hunk_count) {
+        return Err(ChangeSelectionError::UnknownHunk {
+            path: path.to_string(),
+            hunk,
+        });
+    }
+    Ok((old, new, hunk_count))
+}
+
+/// Writes that apply the selected changes to the merge base the diffs were computed against.
+/// Whole files are taken from the worktree, so binary and large files can be selected too.
+pub fn accepted_writes(
+    worktree_path: &Path,
+    diffs: &[Diff],
+    selections: &[FileSelection],
+) -> Result<Vec<FileWrite>, ChangeSelectionError> {
+    let mut writes = Vec::new();
+    for selection in selections {
+        let diff = find_diff(diffs, &selection.path)?;
+        let moved_from = diff
+            .old_path
+            .as_ref()
+            .filter(|old_path| {
+                matches!(diff.change, DiffChangeKind::Renamed)
+                    && diff.new_path.as_ref() != Some(*old_path)
+            })
+            .cloned();
+
+        let mode = match &diff.new_path {
+            Some(new_path) => worktree_file_mode(&worktree_path.join(new_path))?,
+            None => None,
+        };
+        let content = match (&selection.hunks, &diff.new_path) {
+            (None, Some(new_path)) => Some(read_worktree_file(&worktree_path.join(new_path))?),
+            (None, None) => None,
+            (Some(hunks), _) => {
+                let (old, new, _) = checked_sides(diff, &selection.path, hunks)?;
+                let content = apply_selected_hunks(old, new, hunks);
+                // Selecting every hunk of a deletion deletes the file
+                if matches!(diff.change, DiffChangeKind::Deleted) && content.is_empty() {
+                    None
+                } else {
+                    Some(content.into_bytes())
+                }
+            }
+        };
+        writes.push(FileWrite {
+            path: selection.path.clone(),
+            content,
+            mode,
+        });
+        if let Some(old_path) = moved_from {
+            writes.push(FileWrite {
+                path: old_path,
+                content: None,
+                mode: None,
+            });
+        }
+    }
+    Ok(writes)
+}
+
+/// Writes that revert the selected changes in the worktree, leaving the others in place
+pub fn discarded_writes(
+    git: &GitService,
+    worktree_path: &Path,
+    base_commit: &Commit,
+    diffs: &[Diff],
+    selections: &[FileSelection],
+) -> Result<Vec<FileWrite>, ChangeSelectionError> {
+    let mut writes = Vec::new();
+    for selection in selections {
+        let diff = find_diff(diffs, &selection.path)?;
+        match &selection.hunks {
+            None => {
+                if let Some(new_path) = &diff.new_path
+                    && diff.old_path.as_ref() != Some(new_path)
+                {
+                    writes.push(FileWrite {
+                        path: new_path.clone(),
+                        content: None,
+                        mode: None,
+                    });
+                }
+                if let Some(old_path) = &diff.old_path {
+                    writes.push(FileWrite {
+                        path: old_path.clone(),
+                        content: git.read_file_at_commit(worktree_path, base_commit, old_path)?,
+                        mode: None,
+                    });
+                }
+            }
+            Some(hunks) => {
+                let (old, new, hunk_count) = checked_sides(diff, &selection.path, hunks)?;
+                let kept = (0..hunk_count)
+                    .filter(|hunk| !hunks.contains(hunk))
+                    .collect::<Vec<_>>();
+                let content = apply_selected_hunks(old, new, &kept);
+                // Discarding every hunk of an addition removes the file again
+                let content = if matches!(diff.change, DiffChangeKind::Added) && content.is_empty()
+                {
+                    None
+                } else {
+                    Some(content.into_bytes())
+                };
+                writes.push(FileWrite {
+                    path: selection.path.clone(),
+                    content,
+                    mode: None,
+                });
+            }
+        }
+    }
+    Ok(writes)
+}
+
+/// Content of a worktree file as git stores it: the target of a symlink, otherwise its bytes
+fn read_worktree_file(path: &Path) -> std::io::Result<Vec<u8>> {
+    if std::fs::symlink_metadata(path)?.file_type().is_symlink() {
+        let target = std::fs::read_link(path)?;
+        return Ok(target.to_string_lossy().into_owned().into_bytes());
+    }
+    std::fs::read(path)
+}
+
+/// Mode git records for a worktree file, `None` when it no longer exists
+fn worktree_file_mode(path: &Path) -> std::io::Result<Option<FileMode>> {
+    let metadata = match std::fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    if metadata.file_type().is_symlink() {
+        return Ok(Some(FileMode::Link));
+    }
+    Ok(regular_file_mode(&metadata))
+}
+
+#[cfg(unix)]
+fn regular_file_mode(metadata: &std::fs::Metadata) -> Option<FileMode> {
+    use std::os::unix::fs::PermissionsExt;
+    if metadata.permissions().mode() & 0o111 != 0 {
+        Some(FileMode::BlobExecutable)
+    } else {
+        Some(FileMode::Blob)
+    }
+}
+
+/// Without an executable bit to go by, files keep the mode they have on the branch
+#[cfg(not(unix))]
+fn regular_file_mode(_metadata: &std::fs::Metadata) -> Option<FileMode> {
+    None
+}
+
+/// Apply writes to the files of a worktree
+pub fn write_to_worktree(worktree_path: &Path, writes: &[FileWrite]) -> std::io::Result<()> {
+    for write in writes {
+        let path = worktree_path.join(&write.path);
+        match &write.content {
+            Some(content) => {
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(&path, content)?;
+            }
+            None => match std::fs::remove_file(&path) {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e),
+            },
+        }
+    }
+    Ok(())
+}
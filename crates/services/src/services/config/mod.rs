@@ -19,6 +19,11 @@ pub enum ConfigError {
 
 pub type Config = versions::v8::Config;
 pub type NotificationConfig = versions::v8::NotificationConfig;
+pub type WebhookConfig = versions::v8::WebhookConfig;
+pub type WebhookFormat = versions::v8::WebhookFormat;
+pub type NotificationEvent = versions::v8::NotificationEvent;
+pub type SandboxConfig = versions::v8::SandboxConfig;
+pub type AutoRebaseConfig = versions::v8::AutoRebaseConfig;
 pub type EditorConfig = versions::v8::EditorConfig;
 pub type ThemeMode = versions::v8::ThemeMode;
 pub type SoundFile = versions::v8::SoundFile;
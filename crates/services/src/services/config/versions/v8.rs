@@ -1,10 +1,12 @@
 use anyhow::Error;
-use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
+use executors::{
+    executors::BaseCodingAgent, profile::ExecutorProfileId, sandbox::SandboxNetworkAccess,
+};
 use serde::{Deserialize, Serialize};
+use strum_macros::Display;
 use ts_rs::TS;
 pub use v7::{
-    EditorConfig, EditorType, GitHubConfig, NotificationConfig, ShowcaseState, SoundFile,
-    ThemeMode, UiLanguage,
+    EditorConfig, EditorType, GitHubConfig, ShowcaseState, SoundFile, ThemeMode, UiLanguage,
 };
 
 use crate::services::config::versions::v7;
@@ -47,6 +49,10 @@ pub struct Config {
     pub beta_workspaces_invitation_sent: bool,
     #[serde(default)]
     pub commit_reminder: bool,
+    #[serde(default)]
+    pub sandbox: SandboxConfig,
+    #[serde(default)]
+    pub auto_rebase: AutoRebaseConfig,
 }
 
 impl Config {
@@ -60,7 +66,7 @@ impl Config {
             executor_profile: old_config.executor_profile,
             disclaimer_acknowledged: old_config.disclaimer_acknowledged,
             onboarding_acknowledged: old_config.onboarding_acknowledged,
-            notifications: old_config.notifications,
+            notifications: NotificationConfig::from(old_config.notifications),
             editor: old_config.editor,
             github: old_config.github,
             analytics_enabled,
@@ -75,6 +81,8 @@ impl Config {
             beta_workspaces: false,
             beta_workspaces_invitation_sent: false,
             commit_reminder: false,
+            sandbox: SandboxConfig::default(),
+            auto_rebase: AutoRebaseConfig::default(),
         }
     }
 
@@ -128,6 +136,142 @@ impl Default for Config {
             beta_workspaces: false,
             beta_workspaces_invitation_sent: false,
             commit_reminder: false,
+            sandbox: SandboxConfig::default(),
+            auto_rebase: AutoRebaseConfig::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct NotificationConfig {
+    pub sound_enabled: bool,
+    pub push_enabled: bool,
+    pub sound_file: SoundFile,
+    #[serde(default)]
+    pub webhooks: Vec<WebhookConfig>,
+}
+
+impl From<v7::NotificationConfig> for NotificationConfig {
+    fn from(old: v7::NotificationConfig) -> Self {
+        Self {
+            sound_enabled: old.sound_enabled,
+            push_enabled: old.push_enabled,
+            sound_file: old.sound_file,
+            webhooks: Vec::new(),
+        }
+    }
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self::from(v7::NotificationConfig::default())
+    }
+}
+
+/// Outbound HTTP notification target
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct WebhookConfig {
+    pub url: String,
+    #[serde(default = "default_webhook_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub format: WebhookFormat,
+    /// Events delivered to this webhook; empty means all events
+    #[serde(default)]
+    pub events: Vec<NotificationEvent>,
+    /// When set, payloads are signed with HMAC-SHA256 in the `X-Vibe-Kanban-Signature` header
+    #[serde(default)]
+    pub secret: Option<String>,
+}
+
+fn default_webhook_enabled() -> bool {
+    true
+}
+
+impl WebhookConfig {
+    pub fn wants(&self, event: &NotificationEvent) -> bool {
+        self.enabled && (self.events.is_empty() || self.events.contains(event))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(use_ts_enum)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum WebhookFormat {
+    #[default]
+    Json,
+    Slack,
+    Discord,
+    Teams,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS, Display)]
+#[ts(use_ts_enum)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[strum(serialize_all = "snake_case")]
+pub enum NotificationEvent {
+    AgentFinished,
+    ApprovalPending,
+    PrMerged,
+    ExecutionFailed,
+}
+
+/// Confine coding agents with Landlock (Linux only). Agents can read everything but only write to
+/// their workspace, the temp directories and `writable_paths`.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct SandboxConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Extra writable paths, such as the agents' own state directories; `~` is the home directory.
+    /// The defaults only cover per-agent directories and caches, not shared ones like `~/.config`
+    /// where an agent could install something that runs outside the sandbox.
+    #[serde(default = "default_sandbox_writable_paths")]
+    pub writable_paths: Vec<String>,
+    #[serde(default)]
+    pub network: SandboxNetworkAccess,
+    /// TCP ports agents may connect to when `network` is `ALLOWED_PORTS`, besides this app's own
+    /// port, which its MCP server uses. `BLOCKED` is refused for coding agents, since they reach
+    /// their model API over the network.
+    #[serde(default = "default_sandbox_allowed_ports")]
+    pub allowed_ports: Vec<u16>,
+}
+
+/// Rebase idle workspaces onto their target branch when it moves. A rebase that stops on
+/// conflicts is left in progress for the user, or handed to the agent when
+/// `resolve_conflicts_with_agent` is set.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+pub struct AutoRebaseConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub resolve_conflicts_with_agent: bool,
+}
+
+fn default_sandbox_writable_paths() -> Vec<String> {
+    [
+        "~/.claude",
+        "~/.claude.json",
+        "~/.codex",
+        "~/.gemini",
+        "~/.cache",
+        "~/.npm",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+
+fn default_sandbox_allowed_ports() -> Vec<u16> {
+    vec![443]
+}
+
+impl Default for SandboxConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            writable_paths: default_sandbox_writable_paths(),
+            network: SandboxNetworkAccess::default(),
+            allowed_ports: default_sandbox_allowed_ports(),
         }
     }
 }
@@ -1,6 +1,7 @@
 use std::{
     collections::HashMap,
     path::{Path, PathBuf},
+    str::FromStr,
     sync::Arc,
 };
 
@@ -10,19 +11,22 @@ use db::{
     DBService,
     models::{
         coding_agent_turn::{CodingAgentTurn, CreateCodingAgentTurn},
+        conversation_entry::ConversationEntry,
         execution_process::{
             CreateExecutionProcess, ExecutionContext, ExecutionProcess, ExecutionProcessRunReason,
-            ExecutionProcessStatus,
+            ExecutionProcessStatus, ExecutionProcessStopReason,
         },
         execution_process_logs::ExecutionProcessLogs,
         execution_process_repo_state::{
             CreateExecutionProcessRepoState, ExecutionProcessRepoState,
         },
+        execution_process_usage::ExecutionProcessUsage,
+        merge::{DirectMerge, Merge},
         repo::Repo,
         session::{CreateSession, Session, SessionError},
         task::{Task, TaskStatus},
-        workspace::{Workspace, WorkspaceError},
-        workspace_repo::WorkspaceRepo,
+        workspace::{CreateWorkspace, Workspace, WorkspaceError},
+        workspace_repo::{CreateWorkspaceRepo, WorkspaceRepo},
     },
 };
 #[cfg(feature = "qa-mode")]
@@ -32,10 +36,11 @@ use executors::profile::ExecutorConfigs;
 use executors::{
     actions::{
         ExecutorAction, ExecutorActionType,
+        coding_agent_follow_up::CodingAgentFollowUpRequest,
         coding_agent_initial::CodingAgentInitialRequest,
         script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
     },
-    executors::{ExecutorError, StandardCodingAgentExecutor},
+    executors::{BaseCodingAgent, ExecutorError, StandardCodingAgentExecutor},
     logs::{NormalizedEntry, NormalizedEntryError, NormalizedEntryType, utils::ConversationPatch},
     profile::ExecutorProfileId,
 };
@@ -51,9 +56,12 @@ use utils::{
 use uuid::Uuid;
 
 use crate::services::{
+    config::NotificationEvent,
+    conversation_search::index_conversation_patch,
     git::{GitService, GitServiceError},
     notification::NotificationService,
     share::SharePublisher,
+    stacking, verification,
     workspace_manager::WorkspaceError as WorkspaceManagerError,
     worktree_manager::WorktreeError,
 };
@@ -151,6 +159,11 @@ pub trait ContainerService {
             return false;
         }
 
+        // A failed verify script still hands over to the remaining verify and cleanup scripts
+        if verification::failed_verify_continues(&ctx.execution_process) {
+            return false;
+        }
+
         // Always finalize failed or killed executions, regardless of next action
         if matches!(
             ctx.execution_process.status,
@@ -192,14 +205,20 @@ pub trait ContainerService {
         }
 
         let title = format!("Task Complete: {}", ctx.task.title);
-        let message = match ctx.execution_process.status {
-            ExecutionProcessStatus::Completed => format!(
-                "✅ '{}' completed successfully\nBranch: {:?}\nExecutor: {:?}",
-                ctx.task.title, ctx.workspace.branch, ctx.session.executor
+        let (event, message) = match ctx.execution_process.status {
+            ExecutionProcessStatus::Completed => (
+                NotificationEvent::AgentFinished,
+                format!(
+                    "✅ '{}' completed successfully\nBranch: {:?}\nExecutor: {:?}",
+                    ctx.task.title, ctx.workspace.branch, ctx.session.executor
+                ),
             ),
-            ExecutionProcessStatus::Failed => format!(
-                "❌ '{}' execution failed\nBranch: {:?}\nExecutor: {:?}",
-                ctx.task.title, ctx.workspace.branch, ctx.session.executor
+            ExecutionProcessStatus::Failed => (
+                NotificationEvent::ExecutionFailed,
+                format!(
+                    "❌ '{}' execution failed\nBranch: {:?}\nExecutor: {:?}",
+                    ctx.task.title, ctx.workspace.branch, ctx.session.executor
+                ),
             ),
             _ => {
                 tracing::warn!(
@@ -209,7 +228,9 @@ pub trait ContainerService {
                 return;
             }
         };
-        self.notification_service().notify(&title, &message).await;
+        self.notification_service()
+            .notify_event(event, &title, &message)
+            .await;
     }
 
     /// Cleanup executions marked as running in the db, call at startup
@@ -264,12 +285,13 @@ pub trait ContainerService {
             }
             // Process marked as failed
             tracing::info!("Marked orphaned execution process {} as failed", process.id);
-            // Update task status to InReview for coding agent and setup script failures
+            // Update task status to InReview for coding agent and script failures
             if matches!(
                 process.run_reason,
                 ExecutionProcessRunReason::CodingAgent
                     | ExecutionProcessRunReason::SetupScript
                     | ExecutionProcessRunReason::CleanupScript
+                    | ExecutionProcessRunReason::VerifyScript
             ) && let Ok(Some(session)) =
                 Session::find_by_id(&self.db().pool, process.session_id).await
                 && let Ok(Some(workspace)) =
@@ -415,6 +437,53 @@ pub trait ContainerService {
         Some(root_action)
     }
 
+    fn verify_actions_for_repos(&self, repos: &[Repo]) -> Option<ExecutorAction> {
+        let repos_with_verify: Vec<_> =
+            repos.iter().filter(|r| r.verify_script.is_some()).collect();
+
+        if repos_with_verify.is_empty() {
+            return None;
+        }
+
+        let mut iter = repos_with_verify.iter();
+        let first = iter.next()?;
+        let mut root_action = ExecutorAction::new(
+            ExecutorActionType::ScriptRequest(ScriptRequest {
+                script: first.verify_script.clone().unwrap(),
+                language: ScriptRequestLanguage::Bash,
+                context: ScriptContext::VerifyScript,
+                working_dir: Some(first.name.clone()),
+            }),
+            None,
+        );
+
+        for repo in iter {
+            root_action = root_action.append_action(ExecutorAction::new(
+                ExecutorActionType::ScriptRequest(ScriptRequest {
+                    script: repo.verify_script.clone().unwrap(),
+                    language: ScriptRequestLanguage::Bash,
+                    context: ScriptContext::VerifyScript,
+                    working_dir: Some(repo.name.clone()),
+                }),
+                None,
+            ));
+        }
+
+        Some(root_action)
+    }
+
+    /// Actions chained after every coding agent turn: verify scripts first, then cleanup scripts.
+    /// Each one starts when the previous ends, even if a verify script failed.
+    fn post_agent_actions_for_repos(&self, repos: &[Repo]) -> Option<ExecutorAction> {
+        match (
+            self.verify_actions_for_repos(repos),
+            self.cleanup_actions_for_repos(repos),
+        ) {
+            (Some(verify), Some(cleanup)) => Some(verify.append_action(cleanup)),
+            (verify, cleanup) => verify.or(cleanup),
+        }
+    }
+
     fn setup_actions_for_repos(&self, repos: &[Repo]) -> Option<ExecutorAction> {
         let repos_with_setup: Vec<_> = repos.iter().filter(|r| r.setup_script.is_some()).collect();
 
@@ -539,6 +608,87 @@ pub trait ContainerService {
         status: ExecutionProcessStatus,
     ) -> Result<(), ContainerError>;
 
+    /// Record a direct merge of the workspace and wrap it up: the task is done, the workspace
+    /// is archived unless pinned and its dev servers are stopped
+    async fn complete_direct_merge(
+        &self,
+        workspace: &Workspace,
+        repo_id: Uuid,
+        target_branch: &str,
+        merge_commit: &str,
+    ) -> Result<DirectMerge, ContainerError> {
+        let pool = &self.db().pool;
+        let merge =
+            Merge::create_direct(pool, workspace.id, repo_id, target_branch, merge_commit).await?;
+        Task::update_status(pool, workspace.task_id, TaskStatus::Done).await?;
+        if !workspace.pinned {
+            Workspace::set_archived(pool, workspace.id, true).await?;
+        }
+
+        // Stop any running dev servers for this workspace
+        let dev_servers =
+            ExecutionProcess::find_running_dev_servers_by_workspace(pool, workspace.id).await?;
+
+        for dev_server in dev_servers {
+            tracing::info!(
+                "Stopping dev server {} for completed task attempt {}",
+                dev_server.id,
+                workspace.id
+            );
+
+            if let Err(e) = self
+                .stop_execution(&dev_server, ExecutionProcessStatus::Killed)
+                .await
+            {
+                tracing::error!(
+                    "Failed to stop dev server {} for task attempt {}: {}",
+                    dev_server.id,
+                    workspace.id,
+                    e
+                );
+            }
+        }
+
+        // Workspaces stacked on this one now build on what it was merged into
+        if let Err(e) = stacking::restack_after_merge(pool, self.git(), workspace, repo_id).await {
+            tracing::warn!(
+                "Failed to restack workspaces stacked on {}: {}",
+                workspace.id,
+                e
+            );
+        }
+
+        // Try broadcast update to other users in organization
+        if let Some(publisher) = self.share_publisher() {
+            if let Err(err) = publisher.update_shared_task_by_id(workspace.task_id).await {
+                tracing::warn!(
+                    ?err,
+                    "Failed to propagate shared task update for {}",
+                    workspace.task_id
+                );
+            }
+        } else {
+            tracing::debug!(
+                "Share publisher unavailable; skipping remote update for {}",
+                workspace.task_id
+            );
+        }
+
+        Ok(merge)
+    }
+
+    /// Stop a running process on behalf of the system, recording why it was stopped so the UI
+    /// can tell it apart from a user kill.
+    async fn stop_execution_with_reason(
+        &self,
+        execution_process: &ExecutionProcess,
+        reason: ExecutionProcessStopReason,
+    ) -> Result<(), ContainerError> {
+        ExecutionProcess::update_stop_reason(&self.db().pool, execution_process.id, reason).await?;
+        self.stop_execution(execution_process, ExecutionProcessStatus::Killed)
+            .await
+    }
+
     async fn try_commit_changes(&self, ctx: &ExecutionContext) -> Result<bool, ContainerError>;
 
     async fn copy_project_files(
@@ -625,6 +775,35 @@ pub trait ContainerService {
         }
     }
 
+    /// Index the conversations of coding agent processes that ran before conversation search
+    /// existed. Each process leaves the backfill queue once indexed, so an interrupted backfill
+    /// resumes on the next start.
+    async fn backfill_conversation_search(&self) -> Result<(), ContainerError> {
+        let pool = &self.db().pool;
+        let pending = ConversationEntry::find_backfill_pending(pool).await?;
+        if pending.is_empty() {
+            return Ok(());
+        }
+
+        tracing::info!(
+            "Indexing conversations of {} execution processes for search",
+            pending.len()
+        );
+
+        for execution_id in pending {
+            if let Some(mut stream) = self.stream_normalized_logs_from_db(&execution_id).await {
+                while let Some(Ok(msg)) = stream.next().await {
+                    if let LogMsg::JsonPatch(patch) = &msg {
+                        index_conversation_patch(pool, execution_id, patch).await?;
+                    }
+                }
+            }
+            ConversationEntry::complete_backfill(pool, execution_id).await?;
+        }
+
+        Ok(())
+    }
+
     async fn stream_normalized_logs(
         &self,
         id: &Uuid,
@@ -641,160 +820,165 @@ pub trait ContainerService {
                     .boxed(),
             )
         } else {
-            // Fallback: load from DB and normalize
-            let log_records =
-                match ExecutionProcessLogs::find_by_execution_id(&self.db().pool, *id).await {
-                    Ok(records) if !records.is_empty() => records,
-                    Ok(_) => return None, // No logs exist
-                    Err(e) => {
-                        tracing::error!("Failed to fetch logs for execution {}: {}", id, e);
-                        return None;
-                    }
-                };
+            // Fallback: recreate the worktree if it was cleaned up, then load from DB and normalize
+            let pool = &self.db().pool;
+            if let Ok(Some(process)) = ExecutionProcess::find_by_id(pool, *id).await
+                && let Ok(Some((workspace, _))) = process.parent_workspace_and_session(pool).await
+                && let Err(err) = self.ensure_container_exists(&workspace).await
+            {
+                tracing::warn!(
+                    "Failed to recreate worktree before log normalization for workspace {}: {}",
+                    workspace.id,
+                    err
+                );
+            }
+            self.stream_normalized_logs_from_db(id).await
+        }
+    }
 
-            let raw_messages = match ExecutionProcessLogs::parse_logs(&log_records) {
-                Ok(msgs) => msgs,
+    /// Normalize the logs of an execution process from the logs stored in the database, without
+    /// looking at its in-memory store. The stream ends once normalization has finished.
+    /// The working directory is resolved from the workspace record and is never recreated.
+    async fn stream_normalized_logs_from_db(
+        &self,
+        id: &Uuid,
+    ) -> Option<futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>>> {
+        let log_records =
+            match ExecutionProcessLogs::find_by_execution_id(&self.db().pool, *id).await {
+                Ok(records) if !records.is_empty() => records,
+                Ok(_) => return None, // No logs exist
                 Err(e) => {
-                    tracing::error!("Failed to parse logs for execution {}: {}", id, e);
+                    tracing::error!("Failed to fetch logs for execution {}: {}", id, e);
                     return None;
                 }
             };
 
-            // Create temporary store and populate
-            // Include JsonPatch messages (already normalized) and Stdout/Stderr (need normalization)
-            let temp_store = Arc::new(MsgStore::new());
-            for msg in raw_messages {
-                if matches!(
-                    msg,
-                    LogMsg::Stdout(_) | LogMsg::Stderr(_) | LogMsg::JsonPatch(_)
-                ) {
-                    temp_store.push(msg);
-                }
+        let raw_messages = match ExecutionProcessLogs::parse_logs(&log_records) {
+            Ok(msgs) => msgs,
+            Err(e) => {
+                tracing::error!("Failed to parse logs for execution {}: {}", id, e);
+                return None;
+            }
+        };
+
+        // Create temporary store and populate
+        // Include JsonPatch messages (already normalized) and Stdout/Stderr (need normalization)
+        let temp_store = Arc::new(MsgStore::new());
+        for msg in raw_messages {
+            if matches!(
+                msg,
+                LogMsg::Stdout(_) | LogMsg::Stderr(_) | LogMsg::JsonPatch(_)
+            ) {
+                temp_store.push(msg);
+            }
+        }
+        temp_store.push_finished();
+
+        let process = match ExecutionProcess::find_by_id(&self.db().pool, *id).await {
+            Ok(Some(process)) => process,
+            Ok(None) => {
+                tracing::error!("No execution process found for ID: {}", id);
+                return None;
+            }
+            Err(e) => {
+                tracing::error!("Failed to fetch execution process {}: {}", id, e);
+                return None;
             }
-            temp_store.push_finished();
+        };
 
-            let process = match ExecutionProcess::find_by_id(&self.db().pool, *id).await {
-                Ok(Some(process)) => process,
+        // Get the workspace to determine correct directory
+        let (workspace, _session) =
+            match process.parent_workspace_and_session(&self.db().pool).await {
+                Ok(Some((workspace, session))) => (workspace, session),
                 Ok(None) => {
-                    tracing::error!("No execution process found for ID: {}", id);
+                    tracing::error!(
+                        "No workspace/session found for session ID: {}",
+                        process.session_id
+                    );
                     return None;
                 }
                 Err(e) => {
-                    tracing::error!("Failed to fetch execution process {}: {}", id, e);
+                    tracing::error!(
+                        "Failed to fetch workspace for session {}: {}",
+                        process.session_id,
+                        e
+                    );
                     return None;
                 }
             };
 
-            // Get the workspace to determine correct directory
-            let (workspace, _session) =
-                match process.parent_workspace_and_session(&self.db().pool).await {
-                    Ok(Some((workspace, session))) => (workspace, session),
-                    Ok(None) => {
-                        tracing::error!(
-                            "No workspace/session found for session ID: {}",
-                            process.session_id
-                        );
-                        return None;
-                    }
-                    Err(e) => {
-                        tracing::error!(
-                            "Failed to fetch workspace for session {}: {}",
-                            process.session_id,
-                            e
-                        );
-                        return None;
-                    }
-                };
-
-            if let Err(err) = self.ensure_container_exists(&workspace).await {
-                tracing::warn!(
-                    "Failed to recreate worktree before log normalization for workspace {}: {}",
-                    workspace.id,
-                    err
-                );
-            }
-
-            let current_dir = self.workspace_to_current_dir(&workspace);
+        let current_dir = self.workspace_to_current_dir(&workspace);
 
-            let executor_action = if let Ok(executor_action) = process.executor_action() {
-                executor_action
-            } else {
-                tracing::error!(
-                    "Failed to parse executor action: {:?}",
-                    process.executor_action()
-                );
-                return None;
-            };
+        let executor_action = if let Ok(executor_action) = process.executor_action() {
+            executor_action
+        } else {
+            tracing::error!(
+                "Failed to parse executor action: {:?}",
+                process.executor_action()
+            );
+            return None;
+        };
 
-            // Spawn normalizer on populated store
-            match executor_action.typ() {
-                ExecutorActionType::CodingAgentInitialRequest(request) => {
-                    #[cfg(feature = "qa-mode")]
-                    {
-                        let executor = QaMockExecutor;
-                        executor.normalize_logs(
-                            temp_store.clone(),
-                            &request.effective_dir(&current_dir),
-                        );
-                    }
-                    #[cfg(not(feature = "qa-mode"))]
-                    {
-                        let executor = ExecutorConfigs::get_cached()
-                            .get_coding_agent_or_default(&request.executor_profile_id);
-                        executor.normalize_logs(
-                            temp_store.clone(),
-                            &request.effective_dir(&current_dir),
-                        );
-                    }
+        // Spawn normalizer on populated store
+        match executor_action.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(request) => {
+                #[cfg(feature = "qa-mode")]
+                {
+                    let executor = QaMockExecutor;
+                    executor
+                        .normalize_logs(temp_store.clone(), &request.effective_dir(&current_dir));
                 }
-                ExecutorActionType::CodingAgentFollowUpRequest(request) => {
-                    #[cfg(feature = "qa-mode")]
-                    {
-                        let executor = QaMockExecutor;
-                        executor.normalize_logs(
-                            temp_store.clone(),
-                            &request.effective_dir(&current_dir),
-                        );
-                    }
-                    #[cfg(not(feature = "qa-mode"))]
-                    {
-                        let executor = ExecutorConfigs::get_cached()
-                            .get_coding_agent_or_default(&request.executor_profile_id);
-                        executor.normalize_logs(
-                            temp_store.clone(),
-                            &request.effective_dir(&current_dir),
-                        );
-                    }
+                #[cfg(not(feature = "qa-mode"))]
+                {
+                    let executor = ExecutorConfigs::get_cached()
+                        .get_coding_agent_or_default(&request.executor_profile_id);
+                    executor
+                        .normalize_logs(temp_store.clone(), &request.effective_dir(&current_dir));
                 }
+            }
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => {
                 #[cfg(feature = "qa-mode")]
-                ExecutorActionType::ReviewRequest(_request) => {
+                {
                     let executor = QaMockExecutor;
-                    executor.normalize_logs(temp_store.clone(), &current_dir);
+                    executor
+                        .normalize_logs(temp_store.clone(), &request.effective_dir(&current_dir));
                 }
                 #[cfg(not(feature = "qa-mode"))]
-                ExecutorActionType::ReviewRequest(request) => {
+                {
                     let executor = ExecutorConfigs::get_cached()
                         .get_coding_agent_or_default(&request.executor_profile_id);
-                    executor.normalize_logs(temp_store.clone(), &current_dir);
-                }
-                _ => {
-                    tracing::debug!(
-                        "Executor action doesn't support log normalization: {:?}",
-                        process.executor_action()
-                    );
-                    return None;
+                    executor
+                        .normalize_logs(temp_store.clone(), &request.effective_dir(&current_dir));
                 }
             }
-            Some(
-                temp_store
-                    .history_plus_stream()
-                    .filter(|msg| future::ready(matches!(msg, Ok(LogMsg::JsonPatch(..)))))
-                    .chain(futures::stream::once(async {
-                        Ok::<_, std::io::Error>(LogMsg::Finished)
-                    }))
-                    .boxed(),
-            )
+            #[cfg(feature = "qa-mode")]
+            ExecutorActionType::ReviewRequest(_request) => {
+                let executor = QaMockExecutor;
+                executor.normalize_logs(temp_store.clone(), &current_dir);
+            }
+            #[cfg(not(feature = "qa-mode"))]
+            ExecutorActionType::ReviewRequest(request) => {
+                let executor = ExecutorConfigs::get_cached()
+                    .get_coding_agent_or_default(&request.executor_profile_id);
+                executor.normalize_logs(temp_store.clone(), &current_dir);
+            }
+            _ => {
+                tracing::debug!(
+                    "Executor action doesn't support log normalization: {:?}",
+                    process.executor_action()
+                );
+                return None;
+            }
         }
+        Some(
+            temp_store
+                .history_plus_stream()
+                .filter(|msg| future::ready(matches!(msg, Ok(LogMsg::JsonPatch(..)))))
+                .chain(futures::stream::once(async {
+                    Ok::<_, std::io::Error>(LogMsg::Finished)
+                }))
+                .boxed(),
+        )
     }
 
     fn spawn_stream_raw_logs_to_db(&self, execution_id: &Uuid) -> JoinHandle<()> {
@@ -811,6 +995,8 @@ pub trait ContainerService {
 
             if let Some(store) = store {
                 let mut stream = store.history_plus_stream();
+                // Resolved on the first usage report; needed to attribute usage to a profile
+                let mut executor_profile_id = None;
 
                 while let Some(Ok(msg)) = stream.next().await {
                     match &msg {
@@ -861,16 +1047,113 @@ pub trait ContainerService {
                                 );
                             }
                         }
+                        LogMsg::TokenUsage(usage) => {
+                            if executor_profile_id.is_none() {
+                                executor_profile_id = match ExecutionProcess::find_by_id(
+                                    &db.pool,
+                                    execution_id,
+                                )
+                                .await
+                                {
+                                    Ok(Some(process)) => process
+                                        .executor_action()
+                                        .ok()
+                                        .and_then(|action| action.executor_profile_id())
+                                        .cloned(),
+                                    Ok(None) => None,
+                                    Err(e) => {
+                                        tracing::error!(
+                                            "Failed to load execution process {}: {}",
+                                            execution_id,
+                                            e
+                                        );
+                                        None
+                                    }
+                                };
+                            }
+                            let Some(executor_profile_id) = &executor_profile_id else {
+                                continue;
+                            };
+                            if let Err(e) = ExecutionProcessUsage::upsert(
+                                &db.pool,
+                                execution_id,
+                                executor_profile_id,
+                                usage,
+                            )
+                            .await
+                            {
+                                tracing::error!(
+                                    "Failed to record token usage for execution process {}: {}",
+                                    execution_id,
+                                    e
+                                );
+                            }
+                        }
                         LogMsg::Finished => {
                             break;
                         }
-                        LogMsg::JsonPatch(_) | LogMsg::Ready => continue,
+                        LogMsg::JsonPatch(patch) => {
+                            if let Err(e) =
+                                index_conversation_patch(&db.pool, execution_id, patch).await
+                            {
+                                tracing::error!(
+                                    "Failed to index conversation entry for execution process {}: {}",
+                                    execution_id,
+                                    e
+                                );
+                            }
+                        }
+                        LogMsg::Ready => continue,
                     }
                 }
             }
         })
     }
 
+    /// Create a workspace (and its repo rows) for a task without starting it. A workspace stacked
+    /// on `base_workspace_id` is expected to target that workspace's branch in its repos.
+    async fn create_workspace_for_task(
+        &self,
+        task: &Task,
+        repos: &[CreateWorkspaceRepo],
+        base_workspace_id: Option<Uuid>,
+    ) -> Result<Workspace, ContainerError> {
+        let pool = &self.db().pool;
+
+        // Compute agent_working_dir based on repo count:
+        // - Single repo: use repo name as working dir (agent runs in repo directory)
+        // - Multiple repos: use None (agent runs in workspace root)
+        let agent_working_dir = if repos.len() == 1 {
+            let repo = Repo::find_by_id(pool, repos[0].repo_id)
+                .await?
+                .ok_or(SqlxError::RowNotFound)?;
+            Some(repo.name)
+        } else {
+            None
+        };
+
+        let workspace_id = Uuid::new_v4();
+        let git_branch_name = self
+            .git_branch_from_workspace(&workspace_id, &task.title)
+            .await;
+
+        let workspace = Workspace::create(
+            pool,
+            &CreateWorkspace {
+                branch: git_branch_name,
+                agent_working_dir,
+                base_workspace_id,
+            },
+            workspace_id,
+            task.id,
+        )
+        .await?;
+
+        WorkspaceRepo::create_many(pool, workspace.id, repos).await?;
+
+        Ok(workspace)
+    }
+
     async fn start_workspace(
         &self,
         workspace: &Workspace,
@@ -908,7 +1191,7 @@ pub trait ContainerService {
 
         let all_parallel = repos_with_setup.iter().all(|r| r.parallel_setup_script);
 
-        let cleanup_action = self.cleanup_actions_for_repos(&repos);
+        let post_agent_action = self.post_agent_actions_for_repos(&repos);
 
         let working_dir = workspace
             .agent_working_dir
@@ -922,7 +1205,7 @@ pub trait ContainerService {
                 executor_profile_id: executor_profile_id.clone(),
                 working_dir,
             }),
-            cleanup_action.map(Box::new),
+            post_agent_action.map(Box::new),
         );
 
         let execution_process = if all_parallel {
@@ -963,6 +1246,101 @@ pub trait ContainerService {
         Ok(execution_process)
     }
 
+    /// Start a coding agent turn in `session` with `prompt`, keeping the executor variant of the
+    /// session's latest coding agent run
+    async fn start_follow_up(
+        &self,
+        workspace: &Workspace,
+        session: &Session,
+        prompt: &str,
+    ) -> Result<ExecutionProcess, ContainerError> {
+        let variant =
+            ExecutionProcess::latest_executor_profile_for_session(&self.db().pool, session.id)
+                .await
+                .ok()
+                .flatten()
+                .and_then(|profile| profile.variant);
+        self.start_follow_up_with_variant(workspace, session, prompt, variant)
+            .await
+    }
+
+    /// Start a coding agent turn in `session` with `prompt`, continuing the agent's conversation
+    /// when there is one. The executor is the one of the session's latest coding agent run.
+    async fn start_follow_up_with_variant(
+        &self,
+        workspace: &Workspace,
+        session: &Session,
+        prompt: &str,
+        variant: Option<String>,
+    ) -> Result<ExecutionProcess, ContainerError> {
+        let pool = &self.db().pool;
+
+        // Get executor from the latest CodingAgent process, or fall back to session's executor
+        let base_executor =
+            match ExecutionProcess::latest_executor_profile_for_session(pool, session.id)
+                .await
+                .map_err(|e| {
+                    ContainerError::Other(anyhow!("Failed to get executor profile: {e}"))
+                })? {
+                Some(profile) => profile.executor,
+                None => {
+                    // No prior execution - use session's executor field
+                    let executor_str = session.executor.as_ref().ok_or_else(|| {
+                        ContainerError::Other(anyhow!(
+                            "No prior execution and no executor configured on session"
+                        ))
+                    })?;
+                    BaseCodingAgent::from_str(&executor_str.replace('-', "_").to_ascii_uppercase())
+                        .map_err(|_| {
+                            ContainerError::Other(anyhow!("Invalid executor: {}", executor_str))
+                        })?
+                }
+            };
+
+        let executor_profile_id = ExecutorProfileId {
+            executor: base_executor,
+            variant,
+        };
+
+        // Get latest agent session ID for session continuity (from coding agent turns)
+        let latest_agent_session_id =
+            ExecutionProcess::find_latest_coding_agent_turn_session_id(pool, session.id).await?;
+
+        let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
+        let post_agent_action = self.post_agent_actions_for_repos(&repos);
+
+        let working_dir = workspace
+            .agent_working_dir
+            .as_ref()
+            .filter(|dir| !dir.is_empty())
+            .cloned();
+
+        let action_type = if let Some(agent_session_id) = latest_agent_session_id {
+            ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
+                prompt: prompt.to_string(),
+                session_id: agent_session_id,
+                executor_profile_id,
+                working_dir,
+            })
+        } else {
+            ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                prompt: prompt.to_string(),
+                executor_profile_id,
+                working_dir,
+            })
+        };
+
+        let action = ExecutorAction::new(action_type, post_agent_action.map(Box::new));
+
+        self.start_execution(
+            workspace,
+            session,
+            &action,
+            &ExecutionProcessRunReason::CodingAgent,
+        )
+        .await
+    }
+
     async fn start_execution(
         &self,
         workspace: &Workspace,
@@ -1173,6 +1551,21 @@ pub trait ContainerService {
 
         // Determine the run reason of the next action
         let next_run_reason = match (action.typ(), next_action.typ()) {
+            (
+                _,
+                ExecutorActionType::ScriptRequest(ScriptRequest {
+                    context: ScriptContext::VerifyScript,
+                    ..
+                }),
+            ) => ExecutionProcessRunReason::VerifyScript,
+            // Cleanup scripts follow the verify scripts of an agent turn and each other
+            (
+                _,
+                ExecutorActionType::ScriptRequest(ScriptRequest {
+                    context: ScriptContext::CleanupScript,
+                    ..
+                }),
+            ) => ExecutionProcessRunReason::CleanupScript,
             (ExecutorActionType::ScriptRequest(_), ExecutorActionType::ScriptRequest(_)) => {
                 ExecutionProcessRunReason::SetupScript
             }
@@ -0,0 +1,198 @@
+use db::models::conversation_entry::{ConversationEntry, ConversationEntryType};
+use executors::logs::{
+    ActionType, NormalizedEntry, NormalizedEntryType,
+    utils::patch::extract_normalized_entry_from_patch,
+};
+use json_patch::Patch;
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+/// Index the conversation entry a normalized log patch adds or replaces, if it is searchable
+pub async fn index_conversation_patch(
+    pool: &SqlitePool,
+    execution_process_id: Uuid,
+    patch: &Patch,
+) -> Result<(), sqlx::Error> {
+    let Some((index, entry)) = extract_normalized_entry_from_patch(patch) else {
+        return Ok(());
+    };
+    let Some((entry_type, content)) = searchable_entry(&entry) else {
+        return Ok(());
+    };
+    ConversationEntry::upsert(
+        pool,
+        execution_process_id,
+        index as i64,
+        entry_type,
+        &content,
+    )
+    .await
+}
+
+/// Text to index for a normalized conversation entry, or `None` for entries that are not worth
+/// searching (thinking, system messages, errors, ...). Tool uses are indexed together with the
+/// file paths, commands and queries they acted on.
+pub fn searchable_entry(entry: &NormalizedEntry) -> Option<(ConversationEntryType, String)> {
+    let (entry_type, mut parts) = match &entry.entry_type {
+        NormalizedEntryType::UserMessage | NormalizedEntryType::UserFeedback { .. } => (
+            ConversationEntryType::UserMessage,
+            vec![entry.content.as_str()],
+        ),
+        NormalizedEntryType::AssistantMessage => (
+            ConversationEntryType::AssistantMessage,
+            vec![entry.content.as_str()],
+        ),
+        NormalizedEntryType::ToolUse {
+            tool_name,
+            action_type,
+            ..
+        } => {
+            let mut parts = vec![tool_name.as_str(), entry.content.as_str()];
+            match action_type {
+                ActionType::FileRead { path } | ActionType::FileEdit { path, .. } => {
+                    parts.push(path)
+                }
+                ActionType::CommandRun { command, .. } => parts.push(command),
+                ActionType::Search { query } => parts.push(query),
+                ActionType::WebFetch { url } => parts.push(url),
+                ActionType::TaskCreate { description } | ActionType::Other { description } => {
+                    parts.push(description)
+                }
+                ActionType::PlanPresentation { plan } => parts.push(plan),
+                ActionType::TodoManagement { todos, .. } => {
+                    parts.extend(todos.iter().map(|todo| todo.content.as_str()))
+                }
+                ActionType::Tool { .. } => {}
+            }
+            (ConversationEntryType::ToolUse, parts)
+        }
+        _ => return None,
+    };
+
+    parts.retain(|part| !part.trim().is_empty());
+    parts.dedup();
+    if parts.is_empty() {
+        return None;
+    }
+    Some((entry_type, parts.join("\n")))
+}
+
+/// Turn free text into an FTS5 `MATCH` expression that finds entries containing every word.
+/// Each word is quoted so user input can never be parsed as FTS5 query syntax; a trailing `*`
+/// is kept as a prefix search.
+pub fn fts_match_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .filter_map(|word| {
+            let (word, prefix) = match word.strip_suffix('*') {
+                Some(stem) => (stem, true),
+                None => (word, false),
+            };
+            let word = word.replace('"', "");
+            if !word.chars().any(char::is_alphanumeric) {
+                return None;
+            }
+            Some(if prefix {
+                format!("\"{word}\"*")
+            } else {
+                format!("\"{word}\"")
+            })
+        })
+        .collect();
+
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::logs::ToolStatus;
+
+    use super::*;
+
+    fn entry(entry_type: NormalizedEntryType, content: &str) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type,
+            content: content.to_string(),
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn test_tool_use_indexes_paths_and_commands() {
+        let edit = entry(
+            NormalizedEntryType::ToolUse {
+                tool_name: "Edit".to_string(),
+                action_type: ActionType::FileEdit {
+                    path: "src/middleware/auth.rs".to_string(),
+                    changes: vec![],
+                },
+                status: ToolStatus::Success,
+            },
+            "`src/middleware/auth.rs`",
+        );
+        assert_eq!(
+            searchable_entry(&edit),
+            Some((
+                ConversationEntryType::ToolUse,
+                "Edit\n`src/middleware/auth.rs`\nsrc/middleware/auth.rs".to_string()
+            ))
+        );
+
+        let command = entry(
+            NormalizedEntryType::ToolUse {
+                tool_name: "Bash".to_string(),
+                action_type: ActionType::CommandRun {
+                    command: "cargo test".to_string(),
+                    result: None,
+                },
+                status: ToolStatus::Created,
+            },
+            "",
+        );
+        assert_eq!(
+            searchable_entry(&command),
+            Some((
+                ConversationEntryType::ToolUse,
+                "Bash\ncargo test".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_only_messages_and_tool_uses_are_indexed() {
+        assert_eq!(
+            searchable_entry(&entry(NormalizedEntryType::AssistantMessage, "Done")),
+            Some((ConversationEntryType::AssistantMessage, "Done".to_string()))
+        );
+        assert_eq!(
+            searchable_entry(&entry(NormalizedEntryType::UserMessage, "Fix login")),
+            Some((ConversationEntryType::UserMessage, "Fix login".to_string()))
+        );
+        assert_eq!(
+            searchable_entry(&entry(NormalizedEntryType::AssistantMessage, "  ")),
+            None
+        );
+        assert_eq!(
+            searchable_entry(&entry(NormalizedEntryType::Thinking, "hmm")),
+            None
+        );
+        assert_eq!(
+            searchable_entry(&entry(NormalizedEntryType::SystemMessage, "model")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_fts_match_query_quotes_terms() {
+        assert_eq!(
+            fts_match_query("auth middleware"),
+            Some(r#""auth" "middleware""#.to_string())
+        );
+        assert_eq!(
+            fts_match_query(r#"auth* OR "NEAR(" -"#),
+            Some(r#""auth"* "OR" "NEAR(""#.to_string())
+        );
+        assert_eq!(fts_match_query("  \"\" * "), None);
+    }
+}
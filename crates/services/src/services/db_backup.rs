@@ -0,0 +1,181 @@
+use std::{collections::HashSet, fs, path::PathBuf, time::Duration};
+
+use chrono::Utc;
+use db::{
+    DBService,
+    backup::{self, BackupError, BackupFile, BackupKind},
+};
+use tokio::time::interval;
+use tracing::{debug, error, info};
+
+/// How many backups are kept. Scheduled backups are kept as the newest of each of the latest
+/// hours, days and weeks that have one.
+#[derive(Debug, Clone)]
+pub struct BackupRetention {
+    pub hourly: usize,
+    pub daily: usize,
+    pub weekly: usize,
+    /// Pre-migration and pre-restore backups kept, of each kind
+    pub snapshots: usize,
+}
+
+impl Default for BackupRetention {
+    fn default() -> Self {
+        Self {
+            hourly: 24,
+            daily: 7,
+            weekly: 4,
+            snapshots: 5,
+        }
+    }
+}
+
+/// Service to back up the database every hour and drop backups past their retention
+pub struct DbBackupService {
+    db: DBService,
+    dir: PathBuf,
+    poll_interval: Duration,
+    backup_interval: chrono::Duration,
+    retention: BackupRetention,
+}
+
+impl DbBackupService {
+    pub async fn spawn(db: DBService) -> tokio::task::JoinHandle<()> {
+        let service = Self {
+            db,
+            dir: backup::backup_dir(),
+            poll_interval: Duration::from_secs(10 * 60),
+            backup_interval: chrono::Duration::hours(1),
+            retention: BackupRetention::default(),
+        };
+        tokio::spawn(async move {
+            service.start().await;
+        })
+    }
+
+    async fn start(&self) {
+        info!(
+            "Starting database backup service, backing up to {}",
+            self.dir.display()
+        );
+
+        let mut interval = interval(self.poll_interval);
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.back_up_and_rotate().await {
+                error!("Error backing up the database: {}", e);
+            }
+        }
+    }
+
+    async fn back_up_and_rotate(&self) -> Result<(), BackupError> {
+        let mut backups = backup::list_backups(&self.dir)?;
+        let latest = backups.iter().find(|b| b.kind == BackupKind::Scheduled);
+        if latest.is_none_or(|b| Utc::now() - b.created_at >= self.backup_interval) {
+            let backup =
+                backup::create_backup(&self.db.pool, &self.dir, BackupKind::Scheduled).await?;
+            debug!(
+                "Backed up the database to {} ({} bytes)",
+                backup.path.display(),
+                backup.size
+            );
+            backups.insert(0, backup);
+        }
+
+        for expired in expired_backups(&backups, &self.retention) {
+            debug!("Removing expired backup {}", expired.path.display());
+            fs::remove_file(&expired.path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Backups past `retention`, given all backups newest first
+fn expired_backups<'a>(
+    backups: &'a [BackupFile],
+    retention: &BackupRetention,
+) -> Vec<&'a BackupFile> {
+    let mut keep = HashSet::new();
+    for (count, period_format) in [
+        (retention.hourly, "%Y-%m-%dT%H"),
+        (retention.daily, "%Y-%m-%d"),
+        (retention.weekly, "%G-W%V"),
+    ] {
+        let mut periods = HashSet::new();
+        for backup in backups.iter().filter(|b| b.kind == BackupKind::Scheduled) {
+            if periods.len() == count {
+                break;
+            }
+            if periods.insert(backup.created_at.format(period_format).to_string()) {
+                keep.insert(&backup.path);
+            }
+        }
+    }
+    for kind in [BackupKind::PreMigration, BackupKind::PreRestore] {
+        keep.extend(
+            backups
+                .iter()
+                .filter(|b| b.kind == kind)
+                .take(retention.snapshots)
+                .map(|b| &b.path),
+        );
+    }
+
+    backups.iter().filter(|b| !keep.contains(&b.path)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::DateTime;
+
+    use super::*;
+
+    fn backup(kind: BackupKind, created_at: &str) -> BackupFile {
+        BackupFile {
+            path: PathBuf::from(format!("{kind}-{created_at}")),
+            kind,
+            created_at: DateTime::parse_from_rfc3339(created_at)
+                .unwrap()
+                .with_timezone(&Utc),
+            size: 0,
+        }
+    }
+
+    #[test]
+    fn test_expired_backups() {
+        let retention = BackupRetention {
+            hourly: 2,
+            daily: 2,
+            weekly: 2,
+            snapshots: 1,
+        };
+        // Newest first; 2026-03-02 is a Monday
+        let backups = vec![
+            backup(BackupKind::Scheduled, "2026-03-04T10:40:00Z"),
+            backup(BackupKind::Scheduled, "2026-03-04T10:10:00Z"),
+            backup(BackupKind::PreMigration, "2026-03-04T09:50:00Z"),
+            backup(BackupKind::Scheduled, "2026-03-04T09:30:00Z"),
+            backup(BackupKind::Scheduled, "2026-03-04T08:30:00Z"),
+            backup(BackupKind::Scheduled, "2026-03-03T23:30:00Z"),
+            backup(BackupKind::Scheduled, "2026-03-03T22:30:00Z"),
+            backup(BackupKind::PreMigration, "2026-03-02T12:00:00Z"),
+            backup(BackupKind::Scheduled, "2026-03-01T23:30:00Z"),
+            backup(BackupKind::Scheduled, "2026-02-25T23:30:00Z"),
+        ];
+
+        let expired: Vec<String> = expired_backups(&backups, &retention)
+            .into_iter()
+            .map(|b| b.path.to_string_lossy().to_string())
+            .collect();
+        assert_eq!(
+            expired,
+            vec![
+                "scheduled-2026-03-04T10:10:00Z",
+                "scheduled-2026-03-04T08:30:00Z",
+                "scheduled-2026-03-03T22:30:00Z",
+                "pre-migration-2026-03-02T12:00:00Z",
+                "scheduled-2026-02-25T23:30:00Z",
+            ]
+        );
+    }
+}
@@ -3,8 +3,8 @@ use std::{str::FromStr, sync::Arc};
 use db::{
     DBService,
     models::{
-        execution_process::ExecutionProcess, project::Project, scratch::Scratch, session::Session,
-        task::Task, workspace::Workspace,
+        execution_process::ExecutionProcess, merge::Merge, project::Project, scratch::Scratch,
+        session::Session, task::Task, workspace::Workspace,
     },
 };
 use serde_json::json;
@@ -21,7 +21,7 @@ mod streams;
 pub mod types;
 
 pub use patches::{
-    execution_process_patch, project_patch, scratch_patch, task_patch, workspace_patch,
+    execution_process_patch, merge_patch, project_patch, scratch_patch, task_patch, workspace_patch,
 };
 pub use types::{EventError, EventPatch, EventPatchInner, HookTables, RecordTypes};
 
@@ -185,6 +185,10 @@ impl EventService {
                                     // Deletions handled in preupdate hook for reliable data capture
                                     return;
                                 }
+                                (HookTables::Merges, SqliteOperation::Delete) => {
+                                    // Merges are only deleted along with their workspace
+                                    return;
+                                }
                                 (HookTables::Tasks, _) => {
                                     match Task::find_by_rowid(&db.pool, rowid).await {
                                         Ok(Some(task)) => RecordTypes::Task(task),
@@ -245,6 +249,16 @@ impl EventService {
                                         }
                                     }
                                 }
+                                (HookTables::Merges, _) => {
+                                    match Merge::find_by_rowid(&db.pool, rowid).await {
+                                        Ok(Some(merge)) => RecordTypes::Merge(merge),
+                                        Ok(None) => return,
+                                        Err(e) => {
+                                            tracing::error!("Failed to fetch merge: {:?}", e);
+                                            return;
+                                        }
+                                    }
+                                }
                                 (HookTables::Scratch, _) => {
                                     match Scratch::find_by_rowid(&db.pool, rowid).await {
                                         Ok(Some(scratch)) => RecordTypes::Scratch(scratch),
@@ -311,6 +325,14 @@ impl EventService {
                                     msg_store_for_hook.push_patch(patch);
                                     return;
                                 }
+                                RecordTypes::Merge(merge) => {
+                                    let patch = match hook.operation {
+                                        SqliteOperation::Insert => merge_patch::add(merge),
+                                        _ => merge_patch::replace(merge),
+                                    };
+                                    msg_store_for_hook.push_patch(patch);
+                                    return;
+                                }
                                 RecordTypes::Scratch(scratch) => {
                                     let patch = match hook.operation {
                                         SqliteOperation::Insert => scratch_patch::add(scratch),
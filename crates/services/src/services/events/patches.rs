@@ -1,5 +1,5 @@
 use db::models::{
-    execution_process::ExecutionProcess, project::Project, scratch::Scratch,
+    execution_process::ExecutionProcess, merge::Merge, project::Project, scratch::Scratch,
     task::TaskWithAttemptStatus, workspace::WorkspaceWithStatus,
 };
 use json_patch::{AddOperation, Patch, PatchOperation, RemoveOperation, ReplaceOperation};
@@ -214,3 +214,32 @@ pub mod scratch_patch {
         })])
     }
 }
+
+/// Helper functions for creating merge-specific patches
+pub mod merge_patch {
+    use super::*;
+
+    fn merge_path(merge_id: Uuid) -> String {
+        format!("/merges/{}", escape_pointer_segment(&merge_id.to_string()))
+    }
+
+    /// Create patch for adding a new merge
+    pub fn add(merge: &Merge) -> Patch {
+        Patch(vec![PatchOperation::Add(AddOperation {
+            path: merge_path(merge.id())
+                .try_into()
+                .expect("Merge path should be valid"),
+            value: serde_json::to_value(merge).expect("Merge serialization should not fail"),
+        })])
+    }
+
+    /// Create patch for updating an existing merge, e.g. its PR status or checks
+    pub fn replace(merge: &Merge) -> Patch {
+        Patch(vec![PatchOperation::Replace(ReplaceOperation {
+            path: merge_path(merge.id())
+                .try_into()
+                .expect("Merge path should be valid"),
+            value: serde_json::to_value(merge).expect("Merge serialization should not fail"),
+        })])
+    }
+}
@@ -1,5 +1,6 @@
 use db::models::{
     execution_process::ExecutionProcess,
+    merge::Merge,
     project::Project,
     scratch::Scratch,
     task::{Task, TaskWithAttemptStatus},
@@ -522,4 +523,59 @@ impl EventService {
         let initial_stream = futures::stream::iter(vec![Ok(initial_msg), Ok(LogMsg::Ready)]);
         Ok(initial_stream.chain(filtered_stream).boxed())
     }
+
+    /// Stream the merges and PRs of a workspace, with their PR status and CI checks, with
+    /// initial snapshot (raw LogMsg format for WebSocket)
+    pub async fn stream_merges_for_workspace_raw(
+        &self,
+        workspace_id: Uuid,
+    ) -> Result<futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>>, EventError>
+    {
+        let merges = Merge::find_by_workspace_id(&self.db.pool, workspace_id).await?;
+        let merges_map: serde_json::Map<String, serde_json::Value> = merges
+            .into_iter()
+            .map(|merge| (merge.id().to_string(), serde_json::to_value(merge).unwrap()))
+            .collect();
+
+        let initial_patch = json!([{
+            "op": "replace",
+            "path": "/merges",
+            "value": merges_map
+        }]);
+        let initial_msg = LogMsg::JsonPatch(serde_json::from_value(initial_patch).unwrap());
+
+        let workspace_id_str = workspace_id.to_string();
+        let filtered_stream =
+            BroadcastStream::new(self.msg_store.get_receiver()).filter_map(move |msg_result| {
+                let workspace_id_str = workspace_id_str.clone();
+                async move {
+                    match msg_result {
+                        Ok(LogMsg::JsonPatch(patch)) => {
+                            if let Some(op) = patch.0.first()
+                                && op.path().starts_with("/merges/")
+                            {
+                                let value = match op {
+                                    json_patch::PatchOperation::Add(a) => Some(&a.value),
+                                    json_patch::PatchOperation::Replace(r) => Some(&r.value),
+                                    _ => None,
+                                };
+                                if value
+                                    .and_then(|v| v.get("workspace_id"))
+                                    .and_then(|id| id.as_str())
+                                    == Some(workspace_id_str.as_str())
+                                {
+                                    return Some(Ok(LogMsg::JsonPatch(patch)));
+                                }
+                            }
+                            None
+                        }
+                        Ok(other) => Some(Ok(other)),
+                        Err(_) => None,
+                    }
+                }
+            });
+
+        let initial_stream = futures::stream::iter(vec![Ok(initial_msg), Ok(LogMsg::Ready)]);
+        Ok(initial_stream.chain(filtered_stream).boxed())
+    }
 }
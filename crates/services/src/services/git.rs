@@ -2,8 +2,8 @@ use std::{collections::HashMap, path::Path};
 
 use chrono::{DateTime, Utc};
 use git2::{
-    BranchType, Delta, DiffFindOptions, DiffOptions, Error as GitError, Reference, Remote,
-    Repository, Sort,
+    BranchType, Delta, DiffFindOptions, DiffOptions, Error as GitError, FileMode, Reference,
+    Remote, Repository, Sort,
 };
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
@@ -56,6 +56,29 @@ pub enum ConflictOp {
     Revert,
 }
 
+/// A file left unmerged by a conflicted operation, with the version of each side. During a
+/// rebase `ours` is the branch being rebased onto and `theirs` the commit being replayed.
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ConflictFile {
+    pub path: String,
+    /// Null when the side does not have the file
+    pub base: Option<String>,
+    pub ours: Option<String>,
+    pub theirs: Option<String>,
+    /// True when a side is not UTF-8 text; its contents are left out
+    pub is_binary: bool,
+}
+
+/// How to resolve a conflicted file: take one side, or replace it with `content`
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(tag = "type", rename_all = "snake_case")]
+pub enum ConflictResolution {
+    Ours,
+    Theirs,
+    Content { content: String },
+}
+
 #[derive(Debug, Serialize, TS)]
 pub struct GitBranch {
     pub name: String,
@@ -89,6 +112,16 @@ impl std::fmt::Display for Commit {
     }
 }
 
+/// New content of a file at a repo-relative path; `None` removes the file
+#[derive(Debug, Clone)]
+pub struct FileWrite {
+    pub path: String,
+    pub content: Option<Vec<u8>>,
+    /// Mode to commit the file with, where `content` of a `FileMode::Link` is the link target.
+    /// When `None`, the file keeps the mode it has on the branch.
+    pub mode: Option<FileMode>,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct WorktreeResetOptions {
     pub perform_reset: bool,
@@ -137,6 +170,12 @@ pub enum DiffTarget<'p> {
         repo_path: &'p Path,
         commit_sha: &'p str,
     },
+    /// Changes between two commits
+    CommitRange {
+        repo_path: &'p Path,
+        from_commit: &'p str,
+        to_commit: &'p str,
+    },
 }
 
 impl Default for GitService {
@@ -407,6 +446,38 @@ impl GitService {
                 let mut find_opts = git2::DiffFindOptions::new();
                 diff.find_similar(Some(&mut find_opts))?;
 
+                self.convert_diff_to_file_diffs(diff, &repo)
+            }
+            DiffTarget::CommitRange {
+                repo_path,
+                from_commit,
+                to_commit,
+            } => {
+                let repo = self.open_repo(repo_path)?;
+                let find_tree = |sha: &str| -> Result<git2::Tree<'_>, GitServiceError> {
+                    let oid = git2::Oid::from_str(sha).map_err(|_| {
+                        GitServiceError::InvalidRepository(format!("Invalid commit SHA: {sha}"))
+                    })?;
+                    Ok(repo.find_commit(oid)?.tree()?)
+                };
+                let from_tree = find_tree(from_commit)?;
+                let to_tree = find_tree(to_commit)?;
+
+                let mut diff_opts = git2::DiffOptions::new();
+                diff_opts.include_typechange(true);
+
+                if let Some(paths) = path_filter {
+                    for path in paths {
+                        diff_opts.pathspec(*path);
+                    }
+                }
+
+                let mut diff =
+                    repo.diff_tree_to_tree(Some(&from_tree), Some(&to_tree), Some(&mut diff_opts))?;
+
+                let mut find_opts = git2::DiffFindOptions::new();
+                diff.find_similar(Some(&mut find_opts))?;
+
                 self.convert_diff_to_file_diffs(diff, &repo)
             }
         }
@@ -884,6 +955,155 @@ impl GitService {
             }
         }
     }
+
+    /// Move the local base branch forward to the task branch, which must already contain it.
+    /// Returns the new head of the base branch.
+    pub fn fast_forward_branch(
+        &self,
+        repo_path: &Path,
+        task_branch_name: &str,
+        base_branch_name: &str,
+    ) -> Result<String, GitServiceError> {
+        let (_, task_behind) =
+            self.get_branch_status(repo_path, task_branch_name, base_branch_name)?;
+        if task_behind > 0 {
+            return Err(GitServiceError::BranchesDiverged(format!(
+                "Cannot fast-forward: base branch '{base_branch_name}' is {task_behind} commits ahead of task branch '{task_branch_name}'.",
+            )));
+        }
+
+        let repo = self.open_repo(repo_path)?;
+        let base_branch = repo
+            .find_branch(base_branch_name, BranchType::Local)
+            .map_err(|_| GitServiceError::BranchNotFound(base_branch_name.to_string()))?;
+        let task_commit = Self::find_branch(&repo, task_branch_name)?
+            .get()
+            .peel_to_commit()?;
+
+        self.fast_forward_local_branch(
+            repo_path,
+            base_branch,
+            base_branch_name,
+            task_commit.id(),
+            &format!("Fast-forward to {task_branch_name}"),
+        )?;
+
+        Ok(task_commit.id().to_string())
+    }
+
+    /// Commit `files` on top of a local branch without touching any worktree and move the
+    /// branch to the new commit. Returns the new head of the branch, which is unchanged when
+    /// the files already have the given contents.
+    pub fn commit_files_to_branch(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+        files: &[FileWrite],
+        commit_message: &str,
+    ) -> Result<String, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let branch = repo
+            .find_branch(branch_name, BranchType::Local)
+            .map_err(|_| GitServiceError::BranchNotFound(branch_name.to_string()))?;
+        let head_commit = branch.get().peel_to_commit()?;
+        let head_tree = head_commit.tree()?;
+
+        let mut tree_update = git2::build::TreeUpdateBuilder::new();
+        for file in files {
+            match &file.content {
+                Some(content) => {
+                    let blob_id = repo.blob(content)?;
+                    let mode = match (file.mode, head_tree.get_path(Path::new(&file.path))) {
+                        (Some(mode), _) => mode,
+                        (None, Ok(entry)) if entry.filemode() == i32::from(FileMode::Link) => {
+                            FileMode::Link
+                        }
+                        (None, Ok(entry))
+                            if entry.filemode() == i32::from(FileMode::BlobExecutable) =>
+                        {
+                            FileMode::BlobExecutable
+                        }
+                        _ => FileMode::Blob,
+                    };
+                    tree_update.upsert(file.path.as_str(), blob_id, mode);
+                }
+                None => {
+                    tree_update.remove(file.path.as_str());
+                }
+            }
+        }
+        let tree_id = tree_update.create_updated(&repo, &head_tree)?;
+        if tree_id == head_tree.id() {
+            return Ok(head_commit.id().to_string());
+        }
+
+        let tree = repo.find_tree(tree_id)?;
+        let signature = self.signature_with_fallback(&repo)?;
+        let commit_id = repo.commit(
+            None,
+            &signature,
+            &signature,
+            commit_message,
+            &tree,
+            &[&head_commit],
+        )?;
+
+        self.fast_forward_local_branch(
+            repo_path,
+            branch,
+            branch_name,
+            commit_id,
+            "Commit selected changes",
+        )?;
+
+        Ok(commit_id.to_string())
+    }
+
+    /// Move a local branch forward to `commit_id`, which must descend from its head
+    fn fast_forward_local_branch(
+        &self,
+        repo_path: &Path,
+        branch: git2::Branch,
+        branch_name: &str,
+        commit_id: git2::Oid,
+        reflog_message: &str,
+    ) -> Result<(), GitServiceError> {
+        match self.find_checkout_path_for_branch(repo_path, branch_name)? {
+            Some(checkout_path) => {
+                // Let the CLI move the checked out branch so its worktree follows
+                let git_cli = GitCli::new();
+                git_cli
+                    .merge_ff_only(&checkout_path, &commit_id.to_string())
+                    .map_err(|e| {
+                        GitServiceError::InvalidRepository(format!("CLI fast-forward failed: {e}"))
+                    })?;
+            }
+            None => {
+                branch
+                    .into_reference()
+                    .set_target(commit_id, reflog_message)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Raw content of a file as of `commit`, or `None` when it does not exist there
+    pub fn read_file_at_commit(
+        &self,
+        repo_path: &Path,
+        commit: &Commit,
+        path: &str,
+    ) -> Result<Option<Vec<u8>>, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let tree = repo.find_commit(commit.as_oid())?.tree()?;
+        match tree.get_path(Path::new(path)) {
+            Ok(entry) if entry.kind() == Some(git2::ObjectType::Blob) => {
+                Ok(Some(repo.find_blob(entry.id())?.content().to_vec()))
+            }
+            _ => Ok(None),
+        }
+    }
+
     fn get_branch_status_inner(
         &self,
         repo: &Repository,
@@ -959,6 +1179,21 @@ impl GitService {
         self.get_branch_status_inner(&repo, &branch_ref, &base_branch_ref)
     }
 
+    /// Update a remote branch from its remote, so it can be compared against where it is now.
+    /// Local branches are left alone.
+    pub fn fetch_remote_branch(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+    ) -> Result<(), GitServiceError> {
+        let repo = Repository::open(repo_path)?;
+        let branch_ref = Self::find_branch(&repo, branch_name)?.into_reference();
+        if branch_ref.is_remote() {
+            self.fetch_branch_from_remote(&repo, &branch_ref)?;
+        }
+        Ok(())
+    }
+
     pub fn is_worktree_clean(&self, worktree_path: &Path) -> Result<bool, GitServiceError> {
         let repo = self.open_repo(worktree_path)?;
         match self.check_worktree_clean(&repo) {
@@ -1563,6 +1798,95 @@ impl GitService {
         Ok(())
     }
 
+    /// Conflicted files of the worktree with their base, ours and theirs versions
+    pub fn get_conflict_files(
+        &self,
+        worktree_path: &Path,
+    ) -> Result<Vec<ConflictFile>, GitServiceError> {
+        let git = GitCli::new();
+        let mut files = Vec::new();
+        for path in self.get_conflicted_files(worktree_path)? {
+            let stages = git.get_conflict_stages(worktree_path, &path)?;
+            let mut is_binary = false;
+            let mut read_stage = |stage: u8| -> Result<Option<String>, GitServiceError> {
+                let Some(oid) = stages.get(&stage) else {
+                    return Ok(None);
+                };
+                let bytes = git.read_blob(worktree_path, oid)?;
+                match String::from_utf8(bytes) {
+                    Ok(text) if !text.contains('\0') => Ok(Some(text)),
+                    _ => {
+                        is_binary = true;
+                        Ok(None)
+                    }
+                }
+            };
+            let base = read_stage(1)?;
+            let ours = read_stage(2)?;
+            let theirs = read_stage(3)?;
+            files.push(ConflictFile {
+                path,
+                base,
+                ours,
+                theirs,
+                is_binary,
+            });
+        }
+        Ok(files)
+    }
+
+    /// Resolve one conflicted file and stage it. `path` must be one of the conflicted files.
+    pub fn resolve_conflict(
+        &self,
+        worktree_path: &Path,
+        path: &str,
+        resolution: &ConflictResolution,
+    ) -> Result<(), GitServiceError> {
+        let git = GitCli::new();
+        match resolution {
+            ConflictResolution::Ours => git.checkout_conflict_side(worktree_path, path, true)?,
+            ConflictResolution::Theirs => git.checkout_conflict_side(worktree_path, path, false)?,
+            ConflictResolution::Content { content } => {
+                let file_path = worktree_path.join(path);
+                if let Some(parent) = file_path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(&file_path, content)?;
+                git.mark_resolved(worktree_path, path)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Continue the conflicted operation once every file is resolved. Returns `MergeConflicts`
+    /// when it stops again, e.g. on the next commit of a rebase.
+    pub fn continue_conflicts(&self, worktree_path: &Path) -> Result<(), GitServiceError> {
+        let Some(op) = self.detect_conflict_op(worktree_path)? else {
+            return Ok(());
+        };
+        self.ensure_cli_commit_identity(worktree_path)?;
+
+        let git = GitCli::new();
+        let result = match op {
+            ConflictOp::Rebase => git.continue_rebase(worktree_path),
+            ConflictOp::Merge => git.continue_merge(worktree_path),
+            ConflictOp::CherryPick => git.continue_cherry_pick(worktree_path),
+            ConflictOp::Revert => git.continue_revert(worktree_path),
+        };
+        match result {
+            Ok(()) => Ok(()),
+            Err(GitCliError::CommandFailed(msg))
+                if !self
+                    .get_conflicted_files(worktree_path)
+                    .unwrap_or_default()
+                    .is_empty() =>
+            {
+                Err(GitServiceError::MergeConflicts(msg))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
     pub fn find_branch<'a>(
         repo: &'a Repository,
         branch_name: &str,
@@ -1607,6 +1931,12 @@ impl GitService {
             .map_err(GitServiceError::GitCLI)
     }
 
+    /// URL of the remote the repo pushes to by default
+    pub fn get_default_remote_url(&self, repo_path: &Path) -> Result<String, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        self.get_remote_url(repo_path, &self.default_remote_name(&repo))
+    }
+
     pub fn check_remote_branch_exists(
         &self,
         repo_path: &Path,
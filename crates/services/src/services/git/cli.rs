@@ -16,6 +16,7 @@
 //! `git` CLI, while keeping libgit2 for read‑only graph queries and credentialed
 //! network operations when useful.
 use std::{
+    collections::HashMap,
     ffi::{OsStr, OsString},
     io::Write as _,
     path::Path,
@@ -608,6 +609,12 @@ impl GitCli {
         Ok(sha)
     }
 
+    /// Fast-forward the branch checked out in `repo_path` to `from_branch`
+    pub fn merge_ff_only(&self, repo_path: &Path, from_branch: &str) -> Result<(), GitCliError> {
+        self.git(repo_path, ["merge", "--ff-only", from_branch])
+            .map(|_| ())
+    }
+
     /// Update a ref to a specific sha in the repo.
     pub fn update_ref(
         &self,
@@ -654,6 +661,81 @@ impl GitCli {
         }
         Ok(files)
     }
+
+    /// Blob ids of the index stages of a conflicted file, keyed by stage number
+    /// (1 = merge base, 2 = ours, 3 = theirs). Sides that deleted the file have no stage.
+    pub fn get_conflict_stages(
+        &self,
+        worktree_path: &Path,
+        path: &str,
+    ) -> Result<HashMap<u8, String>, GitCliError> {
+        // `ls-files -u` prints "<mode> <oid> <stage>\t<path>" per unmerged stage
+        let out = self.git(worktree_path, ["ls-files", "-u", "--", path])?;
+        let mut stages = HashMap::new();
+        for line in out.lines() {
+            let Some((meta, _)) = line.split_once('\t') else {
+                continue;
+            };
+            let mut fields = meta.split_whitespace();
+            if let (Some(_mode), Some(oid), Some(stage)) =
+                (fields.next(), fields.next(), fields.next())
+                && let Ok(stage) = stage.parse::<u8>()
+            {
+                stages.insert(stage, oid.to_string());
+            }
+        }
+        Ok(stages)
+    }
+
+    /// Raw contents of a blob
+    pub fn read_blob(&self, worktree_path: &Path, oid: &str) -> Result<Vec<u8>, GitCliError> {
+        self.git_impl(worktree_path, ["cat-file", "blob", oid], None, None)
+    }
+
+    /// Resolve a conflicted file with our (`--ours`) or their (`--theirs`) version and stage it.
+    /// When that side deleted the file, the file is removed instead.
+    pub fn checkout_conflict_side(
+        &self,
+        worktree_path: &Path,
+        path: &str,
+        ours: bool,
+    ) -> Result<(), GitCliError> {
+        let stage = if ours { 2 } else { 3 };
+        if !self
+            .get_conflict_stages(worktree_path, path)?
+            .contains_key(&stage)
+        {
+            return self
+                .git(worktree_path, ["rm", "--quiet", "--force", "--", path])
+                .map(|_| ());
+        }
+        let side = if ours { "--ours" } else { "--theirs" };
+        self.git(worktree_path, ["checkout", side, "--", path])?;
+        self.git(worktree_path, ["add", "--", path]).map(|_| ())
+    }
+
+    /// Stage a conflicted file as resolved with whatever is in the worktree
+    pub fn mark_resolved(&self, worktree_path: &Path, path: &str) -> Result<(), GitCliError> {
+        self.git(worktree_path, ["add", "--", path]).map(|_| ())
+    }
+
+    /// Continue an in-progress rebase once all conflicts are resolved, keeping commit messages
+    pub fn continue_rebase(&self, worktree_path: &Path) -> Result<(), GitCliError> {
+        self.git_without_editor(worktree_path, ["rebase", "--continue"])
+    }
+
+    /// Conclude an in-progress merge with the prepared merge message
+    pub fn continue_merge(&self, worktree_path: &Path) -> Result<(), GitCliError> {
+        self.git_without_editor(worktree_path, ["commit", "--no-edit"])
+    }
+
+    pub fn continue_cherry_pick(&self, worktree_path: &Path) -> Result<(), GitCliError> {
+        self.git_without_editor(worktree_path, ["cherry-pick", "--continue"])
+    }
+
+    pub fn continue_revert(&self, worktree_path: &Path) -> Result<(), GitCliError> {
+        self.git_without_editor(worktree_path, ["revert", "--continue"])
+    }
 }
 
 // Private methods
@@ -803,6 +885,17 @@ impl GitCli {
         Ok(String::from_utf8_lossy(&out).to_string())
     }
 
+    /// Run a command that may want to open an editor for a commit message, accepting the
+    /// prepared message instead
+    fn git_without_editor<I, S>(&self, repo_path: &Path, args: I) -> Result<(), GitCliError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        let envs = [(OsString::from("GIT_EDITOR"), OsString::from("true"))];
+        self.git_with_env(repo_path, args, &envs).map(|_| ())
+    }
+
     fn git_with_stdin<I, S>(
         &self,
         repo_path: &Path,
@@ -10,12 +10,12 @@ use std::{
 };
 
 use chrono::{DateTime, Utc};
-use db::models::merge::{MergeStatus, PullRequestInfo};
+use db::models::merge::{MergeStatus, PrCheck, PrCheckStatus, PullRequestInfo};
 use serde::Deserialize;
 use thiserror::Error;
 use utils::shell::resolve_executable_path_blocking;
 
-use crate::services::git_host::types::{CreatePrRequest, UnifiedPrComment};
+use crate::services::git_host::types::{CreatePrRequest, Issue, IssueFilter, UnifiedPrComment};
 
 #[derive(Debug, Clone)]
 pub struct AzureRepoInfo {
@@ -58,6 +58,9 @@ struct AzThreadsResponse {
 struct AzThread {
     comments: Option<Vec<AzThreadComment>>,
     thread_context: Option<AzThreadContext>,
+    /// `active` or `pending` while the thread is open; `fixed`, `wontFix`, `closed` or `byDesign`
+    /// once it was resolved
+    status: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -80,6 +83,9 @@ struct AzThreadComment {
     content: Option<String>,
     published_date: Option<String>,
     comment_type: Option<String>,
+    /// 0 for the first comment of a thread
+    #[serde(default)]
+    parent_comment_id: i64,
 }
 
 #[derive(Deserialize)]
@@ -105,6 +111,59 @@ struct AzRepoProject {
     name: String,
 }
 
+/// Response item from `az boards query`
+#[derive(Deserialize)]
+struct AzWorkItem {
+    id: i64,
+    #[serde(default)]
+    fields: AzWorkItemFields,
+}
+
+#[derive(Deserialize, Default)]
+struct AzWorkItemFields {
+    #[serde(rename = "System.Title", default)]
+    title: String,
+    #[serde(rename = "System.Description")]
+    description: Option<String>,
+    #[serde(rename = "System.Tags")]
+    tags: Option<String>,
+}
+
+/// Response item from `az repos pr policy list`
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AzPolicyEvaluation {
+    status: Option<String>,
+    configuration: Option<AzPolicyConfiguration>,
+    context: Option<AzPolicyContext>,
+}
+
+#[derive(Deserialize)]
+struct AzPolicyConfiguration {
+    #[serde(rename = "type")]
+    policy_type: Option<AzPolicyType>,
+    settings: Option<AzPolicySettings>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AzPolicyType {
+    display_name: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AzPolicySettings {
+    display_name: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AzPolicyContext {
+    build_id: Option<i64>,
+    build_definition_name: Option<String>,
+}
+
 #[derive(Debug, Error)]
 pub enum AzCliError {
     #[error("Azure CLI (`az`) executable not found or not runnable")]
@@ -401,6 +460,187 @@ impl AzCli {
         Self::parse_pr_threads(&raw)
     }
 
+    /// Change the target branch of a PR. `az repos pr update` cannot retarget, so this goes
+    /// through the REST API, whose request body `az devops invoke` only accepts from a file.
+    pub fn update_pr_target(
+        &self,
+        organization_url: &str,
+        project_id: &str,
+        repo_id: &str,
+        pr_id: i64,
+        target_branch: &str,
+    ) -> Result<(), AzCliError> {
+        let body = serde_json::json!({ "targetRefName": format!("refs/heads/{target_branch}") });
+        let body_path =
+            std::env::temp_dir().join(format!("vk-az-pr-{pr_id}-{}.json", uuid::Uuid::new_v4()));
+        std::fs::write(&body_path, body.to_string())
+            .map_err(|e| AzCliError::CommandFailed(format!("Failed to write request body: {e}")))?;
+
+        let mut args: Vec<OsString> = Vec::with_capacity(20);
+        args.push(OsString::from("devops"));
+        args.push(OsString::from("invoke"));
+        args.push(OsString::from("--area"));
+        args.push(OsString::from("git"));
+        args.push(OsString::from("--resource"));
+        args.push(OsString::from("pullRequests"));
+        args.push(OsString::from("--route-parameters"));
+        args.push(OsString::from(format!("project={}", project_id)));
+        args.push(OsString::from(format!("repositoryId={}", repo_id)));
+        args.push(OsString::from(format!("pullRequestId={}", pr_id)));
+        args.push(OsString::from("--organization"));
+        args.push(OsString::from(organization_url));
+        args.push(OsString::from("--http-method"));
+        args.push(OsString::from("PATCH"));
+        args.push(OsString::from("--in-file"));
+        args.push(body_path.clone().into_os_string());
+        args.push(OsString::from("--api-version"));
+        args.push(OsString::from("7.0"));
+        args.push(OsString::from("--output"));
+        args.push(OsString::from("json"));
+
+        let result = self.run(args, None);
+        let _ = std::fs::remove_file(&body_path);
+        result.map(|_| ())
+    }
+
+    /// Start a comment thread on a PR that is not attached to a file. The thread is created
+    /// closed, so it does not hold up PR completion.
+    pub fn create_pr_thread(
+        &self,
+        organization_url: &str,
+        project_id: &str,
+        repo_id: &str,
+        pr_id: i64,
+        content: &str,
+    ) -> Result<(), AzCliError> {
+        let body = serde_json::json!({
+            "comments": [{ "parentCommentId": 0, "content": content, "commentType": "text" }],
+            "status": "closed",
+        });
+        let body_path = std::env::temp_dir().join(format!(
+            "vk-az-pr-thread-{pr_id}-{}.json",
+            uuid::Uuid::new_v4()
+        ));
+        std::fs::write(&body_path, body.to_string())
+            .map_err(|e| AzCliError::CommandFailed(format!("Failed to write request body: {e}")))?;
+
+        let mut args: Vec<OsString> = Vec::with_capacity(20);
+        args.push(OsString::from("devops"));
+        args.push(OsString::from("invoke"));
+        args.push(OsString::from("--area"));
+        args.push(OsString::from("git"));
+        args.push(OsString::from("--resource"));
+        args.push(OsString::from("pullRequestThreads"));
+        args.push(OsString::from("--route-parameters"));
+        args.push(OsString::from(format!("project={}", project_id)));
+        args.push(OsString::from(format!("repositoryId={}", repo_id)));
+        args.push(OsString::from(format!("pullRequestId={}", pr_id)));
+        args.push(OsString::from("--organization"));
+        args.push(OsString::from(organization_url));
+        args.push(OsString::from("--http-method"));
+        args.push(OsString::from("POST"));
+        args.push(OsString::from("--in-file"));
+        args.push(body_path.clone().into_os_string());
+        args.push(OsString::from("--api-version"));
+        args.push(OsString::from("7.0"));
+        args.push(OsString::from("--output"));
+        args.push(OsString::from("json"));
+
+        let result = self.run(args, None);
+        let _ = std::fs::remove_file(&body_path);
+        result.map(|_| ())
+    }
+
+    /// Fetch the build validation policies evaluated on a PR, i.e. its pipeline runs.
+    pub fn get_pr_build_statuses(
+        &self,
+        organization_url: &str,
+        project: &str,
+        pr_id: i64,
+    ) -> Result<Vec<PrCheck>, AzCliError> {
+        let raw = self.run(
+            [
+                "repos",
+                "pr",
+                "policy",
+                "list",
+                "--id",
+                &pr_id.to_string(),
+                "--organization",
+                organization_url,
+                "--output",
+                "json",
+            ],
+            None,
+        )?;
+
+        Self::parse_policy_evaluations(&raw, organization_url, project)
+    }
+
+    /// Query the open work items of a project on Azure Boards. Labels match tags, the
+    /// milestone matches the iteration path and the query matches titles.
+    pub fn query_work_items(
+        &self,
+        organization_url: &str,
+        project: &str,
+        filter: &IssueFilter,
+    ) -> Result<Vec<Issue>, AzCliError> {
+        let raw = self.run(
+            [
+                "boards",
+                "query",
+                "--wiql",
+                &Self::work_items_wiql(filter),
+                "--organization",
+                organization_url,
+                "--project",
+                project,
+                "--output",
+                "json",
+            ],
+            None,
+        )?;
+
+        let mut issues = Self::parse_work_items(&raw, organization_url, project)?;
+        issues.truncate(filter.limit);
+        Ok(issues)
+    }
+
+    /// Close a work item, leaving a comment in its discussion. Which state counts as closed
+    /// depends on the project's process: Agile uses "Closed", Basic and Scrum use "Done".
+    pub fn close_work_item(
+        &self,
+        organization_url: &str,
+        work_item_id: i64,
+        comment: &str,
+    ) -> Result<(), AzCliError> {
+        let id = work_item_id.to_string();
+        let close = |state: &str| {
+            self.run(
+                [
+                    "boards",
+                    "work-item",
+                    "update",
+                    "--id",
+                    &id,
+                    "--state",
+                    state,
+                    "--discussion",
+                    comment,
+                    "--organization",
+                    organization_url,
+                    "--output",
+                    "json",
+                ],
+                None,
+            )
+        };
+        match close("Closed") {
+            Err(AzCliError::CommandFailed(_)) => close("Done").map(|_| ()),
+            result => result.map(|_| ()),
+        }
+    }
+
     /// Parse PR URL to extract organization and PR ID.
     ///
     /// Only extracts the minimal info needed for `az repos pr show`.
@@ -501,6 +741,10 @@ impl AzCli {
                 .as_ref()
                 .and_then(|c| c.right_file_start.as_ref())
                 .and_then(|p| p.line);
+            let resolved = thread
+                .status
+                .as_deref()
+                .is_some_and(|status| !matches!(status, "active" | "pending" | "unknown"));
 
             if let Some(thread_comments) = thread.comments {
                 for c in thread_comments {
@@ -510,6 +754,7 @@ impl AzCli {
                     }
 
                     let id = c.id.unwrap_or(0);
+                    let is_reply = c.parent_comment_id != 0;
                     let author = c
                         .author
                         .and_then(|a| a.display_name)
@@ -533,6 +778,8 @@ impl AzCli {
                             line,
                             side: None,
                             diff_hunk: None,
+                            is_reply,
+                            resolved,
                         });
                     } else {
                         comments.push(UnifiedPrComment::General {
@@ -552,6 +799,162 @@ impl AzCli {
         Ok(comments)
     }
 
+    fn work_items_wiql(filter: &IssueFilter) -> String {
+        let quote = |value: &str| format!("'{}'", value.replace('\'', "''"));
+        let mut conditions = vec![
+            "[System.TeamProject] = @project".to_string(),
+            "[System.State] NOT IN ('Closed', 'Done', 'Removed', 'Resolved')".to_string(),
+        ];
+        for label in &filter.labels {
+            conditions.push(format!("[System.Tags] CONTAINS {}", quote(label)));
+        }
+        if let Some(milestone) = &filter.milestone {
+            conditions.push(format!("[System.IterationPath] UNDER {}", quote(milestone)));
+        }
+        if let Some(query) = &filter.query {
+            conditions.push(format!("[System.Title] CONTAINS {}", quote(query)));
+        }
+        format!(
+            "SELECT [System.Id], [System.Title], [System.Description], [System.Tags] \
+             FROM workitems WHERE {} ORDER BY [System.Id]",
+            conditions.join(" AND ")
+        )
+    }
+
+    fn parse_work_items(
+        raw: &str,
+        organization_url: &str,
+        project: &str,
+    ) -> Result<Vec<Issue>, AzCliError> {
+        let items: Vec<AzWorkItem> = serde_json::from_str(raw.trim()).map_err(|e| {
+            AzCliError::UnexpectedOutput(format!(
+                "Failed to parse work item query response: {e}; raw: {raw}"
+            ))
+        })?;
+
+        Ok(items
+            .into_iter()
+            .map(|item| Issue {
+                number: item.id,
+                title: item.fields.title,
+                body: item
+                    .fields
+                    .description
+                    .as_deref()
+                    .map(Self::html_to_text)
+                    .unwrap_or_default(),
+                url: format!(
+                    "{}/{}/_workitems/edit/{}",
+                    organization_url.trim_end_matches('/'),
+                    project,
+                    item.id
+                ),
+                labels: item
+                    .fields
+                    .tags
+                    .unwrap_or_default()
+                    .split(';')
+                    .map(str::trim)
+                    .filter(|tag| !tag.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+            })
+            .collect())
+    }
+
+    /// Work item descriptions are HTML; keep their text and line breaks
+    fn html_to_text(html: &str) -> String {
+        let mut text = String::with_capacity(html.len());
+        let mut rest = html;
+        while let Some(start) = rest.find('<') {
+            text.push_str(&rest[..start]);
+            let Some(end) = rest[start..].find('>') else {
+                rest = &rest[start..];
+                break;
+            };
+            let inner = &rest[start + 1..start + end];
+            let tag = inner
+                .trim_start_matches('/')
+                .split(|c: char| c.is_whitespace() || c == '/')
+                .next()
+                .unwrap_or_default()
+                .to_ascii_lowercase();
+            // Line breaks, and the ends of blocks
+            let closing = inner.starts_with('/');
+            if tag == "br" || (closing && matches!(tag.as_str(), "p" | "div" | "li" | "tr")) {
+                text.push('\n');
+            }
+            rest = &rest[start + end + 1..];
+        }
+        text.push_str(rest);
+
+        let text = text
+            .replace("&nbsp;", " ")
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&#39;", "'")
+            .replace("&amp;", "&");
+        let lines: Vec<&str> = text.lines().map(str::trim_end).collect();
+        lines.join("\n").trim().to_string()
+    }
+
+    fn parse_policy_evaluations(
+        raw: &str,
+        organization_url: &str,
+        project: &str,
+    ) -> Result<Vec<PrCheck>, AzCliError> {
+        let evaluations: Vec<AzPolicyEvaluation> =
+            serde_json::from_str(raw.trim()).map_err(|e| {
+                AzCliError::UnexpectedOutput(format!(
+                    "Failed to parse policy list response: {e}; raw: {raw}"
+                ))
+            })?;
+
+        Ok(evaluations
+            .into_iter()
+            .filter_map(|evaluation| {
+                let configuration = evaluation.configuration?;
+                let is_build = configuration
+                    .policy_type
+                    .and_then(|t| t.display_name)
+                    .is_some_and(|name| name.eq_ignore_ascii_case("build"));
+                if !is_build {
+                    return None;
+                }
+                let build_id = evaluation.context.as_ref().and_then(|c| c.build_id);
+                let name = configuration
+                    .settings
+                    .and_then(|s| s.display_name)
+                    .filter(|name| !name.is_empty())
+                    .or_else(|| evaluation.context.and_then(|c| c.build_definition_name))
+                    .unwrap_or_else(|| "Build".to_string());
+                Some(PrCheck {
+                    name,
+                    status: Self::map_policy_status(evaluation.status.as_deref().unwrap_or("")),
+                    url: build_id.map(|id| {
+                        format!(
+                            "{}/{}/_build/results?buildId={id}",
+                            organization_url.trim_end_matches('/'),
+                            project
+                        )
+                    }),
+                    description: None,
+                })
+            })
+            .collect())
+    }
+
+    /// Map Azure DevOps policy evaluation status to PrCheckStatus
+    fn map_policy_status(status: &str) -> PrCheckStatus {
+        match status.to_lowercase().as_str() {
+            "approved" => PrCheckStatus::Passed,
+            "rejected" | "broken" => PrCheckStatus::Failed,
+            "running" | "queued" => PrCheckStatus::Pending,
+            _ => PrCheckStatus::Skipped,
+        }
+    }
+
     /// Map Azure DevOps PR status to MergeStatus
     fn map_azure_status(status: &str) -> MergeStatus {
         match status.to_lowercase().as_str() {
@@ -617,6 +1020,93 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_parse_policy_evaluations_keeps_builds() {
+        let raw = r#"[
+            {
+                "status": "rejected",
+                "configuration": {
+                    "type": { "displayName": "Build" },
+                    "settings": { "displayName": "CI" }
+                },
+                "context": { "buildId": 42 }
+            },
+            {
+                "status": "approved",
+                "configuration": {
+                    "type": { "displayName": "Minimum number of reviewers" },
+                    "settings": {}
+                }
+            },
+            {
+                "status": "queued",
+                "configuration": { "type": { "displayName": "Build" }, "settings": {} },
+                "context": { "buildDefinitionName": "nightly" }
+            }
+        ]"#;
+        let checks =
+            AzCli::parse_policy_evaluations(raw, "https://dev.azure.com/myorg/", "myproject")
+                .unwrap();
+        assert_eq!(
+            checks,
+            vec![
+                PrCheck {
+                    name: "CI".to_string(),
+                    status: PrCheckStatus::Failed,
+                    url: Some(
+                        "https://dev.azure.com/myorg/myproject/_build/results?buildId=42"
+                            .to_string()
+                    ),
+                    description: None,
+                },
+                PrCheck {
+                    name: "nightly".to_string(),
+                    status: PrCheckStatus::Pending,
+                    url: None,
+                    description: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_work_items() {
+        let raw = r#"[
+            {
+                "id": 7,
+                "fields": {
+                    "System.Title": "Add export",
+                    "System.Description": "<div>Export &amp; import</div><div>projects<br/>as JSON</div>",
+                    "System.Tags": "backend; api"
+                }
+            }
+        ]"#;
+        let issues =
+            AzCli::parse_work_items(raw, "https://dev.azure.com/myorg", "myproject").unwrap();
+        assert_eq!(
+            issues,
+            vec![Issue {
+                number: 7,
+                title: "Add export".to_string(),
+                body: "Export & import\nprojects\nas JSON".to_string(),
+                url: "https://dev.azure.com/myorg/myproject/_workitems/edit/7".to_string(),
+                labels: vec!["backend".to_string(), "api".to_string()],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_work_items_wiql_quotes_filters() {
+        let wiql = AzCli::work_items_wiql(&IssueFilter {
+            labels: vec!["ui".to_string()],
+            milestone: None,
+            query: Some("user's".to_string()),
+            limit: 10,
+        });
+        assert!(wiql.contains("[System.Tags] CONTAINS 'ui'"));
+        assert!(wiql.contains("[System.Title] CONTAINS 'user''s'"));
+    }
+
     #[test]
     fn test_urls_match() {
         // Exact match
@@ -8,13 +8,13 @@ use async_trait::async_trait;
 use backon::{ExponentialBuilder, Retryable};
 pub use cli::AzCli;
 use cli::{AzCliError, AzureRepoInfo};
-use db::models::merge::PullRequestInfo;
+use db::models::merge::{PrCheck, PullRequestInfo};
 use tokio::task;
 use tracing::info;
 
 use super::{
     GitHostProvider,
-    types::{CreatePrRequest, GitHostError, ProviderKind, UnifiedPrComment},
+    types::{CreatePrRequest, GitHostError, Issue, IssueFilter, ProviderKind, UnifiedPrComment},
 };
 
 #[derive(Debug, Clone)]
@@ -272,6 +272,135 @@ impl GitHostProvider for AzureDevOpsProvider {
         .await
     }
 
+    async fn update_pr_base(
+        &self,
+        repo_path: &Path,
+        remote_url: &str,
+        pr_number: i64,
+        base_branch: &str,
+    ) -> Result<(), GitHostError> {
+        let repo_info = self.get_repo_info(repo_path, remote_url).await?;
+        let cli = self.az_cli.clone();
+        let base_branch = base_branch.to_string();
+        task::spawn_blocking(move || {
+            cli.update_pr_target(
+                &repo_info.organization_url,
+                &repo_info.project_id,
+                &repo_info.repo_id,
+                pr_number,
+                &base_branch,
+            )
+        })
+        .await
+        .map_err(|err| {
+            GitHostError::PullRequest(format!(
+                "Failed to execute Azure CLI for updating PR target: {err}"
+            ))
+        })?
+        .map_err(GitHostError::from)
+    }
+
+    async fn create_pr_comment(
+        &self,
+        repo_path: &Path,
+        remote_url: &str,
+        pr_number: i64,
+        body: &str,
+    ) -> Result<(), GitHostError> {
+        let repo_info = self.get_repo_info(repo_path, remote_url).await?;
+        let cli = self.az_cli.clone();
+        let body = body.to_string();
+        task::spawn_blocking(move || {
+            cli.create_pr_thread(
+                &repo_info.organization_url,
+                &repo_info.project_id,
+                &repo_info.repo_id,
+                pr_number,
+                &body,
+            )
+        })
+        .await
+        .map_err(|err| {
+            GitHostError::PullRequest(format!(
+                "Failed to execute Azure CLI for commenting on PR: {err}"
+            ))
+        })?
+        .map_err(GitHostError::from)
+    }
+
+    async fn get_pr_checks(
+        &self,
+        repo_path: &Path,
+        remote_url: &str,
+        pr_number: i64,
+    ) -> Result<Vec<PrCheck>, GitHostError> {
+        let repo_info = self.get_repo_info(repo_path, remote_url).await?;
+        let cli = self.az_cli.clone();
+        task::spawn_blocking(move || {
+            cli.get_pr_build_statuses(&repo_info.organization_url, &repo_info.project, pr_number)
+        })
+        .await
+        .map_err(|err| {
+            GitHostError::PullRequest(format!(
+                "Failed to execute Azure CLI for fetching PR policies: {err}"
+            ))
+        })?
+        .map_err(GitHostError::from)
+    }
+
+    async fn get_check_log(
+        &self,
+        _repo_path: &Path,
+        _remote_url: &str,
+        _check: &PrCheck,
+    ) -> Result<Option<String>, GitHostError> {
+        // Pipeline logs are split per task and not exposed through `az repos`
+        Ok(None)
+    }
+
+    async fn list_issues(
+        &self,
+        repo_path: &Path,
+        remote_url: &str,
+        filter: &IssueFilter,
+    ) -> Result<Vec<Issue>, GitHostError> {
+        let repo_info = self.get_repo_info(repo_path, remote_url).await?;
+        let cli = self.az_cli.clone();
+        let filter = filter.clone();
+        task::spawn_blocking(move || {
+            cli.query_work_items(&repo_info.organization_url, &repo_info.project, &filter)
+        })
+        .await
+        .map_err(|err| {
+            GitHostError::Repository(format!(
+                "Failed to execute Azure CLI for querying work items: {err}"
+            ))
+        })?
+        .map_err(GitHostError::from)
+    }
+
+    async fn close_issue(
+        &self,
+        repo_path: &Path,
+        remote_url: &str,
+        issue_number: i64,
+        comment: &str,
+    ) -> Result<(), GitHostError> {
+        let repo_info = self.get_repo_info(repo_path, remote_url).await?;
+        let cli = self.az_cli.clone();
+        let comment = comment.to_string();
+        task::spawn_blocking(move || {
+            cli.close_work_item(&repo_info.organization_url, issue_number, &comment)
+        })
+        .await
+        .map_err(|err| {
+            GitHostError::Repository(format!(
+                "Failed to execute Azure CLI for closing a work item: {err}"
+            ))
+        })?
+        .map_err(GitHostError::from)
+    }
+
     fn provider_kind(&self) -> ProviderKind {
         ProviderKind::AzureDevOps
     }
@@ -6,36 +6,75 @@ use super::types::ProviderKind;
 ///
 /// Supports:
 /// - GitHub.com: `https://github.com/owner/repo` or `git@github.com:owner/repo.git`
-/// - GitHub Enterprise: URLs containing `github.` (e.g., `https://github.company.com/owner/repo`)
+/// - GitHub Enterprise: hosts containing `github.` (e.g., `https://github.company.com/owner/repo`)
 /// - Azure DevOps: `https://dev.azure.com/org/project/_git/repo` or legacy `https://org.visualstudio.com/...`
+/// - GitLab: gitlab.com, hosts containing `gitlab`, or merge request URLs (`/-/merge_requests/`)
+/// - Gitea/Forgejo: codeberg.org, hosts containing `gitea` or `forgejo`, or PR URLs (`/pulls/`)
+///
+/// Only the host is matched against names, so repos like `github.corp.com/org/gitlab-tools` are
+/// not mistaken for another provider. Self-hosted GitLab and Gitea instances on other hostnames
+/// need the provider set on the repo.
 pub fn detect_provider_from_url(url: &str) -> ProviderKind {
     let url_lower = url.to_lowercase();
+    let host = url_host(&url_lower);
 
-    if url_lower.contains("github.com") {
+    if host.contains("github.com") {
         return ProviderKind::GitHub;
     }
 
-    // Check Azure patterns before GHE to avoid false positives
-    if url_lower.contains("dev.azure.com")
-        || url_lower.contains(".visualstudio.com")
-        || url_lower.contains("ssh.dev.azure.com")
-    {
+    // Check Azure hosts before GHE to avoid false positives
+    if host.ends_with("dev.azure.com") || host.ends_with(".visualstudio.com") {
         return ProviderKind::AzureDevOps;
     }
 
+    // GitHub Enterprise, before any path-based check
+    if host.contains("github.") {
+        return ProviderKind::GitHub;
+    }
+
     // /_git/ is unique to Azure DevOps
     if url_lower.contains("/_git/") {
         return ProviderKind::AzureDevOps;
     }
 
-    // GitHub Enterprise (contains "github." but not the Azure patterns above)
-    if url_lower.contains("github.") {
-        return ProviderKind::GitHub;
+    // Merge request and PR paths identify self-hosted instances regardless of hostname
+    if url_lower.contains("/-/merge_requests/") {
+        return ProviderKind::GitLab;
+    }
+    if is_gitea_pr_url(&url_lower) {
+        return ProviderKind::Gitea;
+    }
+
+    if host.contains("gitlab") {
+        return ProviderKind::GitLab;
+    }
+
+    if host == "codeberg.org" || host.contains("gitea") || host.contains("forgejo") {
+        return ProviderKind::Gitea;
     }
 
     ProviderKind::Unknown
 }
 
+/// Host of an HTTP(S), `ssh://` or scp-style (`git@host:path`) URL, without user or port
+fn url_host(url: &str) -> &str {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let authority = rest.split('/').next().unwrap_or(rest);
+    let authority = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    // Drops the port, or the path of scp-style URLs
+    authority.split(':').next().unwrap_or(authority)
+}
+
+/// Gitea PR URLs end in `/{owner}/{repo}/pulls/{number}` (GitHub uses `/pull/`)
+fn is_gitea_pr_url(url_lower: &str) -> bool {
+    url_lower
+        .trim_end_matches('/')
+        .rsplit_once("/pulls/")
+        .is_some_and(|(_, number)| !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()))
+}
+
 /// Detect the git hosting provider from a PR URL.
 ///
 /// Supports:
@@ -137,15 +176,82 @@ mod tests {
     }
 
     #[test]
-    fn test_unknown_provider() {
+    fn test_gitlab() {
         assert_eq!(
             detect_provider_from_url("https://gitlab.com/owner/repo"),
+            ProviderKind::GitLab
+        );
+        assert_eq!(
+            detect_provider_from_url("git@gitlab.company.com:group/sub/repo.git"),
+            ProviderKind::GitLab
+        );
+        // Self-hosted instance on an arbitrary hostname, identified by the MR path
+        assert_eq!(
+            detect_provider_from_url("https://git.company.com/group/repo/-/merge_requests/12"),
+            ProviderKind::GitLab
+        );
+    }
+
+    #[test]
+    fn test_gitea() {
+        assert_eq!(
+            detect_provider_from_url("https://codeberg.org/owner/repo.git"),
+            ProviderKind::Gitea
+        );
+        assert_eq!(
+            detect_provider_from_url("ssh://git@gitea.company.com:2222/owner/repo.git"),
+            ProviderKind::Gitea
+        );
+        assert_eq!(
+            detect_provider_from_url("https://forgejo.example.org/owner/repo"),
+            ProviderKind::Gitea
+        );
+        assert_eq!(
+            detect_provider_from_url("https://git.company.com/owner/repo/pulls/7"),
+            ProviderKind::Gitea
+        );
+    }
+
+    #[test]
+    fn test_only_host_is_matched_against_names() {
+        assert_eq!(
+            detect_provider_from_url("https://github.corp.com/org/gitlab-tools"),
+            ProviderKind::GitHub
+        );
+        assert_eq!(
+            detect_provider_from_url("git@github.corp.com:org/gitea-mirror.git"),
+            ProviderKind::GitHub
+        );
+        assert_eq!(
+            detect_provider_from_url("https://gitlab.com/group/github-actions-runner"),
+            ProviderKind::GitLab
+        );
+        assert_eq!(
+            detect_provider_from_url("https://git.company.com/tools/gitlab-exporter"),
+            ProviderKind::Unknown
+        );
+        assert_eq!(
+            url_host("ssh://git@gitea.company.com:2222/owner/repo.git"),
+            "gitea.company.com"
+        );
+        assert_eq!(url_host("git@github.com:owner/repo.git"), "github.com");
+    }
+
+    #[test]
+    fn test_unknown_provider() {
+        assert_eq!(
+            detect_provider_from_url("https://git.company.com/owner/repo"),
             ProviderKind::Unknown
         );
         assert_eq!(
             detect_provider_from_url("https://bitbucket.org/owner/repo"),
             ProviderKind::Unknown
         );
+        // GitHub API URLs use /pulls/ too, but never reach the Gitea check
+        assert_eq!(
+            detect_provider_from_url("https://api.github.com/repos/owner/repo/pulls/1"),
+            ProviderKind::GitHub
+        );
     }
 
     #[test]
@@ -0,0 +1,548 @@
+//! Minimal client for the Gitea/Forgejo REST API (`/api/v1`).
+//!
+//! Authentication uses `GITEA_TOKEN` (or `FORGEJO_TOKEN`) when set, falling back to the token
+//! stored by `tea login add` for the matching instance.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use db::models::merge::{MergeStatus, PullRequestInfo};
+use reqwest::{Method, StatusCode};
+use serde::{Deserialize, de::DeserializeOwned};
+use serde_json::json;
+use thiserror::Error;
+
+use crate::services::git_host::{
+    remote_url::parse_remote_url,
+    types::{CreatePrRequest, UnifiedPrComment},
+};
+
+const TOKEN_ENV_VARS: [&str; 2] = ["GITEA_TOKEN", "FORGEJO_TOKEN"];
+/// Upper bound on pages fetched when listing PRs, since the API can't filter by head branch
+const MAX_LIST_PAGES: u32 = 10;
+const PAGE_SIZE: u32 = 50;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GiteaRepoInfo {
+    /// Web base URL of the instance, including any sub-path it is served under
+    pub base_url: String,
+    pub owner: String,
+    pub repo_name: String,
+}
+
+impl GiteaRepoInfo {
+    /// Owner and repo are the last two path segments; anything before them is the sub-path
+    /// the instance is served under (e.g. `https://example.com/gitea/owner/repo`).
+    pub fn from_remote_url(remote_url: &str) -> Option<Self> {
+        let remote = parse_remote_url(remote_url)?;
+        let mut segments: Vec<&str> = remote.path.split('/').collect();
+        let repo_name = segments.pop()?.to_string();
+        let owner = segments.pop()?.to_string();
+        let base_url = if segments.is_empty() {
+            remote.base_url
+        } else {
+            format!("{}/{}", remote.base_url, segments.join("/"))
+        };
+        Some(Self {
+            base_url,
+            owner,
+            repo_name,
+        })
+    }
+
+    fn api_url(&self, endpoint: &str) -> String {
+        format!(
+            "{}/api/v1/repos/{}/{}/{endpoint}",
+            self.base_url, self.owner, self.repo_name
+        )
+    }
+}
+
+#[derive(Deserialize)]
+struct GiteaPullRequest {
+    number: i64,
+    html_url: String,
+    #[serde(default)]
+    state: String,
+    #[serde(default)]
+    merged: bool,
+    merged_at: Option<DateTime<Utc>>,
+    merge_commit_sha: Option<String>,
+    head: Option<GiteaBranch>,
+}
+
+#[derive(Deserialize)]
+struct GiteaBranch {
+    #[serde(rename = "ref")]
+    ref_name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GiteaUser {
+    login: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GiteaIssueComment {
+    id: i64,
+    #[serde(default)]
+    body: String,
+    user: Option<GiteaUser>,
+    created_at: Option<DateTime<Utc>>,
+    html_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GiteaReview {
+    id: i64,
+    #[serde(default)]
+    comments_count: i64,
+}
+
+#[derive(Deserialize)]
+struct GiteaReviewComment {
+    id: i64,
+    #[serde(default)]
+    body: String,
+    user: Option<GiteaUser>,
+    created_at: Option<DateTime<Utc>>,
+    html_url: Option<String>,
+    #[serde(default)]
+    path: String,
+    /// Line in the new file; 0 when the comment is on a removed line
+    #[serde(default)]
+    position: i64,
+    /// Line in the old file
+    #[serde(default)]
+    original_position: i64,
+    diff_hunk: Option<String>,
+    /// Who resolved the comment's conversation, if it was resolved
+    resolver: Option<GiteaUser>,
+}
+
+/// Subset of tea's `config.yml`
+#[derive(Deserialize)]
+struct TeaConfig {
+    #[serde(default)]
+    logins: Vec<TeaLogin>,
+}
+
+#[derive(Deserialize)]
+struct TeaLogin {
+    url: String,
+    #[serde(default)]
+    token: String,
+}
+
+#[derive(Debug, Error)]
+pub enum GiteaApiError {
+    #[error("Gitea API authentication failed: {0}")]
+    AuthFailed(String),
+    #[error("Gitea API request failed: {0}")]
+    RequestFailed(String),
+    #[error("Gitea API returned unexpected output: {0}")]
+    UnexpectedOutput(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct GiteaApi {
+    http: reqwest::Client,
+}
+
+impl GiteaApi {
+    pub fn new() -> Result<Self, GiteaApiError> {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .user_agent(concat!("vibe-kanban/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .map_err(|e| GiteaApiError::RequestFailed(e.to_string()))?;
+        Ok(Self { http })
+    }
+
+    /// Token for the instance at `base_url`, if one is configured
+    async fn token_for(&self, base_url: &str) -> Option<String> {
+        if let Some(token) = TOKEN_ENV_VARS
+            .iter()
+            .find_map(|var| std::env::var(var).ok().filter(|t| !t.is_empty()))
+        {
+            return Some(token);
+        }
+
+        let path = dirs::config_dir()?.join("tea").join("config.yml");
+        let raw = tokio::fs::read_to_string(path).await.ok()?;
+        let config: TeaConfig = serde_yaml::from_str(&raw).ok()?;
+        Self::token_from_tea_logins(&config.logins, base_url)
+    }
+
+    fn token_from_tea_logins(logins: &[TeaLogin], base_url: &str) -> Option<String> {
+        let normalize = |url: &str| url.trim_end_matches('/').to_lowercase();
+        let base_url = normalize(base_url);
+        logins
+            .iter()
+            .find(|login| normalize(&login.url) == base_url && !login.token.is_empty())
+            .map(|login| login.token.clone())
+    }
+
+    async fn request<T: DeserializeOwned>(
+        &self,
+        repo: &GiteaRepoInfo,
+        method: Method,
+        endpoint: &str,
+        body: Option<serde_json::Value>,
+    ) -> Result<T, GiteaApiError> {
+        let url = repo.api_url(endpoint);
+        tracing::debug!("Gitea API request: {} {}", method, url);
+
+        let mut request = self.http.request(method, &url);
+        if let Some(token) = self.token_for(&repo.base_url).await {
+            request = request.header(reqwest::header::AUTHORIZATION, format!("token {token}"));
+        }
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| GiteaApiError::RequestFailed(e.to_string()))?;
+        let status = response.status();
+        let text = response
+            .text()
+            .await
+            .map_err(|e| GiteaApiError::RequestFailed(e.to_string()))?;
+
+        if status == StatusCode::UNAUTHORIZED {
+            return Err(GiteaApiError::AuthFailed(format!(
+                "{text} (set GITEA_TOKEN or run `tea login add`)"
+            )));
+        }
+        if !status.is_success() {
+            return Err(GiteaApiError::RequestFailed(format!("{status}: {text}")));
+        }
+
+        serde_json::from_str(&text).map_err(|e| {
+            GiteaApiError::UnexpectedOutput(format!("Failed to parse response: {e}; raw: {text}"))
+        })
+    }
+
+    /// Creating a PR always needs a token, so fail early with a clear message
+    pub async fn check_auth(&self, repo: &GiteaRepoInfo) -> Result<(), GiteaApiError> {
+        match self.token_for(&repo.base_url).await {
+            Some(_) => Ok(()),
+            None => Err(GiteaApiError::AuthFailed(format!(
+                "No token for {}: set GITEA_TOKEN or run `tea login add`",
+                repo.base_url
+            ))),
+        }
+    }
+
+    /// Open a PR on `target`. For cross-fork PRs `head_owner` is the fork's owner.
+    pub async fn create_pr(
+        &self,
+        request: &CreatePrRequest,
+        target: &GiteaRepoInfo,
+        head_owner: Option<&str>,
+    ) -> Result<PullRequestInfo, GiteaApiError> {
+        // Gitea marks PRs as work in progress by title prefix
+        let title = if request.draft.unwrap_or(false) {
+            format!("WIP: {}", request.title)
+        } else {
+            request.title.clone()
+        };
+        let head = match head_owner {
+            Some(owner) => format!("{owner}:{}", request.head_branch),
+            None => request.head_branch.clone(),
+        };
+
+        let pr: GiteaPullRequest = self
+            .request(
+                target,
+                Method::POST,
+                "pulls",
+                Some(json!({
+                    "head": head,
+                    "base": request.base_branch,
+                    "title": title,
+                    "body": request.body.as_deref().unwrap_or(""),
+                })),
+            )
+            .await?;
+        Ok(Self::pr_to_info(pr))
+    }
+
+    /// Retrieve details for a PR by URL.
+    pub async fn view_pr(&self, pr_url: &str) -> Result<PullRequestInfo, GiteaApiError> {
+        let (repo, number) = Self::parse_pr_url(pr_url).ok_or_else(|| {
+            GiteaApiError::UnexpectedOutput(format!("Could not parse Gitea PR URL: {pr_url}"))
+        })?;
+        let pr: GiteaPullRequest = self
+            .request(&repo, Method::GET, &format!("pulls/{number}"), None)
+            .await?;
+        Ok(Self::pr_to_info(pr))
+    }
+
+    /// Change the base branch of a PR.
+    pub async fn update_pr_base(
+        &self,
+        repo: &GiteaRepoInfo,
+        number: i64,
+        base_branch: &str,
+    ) -> Result<(), GiteaApiError> {
+        let _: GiteaPullRequest = self
+            .request(
+                repo,
+                Method::PATCH,
+                &format!("pulls/{number}"),
+                Some(json!({ "base": base_branch })),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Comment on a PR's conversation.
+    pub async fn create_pr_comment(
+        &self,
+        repo: &GiteaRepoInfo,
+        number: i64,
+        body: &str,
+    ) -> Result<(), GiteaApiError> {
+        let _: GiteaIssueComment = self
+            .request(
+                repo,
+                Method::POST,
+                &format!("issues/{number}/comments"),
+                Some(json!({ "body": body })),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// List PRs whose head is `branch` (includes closed/merged).
+    pub async fn list_prs_for_branch(
+        &self,
+        repo: &GiteaRepoInfo,
+        branch: &str,
+    ) -> Result<Vec<PullRequestInfo>, GiteaApiError> {
+        let mut matching = Vec::new();
+        for page in 1..=MAX_LIST_PAGES {
+            let prs: Vec<GiteaPullRequest> = self
+                .request(
+                    repo,
+                    Method::GET,
+                    &format!("pulls?state=all&sort=recentupdate&page={page}&limit={PAGE_SIZE}"),
+                    None,
+                )
+                .await?;
+            let last_page = prs.len() < PAGE_SIZE as usize;
+            matching.extend(
+                prs.into_iter()
+                    .filter(|pr| {
+                        pr.head
+                            .as_ref()
+                            .and_then(|h| h.ref_name.as_deref())
+                            .is_some_and(|r| r == branch)
+                    })
+                    .map(Self::pr_to_info),
+            );
+            if last_page {
+                break;
+            }
+        }
+        Ok(matching)
+    }
+
+    /// Fetch conversation comments and inline review comments for a PR.
+    pub async fn get_pr_comments(
+        &self,
+        repo: &GiteaRepoInfo,
+        number: i64,
+    ) -> Result<Vec<UnifiedPrComment>, GiteaApiError> {
+        let general: Vec<GiteaIssueComment> = self
+            .request(
+                repo,
+                Method::GET,
+                &format!("issues/{number}/comments"),
+                None,
+            )
+            .await?;
+        let reviews: Vec<GiteaReview> = self
+            .request(repo, Method::GET, &format!("pulls/{number}/reviews"), None)
+            .await?;
+
+        let mut review_comments = Vec::new();
+        for review in reviews.iter().filter(|r| r.comments_count > 0) {
+            let comments: Vec<GiteaReviewComment> = self
+                .request(
+                    repo,
+                    Method::GET,
+                    &format!("pulls/{number}/reviews/{}/comments", review.id),
+                    None,
+                )
+                .await?;
+            review_comments.extend(comments);
+        }
+
+        Ok(Self::unify_comments(general, review_comments))
+    }
+
+    /// Parse a PR URL into the repo and PR number.
+    ///
+    /// Format: `https://{host}[/{sub-path}]/{owner}/{repo}/pulls/{number}`
+    pub fn parse_pr_url(url: &str) -> Option<(GiteaRepoInfo, i64)> {
+        let (repo_url, rest) = url.rsplit_once("/pulls/")?;
+        let number = rest
+            .split(['/', '?', '#'])
+            .next()
+            .and_then(|s| s.parse().ok())?;
+        Some((GiteaRepoInfo::from_remote_url(repo_url)?, number))
+    }
+}
+
+impl GiteaApi {
+    fn pr_to_info(pr: GiteaPullRequest) -> PullRequestInfo {
+        PullRequestInfo {
+            number: pr.number,
+            url: pr.html_url,
+            status: Self::map_gitea_state(&pr.state, pr.merged),
+            merged_at: pr.merged_at,
+            merge_commit_sha: pr.merge_commit_sha,
+        }
+    }
+
+    fn unify_comments(
+        general: Vec<GiteaIssueComment>,
+        review: Vec<GiteaReviewComment>,
+    ) -> Vec<UnifiedPrComment> {
+        let author = |user: Option<GiteaUser>| {
+            user.and_then(|u| u.login)
+                .unwrap_or_else(|| "unknown".to_string())
+        };
+
+        let mut comments: Vec<UnifiedPrComment> = general
+            .into_iter()
+            .map(|c| UnifiedPrComment::General {
+                id: c.id.to_string(),
+                author: author(c.user),
+                author_association: None,
+                body: c.body,
+                created_at: c.created_at.unwrap_or_else(Utc::now),
+                url: c.html_url,
+            })
+            .collect();
+
+        comments.extend(review.into_iter().map(|c| {
+            let (line, side) = if c.position > 0 {
+                (Some(c.position), Some("RIGHT".to_string()))
+            } else if c.original_position > 0 {
+                (Some(c.original_position), Some("LEFT".to_string()))
+            } else {
+                (None, None)
+            };
+            UnifiedPrComment::Review {
+                id: c.id,
+                author: author(c.user),
+                author_association: None,
+                body: c.body,
+                created_at: c.created_at.unwrap_or_else(Utc::now),
+                url: c.html_url,
+                path: c.path,
+                line,
+                side,
+                diff_hunk: c.diff_hunk,
+                is_reply: false,
+                resolved: c.resolver.is_some(),
+            }
+        }));
+
+        comments.sort_by_key(|c| c.created_at());
+        comments
+    }
+
+    /// Map Gitea PR state to MergeStatus. Merged PRs are reported as `closed` with `merged`.
+    fn map_gitea_state(state: &str, merged: bool) -> MergeStatus {
+        if merged {
+            return MergeStatus::Merged;
+        }
+        match state.to_lowercase().as_str() {
+            "open" => MergeStatus::Open,
+            "closed" => MergeStatus::Closed,
+            _ => MergeStatus::Unknown,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repo_info_from_remote_url() {
+        let repo = GiteaRepoInfo::from_remote_url("git@codeberg.org:owner/repo.git").unwrap();
+        assert_eq!(repo.base_url, "https://codeberg.org");
+        assert_eq!(repo.owner, "owner");
+        assert_eq!(repo.repo_name, "repo");
+        assert_eq!(
+            repo.api_url("pulls/1"),
+            "https://codeberg.org/api/v1/repos/owner/repo/pulls/1"
+        );
+
+        // Instance served under a sub-path
+        let repo =
+            GiteaRepoInfo::from_remote_url("https://example.com/gitea/owner/repo.git").unwrap();
+        assert_eq!(repo.base_url, "https://example.com/gitea");
+        assert_eq!(repo.owner, "owner");
+    }
+
+    #[test]
+    fn test_parse_pr_url() {
+        let (repo, number) =
+            GiteaApi::parse_pr_url("http://git.local:3000/owner/repo/pulls/12/files").unwrap();
+        assert_eq!(repo.base_url, "http://git.local:3000");
+        assert_eq!(repo.owner, "owner");
+        assert_eq!(repo.repo_name, "repo");
+        assert_eq!(number, 12);
+
+        assert!(GiteaApi::parse_pr_url("https://github.com/owner/repo/pull/1").is_none());
+    }
+
+    #[test]
+    fn test_map_gitea_state() {
+        assert!(matches!(
+            GiteaApi::map_gitea_state("open", false),
+            MergeStatus::Open
+        ));
+        assert!(matches!(
+            GiteaApi::map_gitea_state("closed", true),
+            MergeStatus::Merged
+        ));
+        assert!(matches!(
+            GiteaApi::map_gitea_state("closed", false),
+            MergeStatus::Closed
+        ));
+    }
+
+    #[test]
+    fn test_token_from_tea_logins() {
+        let config: TeaConfig = serde_yaml::from_str(
+            r#"
+logins:
+  - name: work
+    url: https://git.company.com/
+    token: abc123
+    default: true
+  - name: codeberg
+    url: https://codeberg.org
+    token: ""
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            GiteaApi::token_from_tea_logins(&config.logins, "https://git.company.com"),
+            Some("abc123".to_string())
+        );
+        assert_eq!(
+            GiteaApi::token_from_tea_logins(&config.logins, "https://codeberg.org"),
+            None
+        );
+    }
+}
@@ -0,0 +1,279 @@
+//! Gitea and Forgejo hosting service implementation.
+
+mod api;
+
+use std::{path::Path, time::Duration};
+
+pub use api::GiteaApi;
+use api::{GiteaApiError, GiteaRepoInfo};
+use async_trait::async_trait;
+use backon::{ExponentialBuilder, Retryable};
+use db::models::merge::{PrCheck, PullRequestInfo};
+use tracing::info;
+
+use super::{
+    GitHostProvider,
+    types::{CreatePrRequest, GitHostError, Issue, IssueFilter, ProviderKind, UnifiedPrComment},
+};
+
+#[derive(Debug, Clone)]
+pub struct GiteaProvider {
+    api: GiteaApi,
+    /// Instance URL configured on the repo, used instead of the one derived from the remote URL
+    base_url: Option<String>,
+}
+
+impl GiteaProvider {
+    pub fn new() -> Result<Self, GitHostError> {
+        Ok(Self {
+            api: GiteaApi::new()?,
+            base_url: None,
+        })
+    }
+
+    pub fn with_base_url(mut self, base_url: Option<&str>) -> Self {
+        self.base_url = base_url.map(str::to_string);
+        self
+    }
+
+    fn get_repo_info(&self, remote_url: &str) -> Result<GiteaRepoInfo, GitHostError> {
+        let mut info = GiteaRepoInfo::from_remote_url(remote_url).ok_or_else(|| {
+            GitHostError::Repository(format!(
+                "Could not parse Gitea repository from remote URL: {remote_url}"
+            ))
+        })?;
+        if let Some(base_url) = &self.base_url {
+            info.base_url = base_url.trim_end_matches('/').to_string();
+        }
+        Ok(info)
+    }
+}
+
+impl From<GiteaApiError> for GitHostError {
+    fn from(error: GiteaApiError) -> Self {
+        match &error {
+            GiteaApiError::AuthFailed(msg) => GitHostError::AuthFailed(msg.clone()),
+            GiteaApiError::RequestFailed(msg) => {
+                let lower = msg.to_ascii_lowercase();
+                if lower.contains("403") || lower.contains("forbidden") {
+                    GitHostError::InsufficientPermissions(msg.clone())
+                } else if lower.contains("404") || lower.contains("not found") {
+                    GitHostError::RepoNotFoundOrNoAccess(msg.clone())
+                } else {
+                    GitHostError::PullRequest(msg.clone())
+                }
+            }
+            GiteaApiError::UnexpectedOutput(msg) => GitHostError::UnexpectedOutput(msg.clone()),
+        }
+    }
+}
+
+#[async_trait]
+impl GitHostProvider for GiteaProvider {
+    async fn create_pr(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        request: &CreatePrRequest,
+    ) -> Result<PullRequestInfo, GitHostError> {
+        let target = self.get_repo_info(remote_url)?;
+
+        // For cross-fork PRs the head branch is referenced as "owner:branch"
+        let head_owner = match &request.head_repo_url {
+            Some(head_url) => {
+                let head = self.get_repo_info(head_url)?;
+                (head.owner != target.owner).then_some(head.owner)
+            }
+            None => None,
+        };
+
+        // Check auth first
+        self.api.check_auth(&target).await?;
+
+        (|| async {
+            let pr = self
+                .api
+                .create_pr(request, &target, head_owner.as_deref())
+                .await
+                .map_err(GitHostError::from)?;
+
+            info!(
+                "Created Gitea PR #{} for branch {}",
+                pr.number, request.head_branch
+            );
+
+            Ok(pr)
+        })
+        .retry(
+            &ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(30))
+                .with_max_times(3)
+                .with_jitter(),
+        )
+        .when(|e: &GitHostError| e.should_retry())
+        .notify(|err: &GitHostError, dur: Duration| {
+            tracing::warn!(
+                "Gitea API call failed, retrying after {:.2}s: {}",
+                dur.as_secs_f64(),
+                err
+            );
+        })
+        .await
+    }
+
+    async fn get_pr_status(&self, pr_url: &str) -> Result<PullRequestInfo, GitHostError> {
+        (|| async { self.api.view_pr(pr_url).await.map_err(GitHostError::from) })
+            .retry(
+                &ExponentialBuilder::default()
+                    .with_min_delay(Duration::from_secs(1))
+                    .with_max_delay(Duration::from_secs(30))
+                    .with_max_times(3)
+                    .with_jitter(),
+            )
+            .when(|err: &GitHostError| err.should_retry())
+            .notify(|err: &GitHostError, dur: Duration| {
+                tracing::warn!(
+                    "Gitea API call failed, retrying after {:.2}s: {}",
+                    dur.as_secs_f64(),
+                    err
+                );
+            })
+            .await
+    }
+
+    async fn list_prs_for_branch(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        branch_name: &str,
+    ) -> Result<Vec<PullRequestInfo>, GitHostError> {
+        let repo_info = self.get_repo_info(remote_url)?;
+
+        (|| async {
+            self.api
+                .list_prs_for_branch(&repo_info, branch_name)
+                .await
+                .map_err(GitHostError::from)
+        })
+        .retry(
+            &ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(30))
+                .with_max_times(3)
+                .with_jitter(),
+        )
+        .when(|e: &GitHostError| e.should_retry())
+        .notify(|err: &GitHostError, dur: Duration| {
+            tracing::warn!(
+                "Gitea API call failed, retrying after {:.2}s: {}",
+                dur.as_secs_f64(),
+                err
+            );
+        })
+        .await
+    }
+
+    async fn get_pr_comments(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        pr_number: i64,
+    ) -> Result<Vec<UnifiedPrComment>, GitHostError> {
+        let repo_info = self.get_repo_info(remote_url)?;
+
+        (|| async {
+            self.api
+                .get_pr_comments(&repo_info, pr_number)
+                .await
+                .map_err(GitHostError::from)
+        })
+        .retry(
+            &ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(30))
+                .with_max_times(3)
+                .with_jitter(),
+        )
+        .when(|e: &GitHostError| e.should_retry())
+        .notify(|err: &GitHostError, dur: Duration| {
+            tracing::warn!(
+                "Gitea API call failed, retrying after {:.2}s: {}",
+                dur.as_secs_f64(),
+                err
+            );
+        })
+        .await
+    }
+
+    async fn update_pr_base(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        pr_number: i64,
+        base_branch: &str,
+    ) -> Result<(), GitHostError> {
+        let repo_info = self.get_repo_info(remote_url)?;
+        self.api
+            .update_pr_base(&repo_info, pr_number, base_branch)
+            .await
+            .map_err(GitHostError::from)
+    }
+
+    async fn create_pr_comment(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        pr_number: i64,
+        body: &str,
+    ) -> Result<(), GitHostError> {
+        let repo_info = self.get_repo_info(remote_url)?;
+        self.api
+            .create_pr_comment(&repo_info, pr_number, body)
+            .await
+            .map_err(GitHostError::from)
+    }
+
+    async fn get_pr_checks(
+        &self,
+        _repo_path: &Path,
+        _remote_url: &str,
+        _pr_number: i64,
+    ) -> Result<Vec<PrCheck>, GitHostError> {
+        // Gitea Actions are not tracked yet
+        Ok(Vec::new())
+    }
+
+    async fn get_check_log(
+        &self,
+        _repo_path: &Path,
+        _remote_url: &str,
+        _check: &PrCheck,
+    ) -> Result<Option<String>, GitHostError> {
+        Ok(None)
+    }
+
+    async fn list_issues(
+        &self,
+        _repo_path: &Path,
+        _remote_url: &str,
+        _filter: &IssueFilter,
+    ) -> Result<Vec<Issue>, GitHostError> {
+        // Importing issues is only supported from GitHub and Azure Boards
+        Err(GitHostError::UnsupportedProvider)
+    }
+
+    async fn close_issue(
+        &self,
+        _repo_path: &Path,
+        _remote_url: &str,
+        _issue_number: i64,
+        _comment: &str,
+    ) -> Result<(), GitHostError> {
+        Err(GitHostError::UnsupportedProvider)
+    }
+
+    fn provider_kind(&self) -> ProviderKind {
+        ProviderKind::Gitea
+    }
+}
@@ -4,6 +4,7 @@
 //! the REST client does not cover well.
 
 use std::{
+    collections::HashSet,
     ffi::{OsStr, OsString},
     io::Write,
     path::Path,
@@ -11,14 +12,15 @@ use std::{
 };
 
 use chrono::{DateTime, Utc};
-use db::models::merge::{MergeStatus, PullRequestInfo};
+use db::models::merge::{MergeStatus, PrCheck, PrCheckStatus, PullRequestInfo};
 use serde::Deserialize;
 use tempfile::NamedTempFile;
 use thiserror::Error;
 use utils::shell::resolve_executable_path_blocking;
 
 use crate::services::git_host::types::{
-    CreatePrRequest, PrComment, PrCommentAuthor, PrReviewComment, ReviewCommentUser,
+    CreatePrRequest, Issue, IssueFilter, PrComment, PrCommentAuthor, PrReviewComment,
+    ReviewCommentUser,
 };
 
 #[derive(Debug, Clone)]
@@ -79,6 +81,59 @@ struct GhReviewCommentResponse {
     diff_hunk: String,
     #[serde(default)]
     author_association: String,
+    in_reply_to_id: Option<i64>,
+}
+
+/// Review threads of a pull request, paged through with `$endCursor` by `gh api --paginate`
+const REVIEW_THREADS_QUERY: &str = r#"query($owner: String!, $name: String!, $number: Int!, $endCursor: String) {
+  repository(owner: $owner, name: $name) {
+    pullRequest(number: $number) {
+      reviewThreads(first: 100, after: $endCursor) {
+        pageInfo { hasNextPage endCursor }
+        nodes { isResolved comments(first: 1) { nodes { databaseId } } }
+      }
+    }
+  }
+}"#;
+
+#[derive(Deserialize)]
+struct GhGraphQlResponse<T> {
+    data: T,
+}
+
+#[derive(Deserialize)]
+struct GhReviewThreadsData {
+    repository: GhReviewThreadsRepository,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GhReviewThreadsRepository {
+    pull_request: GhReviewThreadsPullRequest,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GhReviewThreadsPullRequest {
+    review_threads: GhNodes<GhReviewThread>,
+}
+
+#[derive(Deserialize)]
+struct GhNodes<T> {
+    nodes: Vec<T>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GhReviewThread {
+    is_resolved: bool,
+    comments: GhNodes<GhReviewThreadComment>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GhReviewThreadComment {
+    database_id: Option<i64>,
 }
 
 #[derive(Deserialize)]
@@ -97,6 +152,47 @@ struct GhPrResponse {
     merge_commit: Option<GhMergeCommit>,
 }
 
+#[derive(Deserialize)]
+struct GhIssueResponse {
+    number: i64,
+    title: String,
+    #[serde(default)]
+    body: String,
+    url: String,
+    #[serde(default)]
+    labels: Vec<GhLabel>,
+}
+
+#[derive(Deserialize)]
+struct GhLabel {
+    name: String,
+}
+
+/// An entry of `statusCheckRollup`: a check run (`CheckRun`) or a commit status
+/// (`StatusContext`)
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GhStatusCheck {
+    #[serde(rename = "__typename", default)]
+    typename: String,
+    name: Option<String>,
+    context: Option<String>,
+    workflow_name: Option<String>,
+    status: Option<String>,
+    conclusion: Option<String>,
+    state: Option<String>,
+    details_url: Option<String>,
+    target_url: Option<String>,
+    description: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GhStatusCheckRollup {
+    #[serde(default)]
+    status_check_rollup: Vec<GhStatusCheck>,
+}
+
 #[derive(Debug, Error)]
 pub enum GhCliError {
     #[error("GitHub CLI (`gh`) executable not found or not runnable")]
@@ -255,6 +351,154 @@ impl GhCli {
         Self::parse_pr_view(&raw)
     }
 
+    /// Change the base branch of a pull request.
+    pub fn update_pr_base(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: i64,
+        base_branch: &str,
+    ) -> Result<(), GhCliError> {
+        self.run(
+            [
+                "pr",
+                "edit",
+                &pr_number.to_string(),
+                "--repo",
+                &format!("{owner}/{repo}"),
+                "--base",
+                base_branch,
+            ],
+            None,
+        )?;
+        Ok(())
+    }
+
+    /// Comment on a pull request's conversation.
+    pub fn comment_on_pr(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: i64,
+        body: &str,
+    ) -> Result<(), GhCliError> {
+        self.run(
+            [
+                "pr",
+                "comment",
+                &pr_number.to_string(),
+                "--repo",
+                &format!("{owner}/{repo}"),
+                "--body",
+                body,
+            ],
+            None,
+        )?;
+        Ok(())
+    }
+
+    /// Fetch the check runs and commit statuses of a pull request's head commit.
+    pub fn get_pr_checks(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: i64,
+    ) -> Result<Vec<PrCheck>, GhCliError> {
+        // `gh pr checks` exits non-zero while checks fail or are pending, so read the rollup
+        let raw = self.run(
+            [
+                "pr",
+                "view",
+                &pr_number.to_string(),
+                "--repo",
+                &format!("{owner}/{repo}"),
+                "--json",
+                "statusCheckRollup",
+            ],
+            None,
+        )?;
+        Self::parse_status_check_rollup(&raw)
+    }
+
+    /// Fetch the log of the failed steps of a GitHub Actions job.
+    pub fn get_failed_job_log(
+        &self,
+        owner: &str,
+        repo: &str,
+        job_id: &str,
+    ) -> Result<String, GhCliError> {
+        self.run(
+            [
+                "run",
+                "view",
+                "--job",
+                job_id,
+                "--log-failed",
+                "--repo",
+                &format!("{owner}/{repo}"),
+            ],
+            None,
+        )
+    }
+
+    /// List open issues matching the filter.
+    pub fn list_issues(
+        &self,
+        owner: &str,
+        repo: &str,
+        filter: &IssueFilter,
+    ) -> Result<Vec<Issue>, GhCliError> {
+        let mut args: Vec<OsString> = Vec::with_capacity(16);
+        args.push(OsString::from("issue"));
+        args.push(OsString::from("list"));
+        args.push(OsString::from("--repo"));
+        args.push(OsString::from(format!("{}/{}", owner, repo)));
+        args.push(OsString::from("--state"));
+        args.push(OsString::from("open"));
+        args.push(OsString::from("--limit"));
+        args.push(OsString::from(filter.limit.to_string()));
+        args.push(OsString::from("--json"));
+        args.push(OsString::from("number,title,body,url,labels"));
+        for label in &filter.labels {
+            args.push(OsString::from("--label"));
+            args.push(OsString::from(label));
+        }
+        if let Some(milestone) = &filter.milestone {
+            args.push(OsString::from("--milestone"));
+            args.push(OsString::from(milestone));
+        }
+        if let Some(query) = &filter.query {
+            args.push(OsString::from("--search"));
+            args.push(OsString::from(query));
+        }
+
+        let raw = self.run(args, None)?;
+        Self::parse_issue_list(&raw)
+    }
+
+    /// Close an issue with a comment.
+    pub fn close_issue(
+        &self,
+        owner: &str,
+        repo: &str,
+        issue_number: i64,
+        comment: &str,
+    ) -> Result<(), GhCliError> {
+        self.run(
+            [
+                "issue",
+                "close",
+                &issue_number.to_string(),
+                "--repo",
+                &format!("{owner}/{repo}"),
+                "--comment",
+                comment,
+            ],
+            None,
+        )?;
+        Ok(())
+    }
+
     /// List pull requests for a branch (includes closed/merged).
     pub fn list_prs_for_branch(
         &self,
@@ -302,7 +546,8 @@ impl GhCli {
         Self::parse_pr_comments(&raw)
     }
 
-    /// Fetch inline review comments for a pull request via API.
+    /// Fetch inline review comments for a pull request via API, marking those in resolved
+    /// threads.
     pub fn get_pr_review_comments(
         &self,
         owner: &str,
@@ -316,7 +561,31 @@ impl GhCli {
             ],
             None,
         )?;
-        Self::parse_pr_review_comments(&raw)
+        let mut comments = Self::parse_pr_review_comments(&raw)?;
+
+        // The REST API does not tell whether a thread was resolved, GraphQL does
+        let raw = self.run(
+            [
+                "api",
+                "graphql",
+                "--paginate",
+                "-f",
+                &format!("query={REVIEW_THREADS_QUERY}"),
+                "-f",
+                &format!("owner={owner}"),
+                "-f",
+                &format!("name={repo}"),
+                "-F",
+                &format!("number={pr_number}"),
+            ],
+            None,
+        )?;
+        let resolved = Self::parse_resolved_thread_ids(&raw)?;
+        for comment in &mut comments {
+            let thread_id = comment.in_reply_to_id.unwrap_or(comment.id);
+            comment.resolved = resolved.contains(&thread_id);
+        }
+        Ok(comments)
     }
 }
 
@@ -451,6 +720,103 @@ impl GhCli {
                 side: c.side,
                 diff_hunk: c.diff_hunk,
                 author_association: c.author_association,
+                in_reply_to_id: c.in_reply_to_id,
+                resolved: false,
+            })
+            .collect())
+    }
+
+    /// Ids of the first comments of resolved review threads. `gh api --paginate` prints one
+    /// response per page.
+    fn parse_resolved_thread_ids(raw: &str) -> Result<HashSet<i64>, GhCliError> {
+        let mut ids = HashSet::new();
+        for page in serde_json::Deserializer::from_str(raw.trim())
+            .into_iter::<GhGraphQlResponse<GhReviewThreadsData>>()
+        {
+            let page = page.map_err(|err| {
+                GhCliError::UnexpectedOutput(format!(
+                    "Failed to parse review threads GraphQL response: {err}; raw: {raw}"
+                ))
+            })?;
+            ids.extend(
+                page.data
+                    .repository
+                    .pull_request
+                    .review_threads
+                    .nodes
+                    .into_iter()
+                    .filter(|thread| thread.is_resolved)
+                    .filter_map(|thread| thread.comments.nodes.first()?.database_id),
+            );
+        }
+        Ok(ids)
+    }
+
+    fn parse_issue_list(raw: &str) -> Result<Vec<Issue>, GhCliError> {
+        let issues: Vec<GhIssueResponse> = serde_json::from_str(raw.trim()).map_err(|err| {
+            GhCliError::UnexpectedOutput(format!(
+                "Failed to parse gh issue list response: {err}; raw: {raw}"
+            ))
+        })?;
+        Ok(issues
+            .into_iter()
+            .map(|issue| Issue {
+                number: issue.number,
+                title: issue.title,
+                body: issue.body,
+                url: issue.url,
+                labels: issue.labels.into_iter().map(|label| label.name).collect(),
+            })
+            .collect())
+    }
+
+    fn parse_status_check_rollup(raw: &str) -> Result<Vec<PrCheck>, GhCliError> {
+        let rollup: GhStatusCheckRollup = serde_json::from_str(raw.trim()).map_err(|err| {
+            GhCliError::UnexpectedOutput(format!(
+                "Failed to parse gh pr view --json statusCheckRollup response: {err}; raw: {raw}"
+            ))
+        })?;
+
+        Ok(rollup
+            .status_check_rollup
+            .into_iter()
+            .map(|check| {
+                if check.typename == "StatusContext" {
+                    let status = match check.state.as_deref().unwrap_or_default() {
+                        "SUCCESS" => PrCheckStatus::Passed,
+                        "FAILURE" | "ERROR" => PrCheckStatus::Failed,
+                        "PENDING" | "EXPECTED" => PrCheckStatus::Pending,
+                        _ => PrCheckStatus::Skipped,
+                    };
+                    return PrCheck {
+                        name: check.context.unwrap_or_else(|| "status".to_string()),
+                        status,
+                        url: check.target_url.filter(|url| !url.is_empty()),
+                        description: check.description.filter(|d| !d.is_empty()),
+                    };
+                }
+
+                let status = if check.status.as_deref() != Some("COMPLETED") {
+                    PrCheckStatus::Pending
+                } else {
+                    match check.conclusion.as_deref().unwrap_or_default() {
+                        "SUCCESS" => PrCheckStatus::Passed,
+                        "FAILURE" | "TIMED_OUT" | "ACTION_REQUIRED" | "STARTUP_FAILURE" => {
+                            PrCheckStatus::Failed
+                        }
+                        _ => PrCheckStatus::Skipped,
+                    }
+                };
+                let name = check.name.unwrap_or_else(|| "check".to_string());
+                PrCheck {
+                    name: match check.workflow_name.filter(|w| !w.is_empty()) {
+                        Some(workflow) => format!("{workflow} / {name}"),
+                        None => name,
+                    },
+                    status,
+                    url: check.details_url.filter(|url| !url.is_empty()),
+                    description: None,
+                }
             })
             .collect())
     }
@@ -8,13 +8,13 @@ use async_trait::async_trait;
 use backon::{ExponentialBuilder, Retryable};
 pub use cli::GhCli;
 use cli::{GhCliError, GitHubRepoInfo};
-use db::models::merge::PullRequestInfo;
+use db::models::merge::{PrCheck, PullRequestInfo};
 use tokio::task;
 use tracing::info;
 
 use super::{
     GitHostProvider,
-    types::{CreatePrRequest, GitHostError, ProviderKind, UnifiedPrComment},
+    types::{CreatePrRequest, GitHostError, Issue, IssueFilter, ProviderKind, UnifiedPrComment},
 };
 
 #[derive(Debug, Clone)]
@@ -376,6 +376,8 @@ impl GitHostProvider for GitHubProvider {
                 line: c.line,
                 side: c.side,
                 diff_hunk: Some(c.diff_hunk),
+                is_reply: c.in_reply_to_id.is_some(),
+                resolved: c.resolved,
             });
         }
 
@@ -385,6 +387,158 @@ impl GitHostProvider for GitHubProvider {
         Ok(unified)
     }
 
+    async fn update_pr_base(
+        &self,
+        repo_path: &Path,
+        remote_url: &str,
+        pr_number: i64,
+        base_branch: &str,
+    ) -> Result<(), GitHostError> {
+        let repo_info = self.get_repo_info(remote_url, repo_path).await?;
+        let cli = self.gh_cli.clone();
+        let base_branch = base_branch.to_string();
+        task::spawn_blocking(move || {
+            cli.update_pr_base(
+                &repo_info.owner,
+                &repo_info.repo_name,
+                pr_number,
+                &base_branch,
+            )
+        })
+        .await
+        .map_err(|err| {
+            GitHostError::PullRequest(format!(
+                "Failed to execute GitHub CLI for updating PR base: {err}"
+            ))
+        })?
+        .map_err(GitHostError::from)
+    }
+
+    async fn create_pr_comment(
+        &self,
+        repo_path: &Path,
+        remote_url: &str,
+        pr_number: i64,
+        body: &str,
+    ) -> Result<(), GitHostError> {
+        let repo_info = self.get_repo_info(remote_url, repo_path).await?;
+        let cli = self.gh_cli.clone();
+        let body = body.to_string();
+        task::spawn_blocking(move || {
+            cli.comment_on_pr(&repo_info.owner, &repo_info.repo_name, pr_number, &body)
+        })
+        .await
+        .map_err(|err| {
+            GitHostError::PullRequest(format!(
+                "Failed to execute GitHub CLI for commenting on PR: {err}"
+            ))
+        })?
+        .map_err(GitHostError::from)
+    }
+
+    async fn get_pr_checks(
+        &self,
+        repo_path: &Path,
+        remote_url: &str,
+        pr_number: i64,
+    ) -> Result<Vec<PrCheck>, GitHostError> {
+        let repo_info = self.get_repo_info(remote_url, repo_path).await?;
+        let cli = self.gh_cli.clone();
+        task::spawn_blocking(move || {
+            cli.get_pr_checks(&repo_info.owner, &repo_info.repo_name, pr_number)
+        })
+        .await
+        .map_err(|err| {
+            GitHostError::PullRequest(format!(
+                "Failed to execute GitHub CLI for fetching PR checks: {err}"
+            ))
+        })?
+        .map_err(GitHostError::from)
+    }
+
+    async fn get_check_log(
+        &self,
+        repo_path: &Path,
+        remote_url: &str,
+        check: &PrCheck,
+    ) -> Result<Option<String>, GitHostError> {
+        // Only GitHub Actions jobs have logs reachable through `gh`; their details URL ends in
+        // `/actions/runs/<run id>/job/<job id>`
+        let Some(job_id) = check
+            .url
+            .as_deref()
+            .filter(|url| url.contains("/actions/runs/"))
+            .and_then(|url| url.split("/job/").nth(1))
+            .and_then(|rest| rest.split(['/', '?', '#']).next())
+            .filter(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()))
+            .map(str::to_string)
+        else {
+            return Ok(None);
+        };
+
+        let repo_info = self.get_repo_info(remote_url, repo_path).await?;
+        let cli = self.gh_cli.clone();
+        task::spawn_blocking(move || {
+            cli.get_failed_job_log(&repo_info.owner, &repo_info.repo_name, &job_id)
+        })
+        .await
+        .map_err(|err| {
+            GitHostError::PullRequest(format!(
+                "Failed to execute GitHub CLI for fetching a job log: {err}"
+            ))
+        })?
+        .map(Some)
+        .map_err(GitHostError::from)
+    }
+
+    async fn list_issues(
+        &self,
+        repo_path: &Path,
+        remote_url: &str,
+        filter: &IssueFilter,
+    ) -> Result<Vec<Issue>, GitHostError> {
+        let repo_info = self.get_repo_info(remote_url, repo_path).await?;
+        let cli = self.gh_cli.clone();
+        let filter = filter.clone();
+        task::spawn_blocking(move || {
+            cli.list_issues(&repo_info.owner, &repo_info.repo_name, &filter)
+        })
+        .await
+        .map_err(|err| {
+            GitHostError::Repository(format!(
+                "Failed to execute GitHub CLI for listing issues: {err}"
+            ))
+        })?
+        .map_err(GitHostError::from)
+    }
+
+    async fn close_issue(
+        &self,
+        repo_path: &Path,
+        remote_url: &str,
+        issue_number: i64,
+        comment: &str,
+    ) -> Result<(), GitHostError> {
+        let repo_info = self.get_repo_info(remote_url, repo_path).await?;
+        let cli = self.gh_cli.clone();
+        let comment = comment.to_string();
+        task::spawn_blocking(move || {
+            cli.close_issue(
+                &repo_info.owner,
+                &repo_info.repo_name,
+                issue_number,
+                &comment,
+            )
+        })
+        .await
+        .map_err(|err| {
+            GitHostError::Repository(format!(
+                "Failed to execute GitHub CLI for closing an issue: {err}"
+            ))
+        })?
+        .map_err(GitHostError::from)
+    }
+
     fn provider_kind(&self) -> ProviderKind {
         ProviderKind::GitHub
     }
@@ -0,0 +1,596 @@
+//! Minimal helpers around the GitLab CLI (`glab`).
+//!
+//! All calls go through `glab api` so they work against self-hosted instances via
+//! `--hostname`, reusing whatever credentials `glab auth login` stored for that host.
+
+use std::{ffi::OsStr, path::Path, process::Command};
+
+use chrono::{DateTime, Utc};
+use db::models::merge::{MergeStatus, PullRequestInfo};
+use serde::Deserialize;
+use thiserror::Error;
+use url::{Url, form_urlencoded};
+use utils::shell::resolve_executable_path_blocking;
+
+use crate::services::git_host::{
+    remote_url::parse_remote_url,
+    types::{CreatePrRequest, UnifiedPrComment},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitLabRepoInfo {
+    /// Web base URL, e.g. `https://gitlab.example.com`
+    pub base_url: String,
+    /// Host passed to `glab api --hostname`
+    pub hostname: String,
+    /// Full project path including subgroups, e.g. `group/sub/project`
+    pub project_path: String,
+}
+
+impl GitLabRepoInfo {
+    pub fn from_remote_url(remote_url: &str) -> Option<Self> {
+        let remote = parse_remote_url(remote_url)?;
+        Some(Self {
+            base_url: remote.base_url,
+            hostname: remote.host,
+            project_path: remote.path,
+        })
+    }
+
+    /// Point at an instance whose web URL differs from the remote's host
+    pub fn with_base_url(self, base_url: &str) -> Self {
+        let base_url = base_url.trim_end_matches('/').to_string();
+        let hostname = Url::parse(&base_url)
+            .ok()
+            .and_then(|url| {
+                let host = url.host_str()?;
+                Some(match url.port() {
+                    Some(port) => format!("{host}:{port}"),
+                    None => host.to_string(),
+                })
+            })
+            .unwrap_or(self.hostname);
+        Self {
+            base_url,
+            hostname,
+            project_path: self.project_path,
+        }
+    }
+
+    /// URL-encoded project path, usable as `:id` in API routes
+    fn project_id(&self) -> String {
+        encode(&self.project_path)
+    }
+}
+
+fn encode(value: &str) -> String {
+    form_urlencoded::byte_serialize(value.as_bytes()).collect()
+}
+
+#[derive(Deserialize)]
+struct GlabProject {
+    id: i64,
+}
+
+#[derive(Deserialize)]
+struct GlabMergeRequest {
+    iid: i64,
+    web_url: String,
+    #[serde(default)]
+    state: String,
+    merged_at: Option<DateTime<Utc>>,
+    merge_commit_sha: Option<String>,
+    squash_commit_sha: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GlabDiscussion {
+    #[serde(default)]
+    notes: Vec<GlabNote>,
+}
+
+#[derive(Deserialize)]
+struct GlabNote {
+    id: i64,
+    #[serde(default)]
+    body: String,
+    author: Option<GlabUser>,
+    created_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    system: bool,
+    position: Option<GlabNotePosition>,
+    #[serde(default)]
+    resolved: bool,
+}
+
+#[derive(Deserialize)]
+struct GlabUser {
+    username: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GlabNotePosition {
+    new_path: Option<String>,
+    old_path: Option<String>,
+    new_line: Option<i64>,
+    old_line: Option<i64>,
+}
+
+#[derive(Debug, Error)]
+pub enum GlabCliError {
+    #[error("GitLab CLI (`glab`) executable not found or not runnable")]
+    NotAvailable,
+    #[error("GitLab CLI command failed: {0}")]
+    CommandFailed(String),
+    #[error("GitLab CLI authentication failed: {0}")]
+    AuthFailed(String),
+    #[error("GitLab CLI returned unexpected output: {0}")]
+    UnexpectedOutput(String),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct GlabCli;
+
+impl GlabCli {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Ensure the GitLab CLI binary is discoverable.
+    fn ensure_available(&self) -> Result<(), GlabCliError> {
+        resolve_executable_path_blocking("glab").ok_or(GlabCliError::NotAvailable)?;
+        Ok(())
+    }
+
+    fn run<I, S>(&self, args: I, dir: Option<&Path>) -> Result<String, GlabCliError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        self.ensure_available()?;
+        let glab = resolve_executable_path_blocking("glab").ok_or(GlabCliError::NotAvailable)?;
+        let mut cmd = Command::new(&glab);
+        if let Some(d) = dir {
+            cmd.current_dir(d);
+        }
+        for arg in args {
+            cmd.arg(arg);
+        }
+        tracing::debug!(
+            "Running GitLab CLI command: {:?} {:?}",
+            glab,
+            cmd.get_args()
+        );
+
+        let output = cmd
+            .output()
+            .map_err(|err| GlabCliError::CommandFailed(err.to_string()))?;
+
+        if output.status.success() {
+            return Ok(String::from_utf8_lossy(&output.stdout).to_string());
+        }
+
+        // `glab api` prints the error response body on stdout
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        let message = [stderr, stdout]
+            .into_iter()
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let lower = message.to_ascii_lowercase();
+        if lower.contains("401")
+            || lower.contains("unauthorized")
+            || lower.contains("glab auth login")
+            || lower.contains("not authenticated")
+        {
+            return Err(GlabCliError::AuthFailed(message));
+        }
+
+        Err(GlabCliError::CommandFailed(message))
+    }
+
+    /// Call the REST API of `hostname` and return the raw JSON response
+    fn api(
+        &self,
+        hostname: &str,
+        method: &str,
+        endpoint: &str,
+        fields: &[(&str, &str)],
+    ) -> Result<String, GlabCliError> {
+        let mut args: Vec<String> = vec![
+            "api".to_string(),
+            "--hostname".to_string(),
+            hostname.to_string(),
+            "--method".to_string(),
+            method.to_string(),
+            endpoint.to_string(),
+        ];
+        for (key, value) in fields {
+            args.push("--raw-field".to_string());
+            args.push(format!("{key}={value}"));
+        }
+        self.run(args, None)
+    }
+
+    /// Ensure the GitLab CLI has valid auth for the host.
+    pub fn check_auth(&self, hostname: &str) -> Result<(), GlabCliError> {
+        match self.run(["auth", "status", "--hostname", hostname], None) {
+            Ok(_) => Ok(()),
+            Err(GlabCliError::CommandFailed(msg)) => Err(GlabCliError::AuthFailed(msg)),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn get_project_numeric_id(&self, repo: &GitLabRepoInfo) -> Result<i64, GlabCliError> {
+        let raw = self.api(
+            &repo.hostname,
+            "GET",
+            &format!("projects/{}", repo.project_id()),
+            &[],
+        )?;
+        let project: GlabProject = serde_json::from_str(raw.trim()).map_err(|e| {
+            GlabCliError::UnexpectedOutput(format!("Failed to parse project: {e}; raw: {raw}"))
+        })?;
+        Ok(project.id)
+    }
+
+    /// Open a merge request from `source` (the repo holding the head branch) into `target`.
+    pub fn create_mr(
+        &self,
+        request: &CreatePrRequest,
+        target: &GitLabRepoInfo,
+        source: &GitLabRepoInfo,
+    ) -> Result<PullRequestInfo, GlabCliError> {
+        // GitLab has no draft flag on creation; the title prefix marks it as draft
+        let title = if request.draft.unwrap_or(false) {
+            format!("Draft: {}", request.title)
+        } else {
+            request.title.clone()
+        };
+        let description = request.body.clone().unwrap_or_default();
+
+        let target_project_id = if source.project_path != target.project_path {
+            Some(self.get_project_numeric_id(target)?.to_string())
+        } else {
+            None
+        };
+
+        let mut fields = vec![
+            ("source_branch", request.head_branch.as_str()),
+            ("target_branch", request.base_branch.as_str()),
+            ("title", title.as_str()),
+            ("description", description.as_str()),
+        ];
+        if let Some(id) = &target_project_id {
+            fields.push(("target_project_id", id.as_str()));
+        }
+
+        let raw = self.api(
+            &source.hostname,
+            "POST",
+            &format!("projects/{}/merge_requests", source.project_id()),
+            &fields,
+        )?;
+        Self::parse_mr_response(&raw)
+    }
+
+    /// Retrieve details for a merge request by URL.
+    pub fn view_mr(&self, mr_url: &str) -> Result<PullRequestInfo, GlabCliError> {
+        let (repo, iid) = Self::parse_mr_url(mr_url).ok_or_else(|| {
+            GlabCliError::UnexpectedOutput(format!("Could not parse GitLab MR URL: {mr_url}"))
+        })?;
+        let raw = self.api(
+            &repo.hostname,
+            "GET",
+            &format!("projects/{}/merge_requests/{iid}", repo.project_id()),
+            &[],
+        )?;
+        Self::parse_mr_response(&raw)
+    }
+
+    /// Change the target branch of a merge request.
+    pub fn update_mr_target(
+        &self,
+        repo: &GitLabRepoInfo,
+        iid: i64,
+        target_branch: &str,
+    ) -> Result<(), GlabCliError> {
+        self.api(
+            &repo.hostname,
+            "PUT",
+            &format!("projects/{}/merge_requests/{iid}", repo.project_id()),
+            &[("target_branch", target_branch)],
+        )?;
+        Ok(())
+    }
+
+    /// Add a note to a merge request.
+    pub fn create_mr_note(
+        &self,
+        repo: &GitLabRepoInfo,
+        iid: i64,
+        body: &str,
+    ) -> Result<(), GlabCliError> {
+        self.api(
+            &repo.hostname,
+            "POST",
+            &format!("projects/{}/merge_requests/{iid}/notes", repo.project_id()),
+            &[("body", body)],
+        )?;
+        Ok(())
+    }
+
+    /// List merge requests for a source branch (includes closed/merged).
+    pub fn list_mrs_for_branch(
+        &self,
+        repo: &GitLabRepoInfo,
+        branch: &str,
+    ) -> Result<Vec<PullRequestInfo>, GlabCliError> {
+        let raw = self.api(
+            &repo.hostname,
+            "GET",
+            &format!(
+                "projects/{}/merge_requests?state=all&source_branch={}",
+                repo.project_id(),
+                encode(branch)
+            ),
+            &[],
+        )?;
+        Self::parse_mr_list(&raw)
+    }
+
+    /// Fetch notes (general and diff comments) for a merge request, from its discussions.
+    pub fn get_mr_notes(
+        &self,
+        repo: &GitLabRepoInfo,
+        iid: i64,
+    ) -> Result<Vec<UnifiedPrComment>, GlabCliError> {
+        let raw = self.api(
+            &repo.hostname,
+            "GET",
+            &format!(
+                "projects/{}/merge_requests/{iid}/discussions?per_page=100",
+                repo.project_id()
+            ),
+            &[],
+        )?;
+        let mr_url = format!(
+            "{}/{}/-/merge_requests/{iid}",
+            repo.base_url, repo.project_path
+        );
+        Self::parse_mr_discussions(&raw, &mr_url)
+    }
+
+    /// Parse an MR URL into the project and MR iid.
+    ///
+    /// Format: `https://{host}/{group}/{project}/-/merge_requests/{iid}`
+    pub fn parse_mr_url(url: &str) -> Option<(GitLabRepoInfo, i64)> {
+        let (project_url, rest) = url.split_once("/-/merge_requests/")?;
+        let iid = rest
+            .split(['/', '?', '#'])
+            .next()
+            .and_then(|s| s.parse().ok())?;
+        Some((GitLabRepoInfo::from_remote_url(project_url)?, iid))
+    }
+}
+
+impl GlabCli {
+    fn parse_mr_response(raw: &str) -> Result<PullRequestInfo, GlabCliError> {
+        let mr: GlabMergeRequest = serde_json::from_str(raw.trim()).map_err(|e| {
+            GlabCliError::UnexpectedOutput(format!("Failed to parse MR response: {e}; raw: {raw}"))
+        })?;
+        Ok(Self::mr_to_info(mr))
+    }
+
+    fn parse_mr_list(raw: &str) -> Result<Vec<PullRequestInfo>, GlabCliError> {
+        let mrs: Vec<GlabMergeRequest> = serde_json::from_str(raw.trim()).map_err(|e| {
+            GlabCliError::UnexpectedOutput(format!("Failed to parse MR list: {e}; raw: {raw}"))
+        })?;
+        Ok(mrs.into_iter().map(Self::mr_to_info).collect())
+    }
+
+    fn mr_to_info(mr: GlabMergeRequest) -> PullRequestInfo {
+        PullRequestInfo {
+            number: mr.iid,
+            url: mr.web_url,
+            status: Self::map_gitlab_state(&mr.state),
+            merged_at: mr.merged_at,
+            merge_commit_sha: mr.merge_commit_sha.or(mr.squash_commit_sha),
+        }
+    }
+
+    fn parse_mr_discussions(
+        raw: &str,
+        mr_url: &str,
+    ) -> Result<Vec<UnifiedPrComment>, GlabCliError> {
+        let discussions: Vec<GlabDiscussion> = serde_json::from_str(raw.trim()).map_err(|e| {
+            GlabCliError::UnexpectedOutput(format!(
+                "Failed to parse MR discussions: {e}; raw: {raw}"
+            ))
+        })?;
+
+        let mut comments: Vec<UnifiedPrComment> = discussions
+            .into_iter()
+            // The first note starts the discussion, the others reply to it
+            .flat_map(|discussion| discussion.notes.into_iter().enumerate())
+            // Skip system notes ("added 1 commit", "approved this merge request", ...)
+            .filter(|(_, note)| !note.system)
+            .map(|(index, note)| {
+                let author = note
+                    .author
+                    .and_then(|a| a.username)
+                    .unwrap_or_else(|| "unknown".to_string());
+                let created_at = note.created_at.unwrap_or_else(Utc::now);
+                let url = Some(format!("{mr_url}#note_{}", note.id));
+
+                match note.position {
+                    Some(position)
+                        if position.new_path.is_some() || position.old_path.is_some() =>
+                    {
+                        UnifiedPrComment::Review {
+                            id: note.id,
+                            author,
+                            author_association: None,
+                            body: note.body,
+                            created_at,
+                            url,
+                            path: position.new_path.or(position.old_path).unwrap_or_default(),
+                            line: position.new_line.or(position.old_line),
+                            side: Some(
+                                if position.new_line.is_some() {
+                                    "RIGHT"
+                                } else {
+                                    "LEFT"
+                                }
+                                .to_string(),
+                            ),
+                            diff_hunk: None,
+                            is_reply: index > 0,
+                            resolved: note.resolved,
+                        }
+                    }
+                    _ => UnifiedPrComment::General {
+                        id: note.id.to_string(),
+                        author,
+                        author_association: None,
+                        body: note.body,
+                        created_at,
+                        url,
+                    },
+                }
+            })
+            .collect();
+
+        comments.sort_by_key(|c| c.created_at());
+        Ok(comments)
+    }
+
+    /// Map GitLab MR state to MergeStatus
+    fn map_gitlab_state(state: &str) -> MergeStatus {
+        match state.to_lowercase().as_str() {
+            // `locked` is a transient state while a merge is in progress
+            "opened" | "locked" => MergeStatus::Open,
+            "merged" => MergeStatus::Merged,
+            "closed" => MergeStatus::Closed,
+            _ => MergeStatus::Unknown,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mr_url() {
+        let (repo, iid) =
+            GlabCli::parse_mr_url("https://git.company.com/group/sub/project/-/merge_requests/42")
+                .unwrap();
+        assert_eq!(repo.hostname, "git.company.com");
+        assert_eq!(repo.project_path, "group/sub/project");
+        assert_eq!(repo.project_id(), "group%2Fsub%2Fproject");
+        assert_eq!(iid, 42);
+
+        let (_, iid) =
+            GlabCli::parse_mr_url("https://gitlab.com/owner/repo/-/merge_requests/7/diffs#note_1")
+                .unwrap();
+        assert_eq!(iid, 7);
+    }
+
+    #[test]
+    fn test_parse_mr_url_invalid() {
+        assert!(GlabCli::parse_mr_url("https://github.com/owner/repo/pull/123").is_none());
+        assert!(
+            GlabCli::parse_mr_url("https://gitlab.com/owner/repo/-/merge_requests/abc").is_none()
+        );
+    }
+
+    #[test]
+    fn test_map_gitlab_state() {
+        assert!(matches!(
+            GlabCli::map_gitlab_state("opened"),
+            MergeStatus::Open
+        ));
+        assert!(matches!(
+            GlabCli::map_gitlab_state("merged"),
+            MergeStatus::Merged
+        ));
+        assert!(matches!(
+            GlabCli::map_gitlab_state("closed"),
+            MergeStatus::Closed
+        ));
+        assert!(matches!(
+            GlabCli::map_gitlab_state("weird"),
+            MergeStatus::Unknown
+        ));
+    }
+
+    #[test]
+    fn test_parse_mr_discussions() {
+        let position = r#"{"new_path": "src/lib.rs", "old_path": "src/lib.rs", "new_line": 10, "old_line": null}"#;
+        let raw = format!(
+            r#"[
+            {{"id": "a", "notes": [
+                {{"id": 2, "body": "Please rename", "author": {{"username": "alice"}},
+                  "created_at": "2026-01-02T00:00:00Z", "system": false, "type": "DiffNote",
+                  "resolved": true, "position": {position}}},
+                {{"id": 4, "body": "Done", "author": {{"username": "bob"}},
+                  "created_at": "2026-01-04T00:00:00Z", "system": false, "type": "DiffNote",
+                  "resolved": true, "position": {position}}}
+            ]}},
+            {{"id": "b", "notes": [
+                {{"id": 1, "body": "Looks good", "author": {{"username": "bob"}},
+                  "created_at": "2026-01-01T00:00:00Z", "system": false, "type": null}}
+            ]}},
+            {{"id": "c", "notes": [
+                {{"id": 3, "body": "added 1 commit", "author": {{"username": "bob"}},
+                  "created_at": "2026-01-03T00:00:00Z", "system": true}}
+            ]}}
+        ]"#
+        );
+        let comments =
+            GlabCli::parse_mr_discussions(&raw, "https://gitlab.com/o/r/-/merge_requests/1")
+                .unwrap();
+        assert_eq!(comments.len(), 3);
+
+        match &comments[0] {
+            UnifiedPrComment::General {
+                id, author, url, ..
+            } => {
+                assert_eq!(id, "1");
+                assert_eq!(author, "bob");
+                assert_eq!(
+                    url.as_deref(),
+                    Some("https://gitlab.com/o/r/-/merge_requests/1#note_1")
+                );
+            }
+            other => panic!("expected general comment, got {other:?}"),
+        }
+        match &comments[1] {
+            UnifiedPrComment::Review {
+                path,
+                line,
+                side,
+                is_reply,
+                resolved,
+                ..
+            } => {
+                assert_eq!(path, "src/lib.rs");
+                assert_eq!(*line, Some(10));
+                assert_eq!(side.as_deref(), Some("RIGHT"));
+                assert!(!*is_reply);
+                assert!(*resolved);
+            }
+            other => panic!("expected review comment, got {other:?}"),
+        }
+        match &comments[2] {
+            UnifiedPrComment::Review { id, is_reply, .. } => {
+                assert_eq!(*id, 4);
+                assert!(*is_reply);
+            }
+            other => panic!("expected review comment, got {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,351 @@
+//! GitLab hosting service implementation (gitlab.com and self-hosted).
+
+mod cli;
+
+use std::{path::Path, time::Duration};
+
+use async_trait::async_trait;
+use backon::{ExponentialBuilder, Retryable};
+pub use cli::GlabCli;
+use cli::{GitLabRepoInfo, GlabCliError};
+use db::models::merge::{PrCheck, PullRequestInfo};
+use tokio::task;
+use tracing::info;
+
+use super::{
+    GitHostProvider,
+    types::{CreatePrRequest, GitHostError, Issue, IssueFilter, ProviderKind, UnifiedPrComment},
+};
+
+#[derive(Debug, Clone)]
+pub struct GitLabProvider {
+    glab_cli: GlabCli,
+    /// Instance URL configured on the repo, used instead of the remote URL's host
+    base_url: Option<String>,
+}
+
+impl GitLabProvider {
+    pub fn new() -> Result<Self, GitHostError> {
+        Ok(Self {
+            glab_cli: GlabCli::new(),
+            base_url: None,
+        })
+    }
+
+    pub fn with_base_url(mut self, base_url: Option<&str>) -> Self {
+        self.base_url = base_url.map(str::to_string);
+        self
+    }
+
+    fn get_repo_info(&self, remote_url: &str) -> Result<GitLabRepoInfo, GitHostError> {
+        let info = GitLabRepoInfo::from_remote_url(remote_url).ok_or_else(|| {
+            GitHostError::Repository(format!(
+                "Could not parse GitLab project from remote URL: {remote_url}"
+            ))
+        })?;
+        Ok(match &self.base_url {
+            Some(base_url) => info.with_base_url(base_url),
+            None => info,
+        })
+    }
+
+    async fn check_auth(&self, hostname: &str) -> Result<(), GitHostError> {
+        let cli = self.glab_cli.clone();
+        let hostname = hostname.to_string();
+        task::spawn_blocking(move || cli.check_auth(&hostname))
+            .await
+            .map_err(|err| {
+                GitHostError::Repository(format!(
+                    "Failed to execute GitLab CLI for auth check: {err}"
+                ))
+            })?
+            .map_err(|err| match err {
+                GlabCliError::NotAvailable => GitHostError::CliNotInstalled {
+                    provider: ProviderKind::GitLab,
+                },
+                GlabCliError::AuthFailed(msg) => GitHostError::AuthFailed(msg),
+                GlabCliError::CommandFailed(msg) => {
+                    GitHostError::Repository(format!("GitLab CLI auth check failed: {msg}"))
+                }
+                GlabCliError::UnexpectedOutput(msg) => GitHostError::Repository(format!(
+                    "Unexpected output from GitLab CLI auth check: {msg}"
+                )),
+            })
+    }
+}
+
+impl From<GlabCliError> for GitHostError {
+    fn from(error: GlabCliError) -> Self {
+        match &error {
+            GlabCliError::AuthFailed(msg) => GitHostError::AuthFailed(msg.clone()),
+            GlabCliError::NotAvailable => GitHostError::CliNotInstalled {
+                provider: ProviderKind::GitLab,
+            },
+            GlabCliError::CommandFailed(msg) => {
+                let lower = msg.to_ascii_lowercase();
+                if lower.contains("403") || lower.contains("forbidden") {
+                    GitHostError::InsufficientPermissions(msg.clone())
+                } else if lower.contains("404") || lower.contains("not found") {
+                    GitHostError::RepoNotFoundOrNoAccess(msg.clone())
+                } else {
+                    GitHostError::PullRequest(msg.clone())
+                }
+            }
+            GlabCliError::UnexpectedOutput(msg) => GitHostError::UnexpectedOutput(msg.clone()),
+        }
+    }
+}
+
+#[async_trait]
+impl GitHostProvider for GitLabProvider {
+    async fn create_pr(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        request: &CreatePrRequest,
+    ) -> Result<PullRequestInfo, GitHostError> {
+        let target = self.get_repo_info(remote_url)?;
+        // For cross-fork MRs the MR is opened on the fork and targets the upstream project
+        let source = match &request.head_repo_url {
+            Some(head_url) => self.get_repo_info(head_url)?,
+            None => target.clone(),
+        };
+
+        // Check auth first
+        self.check_auth(&source.hostname).await?;
+
+        (|| async {
+            let cli = self.glab_cli.clone();
+            let request = request.clone();
+            let target = target.clone();
+            let source = source.clone();
+
+            let cli_result =
+                task::spawn_blocking(move || cli.create_mr(&request, &target, &source))
+                    .await
+                    .map_err(|err| {
+                        GitHostError::PullRequest(format!(
+                            "Failed to execute GitLab CLI for MR creation: {err}"
+                        ))
+                    })?
+                    .map_err(GitHostError::from)?;
+
+            info!(
+                "Created GitLab MR !{} for branch {}",
+                cli_result.number, request.head_branch
+            );
+
+            Ok(cli_result)
+        })
+        .retry(
+            &ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(30))
+                .with_max_times(3)
+                .with_jitter(),
+        )
+        .when(|e: &GitHostError| e.should_retry())
+        .notify(|err: &GitHostError, dur: Duration| {
+            tracing::warn!(
+                "GitLab API call failed, retrying after {:.2}s: {}",
+                dur.as_secs_f64(),
+                err
+            );
+        })
+        .await
+    }
+
+    async fn get_pr_status(&self, pr_url: &str) -> Result<PullRequestInfo, GitHostError> {
+        (|| async {
+            let cli = self.glab_cli.clone();
+            let url = pr_url.to_string();
+
+            let pr = task::spawn_blocking(move || cli.view_mr(&url))
+                .await
+                .map_err(|err| {
+                    GitHostError::PullRequest(format!(
+                        "Failed to execute GitLab CLI for viewing MR: {err}"
+                    ))
+                })?;
+            pr.map_err(GitHostError::from)
+        })
+        .retry(
+            &ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(30))
+                .with_max_times(3)
+                .with_jitter(),
+        )
+        .when(|err: &GitHostError| err.should_retry())
+        .notify(|err: &GitHostError, dur: Duration| {
+            tracing::warn!(
+                "GitLab API call failed, retrying after {:.2}s: {}",
+                dur.as_secs_f64(),
+                err
+            );
+        })
+        .await
+    }
+
+    async fn list_prs_for_branch(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        branch_name: &str,
+    ) -> Result<Vec<PullRequestInfo>, GitHostError> {
+        let repo_info = self.get_repo_info(remote_url)?;
+
+        (|| async {
+            let cli = self.glab_cli.clone();
+            let repo_info = repo_info.clone();
+            let branch = branch_name.to_string();
+
+            let prs = task::spawn_blocking(move || cli.list_mrs_for_branch(&repo_info, &branch))
+                .await
+                .map_err(|err| {
+                    GitHostError::PullRequest(format!(
+                        "Failed to execute GitLab CLI for listing MRs: {err}"
+                    ))
+                })?;
+            prs.map_err(GitHostError::from)
+        })
+        .retry(
+            &ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(30))
+                .with_max_times(3)
+                .with_jitter(),
+        )
+        .when(|e: &GitHostError| e.should_retry())
+        .notify(|err: &GitHostError, dur: Duration| {
+            tracing::warn!(
+                "GitLab API call failed, retrying after {:.2}s: {}",
+                dur.as_secs_f64(),
+                err
+            );
+        })
+        .await
+    }
+
+    async fn get_pr_comments(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        pr_number: i64,
+    ) -> Result<Vec<UnifiedPrComment>, GitHostError> {
+        let repo_info = self.get_repo_info(remote_url)?;
+
+        (|| async {
+            let cli = self.glab_cli.clone();
+            let repo_info = repo_info.clone();
+
+            let comments = task::spawn_blocking(move || cli.get_mr_notes(&repo_info, pr_number))
+                .await
+                .map_err(|err| {
+                    GitHostError::PullRequest(format!(
+                        "Failed to execute GitLab CLI for fetching MR comments: {err}"
+                    ))
+                })?;
+            comments.map_err(GitHostError::from)
+        })
+        .retry(
+            &ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(30))
+                .with_max_times(3)
+                .with_jitter(),
+        )
+        .when(|e: &GitHostError| e.should_retry())
+        .notify(|err: &GitHostError, dur: Duration| {
+            tracing::warn!(
+                "GitLab API call failed, retrying after {:.2}s: {}",
+                dur.as_secs_f64(),
+                err
+            );
+        })
+        .await
+    }
+
+    async fn update_pr_base(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        pr_number: i64,
+        base_branch: &str,
+    ) -> Result<(), GitHostError> {
+        let repo_info = self.get_repo_info(remote_url)?;
+        let cli = self.glab_cli.clone();
+        let base_branch = base_branch.to_string();
+        task::spawn_blocking(move || cli.update_mr_target(&repo_info, pr_number, &base_branch))
+            .await
+            .map_err(|err| {
+                GitHostError::PullRequest(format!(
+                    "Failed to execute GitLab CLI for updating MR target: {err}"
+                ))
+            })?
+            .map_err(GitHostError::from)
+    }
+
+    async fn create_pr_comment(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        pr_number: i64,
+        body: &str,
+    ) -> Result<(), GitHostError> {
+        let repo_info = self.get_repo_info(remote_url)?;
+        let cli = self.glab_cli.clone();
+        let body = body.to_string();
+        task::spawn_blocking(move || cli.create_mr_note(&repo_info, pr_number, &body))
+            .await
+            .map_err(|err| {
+                GitHostError::PullRequest(format!(
+                    "Failed to execute GitLab CLI for commenting on MR: {err}"
+                ))
+            })?
+            .map_err(GitHostError::from)
+    }
+
+    async fn get_pr_checks(
+        &self,
+        _repo_path: &Path,
+        _remote_url: &str,
+        _pr_number: i64,
+    ) -> Result<Vec<PrCheck>, GitHostError> {
+        // GitLab pipelines are not tracked yet
+        Ok(Vec::new())
+    }
+
+    async fn get_check_log(
+        &self,
+        _repo_path: &Path,
+        _remote_url: &str,
+        _check: &PrCheck,
+    ) -> Result<Option<String>, GitHostError> {
+        Ok(None)
+    }
+
+    async fn list_issues(
+        &self,
+        _repo_path: &Path,
+        _remote_url: &str,
+        _filter: &IssueFilter,
+    ) -> Result<Vec<Issue>, GitHostError> {
+        // Importing issues is only supported from GitHub and Azure Boards
+        Err(GitHostError::UnsupportedProvider)
+    }
+
+    async fn close_issue(
+        &self,
+        _repo_path: &Path,
+        _remote_url: &str,
+        _issue_number: i64,
+        _comment: &str,
+    ) -> Result<(), GitHostError> {
+        Err(GitHostError::UnsupportedProvider)
+    }
+
+    fn provider_kind(&self) -> ProviderKind {
+        ProviderKind::GitLab
+    }
+}
@@ -1,21 +1,30 @@
 mod detection;
+mod remote_url;
 mod types;
 
 pub mod azure;
+pub mod gitea;
 pub mod github;
+pub mod gitlab;
 
 use std::path::Path;
 
 use async_trait::async_trait;
-use db::models::merge::PullRequestInfo;
+use db::models::{
+    merge::{PrCheck, PullRequestInfo},
+    repo::Repo,
+};
 use detection::detect_provider_from_url;
 use enum_dispatch::enum_dispatch;
 pub use types::{
-    CreatePrRequest, GitHostError, PrComment, PrCommentAuthor, PrReviewComment, ProviderKind,
-    ReviewCommentUser, UnifiedPrComment,
+    CreatePrRequest, GitHostError, Issue, IssueFilter, PrComment, PrCommentAuthor, PrReviewComment,
+    ProviderKind, ReviewCommentUser, UnifiedPrComment,
 };
 
-use self::{azure::AzureDevOpsProvider, github::GitHubProvider};
+use self::{
+    azure::AzureDevOpsProvider, gitea::GiteaProvider, github::GitHubProvider,
+    gitlab::GitLabProvider,
+};
 
 #[async_trait]
 #[enum_dispatch(GitHostService)]
@@ -43,6 +52,57 @@ pub trait GitHostProvider: Send + Sync {
         pr_number: i64,
     ) -> Result<Vec<UnifiedPrComment>, GitHostError>;
 
+    /// Retarget an open PR onto another base branch
+    async fn update_pr_base(
+        &self,
+        repo_path: &Path,
+        remote_url: &str,
+        pr_number: i64,
+        base_branch: &str,
+    ) -> Result<(), GitHostError>;
+
+    /// Post a comment on the PR's conversation
+    async fn create_pr_comment(
+        &self,
+        repo_path: &Path,
+        remote_url: &str,
+        pr_number: i64,
+        body: &str,
+    ) -> Result<(), GitHostError>;
+
+    /// CI check runs and commit statuses reported on the PR's head commit
+    async fn get_pr_checks(
+        &self,
+        repo_path: &Path,
+        remote_url: &str,
+        pr_number: i64,
+    ) -> Result<Vec<PrCheck>, GitHostError>;
+
+    /// Log of a failed check, when the provider exposes it
+    async fn get_check_log(
+        &self,
+        repo_path: &Path,
+        remote_url: &str,
+        check: &PrCheck,
+    ) -> Result<Option<String>, GitHostError>;
+
+    /// Open issues of the repo (work items of the project on Azure Boards)
+    async fn list_issues(
+        &self,
+        repo_path: &Path,
+        remote_url: &str,
+        filter: &IssueFilter,
+    ) -> Result<Vec<Issue>, GitHostError>;
+
+    /// Comment on an issue and close it
+    async fn close_issue(
+        &self,
+        repo_path: &Path,
+        remote_url: &str,
+        issue_number: i64,
+        comment: &str,
+    ) -> Result<(), GitHostError>;
+
     fn provider_kind(&self) -> ProviderKind;
 }
 
@@ -50,13 +110,35 @@ pub trait GitHostProvider: Send + Sync {
 pub enum GitHostService {
     GitHub(GitHubProvider),
     AzureDevOps(AzureDevOpsProvider),
+    GitLab(GitLabProvider),
+    Gitea(GiteaProvider),
 }
 
 impl GitHostService {
     pub fn from_url(url: &str) -> Result<Self, GitHostError> {
-        match detect_provider_from_url(url) {
+        Self::from_kind(detect_provider_from_url(url), None)
+    }
+
+    /// Like `from_url`, but the repo's configured `git_host_provider` takes precedence over
+    /// detection, and its `git_host_base_url` over the instance URL derived from the remote, for
+    /// self-hosted instances whose URLs can't be recognised.
+    pub fn for_repo(repo: &Repo, url: &str) -> Result<Self, GitHostError> {
+        let kind = repo
+            .git_host_provider
+            .as_deref()
+            .and_then(ProviderKind::from_setting)
+            .unwrap_or_else(|| detect_provider_from_url(url));
+        Self::from_kind(kind, repo.git_host_base_url.as_deref())
+    }
+
+    fn from_kind(kind: ProviderKind, base_url: Option<&str>) -> Result<Self, GitHostError> {
+        match kind {
             ProviderKind::GitHub => Ok(Self::GitHub(GitHubProvider::new()?)),
             ProviderKind::AzureDevOps => Ok(Self::AzureDevOps(AzureDevOpsProvider::new()?)),
+            ProviderKind::GitLab => {
+                Ok(Self::GitLab(GitLabProvider::new()?.with_base_url(base_url)))
+            }
+            ProviderKind::Gitea => Ok(Self::Gitea(GiteaProvider::new()?.with_base_url(base_url))),
             ProviderKind::Unknown => Err(GitHostError::UnsupportedProvider),
         }
     }
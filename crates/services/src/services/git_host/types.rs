@@ -8,14 +8,26 @@ use ts_rs::TS;
 pub enum ProviderKind {
     GitHub,
     AzureDevOps,
+    GitLab,
+    // Also covers Forgejo, which shares the Gitea API
+    Gitea,
     Unknown,
 }
 
+impl ProviderKind {
+    /// Parse the provider stored in a repo's `git_host_provider` setting
+    pub fn from_setting(value: &str) -> Option<Self> {
+        serde_json::from_value(serde_json::Value::String(value.to_string())).ok()
+    }
+}
+
 impl std::fmt::Display for ProviderKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ProviderKind::GitHub => write!(f, "GitHub"),
             ProviderKind::AzureDevOps => write!(f, "Azure DevOps"),
+            ProviderKind::GitLab => write!(f, "GitLab"),
+            ProviderKind::Gitea => write!(f, "Gitea"),
             ProviderKind::Unknown => write!(f, "Unknown"),
         }
     }
@@ -32,6 +44,28 @@ pub struct CreatePrRequest {
     pub head_repo_url: Option<String>,
 }
 
+/// Which open issues to list
+#[derive(Debug, Clone, Default)]
+pub struct IssueFilter {
+    /// Issues must have all of these labels (tags on Azure Boards)
+    pub labels: Vec<String>,
+    /// Milestone title (iteration path on Azure Boards)
+    pub milestone: Option<String>,
+    /// Free-text search
+    pub query: Option<String>,
+    pub limit: usize,
+}
+
+/// An open issue, or Azure Boards work item
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    pub number: i64,
+    pub title: String,
+    pub body: String,
+    pub url: String,
+    pub labels: Vec<String>,
+}
+
 #[derive(Debug, Error)]
 pub enum GitHostError {
     #[error("Repository error: {0}")]
@@ -98,6 +132,10 @@ pub struct PrReviewComment {
     pub side: Option<String>,
     pub diff_hunk: String,
     pub author_association: String,
+    /// The first comment of the thread, for replies
+    pub in_reply_to_id: Option<i64>,
+    /// Whether the comment's thread was resolved
+    pub resolved: bool,
 }
 
 #[derive(Debug, Clone, Serialize, TS)]
@@ -123,6 +161,10 @@ pub enum UnifiedPrComment {
         line: Option<i64>,
         side: Option<String>,
         diff_hunk: Option<String>,
+        /// Whether the comment replies to an earlier comment of its thread
+        is_reply: bool,
+        /// Whether the comment's thread was resolved
+        resolved: bool,
     },
 }
 
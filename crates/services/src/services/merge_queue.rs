@@ -0,0 +1,306 @@
+use std::{
+    path::{Path, PathBuf},
+    process::Stdio,
+    sync::Arc,
+    time::Duration,
+};
+
+use db::models::{
+    execution_process::ExecutionProcess,
+    merge_queue::{MergeQueueEntry, MergeQueueStatus, MergeQueueStrategy},
+    repo::Repo,
+    workspace::Workspace,
+};
+use sqlx::error::Error as SqlxError;
+use thiserror::Error;
+use tokio::{process::Command, time::interval};
+use tracing::{error, info, warn};
+use utils::shell::get_shell_command;
+use uuid::Uuid;
+
+use crate::services::{
+    container::{ContainerError, ContainerService},
+    git::GitServiceError,
+    verification,
+    workspace_manager::WorkspaceManager,
+};
+
+/// Longest a repo's verify script may run for a queue entry before the entry is ejected
+const CHECK_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+#[derive(Debug, Error)]
+enum MergeQueueServiceError {
+    #[error(transparent)]
+    Container(#[from] ContainerError),
+    #[error(transparent)]
+    GitService(#[from] GitServiceError),
+    #[error(transparent)]
+    Sqlx(#[from] SqlxError),
+    /// The entry cannot be merged; the message is recorded as the failure reason
+    #[error("{0}")]
+    Ejected(String),
+}
+
+enum EntryOutcome {
+    Merged {
+        merge_id: Uuid,
+    },
+    /// The target moved while the entry was being checked; rebase and check again
+    TargetMoved,
+}
+
+/// Service that works through each repo's merge queue one entry at a time: rebase onto the
+/// current target, run the repo's verify script, then merge
+pub struct MergeQueueService<C> {
+    container: C,
+    poll_interval: Duration,
+}
+
+impl<C> MergeQueueService<C>
+where
+    C: ContainerService + Send + Sync + 'static,
+{
+    pub fn spawn(container: C) -> tokio::task::JoinHandle<()> {
+        let service = Arc::new(Self {
+            container,
+            poll_interval: Duration::from_secs(10),
+        });
+        tokio::spawn(async move {
+            service.start().await;
+        })
+    }
+
+    async fn start(self: Arc<Self>) {
+        info!(
+            "Starting merge queue service with interval {:?}",
+            self.poll_interval
+        );
+
+        match MergeQueueEntry::requeue_running(&self.container.db().pool).await {
+            Ok(0) => {}
+            Ok(count) => info!("Requeued {} interrupted merge queue entries", count),
+            Err(e) => error!("Failed to requeue interrupted merge queue entries: {}", e),
+        }
+
+        let mut interval = interval(self.poll_interval);
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.start_queue_heads().await {
+                error!("Error processing merge queues: {}", e);
+            }
+        }
+    }
+
+    /// Start on the head of every queue that is not already being worked on. Each runs in its
+    /// own task, so a long verify script in one repo does not hold up the other queues.
+    async fn start_queue_heads(self: &Arc<Self>) -> Result<(), MergeQueueServiceError> {
+        let pool = &self.container.db().pool;
+        for entry in MergeQueueEntry::find_queue_heads(pool).await? {
+            // Marked before the task starts so the next poll does not pick it up again
+            MergeQueueEntry::set_status(pool, entry.id, MergeQueueStatus::Running).await?;
+
+            let service = self.clone();
+            tokio::spawn(async move {
+                if let Err(e) = service.process_queue_head(&entry).await {
+                    error!("Error processing merge queue entry {}: {}", entry.id, e);
+                    // Back in the queue rather than left running until the next restart
+                    if let Err(e) = MergeQueueEntry::set_status(
+                        &service.container.db().pool,
+                        entry.id,
+                        MergeQueueStatus::Queued,
+                    )
+                    .await
+                    {
+                        error!("Failed to requeue merge queue entry {}: {}", entry.id, e);
+                    }
+                }
+            });
+        }
+        Ok(())
+    }
+
+    async fn process_queue_head(
+        &self,
+        entry: &MergeQueueEntry,
+    ) -> Result<(), MergeQueueServiceError> {
+        let pool = &self.container.db().pool;
+        // Auto-rebase leaves the workspace alone until the entry is done
+        let _branch_lock = WorkspaceManager::lock_branches(entry.workspace_id).await;
+        match self.process_entry(entry).await {
+            Ok(EntryOutcome::Merged { merge_id }) => {
+                info!(
+                    "Merge queue merged workspace {} into {}",
+                    entry.workspace_id, entry.target_branch_name
+                );
+                MergeQueueEntry::mark_merged(pool, entry.id, merge_id).await?;
+            }
+            Ok(EntryOutcome::TargetMoved) => {
+                MergeQueueEntry::set_status(pool, entry.id, MergeQueueStatus::Queued).await?;
+            }
+            // Database errors say nothing about the entry; it is retried
+            Err(e @ MergeQueueServiceError::Sqlx(_)) => return Err(e),
+            Err(e) => {
+                let reason = e.to_string();
+                warn!(
+                    "Merge queue ejected workspace {}: {}",
+                    entry.workspace_id, reason
+                );
+                MergeQueueEntry::eject(pool, entry.id, &reason).await?;
+                self.notify_ejected(entry, &reason).await;
+            }
+        }
+        Ok(())
+    }
+
+    async fn process_entry(
+        &self,
+        entry: &MergeQueueEntry,
+    ) -> Result<EntryOutcome, MergeQueueServiceError> {
+        let pool = &self.container.db().pool;
+        let git = self.container.git();
+
+        let workspace = Workspace::find_by_id(pool, entry.workspace_id)
+            .await?
+            .ok_or_else(|| MergeQueueServiceError::Ejected("Workspace not found".to_string()))?;
+        if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, workspace.id)
+            .await?
+        {
+            return Err(MergeQueueServiceError::Ejected(
+                "The workspace has running processes".to_string(),
+            ));
+        }
+        let task = workspace
+            .parent_task(pool)
+            .await?
+            .ok_or_else(|| MergeQueueServiceError::Ejected("Task not found".to_string()))?;
+        let repo = Repo::find_by_id(pool, entry.repo_id)
+            .await?
+            .ok_or_else(|| MergeQueueServiceError::Ejected("Repository not found".to_string()))?;
+
+        let container_ref = self.container.ensure_container_exists(&workspace).await?;
+        let worktree_path = PathBuf::from(container_ref).join(&repo.name);
+        let target = entry.target_branch_name.as_str();
+
+        match git.rebase_branch(
+            &repo.path,
+            &worktree_path,
+            target,
+            target,
+            &workspace.branch,
+        ) {
+            Ok(_) => {}
+            Err(GitServiceError::MergeConflicts(_)) => {
+                let files = git.get_conflicted_files(&worktree_path).unwrap_or_default();
+                // Leave the workspace as it was before the queue touched it
+                if let Err(e) = git.abort_conflicts(&worktree_path) {
+                    warn!(
+                        "Failed to abort rebase of ejected workspace {}: {}",
+                        workspace.id, e
+                    );
+                }
+                return Err(MergeQueueServiceError::Ejected(format!(
+                    "Rebasing onto {target} conflicts in {}",
+                    files.join(", ")
+                )));
+            }
+            Err(e) => {
+                return Err(MergeQueueServiceError::Ejected(format!(
+                    "Rebasing onto {target} failed: {e}"
+                )));
+            }
+        }
+
+        if let Some(script) = repo
+            .verify_script
+            .as_deref()
+            .filter(|script| !script.trim().is_empty())
+        {
+            run_check(script, &worktree_path)
+                .await
+                .map_err(MergeQueueServiceError::Ejected)?;
+        }
+
+        let merged = match entry.strategy {
+            MergeQueueStrategy::Squash => git.merge_changes(
+                &repo.path,
+                &worktree_path,
+                &workspace.branch,
+                target,
+                &task.merge_commit_message(),
+            ),
+            MergeQueueStrategy::FastForward => {
+                git.fast_forward_branch(&repo.path, &workspace.branch, target)
+            }
+        };
+        let merge_commit = match merged {
+            Ok(commit) => commit,
+            Err(GitServiceError::BranchesDiverged(_)) => return Ok(EntryOutcome::TargetMoved),
+            Err(e) => return Err(e.into()),
+        };
+
+        let merge = self
+            .container
+            .complete_direct_merge(&workspace, repo.id, target, &merge_commit)
+            .await?;
+        Ok(EntryOutcome::Merged { merge_id: merge.id })
+    }
+
+    async fn notify_ejected(&self, entry: &MergeQueueEntry, reason: &str) {
+        let pool = &self.container.db().pool;
+        let task_title = match Workspace::find_by_id(pool, entry.workspace_id).await {
+            Ok(Some(workspace)) => workspace
+                .parent_task(pool)
+                .await
+                .ok()
+                .flatten()
+                .map(|task| task.title),
+            _ => None,
+        }
+        .unwrap_or_else(|| "Unknown task".to_string());
+        let summary = reason.lines().next().unwrap_or(reason);
+
+        self.container
+            .notification_service()
+            .notify(&format!("Merge Queue Ejected: {task_title}"), summary)
+            .await;
+    }
+}
+
+/// Run a repo's verify script in the worktree. Returns the failure reason when it fails.
+async fn run_check(script: &str, worktree_path: &Path) -> Result<(), String> {
+    let (shell_cmd, shell_arg) = get_shell_command();
+    let output = Command::new(shell_cmd)
+        .arg(shell_arg)
+        .arg(script)
+        .current_dir(worktree_path)
+        .stdin(Stdio::null())
+        .kill_on_drop(true)
+        .output();
+
+    let output = match tokio::time::timeout(CHECK_TIMEOUT, output).await {
+        Ok(Ok(output)) => output,
+        Ok(Err(e)) => return Err(format!("Failed to run the verify script: {e}")),
+        Err(_) => {
+            return Err(format!(
+                "The verify script did not finish within {} minutes",
+                CHECK_TIMEOUT.as_secs() / 60
+            ));
+        }
+    };
+    if output.status.success() {
+        return Ok(());
+    }
+
+    let mut combined = String::from_utf8_lossy(&output.stdout).into_owned();
+    combined.push_str(&String::from_utf8_lossy(&output.stderr));
+    let exit = output
+        .status
+        .code()
+        .map(|code| format!("exit code {code}"))
+        .unwrap_or_else(|| "a signal".to_string());
+    Err(format!(
+        "The verify script failed with {exit}:\n{}",
+        verification::trim_output(&combined)
+    ))
+}
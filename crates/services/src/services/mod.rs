@@ -1,8 +1,13 @@
 pub mod analytics;
 pub mod approvals;
 pub mod auth;
+pub mod auto_rebase;
+pub mod budget;
+pub mod change_selection;
 pub mod config;
 pub mod container;
+pub mod conversation_search;
+pub mod db_backup;
 pub mod diff_stream;
 pub mod events;
 pub mod file_ranker;
@@ -12,15 +17,26 @@ pub mod filesystem_watcher;
 pub mod git;
 pub mod git_host;
 pub mod image;
+pub mod merge_queue;
 pub mod notification;
 pub mod oauth_credentials;
+pub mod pr_checks;
 pub mod pr_monitor;
+pub mod pr_review;
 pub mod project;
+pub mod project_bundle;
 #[cfg(feature = "qa-mode")]
 pub mod qa_repos;
 pub mod queued_message;
 pub mod remote_client;
 pub mod repo;
+pub mod review_comments;
+pub mod session_export;
 pub mod share;
+pub mod stacking;
+pub mod task_dependencies;
+pub mod task_import;
+pub mod task_scheduler;
+pub mod verification;
 pub mod workspace_manager;
 pub mod worktree_manager;
@@ -1,9 +1,22 @@
-use std::sync::{Arc, OnceLock};
+use std::{
+    sync::{Arc, OnceLock},
+    time::Duration,
+};
 
+use backon::{ExponentialBuilder, Retryable};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use serde_json::{Value, json};
+use sha2::Sha256;
 use tokio::sync::RwLock;
 use utils;
 
-use crate::services::config::{Config, NotificationConfig, SoundFile};
+use crate::services::config::{
+    Config, NotificationConfig, NotificationEvent, SoundFile, WebhookConfig, WebhookFormat,
+};
+
+pub const WEBHOOK_SIGNATURE_HEADER: &str = "X-Vibe-Kanban-Signature";
+pub const WEBHOOK_EVENT_HEADER: &str = "X-Vibe-Kanban-Event";
 
 /// Service for handling cross-platform notifications including sound alerts and push notifications
 #[derive(Debug, Clone)]
@@ -25,6 +38,104 @@ impl NotificationService {
         Self::send_notification(&config, title, message).await;
     }
 
+    /// Send sound and push notifications, and deliver the event to configured webhooks in the
+    /// background
+    pub async fn notify_event(&self, event: NotificationEvent, title: &str, message: &str) {
+        let config = self.config.read().await.notifications.clone();
+        Self::send_notification(&config, title, message).await;
+
+        self.spawn_webhooks(event, title, message);
+    }
+
+    /// Deliver an event to configured webhooks in the background, so slow endpoints never hold
+    /// up the caller
+    pub fn spawn_webhooks(&self, event: NotificationEvent, title: &str, message: &str) {
+        let service = self.clone();
+        let title = title.to_string();
+        let message = message.to_string();
+        tokio::spawn(async move { service.send_webhooks(event, &title, &message).await });
+    }
+
+    /// Deliver an event to every enabled webhook subscribed to it, retrying deliveries that hit
+    /// network errors or server errors. Returns once all deliveries have succeeded or exhausted their retries.
+    pub async fn send_webhooks(&self, event: NotificationEvent, title: &str, message: &str) {
+        let webhooks: Vec<WebhookConfig> = self
+            .config
+            .read()
+            .await
+            .notifications
+            .webhooks
+            .iter()
+            .filter(|webhook| webhook.wants(&event))
+            .cloned()
+            .collect();
+        if webhooks.is_empty() {
+            return;
+        }
+
+        let client = reqwest::Client::new();
+        let deliveries = webhooks
+            .iter()
+            .map(|webhook| Self::deliver_webhook(&client, webhook, event, title, message));
+        futures::future::join_all(deliveries).await;
+    }
+
+    async fn deliver_webhook(
+        client: &reqwest::Client,
+        webhook: &WebhookConfig,
+        event: NotificationEvent,
+        title: &str,
+        message: &str,
+    ) {
+        let body = webhook_payload(webhook.format, event, title, message).to_string();
+        let signature = webhook
+            .secret
+            .as_deref()
+            .map(|secret| sign_webhook_payload(secret, body.as_bytes()));
+
+        let send = || async {
+            let mut request = client
+                .post(&webhook.url)
+                .timeout(Duration::from_secs(10))
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(WEBHOOK_EVENT_HEADER, event.to_string())
+                .body(body.clone());
+            if let Some(signature) = &signature {
+                request = request.header(WEBHOOK_SIGNATURE_HEADER, signature);
+            }
+            request.send().await?.error_for_status()
+        };
+
+        let result = send
+            .retry(
+                &ExponentialBuilder::default()
+                    .with_min_delay(Duration::from_millis(500))
+                    .with_max_delay(Duration::from_secs(10))
+                    .with_max_times(3)
+                    .with_jitter(),
+            )
+            // A 4xx means the request itself is wrong; sending it again won't help
+            .when(|err: &reqwest::Error| err.status().is_none_or(|s| s.is_server_error()))
+            .notify(|err: &reqwest::Error, dur: Duration| {
+                tracing::warn!(
+                    "Webhook delivery to {} failed, retrying after {:.2}s: {}",
+                    webhook.url,
+                    dur.as_secs_f64(),
+                    err
+                );
+            })
+            .await;
+
+        if let Err(e) = result {
+            tracing::error!(
+                "Failed to deliver {} webhook to {}: {}",
+                event,
+                webhook.url,
+                e
+            );
+        }
+    }
+
     /// Internal method to send notifications with a given config
     async fn send_notification(config: &NotificationConfig, title: &str, message: &str) {
         if config.sound_enabled {
@@ -236,3 +347,37 @@ impl NotificationService {
         }
     }
 }
+
+/// Build the request body for a webhook in the shape its receiver expects
+pub fn webhook_payload(
+    format: WebhookFormat,
+    event: NotificationEvent,
+    title: &str,
+    message: &str,
+) -> Value {
+    match format {
+        WebhookFormat::Json => json!({
+            "event": event.to_string(),
+            "title": title,
+            "message": message,
+            "timestamp": Utc::now().to_rfc3339(),
+        }),
+        WebhookFormat::Slack => json!({ "text": format!("*{title}*\n{message}") }),
+        WebhookFormat::Discord => json!({ "content": format!("**{title}**\n{message}") }),
+        WebhookFormat::Teams => json!({
+            "@type": "MessageCard",
+            "@context": "https://schema.org/extensions",
+            "summary": title,
+            "title": title,
+            "text": message,
+        }),
+    }
+}
+
+/// `sha256=<hex HMAC-SHA256 of body>`, the same scheme GitHub uses for webhook signatures
+pub fn sign_webhook_payload(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
@@ -0,0 +1,145 @@
+//! CI checks of workspaces' PRs: telling failures apart and asking the agent to fix them.
+
+use db::models::merge::{PrCheck, PrCheckStatus};
+
+/// Lines of a failed check's log included in a follow-up
+pub const LOG_EXCERPT_LINES: usize = 80;
+
+/// Identifies a set of failed checks, so the same failures are acted on only once. The key is
+/// cleared whenever a check is pending again, as not every host gives re-runs a new URL.
+pub fn failure_key(failed: &[&PrCheck]) -> String {
+    let mut entries: Vec<String> = failed
+        .iter()
+        .map(|check| {
+            format!(
+                "{}@{}",
+                check.name,
+                check.url.as_deref().unwrap_or_default()
+            )
+        })
+        .collect();
+    entries.sort();
+    entries.join("\n")
+}
+
+/// The checks that failed, once none of them is still running. `None` while checks are
+/// pending or when nothing failed.
+pub fn settled_failures(checks: &[PrCheck]) -> Option<Vec<&PrCheck>> {
+    if checks
+        .iter()
+        .any(|check| check.status == PrCheckStatus::Pending)
+    {
+        return None;
+    }
+    let failed: Vec<&PrCheck> = checks
+        .iter()
+        .filter(|check| check.status == PrCheckStatus::Failed)
+        .collect();
+    (!failed.is_empty()).then_some(failed)
+}
+
+/// Whether failures already acted on count as new when they happen again: once a check is
+/// pending again, or all of them passed
+pub fn clears_seen_failures(checks: &[PrCheck]) -> bool {
+    !checks.is_empty() && settled_failures(checks).is_none()
+}
+
+/// The last `max_lines` non-blank lines of a log, where the error usually is
+pub fn log_excerpt(log: &str, max_lines: usize) -> String {
+    let lines: Vec<&str> = log
+        .lines()
+        .map(str::trim_end)
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    lines[lines.len().saturating_sub(max_lines)..].join("\n")
+}
+
+/// Follow-up prompt asking the agent to fix failed checks, each with an excerpt of its log
+/// when one could be fetched
+pub fn fix_prompt(pr_number: i64, failed: &[(PrCheck, Option<String>)]) -> String {
+    let mut message = format!("CI checks failed on pull request #{pr_number}. Please fix them:\n");
+    for (index, (check, log)) in failed.iter().enumerate() {
+        message.push_str(&format!("\n{}. {}", index + 1, check.name));
+        if let Some(url) = &check.url {
+            message.push_str(&format!(" ({url})"));
+        }
+        message.push('\n');
+        if let Some(description) = check.description.as_deref().filter(|d| !d.is_empty()) {
+            message.push_str(&format!("\n{description}\n"));
+        }
+        if let Some(log) = log.as_deref().filter(|log| !log.is_empty()) {
+            message.push_str(&format!("\n```\n{log}\n```\n"));
+        }
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(name: &str, status: PrCheckStatus, url: Option<&str>) -> PrCheck {
+        PrCheck {
+            name: name.to_string(),
+            status,
+            url: url.map(str::to_string),
+            description: None,
+        }
+    }
+
+    #[test]
+    fn test_settled_failures_waits_for_pending_checks() {
+        let mut checks = vec![
+            check("CI / test", PrCheckStatus::Failed, Some("https://ci/1")),
+            check("CI / lint", PrCheckStatus::Pending, None),
+            check("CI / build", PrCheckStatus::Passed, None),
+        ];
+        assert!(settled_failures(&checks).is_none());
+
+        checks[1].status = PrCheckStatus::Passed;
+        let failed = settled_failures(&checks).unwrap();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failure_key(&failed), "CI / test@https://ci/1");
+
+        checks[0].status = PrCheckStatus::Passed;
+        assert!(settled_failures(&checks).is_none());
+    }
+
+    #[test]
+    fn test_rerun_of_a_check_without_url_fails_anew() {
+        let mut checks = vec![check("Azure / build", PrCheckStatus::Failed, None)];
+        let first_key = failure_key(&settled_failures(&checks).unwrap());
+        assert!(!clears_seen_failures(&checks));
+
+        checks[0].status = PrCheckStatus::Pending;
+        assert!(clears_seen_failures(&checks));
+
+        checks[0].status = PrCheckStatus::Failed;
+        assert_eq!(failure_key(&settled_failures(&checks).unwrap()), first_key);
+        assert!(!clears_seen_failures(&[]));
+    }
+
+    #[test]
+    fn test_fix_prompt_includes_log_excerpt() {
+        let log = "setup\n\nrunning 2 tests\nassertion failed\n\n";
+        assert_eq!(log_excerpt(log, 2), "running 2 tests\nassertion failed");
+
+        let prompt = fix_prompt(
+            3,
+            &[
+                (
+                    check("CI / test", PrCheckStatus::Failed, Some("https://ci/1")),
+                    Some(log_excerpt(log, 2)),
+                ),
+                (check("deploy", PrCheckStatus::Failed, None), None),
+            ],
+        );
+        assert_eq!(
+            prompt,
+            "CI checks failed on pull request #3. Please fix them:\n\
+             \n1. CI / test (https://ci/1)\n\
+             \n```\nrunning 2 tests\nassertion failed\n```\n\
+             \n2. deploy\n"
+        );
+    }
+}
@@ -1,23 +1,31 @@
-use std::time::Duration;
-
-use db::{
-    DBService,
-    models::{
-        merge::{Merge, MergeStatus, PrMerge},
-        task::{Task, TaskStatus},
-        workspace::{Workspace, WorkspaceError},
-    },
+use std::{collections::HashSet, time::Duration};
+
+use db::models::{
+    execution_process::ExecutionProcess,
+    merge::{Merge, MergeStatus, PrMerge},
+    pr_review_comment::{CreatePrReviewComment, PrReviewComment, PrReviewCommentStatus},
+    project_pr_review_setting::ProjectPrReviewSetting,
+    repo::Repo,
+    session::Session,
+    task::{Task, TaskStatus},
+    workspace::{Workspace, WorkspaceError},
+    workspace_repo::WorkspaceRepo,
 };
 use serde_json::json;
 use sqlx::error::Error as SqlxError;
 use thiserror::Error;
 use tokio::time::interval;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
 use crate::services::{
     analytics::AnalyticsContext,
-    git_host::{self, GitHostError, GitHostProvider},
+    config::NotificationEvent,
+    container::{ContainerError, ContainerService},
+    git::GitServiceError,
+    git_host::{self, GitHostError, GitHostProvider, UnifiedPrComment},
+    pr_checks, pr_review,
     share::SharePublisher,
+    stacking, task_import,
 };
 
 #[derive(Debug, Error)]
@@ -28,24 +36,32 @@ enum PrMonitorError {
     WorkspaceError(#[from] WorkspaceError),
     #[error(transparent)]
     Sqlx(#[from] SqlxError),
+    #[error(transparent)]
+    GitService(#[from] GitServiceError),
+    #[error(transparent)]
+    Container(#[from] ContainerError),
 }
 
-/// Service to monitor PRs and update task status when they are merged
-pub struct PrMonitorService {
-    db: DBService,
+/// Service to monitor PRs: update task status when they are merged, and pick up review
+/// comments left on them and the results of their CI checks
+pub struct PrMonitorService<C> {
+    container: C,
     poll_interval: Duration,
     analytics: Option<AnalyticsContext>,
     publisher: Option<SharePublisher>,
 }
 
-impl PrMonitorService {
+impl<C> PrMonitorService<C>
+where
+    C: ContainerService + Send + Sync + 'static,
+{
     pub async fn spawn(
-        db: DBService,
+        container: C,
         analytics: Option<AnalyticsContext>,
         publisher: Option<SharePublisher>,
     ) -> tokio::task::JoinHandle<()> {
         let service = Self {
-            db,
+            container,
             poll_interval: Duration::from_secs(60), // Check every minute
             analytics,
             publisher,
@@ -73,7 +89,7 @@ impl PrMonitorService {
 
     /// Check all open PRs for updates with the provided GitHub token
     async fn check_all_open_prs(&self) -> Result<(), PrMonitorError> {
-        let open_prs = Merge::get_open_prs(&self.db.pool).await?;
+        let open_prs = Merge::get_open_prs(&self.container.db().pool).await?;
 
         if open_prs.is_empty() {
             debug!("No open PRs to check");
@@ -95,7 +111,11 @@ impl PrMonitorService {
 
     /// Check the status of a specific PR
     async fn check_pr_status(&self, pr_merge: &PrMerge) -> Result<(), PrMonitorError> {
-        let git_host = git_host::GitHostService::from_url(&pr_merge.pr_info.url)?;
+        let repo = Repo::find_by_id(&self.container.db().pool, pr_merge.repo_id).await?;
+        let git_host = match &repo {
+            Some(repo) => git_host::GitHostService::for_repo(repo, &pr_merge.pr_info.url)?,
+            None => git_host::GitHostService::from_url(&pr_merge.pr_info.url)?,
+        };
         let pr_status = git_host.get_pr_status(&pr_merge.pr_info.url).await?;
 
         debug!(
@@ -107,7 +127,7 @@ impl PrMonitorService {
         if !matches!(&pr_status.status, MergeStatus::Open) {
             // Update merge status with the latest information from git host
             Merge::update_status(
-                &self.db.pool,
+                &self.container.db().pool,
                 pr_merge.id,
                 pr_status.status.clone(),
                 pr_status.merge_commit_sha,
@@ -117,22 +137,62 @@ impl PrMonitorService {
             // If the PR was merged, update the task status to done
             if matches!(&pr_status.status, MergeStatus::Merged)
                 && let Some(workspace) =
-                    Workspace::find_by_id(&self.db.pool, pr_merge.workspace_id).await?
+                    Workspace::find_by_id(&self.container.db().pool, pr_merge.workspace_id).await?
             {
                 info!(
                     "PR #{} was merged, updating task {} to done and archiving workspace",
                     pr_merge.pr_info.number, workspace.task_id
                 );
-                Task::update_status(&self.db.pool, workspace.task_id, TaskStatus::Done).await?;
+                Task::update_status(
+                    &self.container.db().pool,
+                    workspace.task_id,
+                    TaskStatus::Done,
+                )
+                .await?;
 
                 // Archive workspace unless pinned
                 if !workspace.pinned {
-                    Workspace::set_archived(&self.db.pool, workspace.id, true).await?;
+                    Workspace::set_archived(&self.container.db().pool, workspace.id, true).await?;
+                }
+
+                // Workspaces stacked on this one now build on the PR's base
+                if let Err(e) = stacking::restack_after_merge(
+                    &self.container.db().pool,
+                    self.container.git(),
+                    &workspace,
+                    pr_merge.repo_id,
+                )
+                .await
+                {
+                    tracing::warn!(
+                        "Failed to restack workspaces stacked on {}: {}",
+                        workspace.id,
+                        e
+                    );
+                }
+
+                // Close the issue the task was imported from
+                if let Err(e) = task_import::close_linked_issue(
+                    &self.container.db().pool,
+                    workspace.task_id,
+                    &pr_merge.pr_info.url,
+                )
+                .await
+                {
+                    warn!(
+                        "Failed to close the issue of task {}: {}",
+                        workspace.task_id, e
+                    );
                 }
 
+                let task = Task::find_by_id(&self.container.db().pool, workspace.task_id)
+                    .await
+                    .ok()
+                    .flatten();
+
                 // Track analytics event
                 if let Some(analytics) = &self.analytics
-                    && let Ok(Some(task)) = Task::find_by_id(&self.db.pool, workspace.task_id).await
+                    && let Some(task) = &task
                 {
                     analytics.analytics_service.track_event(
                         &analytics.user_id,
@@ -145,6 +205,17 @@ impl PrMonitorService {
                     );
                 }
 
+                // Webhook only: merges happen outside the app, so no desktop notification
+                let task_title = task.map(|t| t.title).unwrap_or_default();
+                self.container.notification_service().spawn_webhooks(
+                    NotificationEvent::PrMerged,
+                    &format!("PR Merged: {}", task_title),
+                    &format!(
+                        "PR #{} was merged: {}",
+                        pr_merge.pr_info.number, pr_merge.pr_info.url
+                    ),
+                );
+
                 if let Some(publisher) = &self.publisher
                     && let Err(err) = publisher.update_shared_task_by_id(workspace.task_id).await
                 {
@@ -155,8 +226,330 @@ impl PrMonitorService {
                     );
                 }
             }
+        } else if let Some(repo) = &repo {
+            if let Err(e) = self.check_review_comments(pr_merge, repo, &git_host).await {
+                error!(
+                    "Error checking review comments of PR #{}: {}",
+                    pr_merge.pr_info.number, e
+                );
+            }
+            if let Err(e) = self.check_ci_checks(pr_merge, repo, &git_host).await {
+                error!(
+                    "Error checking CI checks of PR #{}: {}",
+                    pr_merge.pr_info.number, e
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Record the inline review comments of an open PR that were not seen before and tell the
+    /// user about them. Replies inside threads and comments of resolved threads are left out.
+    /// When the project opted in and the workspace is idle, comments posted since and not sent
+    /// yet go to the workspace's latest session as a follow-up.
+    async fn check_review_comments(
+        &self,
+        pr_merge: &PrMerge,
+        repo: &Repo,
+        git_host: &git_host::GitHostService,
+    ) -> Result<(), PrMonitorError> {
+        let pool = &self.container.db().pool;
+        let Some(workspace) = Workspace::find_by_id(pool, pr_merge.workspace_id).await? else {
+            return Ok(());
+        };
+        let Some(workspace_repo) =
+            WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, repo.id).await?
+        else {
+            return Ok(());
+        };
+        let remote_url = pr_review::pr_remote_url(
+            self.container.git(),
+            &repo.path,
+            &workspace_repo.target_branch,
+        )?;
+        let comments = git_host
+            .get_pr_comments(&repo.path, &remote_url, pr_merge.pr_info.number)
+            .await?;
+
+        let task = workspace.parent_task(pool).await?;
+        let follow_up_since = match &task {
+            Some(task) if !workspace.archived => {
+                ProjectPrReviewSetting::auto_follow_up_enabled_at(pool, task.project_id).await?
+            }
+            _ => None,
+        };
+
+        let mut new_count = 0;
+        let mut resolved_ids = HashSet::new();
+        for comment in comments {
+            let UnifiedPrComment::Review {
+                id,
+                author,
+                body,
+                created_at,
+                url,
+                path,
+                line,
+                diff_hunk,
+                is_reply,
+                resolved,
+                ..
+            } = comment
+            else {
+                continue;
+            };
+            // Replies continue a discussion rather than ask for a change
+            if is_reply {
+                continue;
+            }
+            if resolved {
+                resolved_ids.insert(id.to_string());
+                continue;
+            }
+            let status = if follow_up_since.is_some_and(|since| created_at < since) {
+                PrReviewCommentStatus::Skipped
+            } else {
+                PrReviewCommentStatus::Pending
+            };
+            let created = PrReviewComment::create_if_new(
+                pool,
+                &CreatePrReviewComment {
+                    merge_id: pr_merge.id,
+                    workspace_id: workspace.id,
+                    repo_id: repo.id,
+                    comment_id: id.to_string(),
+                    author,
+                    body,
+                    path,
+                    line,
+                    diff_hunk,
+                    url,
+                    status,
+                },
+            )
+            .await?;
+            if created.is_some() {
+                new_count += 1;
+            }
+        }
+
+        let Some(task) = task else {
+            return Ok(());
+        };
+        if new_count > 0 {
+            info!(
+                "Found {} new review comment(s) on PR #{} of workspace {}",
+                new_count, pr_merge.pr_info.number, workspace.id
+            );
+            self.container
+                .notification_service()
+                .notify(
+                    &format!("PR Review Comments: {}", task.title),
+                    &format!(
+                        "{} new review comment(s) on PR #{}: {}",
+                        new_count, pr_merge.pr_info.number, pr_merge.pr_info.url
+                    ),
+                )
+                .await;
+        }
+
+        let Some(since) = follow_up_since else {
+            return Ok(());
+        };
+        let mut pending = Vec::new();
+        for comment in PrReviewComment::find_pending_by_merge_id(pool, pr_merge.id).await? {
+            // Seen before auto follow-up was turned on, or resolved while waiting
+            if comment.created_at < since || resolved_ids.contains(&comment.comment_id) {
+                PrReviewComment::set_status(pool, comment.id, PrReviewCommentStatus::Skipped)
+                    .await?;
+            } else {
+                pending.push(comment);
+            }
+        }
+        if pending.is_empty() {
+            return Ok(());
+        }
+        if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, workspace.id)
+            .await?
+        {
+            debug!(
+                "Deferring review comments of busy workspace {}",
+                workspace.id
+            );
+            return Ok(());
+        }
+        let Some(session) = Session::find_latest_by_workspace_id(pool, workspace.id).await? else {
+            return Ok(());
+        };
+
+        let prompt = pr_review::follow_up_prompt(pr_merge.pr_info.number, &pending);
+        // Claim the comments before starting, so they are never sent twice
+        for comment in &pending {
+            PrReviewComment::set_status(pool, comment.id, PrReviewCommentStatus::Sent).await?;
+        }
+        let process = match self
+            .container
+            .start_follow_up(&workspace, &session, &prompt)
+            .await
+        {
+            Ok(process) => process,
+            Err(e) => {
+                for comment in &pending {
+                    PrReviewComment::set_status(pool, comment.id, PrReviewCommentStatus::Pending)
+                        .await?;
+                }
+                return Err(e.into());
+            }
+        };
+        for comment in &pending {
+            PrReviewComment::mark_sent(pool, comment.id, process.id).await?;
         }
+        info!(
+            "Sent {} review comment(s) on PR #{} to workspace {}",
+            pending.len(),
+            pr_merge.pr_info.number,
+            workspace.id
+        );
+
+        if let Some(analytics) = &self.analytics {
+            analytics.analytics_service.track_event(
+                &analytics.user_id,
+                "pr_review_follow_up_started",
+                Some(json!({
+                    "task_id": task.id.to_string(),
+                    "workspace_id": workspace.id.to_string(),
+                    "project_id": task.project_id.to_string(),
+                    "comment_count": pending.len(),
+                })),
+            );
+        }
+        Ok(())
+    }
 
+    /// Record the CI checks of an open PR. Once its checks settle with failures not seen
+    /// before, the user is told, and when the project opted in and the workspace is idle, the
+    /// failures and excerpts of their logs go to the workspace's latest session as a follow-up.
+    async fn check_ci_checks(
+        &self,
+        pr_merge: &PrMerge,
+        repo: &Repo,
+        git_host: &git_host::GitHostService,
+    ) -> Result<(), PrMonitorError> {
+        let pool = &self.container.db().pool;
+        let Some(workspace) = Workspace::find_by_id(pool, pr_merge.workspace_id).await? else {
+            return Ok(());
+        };
+        let Some(workspace_repo) =
+            WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, repo.id).await?
+        else {
+            return Ok(());
+        };
+        let remote_url = pr_review::pr_remote_url(
+            self.container.git(),
+            &repo.path,
+            &workspace_repo.target_branch,
+        )?;
+        let checks = git_host
+            .get_pr_checks(&repo.path, &remote_url, pr_merge.pr_info.number)
+            .await?;
+        if checks != pr_merge.checks {
+            Merge::update_checks(pool, pr_merge.id, &checks).await?;
+        }
+
+        let seen_key = Merge::checks_failure_key(pool, pr_merge.id).await?;
+        if pr_checks::clears_seen_failures(&checks)
+            && seen_key.as_deref().is_some_and(|key| !key.is_empty())
+        {
+            Merge::set_checks_failure_key(pool, pr_merge.id, "").await?;
+        }
+        let Some(failed) = pr_checks::settled_failures(&checks) else {
+            return Ok(());
+        };
+        let failure_key = pr_checks::failure_key(&failed);
+        if seen_key.as_deref() == Some(failure_key.as_str()) {
+            return Ok(());
+        }
+        let Some(task) = workspace.parent_task(pool).await? else {
+            return Ok(());
+        };
+
+        let auto_fix = !workspace.archived
+            && ProjectPrReviewSetting::auto_fix_checks_enabled(pool, task.project_id).await?;
+        let mut fix_started = false;
+        if auto_fix {
+            if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(
+                pool,
+                workspace.id,
+            )
+            .await?
+            {
+                // The failure key is not recorded, so this is retried on the next poll
+                debug!("Deferring failed checks of busy workspace {}", workspace.id);
+                return Ok(());
+            }
+            if let Some(session) = Session::find_latest_by_workspace_id(pool, workspace.id).await? {
+                let mut failures = Vec::with_capacity(failed.len());
+                for check in &failed {
+                    let log = match git_host.get_check_log(&repo.path, &remote_url, check).await {
+                        Ok(log) => log
+                            .map(|log| pr_checks::log_excerpt(&log, pr_checks::LOG_EXCERPT_LINES)),
+                        Err(e) => {
+                            warn!("Failed to fetch the log of check {}: {}", check.name, e);
+                            None
+                        }
+                    };
+                    failures.push(((*check).clone(), log));
+                }
+
+                let prompt = pr_checks::fix_prompt(pr_merge.pr_info.number, &failures);
+                self.container
+                    .start_follow_up(&workspace, &session, &prompt)
+                    .await?;
+                fix_started = true;
+            }
+        }
+        Merge::set_checks_failure_key(pool, pr_merge.id, &failure_key).await?;
+
+        info!(
+            "{} check(s) failed on PR #{} of workspace {}",
+            failed.len(),
+            pr_merge.pr_info.number,
+            workspace.id
+        );
+        let message = if fix_started {
+            format!(
+                "{} check(s) failed on PR #{}, asked the agent to fix them: {}",
+                failed.len(),
+                pr_merge.pr_info.number,
+                pr_merge.pr_info.url
+            )
+        } else {
+            format!(
+                "{} check(s) failed on PR #{}: {}",
+                failed.len(),
+                pr_merge.pr_info.number,
+                pr_merge.pr_info.url
+            )
+        };
+        self.container
+            .notification_service()
+            .notify(&format!("PR Checks Failed: {}", task.title), &message)
+            .await;
+
+        if let Some(analytics) = &self.analytics {
+            analytics.analytics_service.track_event(
+                &analytics.user_id,
+                "pr_checks_failed",
+                Some(json!({
+                    "task_id": task.id.to_string(),
+                    "workspace_id": workspace.id.to_string(),
+                    "project_id": task.project_id.to_string(),
+                    "failed_count": failed.len(),
+                    "fix_started": fix_started,
+                })),
+            );
+        }
         Ok(())
     }
 }
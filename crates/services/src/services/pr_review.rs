@@ -0,0 +1,242 @@
+//! Review comments left on workspaces' PRs: sending them to the agent as a follow-up and, once
+//! its turn is over, pushing the result and replying on the PR.
+
+use std::path::{Path, PathBuf};
+
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessStatus},
+    merge::Merge,
+    pr_review_comment::{PrReviewComment, PrReviewCommentStatus},
+    repo::Repo,
+    workspace::Workspace,
+    workspace_repo::WorkspaceRepo,
+};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::services::{
+    git::{GitService, GitServiceError},
+    git_host::{GitHostError, GitHostProvider, GitHostService},
+};
+
+#[derive(Debug, Error)]
+pub enum PrReviewError {
+    #[error(transparent)]
+    GitService(#[from] GitServiceError),
+    #[error(transparent)]
+    GitHost(#[from] GitHostError),
+    #[error(transparent)]
+    Sqlx(#[from] sqlx::Error),
+    #[error("The PR of the review comments is no longer linked to the workspace")]
+    PrNotFound,
+    #[error("The workspace has no worktree")]
+    NoWorktree,
+}
+
+/// Remote of the repo that PRs into `target_branch` are opened on
+pub fn pr_remote_url(
+    git: &GitService,
+    repo_path: &Path,
+    target_branch: &str,
+) -> Result<String, GitServiceError> {
+    git.get_remote_url(
+        repo_path,
+        &git.resolve_remote_name_for_branch(repo_path, target_branch)?,
+    )
+}
+
+/// Follow-up prompt presenting PR review comments with their file paths, lines and diff hunks
+pub fn follow_up_prompt(pr_number: i64, comments: &[PrReviewComment]) -> String {
+    let mut message =
+        format!("Please address these review comments left on pull request #{pr_number}:\n");
+    for (index, comment) in comments.iter().enumerate() {
+        let line = comment
+            .line
+            .map(|line| format!(" line {line}"))
+            .unwrap_or_default();
+        message.push_str(&format!(
+            "\n{}. `{}`{line}, from @{}:\n",
+            index + 1,
+            comment.path,
+            comment.author
+        ));
+        if let Some(diff_hunk) = comment
+            .diff_hunk
+            .as_deref()
+            .filter(|h| !h.trim().is_empty())
+        {
+            message.push_str(&format!("\n```diff\n{}\n```\n", diff_hunk.trim_end()));
+        }
+        message.push_str(&format!("\n{}\n", comment.body.trim()));
+    }
+    message
+}
+
+/// PR comment saying which review comments were addressed, and in which commit
+pub fn addressed_reply(comments: &[&PrReviewComment], commit_sha: &str) -> String {
+    let short_sha = commit_sha.get(..7).unwrap_or(commit_sha);
+    let mut reply = format!("Addressed these review comments in {short_sha}:\n");
+    for comment in comments {
+        let location = match comment.line {
+            Some(line) => format!("`{}` line {line}", comment.path),
+            None => format!("`{}`", comment.path),
+        };
+        let location = match &comment.url {
+            Some(url) => format!("[{location}]({url})"),
+            None => location,
+        };
+        reply.push_str(&format!("\n- {location} from @{}", comment.author));
+    }
+    reply
+}
+
+/// Settle the PR review comments sent in a coding agent turn that just ended. When the turn
+/// completed, the workspace branch is pushed and each PR is replied to; otherwise, or when
+/// that fails, the comments are marked failed.
+pub async fn complete_follow_up(
+    pool: &SqlitePool,
+    git: &GitService,
+    workspace: &Workspace,
+    execution_process: &ExecutionProcess,
+) -> Result<(), PrReviewError> {
+    let comments =
+        PrReviewComment::find_sent_by_execution_process_id(pool, execution_process.id).await?;
+    if comments.is_empty() {
+        return Ok(());
+    }
+
+    let mut merge_ids: Vec<Uuid> = comments.iter().map(|comment| comment.merge_id).collect();
+    merge_ids.sort();
+    merge_ids.dedup();
+
+    for merge_id in merge_ids {
+        let pr_comments: Vec<&PrReviewComment> = comments
+            .iter()
+            .filter(|comment| comment.merge_id == merge_id)
+            .collect();
+        let status = if execution_process.status != ExecutionProcessStatus::Completed {
+            PrReviewCommentStatus::Failed
+        } else {
+            match reply_addressed(pool, git, workspace, &pr_comments).await {
+                Ok(()) => PrReviewCommentStatus::Addressed,
+                Err(e) => {
+                    warn!(
+                        "Failed to push and reply to review comments of workspace {}: {}",
+                        workspace.id, e
+                    );
+                    PrReviewCommentStatus::Failed
+                }
+            }
+        };
+        for comment in pr_comments {
+            PrReviewComment::set_status(pool, comment.id, status).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Push the workspace branch of the comments' repo and reply on their PR
+async fn reply_addressed(
+    pool: &SqlitePool,
+    git: &GitService,
+    workspace: &Workspace,
+    comments: &[&PrReviewComment],
+) -> Result<(), PrReviewError> {
+    let Some(first) = comments.first() else {
+        return Ok(());
+    };
+    let workspace_repo =
+        WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, first.repo_id)
+            .await?
+            .ok_or(PrReviewError::PrNotFound)?;
+    let repo = Repo::find_by_id(pool, first.repo_id)
+        .await?
+        .ok_or(PrReviewError::PrNotFound)?;
+    let pr_info = Merge::find_by_workspace_and_repo_id(pool, workspace.id, first.repo_id)
+        .await?
+        .into_iter()
+        .find_map(|merge| match merge {
+            Merge::Pr(pr_merge) if pr_merge.id == first.merge_id => Some(pr_merge.pr_info),
+            _ => None,
+        })
+        .ok_or(PrReviewError::PrNotFound)?;
+    let container_ref = workspace
+        .container_ref
+        .as_deref()
+        .ok_or(PrReviewError::NoWorktree)?;
+    let worktree_path = PathBuf::from(container_ref).join(&repo.name);
+
+    git.push_to_remote(&worktree_path, &workspace.branch, false)?;
+    let head_sha = git.get_head_info(&worktree_path)?.oid;
+
+    let remote_url = pr_remote_url(git, &repo.path, &workspace_repo.target_branch)?;
+    let git_host = GitHostService::for_repo(&repo, &remote_url)?;
+    git_host
+        .create_pr_comment(
+            &repo.path,
+            &remote_url,
+            pr_info.number,
+            &addressed_reply(comments, &head_sha),
+        )
+        .await?;
+    info!(
+        "Pushed workspace {} and replied to {} review comment(s) on PR #{}",
+        workspace.id,
+        comments.len(),
+        pr_info.number
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn comment(path: &str, line: Option<i64>, url: Option<&str>) -> PrReviewComment {
+        PrReviewComment {
+            id: Uuid::new_v4(),
+            merge_id: Uuid::new_v4(),
+            workspace_id: Uuid::new_v4(),
+            repo_id: Uuid::new_v4(),
+            comment_id: "42".to_string(),
+            author: "alice".to_string(),
+            body: "Handle the empty case\n".to_string(),
+            path: path.to_string(),
+            line,
+            diff_hunk: Some("@@ -1,2 +1,3 @@\n fn main() {\n+    run();\n".to_string()),
+            url: url.map(str::to_string),
+            status: PrReviewCommentStatus::Pending,
+            execution_process_id: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_follow_up_prompt_includes_path_line_and_hunk() {
+        let prompt = follow_up_prompt(7, &[comment("src/main.rs", Some(2), None)]);
+        assert_eq!(
+            prompt,
+            "Please address these review comments left on pull request #7:\n\
+             \n1. `src/main.rs` line 2, from @alice:\n\
+             \n```diff\n@@ -1,2 +1,3 @@\n fn main() {\n+    run();\n```\n\
+             \nHandle the empty case\n"
+        );
+    }
+
+    #[test]
+    fn test_addressed_reply_links_comments() {
+        let linked = comment("src/main.rs", Some(2), Some("https://example.com/c/42"));
+        let unlinked = comment("README.md", None, None);
+        assert_eq!(
+            addressed_reply(&[&linked, &unlinked], "0123456789abcdef"),
+            "Addressed these review comments in 0123456:\n\
+             \n- [`src/main.rs` line 2](https://example.com/c/42) from @alice\
+             \n- `README.md` from @alice"
+        );
+    }
+}
@@ -0,0 +1,258 @@
+//! Project bundles as `.tar.gz` archives: exporting a project with its agent conversations
+//! normalized, and reading bundles back for import.
+
+use std::{collections::HashMap, io::Read, path::PathBuf};
+
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason},
+    project::Project,
+    project_bundle::{ProjectBundle, ProjectBundleError},
+};
+use executors::{logs::replay::replay, profile::ExecutorConfigs};
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tar::{Archive, Builder, Header};
+use thiserror::Error;
+use tracing::warn;
+use ts_rs::TS;
+use utils::log_msg::LogMsg;
+use uuid::Uuid;
+
+use crate::services::{
+    container::{ContainerError, ContainerService},
+    repo::{RepoError, RepoService},
+    session_export,
+};
+
+/// Archive entry holding the bundle
+const BUNDLE_ENTRY: &str = "bundle.json";
+
+pub const ARCHIVE_CONTENT_TYPE: &str = "application/gzip";
+pub const ARCHIVE_EXTENSION: &str = "vkbundle.tar.gz";
+
+#[derive(Debug, Error)]
+pub enum ProjectBundleServiceError {
+    #[error(transparent)]
+    Bundle(#[from] ProjectBundleError),
+    #[error(transparent)]
+    Container(#[from] ContainerError),
+    #[error(transparent)]
+    Repo(#[from] RepoError),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("Not a project bundle: {0}")]
+    InvalidArchive(String),
+}
+
+/// What a bundle contains, shown before importing it so its repos can be mapped to local paths
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ProjectBundleSummary {
+    pub version: u32,
+    pub project_name: String,
+    pub repos: Vec<ProjectBundleRepoSummary>,
+    pub task_count: usize,
+    pub tag_count: usize,
+    /// Workspaces whose history is included
+    pub workspace_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ProjectBundleRepoSummary {
+    pub id: Uuid,
+    pub display_name: String,
+    /// Where the repo was checked out on the exporting machine
+    pub path: String,
+}
+
+impl From<&ProjectBundle> for ProjectBundleSummary {
+    fn from(bundle: &ProjectBundle) -> Self {
+        Self {
+            version: bundle.version,
+            project_name: bundle.project_name.clone(),
+            repos: bundle
+                .repos
+                .iter()
+                .map(|repo| ProjectBundleRepoSummary {
+                    id: repo.id,
+                    display_name: repo.display_name.clone(),
+                    path: repo.path.to_string_lossy().to_string(),
+                })
+                .collect(),
+            task_count: bundle.tasks.len(),
+            tag_count: bundle.tags.len(),
+            workspace_count: bundle.workspaces.len(),
+        }
+    }
+}
+
+/// Snapshot a project. With `include_histories`, the raw output of coding agent runs is
+/// replaced by the conversation its executor's normalizer builds from it, so the history reads
+/// the same wherever the bundle is imported. Runs that cannot be normalized keep their output.
+pub async fn export_project<C>(
+    container: &C,
+    project_id: Uuid,
+    include_histories: bool,
+) -> Result<ProjectBundle, ProjectBundleServiceError>
+where
+    C: ContainerService + Sync,
+{
+    let pool = &container.db().pool;
+    let mut bundle = ProjectBundle::export(pool, project_id, include_histories).await?;
+
+    let processes = bundle
+        .workspaces
+        .iter_mut()
+        .flat_map(|workspace| workspace.sessions.iter_mut())
+        .flat_map(|session| session.processes.iter_mut())
+        .filter(|process| process.run_reason == ExecutionProcessRunReason::CodingAgent);
+    for bundle_process in processes {
+        let Some(process) = ExecutionProcess::find_by_id(pool, bundle_process.id)
+            .await
+            .map_err(ProjectBundleError::from)?
+        else {
+            continue;
+        };
+        let Some(capture) = session_export::replay_capture(container, &process).await? else {
+            continue;
+        };
+        let executor =
+            ExecutorConfigs::get_cached().get_coding_agent_or_default(&capture.executor_profile_id);
+        match replay(&executor, &capture).await {
+            Ok(output) => {
+                bundle_process.logs = output.patches.into_iter().map(LogMsg::JsonPatch).collect();
+            }
+            Err(e) => warn!(
+                "Failed to normalize logs of execution process {} for export: {}",
+                process.id, e
+            ),
+        }
+    }
+
+    Ok(bundle)
+}
+
+/// Import a bundle once every repo it needs is a git repository on this machine. `repo_paths`
+/// maps ids of bundled repos to local paths; the others are looked up at their original path.
+pub async fn import_project(
+    pool: &SqlitePool,
+    repo_service: &RepoService,
+    bundle: &ProjectBundle,
+    repo_paths: &HashMap<Uuid, String>,
+) -> Result<Project, ProjectBundleServiceError> {
+    let mut local_paths: HashMap<Uuid, PathBuf> = HashMap::new();
+    for repo in &bundle.repos {
+        let path = match repo_paths.get(&repo.id) {
+            Some(path) => repo_service.normalize_path(path)?,
+            None => repo.path.clone(),
+        };
+        repo_service.validate_git_repo_path(&path)?;
+        local_paths.insert(repo.id, path);
+    }
+    Ok(bundle.import(pool, &local_paths).await?)
+}
+
+/// Pack a bundle into a versioned `.tar.gz` archive
+pub fn write_archive(bundle: &ProjectBundle) -> Result<Vec<u8>, ProjectBundleServiceError> {
+    let json = serde_json::to_vec_pretty(bundle)?;
+    let mut buffer = Vec::new();
+    {
+        let encoder = GzEncoder::new(&mut buffer, Compression::default());
+        let mut archive = Builder::new(encoder);
+        let mut header = Header::new_gnu();
+        header.set_size(json.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(bundle.exported_at.timestamp().max(0) as u64);
+        header.set_cksum();
+        archive.append_data(&mut header, BUNDLE_ENTRY, json.as_slice())?;
+        archive.into_inner()?.finish()?;
+    }
+    Ok(buffer)
+}
+
+/// Unpack a bundle written by [`write_archive`]
+pub fn read_archive(bytes: &[u8]) -> Result<ProjectBundle, ProjectBundleServiceError> {
+    let mut archive = Archive::new(GzDecoder::new(bytes));
+    let entries = archive
+        .entries()
+        .map_err(|e| ProjectBundleServiceError::InvalidArchive(e.to_string()))?;
+    for entry in entries {
+        let mut entry =
+            entry.map_err(|e| ProjectBundleServiceError::InvalidArchive(e.to_string()))?;
+        if entry.path()?.to_str() != Some(BUNDLE_ENTRY) {
+            continue;
+        }
+        let mut json = Vec::new();
+        entry.read_to_end(&mut json)?;
+        return serde_json::from_slice(&json)
+            .map_err(|e| ProjectBundleServiceError::InvalidArchive(e.to_string()));
+    }
+    Err(ProjectBundleServiceError::InvalidArchive(format!(
+        "{BUNDLE_ENTRY} is missing"
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use db::models::{
+        project_bundle::{BundleRepo, BundleTag, BundleTask, PROJECT_BUNDLE_VERSION},
+        task::TaskStatus,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_archive_round_trip() {
+        let bundle = ProjectBundle {
+            version: PROJECT_BUNDLE_VERSION,
+            exported_at: Utc::now(),
+            project_name: "Storefront".to_string(),
+            repos: vec![BundleRepo {
+                id: Uuid::new_v4(),
+                path: "/work/storefront".into(),
+                display_name: "Storefront".to_string(),
+                setup_script: Some("npm install".to_string()),
+                cleanup_script: None,
+                copy_files: Some(".env".to_string()),
+                parallel_setup_script: false,
+                dev_server_script: Some("npm run dev".to_string()),
+                git_host_provider: None,
+                git_host_base_url: None,
+                verify_script: None,
+                verify_max_retries: 2,
+            }],
+            tasks: vec![BundleTask {
+                id: Uuid::new_v4(),
+                title: "Build checkout".to_string(),
+                description: None,
+                status: TaskStatus::Todo,
+                depends_on: Vec::new(),
+            }],
+            tags: vec![BundleTag {
+                tag_name: "tests".to_string(),
+                content: "Add tests".to_string(),
+            }],
+            workspaces: Vec::new(),
+        };
+
+        let read = read_archive(&write_archive(&bundle).unwrap()).unwrap();
+        assert_eq!(
+            serde_json::to_value(&read).unwrap(),
+            serde_json::to_value(&bundle).unwrap()
+        );
+        let summary = ProjectBundleSummary::from(&read);
+        assert_eq!(summary.repos[0].path, "/work/storefront");
+        assert_eq!(summary.task_count, 1);
+    }
+
+    #[test]
+    fn test_read_archive_rejects_other_files() {
+        assert!(matches!(
+            read_archive(b"not an archive"),
+            Err(ProjectBundleServiceError::InvalidArchive(_))
+        ));
+    }
+}
@@ -0,0 +1,184 @@
+//! Review comments on line ranges of a workspace's files: capturing the commented lines,
+//! following them as the file changes, and formatting comments into a follow-up for the agent.
+
+use std::path::PathBuf;
+
+use db::models::{
+    review_comment::{ReviewComment, ReviewCommentStatus},
+    workspace::Workspace,
+    workspace_repo::WorkspaceRepo,
+};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+/// Lines shown around the commented lines in a follow-up
+const CONTEXT_LINES: usize = 2;
+
+/// Lines `line_start..=line_end` (1-based) of `content`, or `None` when the file is shorter
+pub fn anchor_text(content: &str, line_start: i64, line_end: i64) -> Option<String> {
+    if line_start < 1 || line_end < line_start {
+        return None;
+    }
+    let lines: Vec<&str> = content.lines().collect();
+    let end = usize::try_from(line_end).ok()?;
+    if end > lines.len() {
+        return None;
+    }
+    Some(lines[line_start as usize - 1..end].join("\n"))
+}
+
+/// Where the commented lines start in `content` now: at `line_start` if they are unchanged,
+/// otherwise wherever they occur exactly once. `None` when they were changed.
+pub fn locate_anchor(content: &str, anchor_text: &str, line_start: i64) -> Option<i64> {
+    // `anchor_text` joins whole lines, so splitting it also keeps blank lines
+    let anchor: Vec<&str> = anchor_text.split('\n').collect();
+    let lines: Vec<&str> = content.lines().collect();
+    if anchor.len() > lines.len() {
+        return None;
+    }
+
+    let matches_at = |start: usize| lines[start..start + anchor.len()] == anchor[..];
+    if line_start >= 1 {
+        let start = line_start as usize - 1;
+        if start + anchor.len() <= lines.len() && matches_at(start) {
+            return Some(line_start);
+        }
+    }
+
+    let mut found = (0..=lines.len() - anchor.len()).filter(|&start| matches_at(start));
+    match (found.next(), found.next()) {
+        (Some(start), None) => Some(start as i64 + 1),
+        _ => None,
+    }
+}
+
+/// The commented lines with a little context, numbered, commented lines marked with `>`
+fn code_context(content: &str, line_start: i64, line_end: i64) -> String {
+    let lines: Vec<&str> = content.lines().collect();
+    let first = (line_start as usize).saturating_sub(CONTEXT_LINES).max(1);
+    let last = (line_end as usize + CONTEXT_LINES).min(lines.len());
+    let width = last.to_string().len();
+    (first..=last)
+        .map(|number| {
+            let marker = if (line_start..=line_end).contains(&(number as i64)) {
+                '>'
+            } else {
+                ' '
+            };
+            format!("{marker} {number:>width$} | {}", lines[number - 1])
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Follow-up prompt presenting `comments` with their file paths, lines and code. Each comment
+/// comes with the current content of its file, when it could be read.
+pub fn follow_up_prompt(prompt: &str, comments: &[(ReviewComment, Option<String>)]) -> String {
+    let mut message = String::new();
+    if !prompt.trim().is_empty() {
+        message.push_str(prompt.trim());
+        message.push_str("\n\n");
+    }
+    message.push_str("Please address these review comments on your changes:\n");
+
+    for (index, (comment, content)) in comments.iter().enumerate() {
+        let lines = if comment.line_start == comment.line_end {
+            format!("line {}", comment.line_start)
+        } else {
+            format!("lines {}-{}", comment.line_start, comment.line_end)
+        };
+        let code = content
+            .as_deref()
+            .filter(|content| anchor_text(content, comment.line_start, comment.line_end).is_some())
+            .map(|content| code_context(content, comment.line_start, comment.line_end))
+            .unwrap_or_else(|| comment.anchor_text.clone());
+        message.push_str(&format!(
+            "\n{}. `{}` {lines}:\n\n```\n{code}\n```\n\n{}\n",
+            index + 1,
+            comment.file_path,
+            comment.body.trim()
+        ));
+    }
+    message
+}
+
+/// Follow open and sent comments of a workspace to where their lines are now. Open comments
+/// whose lines changed become outdated. Comments sent in `finished_turn`, a coding agent turn
+/// that just ended, are resolved when that turn changed their lines.
+pub async fn refresh_statuses(
+    pool: &SqlitePool,
+    workspace: &Workspace,
+    finished_turn: Option<Uuid>,
+) -> Result<(), sqlx::Error> {
+    let Some(container_ref) = workspace.container_ref.as_deref() else {
+        return Ok(());
+    };
+    let comments = ReviewComment::find_by_workspace_id(pool, workspace.id).await?;
+    if comments.is_empty() {
+        return Ok(());
+    }
+    let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
+
+    for comment in comments {
+        let sent_in_finished_turn = comment.status == ReviewCommentStatus::Sent
+            && finished_turn.is_some()
+            && comment.execution_process_id == finished_turn;
+        if comment.status != ReviewCommentStatus::Open && !sent_in_finished_turn {
+            continue;
+        }
+        let Some(repo) = repos.iter().find(|repo| repo.id == comment.repo_id) else {
+            continue;
+        };
+        let path = PathBuf::from(container_ref)
+            .join(&repo.name)
+            .join(&comment.file_path);
+        let content = std::fs::read_to_string(path).unwrap_or_default();
+
+        match locate_anchor(&content, &comment.anchor_text, comment.line_start) {
+            Some(line_start) if line_start == comment.line_start => {}
+            Some(line_start) => {
+                let line_end = line_start + (comment.line_end - comment.line_start);
+                ReviewComment::move_anchor(pool, comment.id, line_start, line_end).await?;
+            }
+            None => {
+                let status = if sent_in_finished_turn {
+                    ReviewCommentStatus::Resolved
+                } else {
+                    ReviewCommentStatus::Outdated
+                };
+                ReviewComment::set_status(pool, comment.id, status).await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILE: &str =
+        "fn main() {\n    let a = 1;\n    let b = 2;\n    println!(\"{}\", a + b);\n}\n";
+
+    #[test]
+    fn test_locate_anchor_follows_moved_lines() {
+        let anchor = anchor_text(FILE, 2, 3).unwrap();
+        assert_eq!(anchor, "    let a = 1;\n    let b = 2;");
+        assert_eq!(locate_anchor(FILE, &anchor, 2), Some(2));
+
+        let moved = format!("// header\n\n{FILE}");
+        assert_eq!(locate_anchor(&moved, &anchor, 2), Some(4));
+
+        let changed = FILE.replace("let b = 2;", "let b = 3;");
+        assert_eq!(locate_anchor(&changed, &anchor, 2), None);
+        assert_eq!(anchor_text(FILE, 5, 9), None);
+    }
+
+    #[test]
+    fn test_code_context_marks_commented_lines() {
+        assert_eq!(
+            code_context(FILE, 2, 2),
+            "  1 | fn main() {\n> 2 |     let a = 1;\n  3 |     let b = 2;\n  4 |     println!(\"{}\", a + b);"
+        );
+    }
+}
@@ -0,0 +1,908 @@
+//! Self-contained transcripts of a workspace session, built from the logs stored in the database.
+
+use std::fmt::Write as _;
+
+use chrono::{DateTime, Utc};
+use db::models::{
+    coding_agent_turn::CodingAgentTurn,
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+    execution_process_logs::ExecutionProcessLogs,
+    execution_process_repo_state::ExecutionProcessRepoState,
+    repo::Repo,
+    session::Session,
+    workspace::Workspace,
+};
+use executors::{
+    actions::ExecutorActionType,
+    logs::{
+        ActionType, CommandExitStatus, FileChange, NormalizedConversation, NormalizedEntry,
+        NormalizedEntryType, ToolResultValueType, ToolStatus, replay::ReplayCapture,
+        utils::patch::apply_conversation_patches,
+    },
+};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use sqlx::Error as SqlxError;
+use ts_rs::TS;
+use utils::{
+    diff::{DiffChangeKind, create_unified_diff},
+    log_msg::LogMsg,
+};
+use uuid::Uuid;
+
+use crate::services::{
+    container::{ContainerError, ContainerService},
+    git::DiffTarget,
+};
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionExportFormat {
+    #[default]
+    Markdown,
+    Html,
+    Json,
+}
+
+impl SessionExportFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Markdown => "text/markdown; charset=utf-8",
+            Self::Html => "text/html; charset=utf-8",
+            Self::Json => "application/json",
+        }
+    }
+
+    pub fn file_extension(&self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Html => "html",
+            Self::Json => "json",
+        }
+    }
+}
+
+/// Everything that happened in a session: agent conversations, script output and the resulting
+/// changes
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct SessionTranscript {
+    pub session_id: Uuid,
+    pub workspace_id: Uuid,
+    pub workspace_branch: String,
+    pub task_id: Uuid,
+    pub task_title: String,
+    pub task_description: Option<String>,
+    pub executor: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub processes: Vec<ProcessTranscript>,
+    /// Changes committed during the session, per repository
+    pub diffs: Vec<RepoDiffTranscript>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ProcessTranscript {
+    pub id: Uuid,
+    pub run_reason: ExecutionProcessRunReason,
+    pub status: ExecutionProcessStatus,
+    pub exit_code: Option<i64>,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    /// Normalized conversation of a coding agent run
+    pub conversation: Option<NormalizedConversation>,
+    /// Combined stdout/stderr of a setup or cleanup script
+    pub output: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct RepoDiffTranscript {
+    pub repo_name: String,
+    pub from_commit: String,
+    pub to_commit: String,
+    pub files: Vec<FileDiffTranscript>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct FileDiffTranscript {
+    pub path: String,
+    pub change: DiffChangeKind,
+    /// Unified diff, omitted for files too large to include
+    pub unified_diff: Option<String>,
+}
+
+/// Collect the transcript of a session from the database. Conversations are re-normalized from
+/// the stored raw logs, so this works for sessions whose processes are no longer in memory.
+pub async fn build_transcript<C>(
+    container: &C,
+    session: &Session,
+) -> Result<SessionTranscript, ContainerError>
+where
+    C: ContainerService + Sync,
+{
+    let pool = &container.db().pool;
+    let workspace = Workspace::find_by_id(pool, session.workspace_id)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+    let task = workspace
+        .parent_task(pool)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+
+    let processes: Vec<ExecutionProcess> =
+        ExecutionProcess::find_by_session_id(pool, session.id, false)
+            .await?
+            .into_iter()
+            .filter(|process| process.run_reason != ExecutionProcessRunReason::DevServer)
+            .collect();
+
+    let mut transcripts = Vec::with_capacity(processes.len());
+    // Per repo: head before the first process and after the last one
+    let mut repo_ranges: Vec<(Uuid, Option<String>, Option<String>)> = Vec::new();
+
+    for process in &processes {
+        for state in
+            ExecutionProcessRepoState::find_by_execution_process_id(pool, process.id).await?
+        {
+            match repo_ranges
+                .iter_mut()
+                .find(|(id, _, _)| *id == state.repo_id)
+            {
+                Some((_, from, to)) => {
+                    if from.is_none() {
+                        *from = state.before_head_commit;
+                    }
+                    if state.after_head_commit.is_some() {
+                        *to = state.after_head_commit;
+                    }
+                }
+                None => repo_ranges.push((
+                    state.repo_id,
+                    state.before_head_commit,
+                    state.after_head_commit,
+                )),
+            }
+        }
+
+        let (conversation, output) = match process.run_reason {
+            ExecutionProcessRunReason::CodingAgent => {
+                (Some(load_conversation(container, process).await?), None)
+            }
+            _ => (None, Some(load_script_output(pool, process.id).await?)),
+        };
+
+        transcripts.push(ProcessTranscript {
+            id: process.id,
+            run_reason: process.run_reason.clone(),
+            status: process.status.clone(),
+            exit_code: process.exit_code,
+            started_at: process.started_at,
+            completed_at: process.completed_at,
+            conversation,
+            output,
+        });
+    }
+
+    let mut diffs = Vec::new();
+    for (repo_id, from, to) in repo_ranges {
+        let (Some(from), Some(to)) = (from, to) else {
+            continue;
+        };
+        if from == to {
+            continue;
+        }
+        let Some(repo) = Repo::find_by_id(pool, repo_id).await? else {
+            continue;
+        };
+        let files = container
+            .git()
+            .get_diffs(
+                DiffTarget::CommitRange {
+                    repo_path: &repo.path,
+                    from_commit: &from,
+                    to_commit: &to,
+                },
+                None,
+            )?
+            .into_iter()
+            .map(|diff| {
+                let path = diff
+                    .new_path
+                    .clone()
+                    .or_else(|| diff.old_path.clone())
+                    .unwrap_or_default();
+                let unified_diff = (!diff.content_omitted).then(|| {
+                    create_unified_diff(
+                        &path,
+                        diff.old_content.as_deref().unwrap_or_default(),
+                        diff.new_content.as_deref().unwrap_or_default(),
+                    )
+                });
+                FileDiffTranscript {
+                    path,
+                    change: diff.change,
+                    unified_diff,
+                }
+            })
+            .collect();
+        diffs.push(RepoDiffTranscript {
+            repo_name: repo.name,
+            from_commit: from,
+            to_commit: to,
+            files,
+        });
+    }
+
+    Ok(SessionTranscript {
+        session_id: session.id,
+        workspace_id: workspace.id,
+        workspace_branch: workspace.branch,
+        task_id: task.id,
+        task_title: task.title,
+        task_description: task.description,
+        executor: session.executor.clone(),
+        created_at: session.created_at,
+        processes: transcripts,
+        diffs,
+    })
+}
+
+async fn load_conversation<C>(
+    container: &C,
+    process: &ExecutionProcess,
+) -> Result<NormalizedConversation, ContainerError>
+where
+    C: ContainerService + Sync,
+{
+    let messages: Vec<LogMsg> = match container.stream_normalized_logs_from_db(&process.id).await {
+        Some(stream) => {
+            stream
+                .filter_map(|msg| async move { msg.ok() })
+                .collect()
+                .await
+        }
+        None => Vec::new(),
+    };
+    let turn =
+        CodingAgentTurn::find_by_execution_process_id(&container.db().pool, process.id).await?;
+    let executor_type = process
+        .executor_action()
+        .ok()
+        .and_then(|action| action.executor_profile_id().cloned())
+        .map(|profile| profile.executor.to_string())
+        .unwrap_or_default();
+
+    Ok(NormalizedConversation {
+        entries: entries_from_patches(&messages),
+        session_id: turn.as_ref().and_then(|t| t.agent_session_id.clone()),
+        executor_type,
+        prompt: turn.as_ref().and_then(|t| t.prompt.clone()),
+        summary: turn.and_then(|t| t.summary),
+    })
+}
+
+async fn load_script_output(
+    pool: &sqlx::SqlitePool,
+    execution_id: Uuid,
+) -> Result<String, ContainerError> {
+    let records = ExecutionProcessLogs::find_by_execution_id(pool, execution_id).await?;
+    let messages = ExecutionProcessLogs::parse_logs(&records)
+        .map_err(|e| ContainerError::Other(anyhow::anyhow!("Failed to parse logs: {e}")))?;
+    Ok(messages
+        .into_iter()
+        .filter_map(|msg| match msg {
+            LogMsg::Stdout(s) | LogMsg::Stderr(s) => Some(s),
+            _ => None,
+        })
+        .collect())
+}
+
+/// Raw output of a coding agent process as a [`ReplayCapture`], for replaying it through the
+/// executor's log normalizer. Returns `None` for script processes, which are not normalized.
+pub async fn replay_capture<C>(
+    container: &C,
+    process: &ExecutionProcess,
+) -> Result<Option<ReplayCapture>, ContainerError>
+where
+    C: ContainerService + Sync,
+{
+    let pool = &container.db().pool;
+    let action = process.executor_action()?;
+    let Some(executor_profile_id) = action.executor_profile_id().cloned() else {
+        return Ok(None);
+    };
+    let (workspace, _) = process
+        .parent_workspace_and_session(pool)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+    let current_dir = container.workspace_to_current_dir(&workspace);
+    let worktree_path = match action.typ() {
+        ExecutorActionType::CodingAgentInitialRequest(request) => {
+            request.effective_dir(&current_dir)
+        }
+        ExecutorActionType::CodingAgentFollowUpRequest(request) => {
+            request.effective_dir(&current_dir)
+        }
+        _ => current_dir,
+    };
+
+    let records = ExecutionProcessLogs::find_by_execution_id(pool, process.id).await?;
+    let logs = ExecutionProcessLogs::parse_logs(&records)
+        .map_err(|e| ContainerError::Other(anyhow::anyhow!("Failed to parse logs: {e}")))?
+        .into_iter()
+        .filter(|msg| matches!(msg, LogMsg::Stdout(_) | LogMsg::Stderr(_)))
+        .collect();
+
+    Ok(Some(ReplayCapture {
+        executor_profile_id,
+        worktree_path,
+        logs,
+    }))
+}
+
+/// Replay conversation patches the way the frontend does, returning the final entries
+pub fn entries_from_patches(messages: &[LogMsg]) -> Vec<NormalizedEntry> {
+    apply_conversation_patches(messages.iter().filter_map(|msg| match msg {
+        LogMsg::JsonPatch(patch) => Some(patch),
+        _ => None,
+    }))
+}
+
+/// Format-independent view of a conversation entry
+struct EntryView {
+    heading: String,
+    blocks: Vec<Block>,
+}
+
+enum Block {
+    Text(String),
+    Code {
+        language: &'static str,
+        code: String,
+    },
+}
+
+fn entry_view(entry: &NormalizedEntry) -> Option<EntryView> {
+    let text = |heading: &str| {
+        Some(EntryView {
+            heading: heading.to_string(),
+            blocks: vec![Block::Text(entry.content.clone())],
+        })
+    };
+    match &entry.entry_type {
+        NormalizedEntryType::UserMessage => text("User"),
+        NormalizedEntryType::AssistantMessage => text("Assistant"),
+        NormalizedEntryType::Thinking => text("Thinking"),
+        NormalizedEntryType::SystemMessage => text("System"),
+        NormalizedEntryType::ErrorMessage { .. } => text("Error"),
+        NormalizedEntryType::UserFeedback { denied_tool } => {
+            text(&format!("User denied {denied_tool}"))
+        }
+        NormalizedEntryType::ToolUse {
+            tool_name,
+            action_type,
+            status,
+        } => Some(tool_view(tool_name, action_type, status, &entry.content)),
+        NormalizedEntryType::Loading
+        | NormalizedEntryType::NextAction { .. }
+        | NormalizedEntryType::TokenUsageInfo(_) => None,
+    }
+}
+
+fn tool_view(
+    tool_name: &str,
+    action_type: &ActionType,
+    status: &ToolStatus,
+    content: &str,
+) -> EntryView {
+    let mut blocks = Vec::new();
+    let mut heading = match action_type {
+        ActionType::FileRead { path } => format!("Read {path}"),
+        ActionType::FileEdit { path, changes } => {
+            for change in changes {
+                match change {
+                    FileChange::Edit { unified_diff, .. } => blocks.push(Block::Code {
+                        language: "diff",
+                        code: unified_diff.clone(),
+                    }),
+                    FileChange::Write { content } => blocks.push(Block::Code {
+                        language: "",
+                        code: content.clone(),
+                    }),
+                    FileChange::Delete => blocks.push(Block::Text("Deleted".to_string())),
+                    FileChange::Rename { new_path } => {
+                        blocks.push(Block::Text(format!("Renamed to {new_path}")))
+                    }
+                }
+            }
+            format!("Edited {path}")
+        }
+        ActionType::CommandRun { command, result } => {
+            blocks.push(Block::Code {
+                language: "sh",
+                code: command.clone(),
+            });
+            if let Some(result) = result {
+                if let Some(output) = result.output.as_deref().filter(|o| !o.trim().is_empty()) {
+                    blocks.push(Block::Code {
+                        language: "",
+                        code: output.to_string(),
+                    });
+                }
+                match result.exit_status {
+                    Some(CommandExitStatus::ExitCode { code }) if code != 0 => {
+                        blocks.push(Block::Text(format!("Exit code {code}")))
+                    }
+                    Some(CommandExitStatus::Success { success: false }) => {
+                        blocks.push(Block::Text("Command failed".to_string()))
+                    }
+                    _ => {}
+                }
+            }
+            "Ran command".to_string()
+        }
+        ActionType::Search { query } => format!("Searched for {query}"),
+        ActionType::WebFetch { url } => format!("Fetched {url}"),
+        ActionType::Tool {
+            tool_name,
+            arguments,
+            result,
+        } => {
+            if let Some(arguments) = arguments {
+                blocks.push(Block::Code {
+                    language: "json",
+                    code: serde_json::to_string_pretty(arguments).unwrap_or_default(),
+                });
+            }
+            if let Some(result) = result {
+                blocks.push(match (&result.r#type, result.value.as_str()) {
+                    (ToolResultValueType::Markdown, Some(markdown)) => {
+                        Block::Text(markdown.to_string())
+                    }
+                    _ => Block::Code {
+                        language: "json",
+                        code: serde_json::to_string_pretty(&result.value).unwrap_or_default(),
+                    },
+                });
+            }
+            format!("Called {tool_name}")
+        }
+        ActionType::TaskCreate { description } => {
+            blocks.push(Block::Text(description.clone()));
+            "Created subtask".to_string()
+        }
+        ActionType::PlanPresentation { plan } => {
+            blocks.push(Block::Text(plan.clone()));
+            "Presented plan".to_string()
+        }
+        ActionType::TodoManagement { todos, .. } => {
+            blocks.push(Block::Text(
+                todos
+                    .iter()
+                    .map(|todo| format!("- [{}] {}", todo.status, todo.content))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ));
+            "Updated todos".to_string()
+        }
+        ActionType::Other { description } => {
+            if !content.trim().is_empty() && content != description {
+                blocks.push(Block::Text(content.to_string()));
+            }
+            format!("{tool_name}: {description}")
+        }
+    };
+
+    match status {
+        ToolStatus::Failed => heading.push_str(" (failed)"),
+        ToolStatus::Denied { reason } => {
+            heading.push_str(" (denied)");
+            if let Some(reason) = reason {
+                blocks.push(Block::Text(reason.clone()));
+            }
+        }
+        ToolStatus::TimedOut => heading.push_str(" (approval timed out)"),
+        _ => {}
+    }
+
+    EntryView { heading, blocks }
+}
+
+fn process_title(process: &ProcessTranscript) -> &'static str {
+    match process.run_reason {
+        ExecutionProcessRunReason::SetupScript => "Setup script",
+        ExecutionProcessRunReason::CleanupScript => "Cleanup script",
+        ExecutionProcessRunReason::CodingAgent => "Coding agent",
+        ExecutionProcessRunReason::DevServer => "Dev server",
+        ExecutionProcessRunReason::VerifyScript => "Verify script",
+    }
+}
+
+fn process_status(process: &ProcessTranscript) -> String {
+    let status = match process.status {
+        ExecutionProcessStatus::Running => "running",
+        ExecutionProcessStatus::Completed => "completed",
+        ExecutionProcessStatus::Failed => "failed",
+        ExecutionProcessStatus::Killed => "killed",
+    };
+    match process.exit_code {
+        Some(code) => format!("{status} (exit code {code})"),
+        None => status.to_string(),
+    }
+}
+
+/// Pick a code fence longer than any run of backticks in the content
+fn code_fence(code: &str) -> String {
+    let mut longest = 0;
+    let mut current = 0;
+    for c in code.chars() {
+        if c == '`' {
+            current += 1;
+            longest = longest.max(current);
+        } else {
+            current = 0;
+        }
+    }
+    "`".repeat(longest.max(2) + 1)
+}
+
+fn push_code_markdown(out: &mut String, language: &str, code: &str) {
+    let fence = code_fence(code);
+    let _ = writeln!(
+        out,
+        "{fence}{language}\n{}\n{fence}\n",
+        code.trim_end_matches('\n')
+    );
+}
+
+pub fn render_markdown(transcript: &SessionTranscript) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# {}\n", transcript.task_title);
+    let _ = writeln!(out, "- Branch: `{}`", transcript.workspace_branch);
+    if let Some(executor) = &transcript.executor {
+        let _ = writeln!(out, "- Executor: {executor}");
+    }
+    let _ = writeln!(out, "- Session: `{}`", transcript.session_id);
+    let _ = writeln!(out, "- Started: {}\n", transcript.created_at.to_rfc3339());
+    if let Some(description) = transcript
+        .task_description
+        .as_deref()
+        .filter(|d| !d.trim().is_empty())
+    {
+        let _ = writeln!(out, "{description}\n");
+    }
+
+    for process in &transcript.processes {
+        let _ = writeln!(
+            out,
+            "## {} — {}\n",
+            process_title(process),
+            process_status(process)
+        );
+        if let Some(output) = &process.output {
+            push_code_markdown(&mut out, "", output);
+        }
+        let Some(conversation) = &process.conversation else {
+            continue;
+        };
+        for view in conversation.entries.iter().filter_map(entry_view) {
+            let _ = writeln!(out, "### {}\n", view.heading);
+            for block in view.blocks {
+                match block {
+                    Block::Text(text) => {
+                        let _ = writeln!(out, "{}\n", text.trim_end());
+                    }
+                    Block::Code { language, code } => push_code_markdown(&mut out, language, &code),
+                }
+            }
+        }
+    }
+
+    if !transcript.diffs.is_empty() {
+        let _ = writeln!(out, "## Changes\n");
+        for repo in &transcript.diffs {
+            let _ = writeln!(
+                out,
+                "### {} ({}..{})\n",
+                repo.repo_name,
+                short_sha(&repo.from_commit),
+                short_sha(&repo.to_commit)
+            );
+            for file in &repo.files {
+                match &file.unified_diff {
+                    Some(diff) => push_code_markdown(&mut out, "diff", diff),
+                    None => {
+                        let _ = writeln!(out, "`{}`: diff omitted (file too large)\n", file.path);
+                    }
+                }
+            }
+        }
+    }
+
+    out
+}
+
+fn short_sha(sha: &str) -> &str {
+    sha.get(..8).unwrap_or(sha)
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn push_code_html(out: &mut String, language: &str, code: &str) {
+    if language == "diff" {
+        out.push_str("<pre class=\"diff\">");
+        for line in code.lines() {
+            let class = match line.chars().next() {
+                Some('+') if !line.starts_with("+++") => "add",
+                Some('-') if !line.starts_with("---") => "del",
+                Some('@') => "hunk",
+                _ => "ctx",
+            };
+            let _ = writeln!(out, "<span class=\"{class}\">{}</span>", escape_html(line));
+        }
+        out.push_str("</pre>\n");
+    } else {
+        let _ = writeln!(
+            out,
+            "<pre>{}</pre>",
+            escape_html(code.trim_end_matches('\n'))
+        );
+    }
+}
+
+const HTML_STYLE: &str = "body{font-family:system-ui,sans-serif;max-width:960px;margin:2rem auto;padding:0 1rem;color:#1f2328}\
+pre{background:#f6f8fa;padding:.75rem;overflow-x:auto;white-space:pre-wrap;word-break:break-word}\
+.entry{border-left:3px solid #d0d7de;padding-left:.75rem;margin:1rem 0}\
+.entry h3{font-size:.9rem;margin:0 0 .5rem;color:#57606a}\
+.text{white-space:pre-wrap}\
+.diff span{display:block}.add{background:#dafbe1}.del{background:#ffebe9}.hunk{color:#8250df}\
+.meta{color:#57606a}";
+
+pub fn render_html(transcript: &SessionTranscript) -> String {
+    let mut out = String::new();
+    let title = escape_html(&transcript.task_title);
+    let _ = writeln!(
+        out,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>{HTML_STYLE}</style>\n</head>\n<body>"
+    );
+    let _ = writeln!(out, "<h1>{title}</h1>");
+    let _ = write!(
+        out,
+        "<p class=\"meta\">Branch <code>{}</code>",
+        escape_html(&transcript.workspace_branch)
+    );
+    if let Some(executor) = &transcript.executor {
+        let _ = write!(out, " · {}", escape_html(executor));
+    }
+    let _ = writeln!(
+        out,
+        " · Session <code>{}</code> · {}</p>",
+        transcript.session_id,
+        transcript.created_at.to_rfc3339()
+    );
+    if let Some(description) = transcript
+        .task_description
+        .as_deref()
+        .filter(|d| !d.trim().is_empty())
+    {
+        let _ = writeln!(
+            out,
+            "<div class=\"text\">{}</div>",
+            escape_html(description)
+        );
+    }
+
+    for process in &transcript.processes {
+        let _ = writeln!(
+            out,
+            "<h2>{} — {}</h2>",
+            process_title(process),
+            escape_html(&process_status(process))
+        );
+        if let Some(output) = &process.output {
+            push_code_html(&mut out, "", output);
+        }
+        let Some(conversation) = &process.conversation else {
+            continue;
+        };
+        for view in conversation.entries.iter().filter_map(entry_view) {
+            let _ = writeln!(
+                out,
+                "<div class=\"entry\">\n<h3>{}</h3>",
+                escape_html(&view.heading)
+            );
+            for block in view.blocks {
+                match block {
+                    Block::Text(text) => {
+                        let _ = writeln!(
+                            out,
+                            "<div class=\"text\">{}</div>",
+                            escape_html(text.trim_end())
+                        );
+                    }
+                    Block::Code { language, code } => push_code_html(&mut out, language, &code),
+                }
+            }
+            out.push_str("</div>\n");
+        }
+    }
+
+    if !transcript.diffs.is_empty() {
+        out.push_str("<h2>Changes</h2>\n");
+        for repo in &transcript.diffs {
+            let _ = writeln!(
+                out,
+                "<h3>{} <span class=\"meta\">{}..{}</span></h3>",
+                escape_html(&repo.repo_name),
+                short_sha(&repo.from_commit),
+                short_sha(&repo.to_commit)
+            );
+            for file in &repo.files {
+                match &file.unified_diff {
+                    Some(diff) => push_code_html(&mut out, "diff", diff),
+                    None => {
+                        let _ = writeln!(
+                            out,
+                            "<p><code>{}</code>: diff omitted (file too large)</p>",
+                            escape_html(&file.path)
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+/// Render a transcript in the requested format
+pub fn render(transcript: &SessionTranscript, format: SessionExportFormat) -> String {
+    match format {
+        SessionExportFormat::Markdown => render_markdown(transcript),
+        SessionExportFormat::Html => render_html(transcript),
+        SessionExportFormat::Json => serde_json::to_string_pretty(transcript).unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::logs::{CommandRunResult, utils::patch::ConversationPatch};
+
+    use super::*;
+
+    fn entry(entry_type: NormalizedEntryType, content: &str) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type,
+            content: content.to_string(),
+            metadata: None,
+        }
+    }
+
+    fn transcript(entries: Vec<NormalizedEntry>) -> SessionTranscript {
+        SessionTranscript {
+            session_id: Uuid::nil(),
+            workspace_id: Uuid::nil(),
+            workspace_branch: "vk/1234-fix-login".to_string(),
+            task_id: Uuid::nil(),
+            task_title: "Fix <login>".to_string(),
+            task_description: None,
+            executor: Some("CLAUDE_CODE".to_string()),
+            created_at: DateTime::<Utc>::default(),
+            processes: vec![ProcessTranscript {
+                id: Uuid::nil(),
+                run_reason: ExecutionProcessRunReason::CodingAgent,
+                status: ExecutionProcessStatus::Completed,
+                exit_code: Some(0),
+                started_at: DateTime::<Utc>::default(),
+                completed_at: None,
+                conversation: Some(NormalizedConversation {
+                    entries,
+                    session_id: None,
+                    executor_type: "CLAUDE_CODE".to_string(),
+                    prompt: None,
+                    summary: None,
+                }),
+                output: None,
+            }],
+            diffs: vec![RepoDiffTranscript {
+                repo_name: "app".to_string(),
+                from_commit: "0123456789abcdef".to_string(),
+                to_commit: "fedcba9876543210".to_string(),
+                files: vec![FileDiffTranscript {
+                    path: "src/login.rs".to_string(),
+                    change: DiffChangeKind::Modified,
+                    unified_diff: Some(create_unified_diff("src/login.rs", "a\n", "b\n")),
+                }],
+            }],
+        }
+    }
+
+    #[test]
+    fn test_entries_from_patches_applies_replace_and_remove() {
+        let messages = vec![
+            LogMsg::JsonPatch(ConversationPatch::add_normalized_entry(
+                0,
+                entry(NormalizedEntryType::UserMessage, "fix it"),
+            )),
+            LogMsg::JsonPatch(ConversationPatch::add_normalized_entry(
+                1,
+                entry(NormalizedEntryType::Loading, ""),
+            )),
+            LogMsg::JsonPatch(ConversationPatch::add_normalized_entry(
+                2,
+                entry(NormalizedEntryType::AssistantMessage, "Look"),
+            )),
+            LogMsg::JsonPatch(ConversationPatch::replace(
+                2,
+                entry(NormalizedEntryType::AssistantMessage, "Looking"),
+            )),
+            LogMsg::JsonPatch(ConversationPatch::remove(1)),
+            LogMsg::Stdout("raw".to_string()),
+        ];
+
+        let contents: Vec<String> = entries_from_patches(&messages)
+            .into_iter()
+            .map(|entry| entry.content)
+            .collect();
+        assert_eq!(contents, vec!["fix it", "Looking"]);
+    }
+
+    #[test]
+    fn test_markdown_fences_outlast_backticks_in_content() {
+        let command = entry(
+            NormalizedEntryType::ToolUse {
+                tool_name: "Bash".to_string(),
+                action_type: ActionType::CommandRun {
+                    command: "echo '```'".to_string(),
+                    result: Some(CommandRunResult {
+                        exit_status: Some(CommandExitStatus::ExitCode { code: 1 }),
+                        output: Some("```\n".to_string()),
+                    }),
+                },
+                status: ToolStatus::Failed,
+            },
+            "echo",
+        );
+        let markdown = render_markdown(&transcript(vec![
+            entry(NormalizedEntryType::UserMessage, "Fix the login"),
+            command,
+            entry(NormalizedEntryType::Loading, ""),
+        ]));
+
+        assert!(markdown.starts_with("# Fix <login>\n"));
+        assert!(markdown.contains("### User\n\nFix the login\n"));
+        assert!(markdown.contains("### Ran command (failed)\n\n````sh\necho '```'\n````\n"));
+        assert!(markdown.contains("Exit code 1"));
+        assert!(markdown.contains("### app (01234567..fedcba98)"));
+        assert!(markdown.contains("```diff\n"));
+        assert!(!markdown.contains("Loading"));
+    }
+
+    #[test]
+    fn test_html_is_escaped_and_standalone() {
+        let html = render_html(&transcript(vec![entry(
+            NormalizedEntryType::AssistantMessage,
+            "<script>alert(1)</script>",
+        )]));
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>Fix &lt;login&gt;</title>"));
+        assert!(html.contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("<span class=\"add\">+b</span>"));
+        assert!(html.trim_end().ends_with("</html>"));
+    }
+}
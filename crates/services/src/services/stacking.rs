@@ -0,0 +1,444 @@
+//! Workspaces stacked on another workspace's branch. A stacked workspace's repos target the
+//! base workspace's branch until the base is merged, at which point the stacked workspace is
+//! moved onto whatever the base targeted.
+
+use std::path::{Path, PathBuf};
+
+use db::models::{
+    execution_process::ExecutionProcess,
+    merge::{Merge, MergeStatus},
+    repo::Repo,
+    workspace::Workspace,
+    workspace_repo::WorkspaceRepo,
+};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::services::{
+    git::{GitService, GitServiceError},
+    git_host::{GitHostError, GitHostProvider, GitHostService},
+};
+
+#[derive(Debug, Error)]
+pub enum StackError {
+    #[error(transparent)]
+    Sqlx(#[from] sqlx::Error),
+    #[error(transparent)]
+    GitService(#[from] GitServiceError),
+    #[error(transparent)]
+    GitHost(#[from] GitHostError),
+}
+
+/// Branch name a PR uses as its base for a target branch, without any remote prefix
+fn pr_base_branch(git: &GitService, repo_path: &Path, target_branch: &str) -> String {
+    match git.get_remote_name_from_branch_name(repo_path, target_branch) {
+        Ok(remote) => target_branch
+            .strip_prefix(&format!("{remote}/"))
+            .unwrap_or(target_branch)
+            .to_string(),
+        Err(_) => target_branch.to_string(),
+    }
+}
+
+/// After `base` was merged in `repo_id`, move the workspaces stacked on it onto the branch
+/// `base` targeted in that repo: rebase their own commits onto it, retarget the repo and any
+/// open PR, and once no repo targets `base` any more, stack them on `base`'s own base.
+pub async fn restack_after_merge(
+    pool: &SqlitePool,
+    git: &GitService,
+    base: &Workspace,
+    repo_id: Uuid,
+) -> Result<(), StackError> {
+    let stacked = Workspace::find_stacked_on(pool, base.id).await?;
+    if stacked.is_empty() {
+        return Ok(());
+    }
+    let Some(base_repo) =
+        WorkspaceRepo::find_by_workspace_and_repo_id(pool, base.id, repo_id).await?
+    else {
+        return Ok(());
+    };
+    let Some(repo) = Repo::find_by_id(pool, repo_id).await? else {
+        return Ok(());
+    };
+    let new_target = base_repo.target_branch;
+
+    for workspace in stacked {
+        let Some(workspace_repo) =
+            WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, repo_id).await?
+        else {
+            continue;
+        };
+        if workspace_repo.target_branch != base.branch {
+            continue;
+        }
+
+        let rebased_worktree =
+            match rebase_off_base(pool, git, &repo, &workspace, base, &new_target).await {
+                Ok(worktree_path) => worktree_path,
+                Err(e) => {
+                    warn!(
+                        "Failed to restack workspace {} onto {}: {}",
+                        workspace.id, new_target, e
+                    );
+                    None
+                }
+            };
+        WorkspaceRepo::update_target_branch(pool, workspace.id, repo_id, &new_target).await?;
+        if let Err(e) = retarget_open_prs(
+            pool,
+            git,
+            &repo,
+            &workspace,
+            &new_target,
+            rebased_worktree.as_deref(),
+        )
+        .await
+        {
+            warn!(
+                "Failed to retarget PRs of workspace {} onto {}: {}",
+                workspace.id, new_target, e
+            );
+        }
+
+        let still_on_base = WorkspaceRepo::find_by_workspace_id(pool, workspace.id)
+            .await?
+            .iter()
+            .any(|wr| wr.target_branch == base.branch);
+        if !still_on_base {
+            Workspace::set_base_workspace(pool, workspace.id, base.base_workspace_id).await?;
+        }
+        info!(
+            "Restacked workspace {} from merged workspace {} onto {}",
+            workspace.id, base.id, new_target
+        );
+    }
+    Ok(())
+}
+
+/// Replay the workspace's own commits, i.e. those not on the base workspace's branch, onto
+/// `new_target` as it is on its remote. Busy workspaces and ones without a worktree are only
+/// retargeted. Returns the worktree when it was rebased.
+async fn rebase_off_base(
+    pool: &SqlitePool,
+    git: &GitService,
+    repo: &Repo,
+    workspace: &Workspace,
+    base: &Workspace,
+    new_target: &str,
+) -> Result<Option<PathBuf>, StackError> {
+    let Some(container_ref) = workspace.container_ref.as_deref() else {
+        return Ok(None);
+    };
+    let worktree_path = PathBuf::from(container_ref).join(&repo.name);
+    if !worktree_path.exists()
+        || ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, workspace.id)
+            .await?
+    {
+        return Ok(None);
+    }
+
+    git.fetch_remote_branch(&repo.path, new_target)?;
+    git.rebase_branch(
+        &repo.path,
+        &worktree_path,
+        new_target,
+        &base.branch,
+        &workspace.branch,
+    )?;
+    Ok(Some(worktree_path))
+}
+
+/// Point the workspace's open PRs at `new_target`. A rebased branch is force-pushed first, so
+/// the PRs never show the merged base's commits against their new base.
+async fn retarget_open_prs(
+    pool: &SqlitePool,
+    git: &GitService,
+    repo: &Repo,
+    workspace: &Workspace,
+    new_target: &str,
+    rebased_worktree: Option<&Path>,
+) -> Result<(), StackError> {
+    let open_prs = Merge::find_by_workspace_and_repo_id(pool, workspace.id, repo.id)
+        .await?
+        .into_iter()
+        .filter_map(|merge| match merge {
+            Merge::Pr(pr) if matches!(pr.pr_info.status, MergeStatus::Open) => Some(pr),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    if open_prs.is_empty() {
+        return Ok(());
+    }
+    if let Some(worktree_path) = rebased_worktree {
+        git.push_to_remote(worktree_path, &workspace.branch, true)?;
+    }
+
+    let base_branch = pr_base_branch(git, &repo.path, new_target);
+    let remote_url = git.get_remote_url(
+        &repo.path,
+        &git.resolve_remote_name_for_branch(&repo.path, new_target)?,
+    )?;
+    for pr in open_prs {
+        let git_host = GitHostService::for_repo(repo, &pr.pr_info.url)?;
+        git_host
+            .update_pr_base(&repo.path, &remote_url, pr.pr_info.number, &base_branch)
+            .await?;
+        Merge::update_target_branch_name(pool, pr.id, &base_branch).await?;
+        info!(
+            "Retargeted PR #{} of workspace {} onto {}",
+            pr.pr_info.number, workspace.id, base_branch
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use db::{
+        models::{
+            execution_process::{
+                CreateFinishedExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus,
+            },
+            project::{CreateProject, Project},
+            session::{CreateSession, Session},
+            task::{CreateTask, Task},
+            workspace::CreateWorkspace,
+            workspace_repo::CreateWorkspaceRepo,
+        },
+        test_utils::memory_pool,
+    };
+    use git2::Repository;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::services::git::GitCli;
+
+    fn git(dir: &Path, args: &[&str]) {
+        GitCli::new().git(dir, args).unwrap();
+    }
+
+    fn commit_file(dir: &Path, name: &str) {
+        std::fs::write(dir.join(name), name).unwrap();
+        git(dir, &["add", name]);
+        git(dir, &["commit", "-q", "-m", name]);
+    }
+
+    fn branch_tip(repo_path: &Path, branch: &str) -> git2::Oid {
+        Repository::open(repo_path)
+            .unwrap()
+            .revparse_single(branch)
+            .unwrap()
+            .peel_to_commit()
+            .unwrap()
+            .id()
+    }
+
+    async fn target_branch(pool: &SqlitePool, workspace: &Workspace, repo: &Repo) -> String {
+        WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, repo.id)
+            .await
+            .unwrap()
+            .unwrap()
+            .target_branch
+    }
+
+    /// A workspace on `branch`, checked out in its own worktree, whose repo targets `target`
+    async fn add_workspace(
+        pool: &SqlitePool,
+        root: &Path,
+        task: &Task,
+        repo: &Repo,
+        branch: &str,
+        target: &str,
+        base: Option<&Workspace>,
+    ) -> Workspace {
+        let workspace = Workspace::create(
+            pool,
+            &CreateWorkspace {
+                branch: branch.to_string(),
+                agent_working_dir: None,
+                base_workspace_id: base.map(|base| base.id),
+            },
+            Uuid::new_v4(),
+            task.id,
+        )
+        .await
+        .unwrap();
+        WorkspaceRepo::create_many(
+            pool,
+            workspace.id,
+            &[CreateWorkspaceRepo {
+                repo_id: repo.id,
+                target_branch: target.to_string(),
+            }],
+        )
+        .await
+        .unwrap();
+
+        let container = root.join(workspace.id.to_string());
+        let worktree = container.join(&repo.name);
+        git(
+            &repo.path,
+            &["worktree", "add", "-q", worktree.to_str().unwrap(), branch],
+        );
+        Workspace::update_container_ref(pool, workspace.id, container.to_str().unwrap())
+            .await
+            .unwrap();
+        Workspace::find_by_id(pool, workspace.id)
+            .await
+            .unwrap()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_restack_after_merge() {
+        let pool = memory_pool().await;
+        let root = TempDir::new().unwrap();
+        let git_service = GitService::new();
+
+        // main <- vk/base <- vk/child, vk/busy and vk/loose
+        let repo_path = root.path().join("repo");
+        git_service
+            .initialize_repo_with_main_branch(&repo_path)
+            .unwrap();
+        git(&repo_path, &["config", "user.name", "Test User"]);
+        git(&repo_path, &["config", "user.email", "test@example.com"]);
+        git(&repo_path, &["checkout", "-q", "-b", "vk/base"]);
+        commit_file(&repo_path, "base.txt");
+        for branch in ["vk/child", "vk/busy", "vk/loose"] {
+            git(&repo_path, &["checkout", "-q", "-b", branch, "vk/base"]);
+            commit_file(&repo_path, &format!("{}.txt", &branch[3..]));
+        }
+        git(&repo_path, &["checkout", "-q", "main"]);
+
+        let project = Project::create(
+            &pool,
+            &CreateProject {
+                name: "Shop".to_string(),
+                repositories: Vec::new(),
+            },
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap();
+        let task = Task::create(
+            &pool,
+            &CreateTask::from_title_description(project.id, "Checkout".to_string(), None),
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap();
+        let repo = Repo::find_or_create(&pool, &repo_path, "Repo")
+            .await
+            .unwrap();
+
+        let base = Workspace::create(
+            &pool,
+            &CreateWorkspace {
+                branch: "vk/base".to_string(),
+                agent_working_dir: None,
+                base_workspace_id: None,
+            },
+            Uuid::new_v4(),
+            task.id,
+        )
+        .await
+        .unwrap();
+        WorkspaceRepo::create_many(
+            &pool,
+            base.id,
+            &[CreateWorkspaceRepo {
+                repo_id: repo.id,
+                target_branch: "main".to_string(),
+            }],
+        )
+        .await
+        .unwrap();
+        let root_path = root.path();
+        let child = add_workspace(
+            &pool,
+            root_path,
+            &task,
+            &repo,
+            "vk/child",
+            "vk/base",
+            Some(&base),
+        )
+        .await;
+        let busy = add_workspace(
+            &pool,
+            root_path,
+            &task,
+            &repo,
+            "vk/busy",
+            "vk/base",
+            Some(&base),
+        )
+        .await;
+        // Targets the base branch without being stacked on the workspace
+        let loose =
+            add_workspace(&pool, root_path, &task, &repo, "vk/loose", "vk/base", None).await;
+
+        let session = Session::create(
+            &pool,
+            &CreateSession { executor: None },
+            Uuid::new_v4(),
+            busy.id,
+        )
+        .await
+        .unwrap();
+        ExecutionProcess::create_finished(
+            &pool,
+            &CreateFinishedExecutionProcess {
+                session_id: session.id,
+                run_reason: ExecutionProcessRunReason::CodingAgent,
+                executor_action: json!({ "typ": { "type": "ScriptRequest", "script": "true" } }),
+                status: ExecutionProcessStatus::Running,
+                exit_code: None,
+                started_at: Utc::now(),
+                completed_at: None,
+            },
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap();
+
+        // Squash-merge the base workspace into main
+        git(&repo_path, &["merge", "-q", "--squash", "vk/base"]);
+        git(&repo_path, &["commit", "-q", "-m", "Merge base"]);
+        let busy_tip = branch_tip(&repo_path, "vk/busy");
+        let loose_tip = branch_tip(&repo_path, "vk/loose");
+
+        restack_after_merge(&pool, &git_service, &base, repo.id)
+            .await
+            .unwrap();
+
+        // The idle stacked workspace is replayed onto main and no longer stacked
+        let repository = Repository::open(&repo_path).unwrap();
+        let child_tip = repository
+            .find_commit(branch_tip(&repo_path, "vk/child"))
+            .unwrap();
+        assert_eq!(
+            child_tip.parent_id(0).unwrap(),
+            branch_tip(&repo_path, "main")
+        );
+        assert_eq!(target_branch(&pool, &child, &repo).await, "main");
+        let child = Workspace::find_by_id(&pool, child.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(child.base_workspace_id, None);
+
+        // The busy one is retargeted, but its branch is left alone
+        assert_eq!(branch_tip(&repo_path, "vk/busy"), busy_tip);
+        assert_eq!(target_branch(&pool, &busy, &repo).await, "main");
+
+        // Workspaces not stacked on the merged one are not touched
+        assert_eq!(branch_tip(&repo_path, "vk/loose"), loose_tip);
+        assert_eq!(target_branch(&pool, &loose, &repo).await, "vk/base");
+    }
+}
@@ -0,0 +1,219 @@
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use db::models::{
+    execution_process::ExecutionProcess,
+    project_repo::ProjectRepo,
+    task::{Task, TaskStatus, TaskWithAttemptStatus},
+    task_dependency::TaskDependency,
+    workspace::{Workspace, WorkspaceError},
+    workspace_repo::{CreateWorkspaceRepo, WorkspaceRepo},
+};
+use executors::profile::ExecutorProfileId;
+use futures::StreamExt;
+use json_patch::PatchOperation;
+use sqlx::error::Error as SqlxError;
+use thiserror::Error;
+use tokio::{sync::RwLock, time::interval};
+use tokio_stream::wrappers::BroadcastStream;
+use tracing::{debug, error, info, warn};
+use utils::{log_msg::LogMsg, msg_store::MsgStore};
+use uuid::Uuid;
+
+use crate::services::{
+    config::Config,
+    container::{ContainerError, ContainerService},
+};
+
+#[derive(Debug, Error)]
+enum TaskDependencyServiceError {
+    #[error(transparent)]
+    Container(#[from] ContainerError),
+    #[error(transparent)]
+    WorkspaceError(#[from] WorkspaceError),
+    #[error(transparent)]
+    Sqlx(#[from] SqlxError),
+}
+
+/// Service that starts tasks once every task they depend on is done
+pub struct TaskDependencyService<C> {
+    container: C,
+    config: Arc<RwLock<Config>>,
+    /// Database events, watched for tasks moving to done
+    events: Arc<MsgStore>,
+    /// Backstop for events that were missed
+    poll_interval: Duration,
+    /// Unblocked tasks already reported as having no repositories, so each is only reported once
+    skipped_without_repos: Mutex<HashSet<Uuid>>,
+}
+
+impl<C> TaskDependencyService<C>
+where
+    C: ContainerService + Send + Sync + 'static,
+{
+    pub fn spawn(
+        container: C,
+        config: Arc<RwLock<Config>>,
+        events: Arc<MsgStore>,
+    ) -> tokio::task::JoinHandle<()> {
+        let service = Self {
+            container,
+            config,
+            events,
+            poll_interval: Duration::from_secs(30),
+            skipped_without_repos: Mutex::new(HashSet::new()),
+        };
+        tokio::spawn(async move {
+            service.start().await;
+        })
+    }
+
+    async fn start(&self) {
+        info!(
+            "Starting task dependency service with interval {:?}",
+            self.poll_interval
+        );
+
+        let mut interval = interval(self.poll_interval);
+        let mut events = BroadcastStream::new(self.events.get_receiver());
+
+        // Tasks are started from this loop only, so a task is never started twice
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {}
+                Some(event) = events.next() => {
+                    // A lagged receiver may have missed a task moving to done
+                    if event.is_ok_and(|msg| !marks_task_done(&msg)) {
+                        continue;
+                    }
+                }
+            }
+            if let Err(e) = self.start_unblocked_tasks().await {
+                error!("Error starting unblocked tasks: {}", e);
+            }
+        }
+    }
+
+    async fn start_unblocked_tasks(&self) -> Result<(), TaskDependencyServiceError> {
+        let tasks = TaskDependency::find_unblocked_tasks(&self.container.db().pool).await?;
+
+        if tasks.is_empty() {
+            debug!("No unblocked tasks to start");
+            return Ok(());
+        }
+
+        info!("Starting {} unblocked tasks", tasks.len());
+
+        for task in tasks {
+            if let Err(e) = self.start_task(&task).await {
+                error!("Error starting unblocked task {}: {}", task.id, e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Start a workspace for the task with the project's default executor profile. Each repo
+    /// targets the branch the most recent blocker was merged into, falling back to the repo's
+    /// current branch.
+    async fn start_task(&self, task: &Task) -> Result<(), TaskDependencyServiceError> {
+        let pool = &self.container.db().pool;
+
+        let mut blocker_targets = Vec::new();
+        for blocker in TaskDependency::find_blocked_by(pool, task.id).await? {
+            if let Some(workspace) = Workspace::fetch_all(pool, Some(blocker.id))
+                .await?
+                .into_iter()
+                .next()
+            {
+                blocker_targets
+                    .extend(WorkspaceRepo::find_by_workspace_id(pool, workspace.id).await?);
+            }
+        }
+
+        let mut workspace_repos = Vec::new();
+        for repo in ProjectRepo::find_repos_for_project(pool, task.project_id).await? {
+            let target_branch = match blocker_targets.iter().find(|wr| wr.repo_id == repo.id) {
+                Some(wr) => wr.target_branch.clone(),
+                None => match self.container.git().get_current_branch(&repo.path) {
+                    Ok(branch) => branch,
+                    Err(e) => {
+                        warn!(
+                            "Skipping repo {} for task {}: failed to resolve branch: {}",
+                            repo.name, task.id, e
+                        );
+                        continue;
+                    }
+                },
+            };
+            workspace_repos.push(CreateWorkspaceRepo {
+                repo_id: repo.id,
+                target_branch,
+            });
+        }
+
+        if workspace_repos.is_empty() {
+            if self.skipped_without_repos.lock().unwrap().insert(task.id) {
+                warn!(
+                    "Task {} has no repositories to start a workspace in",
+                    task.id
+                );
+            }
+            return Ok(());
+        }
+        self.skipped_without_repos.lock().unwrap().remove(&task.id);
+
+        let executor_profile_id = self.default_executor_profile(task).await;
+        let workspace = self
+            .container
+            .create_workspace_for_task(task, &workspace_repos, None)
+            .await?;
+        self.container
+            .start_workspace(&workspace, executor_profile_id)
+            .await?;
+
+        info!(
+            "Started workspace {} for unblocked task {}",
+            workspace.id, task.id
+        );
+        Ok(())
+    }
+
+    /// The project's default executor profile: the one its latest coding agent run used, as
+    /// preselected when starting an attempt, or the global default
+    async fn default_executor_profile(&self, task: &Task) -> ExecutorProfileId {
+        match ExecutionProcess::latest_executor_profile_for_project(
+            &self.container.db().pool,
+            task.project_id,
+        )
+        .await
+        {
+            Ok(Some(profile)) => return profile,
+            Ok(None) => {}
+            Err(e) => warn!(
+                "Failed to resolve the executor profile of project {}: {}",
+                task.project_id, e
+            ),
+        }
+        self.config.read().await.executor_profile.clone()
+    }
+}
+
+/// Whether a database event shows a task that is done
+fn marks_task_done(msg: &LogMsg) -> bool {
+    let LogMsg::JsonPatch(patch) = msg else {
+        return false;
+    };
+    patch.0.iter().any(|op| {
+        let value = match op {
+            PatchOperation::Add(add) => &add.value,
+            PatchOperation::Replace(replace) => &replace.value,
+            _ => return false,
+        };
+        op.path().starts_with("/tasks/")
+            && serde_json::from_value::<TaskWithAttemptStatus>(value.clone())
+                .is_ok_and(|task| task.status == TaskStatus::Done)
+    })
+}
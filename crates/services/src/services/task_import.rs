@@ -0,0 +1,401 @@
+//! Importing tasks from git host issues, Markdown checklists and CSV files, and closing the
+//! issues tasks were imported from once a PR of the task merges.
+
+use std::{collections::HashSet, str::FromStr};
+
+use db::models::{
+    repo::Repo,
+    task::{CreateTask, Task, TaskStatus},
+    task_issue_link::{CreateTaskIssueLink, TaskIssueLink},
+};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::services::{
+    git::{GitService, GitServiceError},
+    git_host::{GitHostError, GitHostProvider, GitHostService, IssueFilter},
+};
+
+#[derive(Debug, Error)]
+pub enum TaskImportError {
+    #[error(transparent)]
+    GitService(#[from] GitServiceError),
+    #[error(transparent)]
+    GitHost(#[from] GitHostError),
+    #[error(transparent)]
+    Sqlx(#[from] sqlx::Error),
+    #[error("Invalid CSV: {0}")]
+    InvalidCsv(String),
+}
+
+/// The git host issue an imported task comes from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedIssue {
+    pub repo_id: Uuid,
+    pub remote_url: String,
+    pub number: i64,
+    pub url: String,
+}
+
+/// A task to create, whichever source it was read from
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedTask {
+    pub title: String,
+    pub description: Option<String>,
+    pub status: Option<TaskStatus>,
+    pub issue: Option<ImportedIssue>,
+}
+
+#[derive(Debug)]
+pub struct ImportOutcome {
+    pub created: Vec<Task>,
+    /// Issues that were imported into the project before
+    pub skipped: usize,
+}
+
+/// Open issues of the repo's default remote, as tasks referring back to them
+pub async fn fetch_issues(
+    git: &GitService,
+    repo: &Repo,
+    filter: &IssueFilter,
+) -> Result<Vec<ImportedTask>, TaskImportError> {
+    let remote_url = git.get_default_remote_url(&repo.path)?;
+    let git_host = GitHostService::for_repo(repo, &remote_url)?;
+    let issues = git_host
+        .list_issues(&repo.path, &remote_url, filter)
+        .await?;
+
+    Ok(issues
+        .into_iter()
+        .map(|issue| {
+            let body = issue.body.trim();
+            let description = if body.is_empty() {
+                format!("Imported from {}", issue.url)
+            } else {
+                format!("{body}\n\nImported from {}", issue.url)
+            };
+            ImportedTask {
+                title: issue.title,
+                description: Some(description),
+                status: None,
+                issue: Some(ImportedIssue {
+                    repo_id: repo.id,
+                    remote_url: remote_url.clone(),
+                    number: issue.number,
+                    url: issue.url,
+                }),
+            }
+        })
+        .collect())
+}
+
+/// If `line` is a checklist item (`- [ ] title`, `* [x] title`, `1. [ ] title`), its
+/// indentation, whether it is checked, and its title
+fn checklist_item(line: &str) -> Option<(usize, bool, &str)> {
+    let indent = line.len() - line.trim_start().len();
+    let rest = line.trim_start();
+    let rest = if let Some(rest) = rest.strip_prefix(['-', '*', '+']) {
+        rest
+    } else {
+        let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        if digits == 0 {
+            return None;
+        }
+        rest[digits..].strip_prefix(['.', ')'])?
+    };
+    let rest = rest.strip_prefix(' ')?.trim_start();
+    let (checked, title) = if let Some(title) = rest.strip_prefix("[ ]") {
+        (false, title)
+    } else if let Some(title) = rest
+        .strip_prefix("[x]")
+        .or_else(|| rest.strip_prefix("[X]"))
+    {
+        (true, title)
+    } else {
+        return None;
+    };
+    let title = title.trim();
+    (!title.is_empty()).then_some((indent, checked, title))
+}
+
+/// Tasks from the checklist items of a Markdown document. Lines indented under an item,
+/// including nested items, become its description; checked items are imported as done.
+pub fn parse_markdown_checklist(content: &str) -> Vec<ImportedTask> {
+    let mut tasks = Vec::new();
+    // The item being read, its indentation and the lines under it
+    let mut current: Option<(ImportedTask, usize, Vec<String>)> = None;
+
+    let finish = |current: Option<(ImportedTask, usize, Vec<String>)>,
+                  tasks: &mut Vec<ImportedTask>| {
+        if let Some((mut task, _, lines)) = current {
+            let description = lines.join("\n").trim().to_string();
+            task.description = (!description.is_empty()).then_some(description);
+            tasks.push(task);
+        }
+    };
+
+    for line in content.lines() {
+        let line = line.trim_end();
+        let indent = line.len() - line.trim_start().len();
+        let under_current = current
+            .as_ref()
+            .is_some_and(|(_, item_indent, _)| line.is_empty() || indent > *item_indent);
+        if under_current {
+            if let Some((_, item_indent, lines)) = current.as_mut() {
+                let strip = indent.min(*item_indent + 2);
+                lines.push(line.get(strip..).unwrap_or_default().to_string());
+            }
+            continue;
+        }
+
+        finish(current.take(), &mut tasks);
+        if let Some((indent, checked, title)) = checklist_item(line) {
+            let task = ImportedTask {
+                title: title.to_string(),
+                description: None,
+                status: checked.then_some(TaskStatus::Done),
+                issue: None,
+            };
+            current = Some((task, indent, Vec::new()));
+        }
+    }
+    finish(current, &mut tasks);
+    tasks
+}
+
+/// Records of a CSV document, with quoted fields that may contain commas, quotes (`""`) and
+/// line breaks
+fn csv_records(content: &str) -> Result<Vec<Vec<String>>, TaskImportError> {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, in_quotes) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            ('"', true) => in_quotes = false,
+            ('"', false) if field.is_empty() => in_quotes = true,
+            (',', false) => record.push(std::mem::take(&mut field)),
+            ('\r', false) if chars.peek() == Some(&'\n') => {}
+            ('\n', false) => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            (c, _) => field.push(c),
+        }
+    }
+    if in_quotes {
+        return Err(TaskImportError::InvalidCsv(
+            "A quoted field is not closed".to_string(),
+        ));
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    // Skip blank lines
+    records.retain(|record| record.iter().any(|field| !field.trim().is_empty()));
+    Ok(records)
+}
+
+/// Tasks from a CSV document whose header names a `title` column, and optionally
+/// `description` and `status` columns. Rows without a title are left out.
+pub fn parse_csv(content: &str) -> Result<Vec<ImportedTask>, TaskImportError> {
+    let mut records = csv_records(content)?.into_iter();
+    let Some(header) = records.next() else {
+        return Ok(Vec::new());
+    };
+    let column = |names: &[&str]| {
+        header
+            .iter()
+            .position(|name| names.contains(&name.trim().to_lowercase().as_str()))
+    };
+    let title_column = column(&["title", "name", "summary"]).ok_or_else(|| {
+        TaskImportError::InvalidCsv("The header has no `title` column".to_string())
+    })?;
+    let description_column = column(&["description", "body", "details"]);
+    let status_column = column(&["status", "state"]);
+
+    let mut tasks = Vec::new();
+    for (index, record) in records.enumerate() {
+        let field = |column: Option<usize>| {
+            column
+                .and_then(|column| record.get(column))
+                .map(|value| value.trim())
+                .filter(|value| !value.is_empty())
+        };
+        let Some(title) = field(Some(title_column)) else {
+            continue;
+        };
+        let status = field(status_column)
+            .map(|status| {
+                let normalized: String = status
+                    .chars()
+                    .filter(|c| !matches!(c, ' ' | '_' | '-'))
+                    .collect();
+                TaskStatus::from_str(&normalized.to_lowercase()).map_err(|_| {
+                    TaskImportError::InvalidCsv(format!(
+                        "Unknown status `{status}` on row {}",
+                        index + 2
+                    ))
+                })
+            })
+            .transpose()?;
+        tasks.push(ImportedTask {
+            title: title.to_string(),
+            description: field(description_column).map(str::to_string),
+            status,
+            issue: None,
+        });
+    }
+    Ok(tasks)
+}
+
+/// Create the tasks in the project, linking each to the issue it comes from. Issues already
+/// imported into the project are skipped, so importing again only picks up new issues.
+pub async fn import_tasks(
+    pool: &SqlitePool,
+    project_id: Uuid,
+    tasks: Vec<ImportedTask>,
+) -> Result<ImportOutcome, sqlx::Error> {
+    let mut imported_urls: HashSet<String> =
+        TaskIssueLink::find_issue_urls_by_project_id(pool, project_id)
+            .await?
+            .into_iter()
+            .collect();
+    let mut outcome = ImportOutcome {
+        created: Vec::new(),
+        skipped: 0,
+    };
+
+    for imported in tasks {
+        if let Some(issue) = &imported.issue
+            && !imported_urls.insert(issue.url.clone())
+        {
+            outcome.skipped += 1;
+            continue;
+        }
+        let mut create =
+            CreateTask::from_title_description(project_id, imported.title, imported.description);
+        if imported.status.is_some() {
+            create.status = imported.status;
+        }
+        let task = Task::create(pool, &create, Uuid::new_v4()).await?;
+        if let Some(issue) = imported.issue {
+            TaskIssueLink::create(
+                pool,
+                &CreateTaskIssueLink {
+                    task_id: task.id,
+                    repo_id: issue.repo_id,
+                    remote_url: issue.remote_url,
+                    issue_number: issue.number,
+                    issue_url: issue.url,
+                },
+            )
+            .await?;
+        }
+        outcome.created.push(task);
+    }
+    Ok(outcome)
+}
+
+/// Comment on and close the issue the task was imported from, now that `pr_url` merged.
+/// Returns whether an issue was closed.
+pub async fn close_linked_issue(
+    pool: &SqlitePool,
+    task_id: Uuid,
+    pr_url: &str,
+) -> Result<bool, TaskImportError> {
+    let Some(link) = TaskIssueLink::find_by_task_id(pool, task_id).await? else {
+        return Ok(false);
+    };
+    if link.closed_at.is_some() {
+        return Ok(false);
+    }
+    let Some(repo) = Repo::find_by_id(pool, link.repo_id).await? else {
+        return Ok(false);
+    };
+
+    let git_host = GitHostService::for_repo(&repo, &link.remote_url)?;
+    git_host
+        .close_issue(
+            &repo.path,
+            &link.remote_url,
+            link.issue_number,
+            &format!("Resolved by {pr_url}"),
+        )
+        .await?;
+    TaskIssueLink::mark_closed(pool, link.id).await?;
+    info!("Closed issue {} of task {}", link.issue_url, task_id);
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_markdown_checklist() {
+        let content = "# Backlog\n\
+                       \n\
+                       - [ ] Add export\n  \
+                         Bundle the project as JSON\n  \
+                         - [ ] include tags\n\
+                       * [x] Fix login\n\
+                       Some notes\n\
+                       1. [ ] Write docs\n\
+                       - not a task\n";
+        let tasks = parse_markdown_checklist(content);
+        let summary: Vec<(&str, Option<&str>, Option<TaskStatus>)> = tasks
+            .iter()
+            .map(|t| (t.title.as_str(), t.description.as_deref(), t.status.clone()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    "Add export",
+                    Some("Bundle the project as JSON\n- [ ] include tags"),
+                    None
+                ),
+                ("Fix login", None, Some(TaskStatus::Done)),
+                ("Write docs", None, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let content = "\u{feff}Title,Description,Status\r\n\
+                       Add export,\"Bundle as JSON, with tags\nand images\",in progress\r\n\
+                       ,no title,\r\n\
+                       \"Say \"\"hi\"\"\",,\r\n";
+        let tasks = parse_csv(content).unwrap();
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[0].title, "Add export");
+        assert_eq!(
+            tasks[0].description.as_deref(),
+            Some("Bundle as JSON, with tags\nand images")
+        );
+        assert_eq!(tasks[0].status, Some(TaskStatus::InProgress));
+        assert_eq!(tasks[1].title, "Say \"hi\"");
+        assert_eq!(tasks[1].description, None);
+
+        assert!(matches!(
+            parse_csv("name,status\nA,unknown\n"),
+            Err(TaskImportError::InvalidCsv(_))
+        ));
+        assert!(matches!(
+            parse_csv("description\nA\n"),
+            Err(TaskImportError::InvalidCsv(_))
+        ));
+    }
+}
@@ -0,0 +1,386 @@
+use std::{collections::BTreeSet, str::FromStr, time::Duration};
+
+use chrono::{DateTime, Utc};
+use cron::Schedule;
+use db::models::{
+    task::{CreateTask, Task},
+    task_schedule::TaskSchedule,
+};
+use sqlx::error::Error as SqlxError;
+use thiserror::Error;
+use tokio::time::interval;
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+use crate::services::container::{ContainerError, ContainerService};
+
+/// Upper bound when counting missed fire times, so a per-minute cron left closed for months
+/// doesn't iterate forever
+const MAX_COUNTED_MISSED_RUNS: usize = 10_000;
+
+#[derive(Debug, Error)]
+pub enum ScheduleError {
+    #[error("Invalid cron expression '{0}': {1}")]
+    InvalidCron(String, String),
+    #[error("A schedule needs exactly one of cron_expression or run_at")]
+    InvalidTiming,
+}
+
+#[derive(Debug, Error)]
+enum TaskSchedulerError {
+    #[error(transparent)]
+    Container(#[from] ContainerError),
+    #[error(transparent)]
+    Schedule(#[from] ScheduleError),
+    #[error(transparent)]
+    Sqlx(#[from] SqlxError),
+}
+
+/// Parse a cron expression. Standard 5-field expressions (`min hour dom month dow`) use POSIX
+/// day-of-week numbers (0-7, Sunday is 0 or 7). The 6/7-field form with seconds (and year) is
+/// passed to the `cron` crate as is, where days are numbered 1-7 from Sunday.
+pub fn parse_cron(expression: &str) -> Result<Schedule, ScheduleError> {
+    let invalid = |reason: String| ScheduleError::InvalidCron(expression.to_string(), reason);
+    let fields: Vec<&str> = expression.split_whitespace().collect();
+    let normalized = if let [minute, hour, day_of_month, month, day_of_week] = fields[..] {
+        let day_of_week = posix_day_of_week(day_of_week).map_err(invalid)?;
+        format!("0 {minute} {hour} {day_of_month} {month} {day_of_week}")
+    } else {
+        fields.join(" ")
+    };
+    Schedule::from_str(&normalized).map_err(|e| invalid(e.to_string()))
+}
+
+/// Rewrite a POSIX day-of-week field as the list of days it selects, numbered for the `cron` crate
+fn posix_day_of_week(field: &str) -> Result<String, String> {
+    if field == "*" || field == "?" {
+        return Ok(field.to_string());
+    }
+    let mut days = BTreeSet::new();
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => match step.parse::<usize>() {
+                Ok(step) if step > 0 => (range, step),
+                _ => return Err(format!("Invalid step in day of week '{part}'")),
+            },
+            None => (part, 1),
+        };
+        let (start, end) = match range.split_once('-') {
+            _ if range == "*" => (0, 6),
+            Some((start, end)) => (posix_day(start)?, posix_day(end)?),
+            // `n/step` runs from n to the end of the week
+            None if step > 1 => (posix_day(range)?, 6),
+            None => (posix_day(range)?, posix_day(range)?),
+        };
+        if start > end {
+            return Err(format!("Invalid day of week range '{range}'"));
+        }
+        days.extend((start..=end).step_by(step).map(|day| day % 7 + 1));
+    }
+    Ok(days
+        .iter()
+        .map(|day| day.to_string())
+        .collect::<Vec<_>>()
+        .join(","))
+}
+
+/// POSIX number of a day of week given as 0-7 or a three-letter name
+fn posix_day(day: &str) -> Result<usize, String> {
+    const NAMES: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+    if let Some(index) = NAMES.iter().position(|name| name.eq_ignore_ascii_case(day)) {
+        return Ok(index);
+    }
+    match day.parse::<usize>() {
+        Ok(day) if day <= 7 => Ok(day),
+        _ => Err(format!("Invalid day of week '{day}'")),
+    }
+}
+
+/// Compute the first fire time strictly after `after`. One-off schedules fire at `run_at`
+/// (immediately if it is already in the past) and have no further runs once fired.
+pub fn next_run_at(
+    cron_expression: Option<&str>,
+    run_at: Option<DateTime<Utc>>,
+    after: DateTime<Utc>,
+) -> Result<Option<DateTime<Utc>>, ScheduleError> {
+    match (cron_expression, run_at) {
+        (Some(expression), None) => Ok(parse_cron(expression)?.after(&after).next()),
+        (None, Some(run_at)) => Ok(Some(run_at)),
+        _ => Err(ScheduleError::InvalidTiming),
+    }
+}
+
+/// Next cron fire time after `now`, or `None` for one-off schedules
+fn next_recurring_run(
+    schedule: &TaskSchedule,
+    now: DateTime<Utc>,
+) -> Result<Option<DateTime<Utc>>, ScheduleError> {
+    match schedule.cron_expression.as_deref() {
+        Some(expression) => Ok(parse_cron(expression)?.after(&now).next()),
+        None => Ok(None),
+    }
+}
+
+/// Service that creates and starts tasks from due schedules
+pub struct TaskSchedulerService<C> {
+    container: C,
+    poll_interval: Duration,
+}
+
+impl<C> TaskSchedulerService<C>
+where
+    C: ContainerService + Send + Sync + 'static,
+{
+    pub fn spawn(container: C) -> tokio::task::JoinHandle<()> {
+        let service = Self {
+            container,
+            poll_interval: Duration::from_secs(30),
+        };
+        tokio::spawn(async move {
+            service.start().await;
+        })
+    }
+
+    async fn start(&self) {
+        info!(
+            "Starting task scheduler service with interval {:?}",
+            self.poll_interval
+        );
+
+        // Anything already due at startup came due while the app was closed
+        if let Err(e) = self.handle_missed_runs().await {
+            error!("Error handling missed scheduled runs: {}", e);
+        }
+
+        let mut interval = interval(self.poll_interval);
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.fire_due_schedules().await {
+                error!("Error firing due schedules: {}", e);
+            }
+        }
+    }
+
+    async fn handle_missed_runs(&self) -> Result<(), TaskSchedulerError> {
+        let now = Utc::now();
+        let overdue = TaskSchedule::find_due(&self.container.db().pool, now).await?;
+
+        for schedule in overdue {
+            if let Err(e) = self.handle_missed_schedule(&schedule, now).await {
+                error!(
+                    "Error handling missed runs for schedule {}: {}",
+                    schedule.id, e
+                );
+            }
+        }
+        Ok(())
+    }
+
+    async fn handle_missed_schedule(
+        &self,
+        schedule: &TaskSchedule,
+        now: DateTime<Utc>,
+    ) -> Result<(), TaskSchedulerError> {
+        let Some(first_missed) = schedule.next_run_at else {
+            return Ok(());
+        };
+
+        let missed = match schedule.cron_expression.as_deref() {
+            Some(expression) => {
+                // `first_missed` itself plus every later fire time up to now
+                let cron = parse_cron(expression)?;
+                1 + cron
+                    .after(&first_missed)
+                    .take_while(|t| *t <= now)
+                    .take(MAX_COUNTED_MISSED_RUNS)
+                    .count()
+            }
+            None => 1,
+        };
+
+        warn!(
+            "Schedule {} ('{}') missed {} run(s) while the app was closed",
+            schedule.id, schedule.title, missed
+        );
+        let action = if schedule.catch_up_missed {
+            "Running it once now."
+        } else {
+            "Skipping to the next run."
+        };
+        self.container
+            .notification_service()
+            .notify(
+                &format!("Missed Scheduled Task: {}", schedule.title),
+                &format!(
+                    "'{}' missed {} run(s) while Vibe Kanban was closed. {}",
+                    schedule.title, missed, action
+                ),
+            )
+            .await;
+
+        let pool = &self.container.db().pool;
+        if schedule.catch_up_missed {
+            TaskSchedule::record_missed(pool, schedule.id, missed as i64 - 1, schedule.next_run_at)
+                .await?;
+            self.fire(schedule, now).await
+        } else {
+            let next = next_recurring_run(schedule, now)?;
+            TaskSchedule::record_missed(pool, schedule.id, missed as i64, next).await?;
+            Ok(())
+        }
+    }
+
+    async fn fire_due_schedules(&self) -> Result<(), TaskSchedulerError> {
+        let now = Utc::now();
+        let due = TaskSchedule::find_due(&self.container.db().pool, now).await?;
+
+        if due.is_empty() {
+            debug!("No scheduled tasks due");
+            return Ok(());
+        }
+
+        info!("Firing {} scheduled tasks", due.len());
+
+        for schedule in due {
+            if let Err(e) = self.fire(&schedule, now).await {
+                error!("Error firing schedule {}: {}", schedule.id, e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Create a task from the schedule's template and start a workspace for it
+    async fn fire(
+        &self,
+        schedule: &TaskSchedule,
+        now: DateTime<Utc>,
+    ) -> Result<(), TaskSchedulerError> {
+        let pool = &self.container.db().pool;
+
+        let next = next_recurring_run(schedule, now)?;
+
+        let task = Task::create(
+            pool,
+            &CreateTask::from_title_description(
+                schedule.project_id,
+                schedule.title.clone(),
+                schedule.description.clone(),
+            ),
+            Uuid::new_v4(),
+        )
+        .await?;
+
+        // Record the run before starting so a failing start doesn't refire every tick
+        TaskSchedule::record_run(pool, schedule.id, task.id, now, next).await?;
+
+        let workspace = self
+            .container
+            .create_workspace_for_task(&task, &schedule.repos.0, None)
+            .await?;
+        self.container
+            .start_workspace(&workspace, schedule.executor_profile_id.0.clone())
+            .await?;
+
+        info!(
+            "Started task {} from schedule {} ('{}')",
+            task.id, schedule.id, schedule.title
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Datelike, TimeZone, Weekday};
+
+    use super::*;
+
+    #[test]
+    fn test_five_field_cron_is_accepted() {
+        let after = Utc.with_ymd_and_hms(2026, 1, 15, 12, 0, 0).unwrap();
+        let next = next_run_at(Some("30 2 * * *"), None, after).unwrap();
+        assert_eq!(
+            next,
+            Some(Utc.with_ymd_and_hms(2026, 1, 16, 2, 30, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_six_field_cron_is_accepted() {
+        let after = Utc.with_ymd_and_hms(2026, 1, 15, 12, 0, 0).unwrap();
+        let next = next_run_at(Some("15 0 13 * * *"), None, after).unwrap();
+        assert_eq!(
+            next,
+            Some(Utc.with_ymd_and_hms(2026, 1, 15, 13, 0, 15).unwrap())
+        );
+    }
+
+    /// Weekdays of the next `count` fire times
+    fn next_weekdays(expression: &str, count: usize) -> Vec<Weekday> {
+        // A Monday
+        let after = Utc.with_ymd_and_hms(2026, 1, 12, 12, 0, 0).unwrap();
+        parse_cron(expression)
+            .unwrap()
+            .after(&after)
+            .take(count)
+            .map(|t| t.weekday())
+            .collect()
+    }
+
+    #[test]
+    fn test_five_field_cron_uses_posix_days_of_week() {
+        assert_eq!(
+            next_weekdays("0 9 * * 1-5", 5),
+            vec![
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri,
+                Weekday::Mon
+            ]
+        );
+        assert_eq!(next_weekdays("0 9 * * 0", 2), vec![Weekday::Sun; 2]);
+        assert_eq!(next_weekdays("0 9 * * 7", 1), vec![Weekday::Sun]);
+        assert_eq!(
+            next_weekdays("0 9 * * 5-7", 3),
+            vec![Weekday::Fri, Weekday::Sat, Weekday::Sun]
+        );
+        assert_eq!(
+            next_weekdays("0 9 * * sun,3", 2),
+            vec![Weekday::Wed, Weekday::Sun]
+        );
+        assert!(parse_cron("0 9 * * 8").is_err());
+    }
+
+    #[test]
+    fn test_invalid_cron_is_rejected() {
+        assert!(matches!(
+            parse_cron("not a cron"),
+            Err(ScheduleError::InvalidCron(_, _))
+        ));
+    }
+
+    #[test]
+    fn test_one_off_fires_at_run_at() {
+        let run_at = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+        let after = Utc.with_ymd_and_hms(2026, 1, 15, 12, 0, 0).unwrap();
+        assert_eq!(
+            next_run_at(None, Some(run_at), after).unwrap(),
+            Some(run_at)
+        );
+    }
+
+    #[test]
+    fn test_timing_must_be_exactly_one_kind() {
+        let now = Utc::now();
+        assert!(matches!(
+            next_run_at(None, None, now),
+            Err(ScheduleError::InvalidTiming)
+        ));
+        assert!(matches!(
+            next_run_at(Some("* * * * *"), Some(now), now),
+            Err(ScheduleError::InvalidTiming)
+        ));
+    }
+}
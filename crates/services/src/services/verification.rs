@@ -0,0 +1,251 @@
+use db::models::execution_process::{
+    ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus,
+};
+use executors::actions::ExecutorActionType;
+
+/// First line of the follow-ups queued after a failed verify script; later runs use it to tell
+/// automatic retries apart from turns the user started
+pub const FOLLOW_UP_HEADER: &str = "Verification failed after your last changes.";
+
+/// Only the tail of the output is sent back; that is where test runners and linters summarize
+const MAX_OUTPUT_LINES: usize = 80;
+const MAX_OUTPUT_CHARS: usize = 6000;
+
+/// Last lines of a verify script's output, short enough to send back to the agent
+pub fn trim_output(output: &str) -> String {
+    let lines: Vec<&str> = output.trim_end().lines().collect();
+    let skipped = lines.len().saturating_sub(MAX_OUTPUT_LINES);
+    let mut tail = lines[skipped..].join("\n");
+
+    if tail.len() > MAX_OUTPUT_CHARS {
+        let mut start = tail.len() - MAX_OUTPUT_CHARS;
+        while !tail.is_char_boundary(start) {
+            start += 1;
+        }
+        tail = tail[start..].to_string();
+    }
+
+    if skipped > 0 || tail.len() < output.trim_end().len() {
+        format!("[... earlier output omitted ...]\n{tail}")
+    } else {
+        tail
+    }
+}
+
+/// Follow-up asking the agent to fix what the verify script of `repo_name` reported
+pub fn follow_up_prompt(
+    repo_name: &str,
+    script: &str,
+    output: &str,
+    attempt: i64,
+    max_retries: i64,
+) -> String {
+    format!(
+        "{FOLLOW_UP_HEADER}\n\n\
+         The verify script of `{repo_name}` (attempt {attempt} of {max_retries}) failed:\n\n\
+         ```\n{script}\n```\n\n\
+         Output:\n\n\
+         ```\n{output}\n```\n\n\
+         Fix the failures above. Do not change the verify script or disable checks to make it pass."
+    )
+}
+
+pub fn is_follow_up_prompt(prompt: &str) -> bool {
+    prompt.starts_with(FOLLOW_UP_HEADER)
+}
+
+/// Whether a verify run failed but still hands over to the scripts chained after it, so the
+/// other repos' verify scripts and the cleanup scripts run whatever its result
+pub fn failed_verify_continues(process: &ExecutionProcess) -> bool {
+    process.run_reason == ExecutionProcessRunReason::VerifyScript
+        && process.status == ExecutionProcessStatus::Failed
+        && process
+            .executor_action()
+            .is_ok_and(|action| action.next_action().is_some())
+}
+
+/// Agent turns since the last one the user started whose verify runs failed, counting the
+/// current one. `processes` are the processes of one session, oldest first.
+pub fn failed_attempts(processes: &[ExecutionProcess]) -> i64 {
+    let mut failed = 0;
+    let mut turn_failed = false;
+    for process in processes.iter().rev() {
+        match process.run_reason {
+            ExecutionProcessRunReason::VerifyScript
+                if process.status == ExecutionProcessStatus::Failed =>
+            {
+                turn_failed = true;
+            }
+            ExecutionProcessRunReason::CodingAgent => {
+                if turn_failed {
+                    failed += 1;
+                    turn_failed = false;
+                }
+                let started_by_verification = process
+                    .executor_action()
+                    .ok()
+                    .and_then(|action| match action.typ() {
+                        ExecutorActionType::CodingAgentInitialRequest(request) => {
+                            Some(request.prompt.as_str())
+                        }
+                        ExecutorActionType::CodingAgentFollowUpRequest(request) => {
+                            Some(request.prompt.as_str())
+                        }
+                        _ => None,
+                    })
+                    .is_some_and(is_follow_up_prompt);
+                if !started_by_verification {
+                    return failed;
+                }
+            }
+            _ => {}
+        }
+    }
+    failed + i64::from(turn_failed)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use db::models::execution_process::ExecutorActionField;
+    use executors::{
+        actions::{
+            ExecutorAction,
+            coding_agent_follow_up::CodingAgentFollowUpRequest,
+            script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
+        },
+        executors::BaseCodingAgent,
+        profile::ExecutorProfileId,
+    };
+    use sqlx::types::Json;
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn process(
+        run_reason: ExecutionProcessRunReason,
+        status: ExecutionProcessStatus,
+        typ: ExecutorActionType,
+    ) -> ExecutionProcess {
+        let now = Utc::now();
+        ExecutionProcess {
+            id: Uuid::new_v4(),
+            session_id: Uuid::nil(),
+            run_reason,
+            executor_action: Json(ExecutorActionField::ExecutorAction(ExecutorAction::new(
+                typ, None,
+            ))),
+            status,
+            exit_code: None,
+            stop_reason: None,
+            dropped: false,
+            started_at: now,
+            completed_at: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn agent_turn(prompt: &str) -> ExecutionProcess {
+        process(
+            ExecutionProcessRunReason::CodingAgent,
+            ExecutionProcessStatus::Completed,
+            ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
+                prompt: prompt.to_string(),
+                session_id: "agent-session".to_string(),
+                executor_profile_id: ExecutorProfileId::new(BaseCodingAgent::ClaudeCode),
+                working_dir: None,
+            }),
+        )
+    }
+
+    fn verify_run(status: ExecutionProcessStatus) -> ExecutionProcess {
+        process(
+            ExecutionProcessRunReason::VerifyScript,
+            status,
+            ExecutorActionType::ScriptRequest(ScriptRequest {
+                script: "cargo test".to_string(),
+                language: ScriptRequestLanguage::Bash,
+                context: ScriptContext::VerifyScript,
+                working_dir: Some("app".to_string()),
+            }),
+        )
+    }
+
+    #[test]
+    fn test_failed_attempts_stop_at_user_turn() {
+        let retry = follow_up_prompt("app", "cargo test", "boom", 1, 2);
+        let processes = vec![
+            agent_turn("Add a login page"),
+            verify_run(ExecutionProcessStatus::Failed),
+            agent_turn("Fix the typo too"),
+            verify_run(ExecutionProcessStatus::Failed),
+            agent_turn(&retry),
+            verify_run(ExecutionProcessStatus::Failed),
+        ];
+        assert_eq!(failed_attempts(&processes), 2);
+        assert_eq!(failed_attempts(&processes[..2]), 1);
+    }
+
+    #[test]
+    fn test_failed_verify_runs_count_once_per_turn() {
+        let processes = vec![
+            agent_turn("Add a login page"),
+            verify_run(ExecutionProcessStatus::Failed),
+            verify_run(ExecutionProcessStatus::Failed),
+            verify_run(ExecutionProcessStatus::Completed),
+        ];
+        assert_eq!(failed_attempts(&processes), 1);
+    }
+
+    #[test]
+    fn test_failed_verify_hands_over_to_later_scripts() {
+        let script = |context: ScriptContext, repo: &str| {
+            ExecutorAction::new(
+                ExecutorActionType::ScriptRequest(ScriptRequest {
+                    script: "true".to_string(),
+                    language: ScriptRequestLanguage::Bash,
+                    context,
+                    working_dir: Some(repo.to_string()),
+                }),
+                None,
+            )
+        };
+        // Verify app, verify api, clean up app: what follows an agent turn in two repos
+        let chain = script(ScriptContext::VerifyScript, "app")
+            .append_action(script(ScriptContext::VerifyScript, "api"))
+            .append_action(script(ScriptContext::CleanupScript, "app"));
+
+        let mut failed = verify_run(ExecutionProcessStatus::Failed);
+        failed.executor_action = Json(ExecutorActionField::ExecutorAction(chain.clone()));
+        assert!(failed_verify_continues(&failed));
+
+        // The last verify run still leads to the cleanup script
+        let remaining = chain.next_action().unwrap().clone();
+        failed.executor_action = Json(ExecutorActionField::ExecutorAction(remaining));
+        assert!(failed_verify_continues(&failed));
+
+        let mut killed = failed.clone();
+        killed.status = ExecutionProcessStatus::Killed;
+        assert!(!failed_verify_continues(&killed));
+        assert!(!failed_verify_continues(&verify_run(
+            ExecutionProcessStatus::Failed
+        )));
+    }
+
+    #[test]
+    fn test_trim_output_keeps_the_tail() {
+        assert_eq!(trim_output("error: boom\n"), "error: boom");
+
+        let output = (0..200)
+            .map(|i| format!("line {i}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let trimmed = trim_output(&output);
+        assert!(trimmed.starts_with("[... earlier output omitted ...]\nline 120\n"));
+        assert!(trimmed.ends_with("line 199"));
+
+        let long_line = "é".repeat(MAX_OUTPUT_CHARS);
+        assert!(trim_output(&long_line).len() <= MAX_OUTPUT_CHARS + 40);
+    }
+}
@@ -1,13 +1,23 @@
-use std::path::{Path, PathBuf};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, LazyLock, Mutex},
+};
 
 use db::models::{repo::Repo, workspace::Workspace as DbWorkspace};
 use sqlx::{Pool, Sqlite};
 use thiserror::Error;
+use tokio::sync::OwnedMutexGuard;
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
 use super::worktree_manager::{WorktreeCleanup, WorktreeError, WorktreeManager};
 
+// Held while a background service rewrites a workspace's branches, so the merge queue and
+// auto-rebase never work on the same workspace at once
+static WORKSPACE_BRANCH_LOCKS: LazyLock<Mutex<HashMap<Uuid, Arc<tokio::sync::Mutex<()>>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
 #[derive(Debug, Clone)]
 pub struct RepoWorkspaceInput {
     pub repo: Repo,
@@ -204,6 +214,24 @@ impl WorkspaceManager {
         Ok(())
     }
 
+    /// Wait for and take the lock on rewriting the workspace's branches
+    pub async fn lock_branches(workspace_id: Uuid) -> OwnedMutexGuard<()> {
+        Self::branch_lock(workspace_id).lock_owned().await
+    }
+
+    /// Take the lock on rewriting the workspace's branches, unless another service holds it
+    pub fn try_lock_branches(workspace_id: Uuid) -> Option<OwnedMutexGuard<()>> {
+        Self::branch_lock(workspace_id).try_lock_owned().ok()
+    }
+
+    fn branch_lock(workspace_id: Uuid) -> Arc<tokio::sync::Mutex<()>> {
+        let mut locks = WORKSPACE_BRANCH_LOCKS.lock().unwrap();
+        locks
+            .entry(workspace_id)
+            .or_insert_with(|| Arc::new(tokio::sync::Mutex::new(())))
+            .clone()
+    }
+
     /// Get the base directory for workspaces (same as worktree base dir)
     pub fn get_workspace_base_dir() -> PathBuf {
         WorktreeManager::get_worktree_base_dir()
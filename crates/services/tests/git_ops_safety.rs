@@ -5,7 +5,10 @@ use std::{
 };
 
 use git2::{PushOptions, Repository, build::CheckoutBuilder};
-use services::services::git::{GitCli, GitCliError, GitService};
+use services::services::{
+    change_selection::{self, FileSelection},
+    git::{ConflictOp, ConflictResolution, GitCli, GitCliError, GitService, GitServiceError},
+};
 use tempfile::TempDir;
 // Avoid direct git CLI usage in tests; exercise GitService instead.
 
@@ -1406,3 +1409,352 @@ fn merge_base_ahead_of_task_should_error() {
         "Merge should error when base branch is ahead of task branch"
     );
 }
+
+#[test]
+fn rebase_conflict_resolved_with_content_and_continued() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_direct_conflict_repo(&td);
+    let g = GitService::new();
+
+    let res = g.rebase_branch(&repo_path, &worktree_path, "main", "main", "feature");
+    assert!(matches!(res, Err(GitServiceError::MergeConflicts(_))));
+
+    let files = g.get_conflict_files(&worktree_path).unwrap();
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].path, "conflict.txt");
+    assert_eq!(files[0].base.as_deref(), Some("base\n"));
+    assert_eq!(files[0].ours.as_deref(), Some("main change\n"));
+    assert_eq!(files[0].theirs.as_deref(), Some("feature change\n"));
+    assert!(!files[0].is_binary);
+
+    g.resolve_conflict(
+        &worktree_path,
+        "conflict.txt",
+        &ConflictResolution::Content {
+            content: "main and feature change\n".to_string(),
+        },
+    )
+    .unwrap();
+    assert!(g.get_conflicted_files(&worktree_path).unwrap().is_empty());
+
+    g.continue_conflicts(&worktree_path).unwrap();
+    assert!(g.detect_conflict_op(&worktree_path).unwrap().is_none());
+    assert_eq!(
+        fs::read_to_string(worktree_path.join("conflict.txt")).unwrap(),
+        "main and feature change\n"
+    );
+
+    // The feature branch now sits on top of main
+    let (ahead, behind) = g.get_branch_status(&repo_path, "feature", "main").unwrap();
+    assert_eq!((ahead, behind), (1, 0));
+}
+
+#[test]
+fn rebase_conflict_resolved_with_theirs() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_direct_conflict_repo(&td);
+    let g = GitService::new();
+
+    let _ = g
+        .rebase_branch(&repo_path, &worktree_path, "main", "main", "feature")
+        .expect_err("rebase should stop on conflicts");
+    assert_eq!(
+        g.detect_conflict_op(&worktree_path).unwrap(),
+        Some(ConflictOp::Rebase)
+    );
+
+    g.resolve_conflict(&worktree_path, "conflict.txt", &ConflictResolution::Theirs)
+        .unwrap();
+    g.continue_conflicts(&worktree_path).unwrap();
+
+    assert!(g.detect_conflict_op(&worktree_path).unwrap().is_none());
+    assert_eq!(
+        fs::read_to_string(worktree_path.join("conflict.txt")).unwrap(),
+        "feature change\n"
+    );
+}
+
+#[test]
+fn fast_forward_moves_base_to_task_branch() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, _worktree_path) = setup_repo_with_worktree(&td);
+    let g = GitService::new();
+    let feature_oid = g.get_branch_oid(&repo_path, "feature").unwrap();
+
+    let merged = g
+        .fast_forward_branch(&repo_path, "feature", "main")
+        .expect("fast-forward should succeed");
+
+    assert_eq!(merged, feature_oid);
+    assert_eq!(g.get_branch_oid(&repo_path, "main").unwrap(), feature_oid);
+}
+
+#[test]
+fn fast_forward_refuses_when_base_has_moved() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, _worktree_path) = setup_repo_with_worktree(&td);
+    let repo = Repository::open(&repo_path).unwrap();
+    checkout_branch(&repo, "main");
+    write_file(&repo_path, "base_ahead.txt", "base ahead\n");
+    commit_all(&repo, "base ahead commit");
+
+    let g = GitService::new();
+    let before_main = g.get_branch_oid(&repo_path, "main").unwrap();
+    let res = g.fast_forward_branch(&repo_path, "feature", "main");
+
+    assert!(matches!(res, Err(GitServiceError::BranchesDiverged(_))));
+    assert_eq!(g.get_branch_oid(&repo_path, "main").unwrap(), before_main);
+}
+
+#[test]
+fn restack_replays_only_child_commits_after_base_squash_merged() {
+    let td = TempDir::new().unwrap();
+    let repo_path = td.path().join("repo");
+    let child_path = td.path().join("wt-child");
+    let g = GitService::new();
+    g.initialize_repo_with_main_branch(&repo_path).unwrap();
+    let repo = Repository::open(&repo_path).unwrap();
+    configure_user(&repo);
+    checkout_branch(&repo, "main");
+    write_file(&repo_path, "common.txt", "base\n");
+    commit_all(&repo, "initial main commit");
+
+    // The base workspace's branch, and a child stacked on top of it
+    create_branch_from_head(&repo, "parent");
+    checkout_branch(&repo, "parent");
+    write_file(&repo_path, "parent.txt", "from parent\n");
+    commit_all(&repo, "parent commit");
+    create_branch_from_head(&repo, "child");
+    g.add_worktree(&repo_path, &child_path, "child", false)
+        .unwrap();
+    let child_repo = Repository::open(&child_path).unwrap();
+    write_file(&child_path, "child.txt", "from child\n");
+    commit_all(&child_repo, "child commit");
+
+    // The base lands on main as a squash commit, so its own commit is not an ancestor of main
+    checkout_branch(&repo, "main");
+    write_file(&repo_path, "parent.txt", "from parent\n");
+    commit_all(&repo, "squashed parent");
+
+    g.rebase_branch(&repo_path, &child_path, "main", "parent", "child")
+        .expect("restack should succeed");
+
+    assert_eq!(
+        g.get_branch_status(&repo_path, "child", "main").unwrap(),
+        (1, 0)
+    );
+    assert_eq!(
+        fs::read_to_string(child_path.join("child.txt")).unwrap(),
+        "from child\n"
+    );
+    assert_eq!(
+        fs::read_to_string(child_path.join("parent.txt")).unwrap(),
+        "from parent\n"
+    );
+}
+
+fn numbered_lines(count: usize) -> String {
+    (1..=count).map(|i| format!("line {i}\n")).collect()
+}
+
+/// A worktree on `feature`, branched from `main`, with uncommitted changes: two separate hunks
+/// in `lines.txt` and a new `added.txt`
+fn setup_selection_repo(root: &TempDir) -> (PathBuf, PathBuf) {
+    let repo_path = root.path().join("repo");
+    let worktree_path = root.path().join("wt-feature");
+    let g = GitService::new();
+    g.initialize_repo_with_main_branch(&repo_path).unwrap();
+    let repo = Repository::open(&repo_path).unwrap();
+    configure_user(&repo);
+    checkout_branch(&repo, "main");
+    write_file(&repo_path, "lines.txt", &numbered_lines(30));
+    commit_all(&repo, "add lines");
+    create_branch_from_head(&repo, "feature");
+    g.add_worktree(&repo_path, &worktree_path, "feature", false)
+        .unwrap();
+
+    let changed = numbered_lines(30)
+        .replace("line 2\n", "line two\n")
+        .replace("line 25\n", "line twenty-five\n");
+    write_file(&worktree_path, "lines.txt", &changed);
+    write_file(&worktree_path, "added.txt", "new file\n");
+    (repo_path, worktree_path)
+}
+
+fn file_at_branch(repo_path: &Path, branch: &str, path: &str) -> Option<String> {
+    let repo = Repository::open(repo_path).unwrap();
+    let tree = repo
+        .find_branch(branch, git2::BranchType::Local)
+        .unwrap()
+        .get()
+        .peel_to_tree()
+        .unwrap();
+    let entry = tree.get_path(Path::new(path)).ok()?;
+    let blob = repo.find_blob(entry.id()).unwrap();
+    Some(String::from_utf8(blob.content().to_vec()).unwrap())
+}
+
+#[test]
+fn selected_hunks_are_committed_onto_checked_out_target() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_selection_repo(&td);
+    let g = GitService::new();
+    let base = g.get_base_commit(&repo_path, "feature", "main").unwrap();
+    let diffs = g
+        .get_diffs(
+            DiffTarget::Worktree {
+                worktree_path: &worktree_path,
+                base_commit: &base,
+            },
+            None,
+        )
+        .unwrap();
+
+    let hunks = change_selection::file_hunks(&diffs);
+    let lines = hunks.iter().find(|f| f.path == "lines.txt").unwrap();
+    assert_eq!(lines.hunks.len(), 2);
+
+    let selection = vec![
+        FileSelection {
+            path: "lines.txt".to_string(),
+            hunks: Some(vec![1]),
+        },
+        FileSelection {
+            path: "added.txt".to_string(),
+            hunks: None,
+        },
+    ];
+    let writes = change_selection::accepted_writes(&worktree_path, &diffs, &selection).unwrap();
+    let head = g
+        .commit_files_to_branch(&repo_path, "main", &writes, "take selected changes")
+        .unwrap();
+
+    assert_eq!(g.get_branch_oid(&repo_path, "main").unwrap(), head);
+    let expected = numbered_lines(30).replace("line 25\n", "line twenty-five\n");
+    assert_eq!(
+        file_at_branch(&repo_path, "main", "lines.txt").as_deref(),
+        Some(expected.as_str())
+    );
+    assert_eq!(
+        file_at_branch(&repo_path, "main", "added.txt").as_deref(),
+        Some("new file\n")
+    );
+    // main is checked out in the repo, so its files follow the branch
+    assert_eq!(
+        fs::read_to_string(repo_path.join("lines.txt")).unwrap(),
+        expected
+    );
+    // The workspace keeps all of its changes
+    assert!(
+        fs::read_to_string(worktree_path.join("lines.txt"))
+            .unwrap()
+            .contains("line two")
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn selected_files_keep_their_mode() {
+    use std::os::unix::fs::{PermissionsExt, symlink};
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_selection_repo(&td);
+    write_file(&worktree_path, "run.sh", "#!/bin/sh\n");
+    fs::set_permissions(
+        worktree_path.join("run.sh"),
+        fs::Permissions::from_mode(0o755),
+    )
+    .unwrap();
+    symlink("lines.txt", worktree_path.join("link.txt")).unwrap();
+    let g = GitService::new();
+    let base = g.get_base_commit(&repo_path, "feature", "main").unwrap();
+    let diffs = g
+        .get_diffs(
+            DiffTarget::Worktree {
+                worktree_path: &worktree_path,
+                base_commit: &base,
+            },
+            None,
+        )
+        .unwrap();
+
+    let selection = vec![
+        FileSelection {
+            path: "run.sh".to_string(),
+            hunks: None,
+        },
+        FileSelection {
+            path: "link.txt".to_string(),
+            hunks: None,
+        },
+    ];
+    let writes = change_selection::accepted_writes(&worktree_path, &diffs, &selection).unwrap();
+    g.commit_files_to_branch(&repo_path, "main", &writes, "take selected changes")
+        .unwrap();
+
+    let repo = Repository::open(&repo_path).unwrap();
+    let tree = repo
+        .find_branch("main", git2::BranchType::Local)
+        .unwrap()
+        .get()
+        .peel_to_tree()
+        .unwrap();
+    assert_eq!(
+        tree.get_path(Path::new("run.sh")).unwrap().filemode(),
+        i32::from(git2::FileMode::BlobExecutable)
+    );
+    assert_eq!(
+        tree.get_path(Path::new("link.txt")).unwrap().filemode(),
+        i32::from(git2::FileMode::Link)
+    );
+    // A link is stored as its target, not the content it points at
+    assert_eq!(
+        file_at_branch(&repo_path, "main", "link.txt").as_deref(),
+        Some("lines.txt")
+    );
+}
+
+#[test]
+fn discarding_hunks_keeps_the_other_changes() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_selection_repo(&td);
+    let g = GitService::new();
+    let base = g.get_base_commit(&repo_path, "feature", "main").unwrap();
+    let diffs = g
+        .get_diffs(
+            DiffTarget::Worktree {
+                worktree_path: &worktree_path,
+                base_commit: &base,
+            },
+            None,
+        )
+        .unwrap();
+
+    let selection = vec![
+        FileSelection {
+            path: "lines.txt".to_string(),
+            hunks: Some(vec![0]),
+        },
+        FileSelection {
+            path: "added.txt".to_string(),
+            hunks: None,
+        },
+    ];
+    let writes =
+        change_selection::discarded_writes(&g, &worktree_path, &base, &diffs, &selection).unwrap();
+    change_selection::write_to_worktree(&worktree_path, &writes).unwrap();
+
+    assert_eq!(
+        fs::read_to_string(worktree_path.join("lines.txt")).unwrap(),
+        numbered_lines(30).replace("line 25\n", "line twenty-five\n")
+    );
+    assert!(!worktree_path.join("added.txt").exists());
+
+    let out_of_range = vec![FileSelection {
+        path: "lines.txt".to_string(),
+        hunks: Some(vec![5]),
+    }];
+    assert!(
+        change_selection::discarded_writes(&g, &worktree_path, &base, &diffs, &out_of_range)
+            .is_err()
+    );
+}
@@ -256,6 +256,39 @@ fn commit_and_is_worktree_clean() {
     );
 }
 
+#[test]
+fn diff_commit_range_spans_several_commits() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    let from = s.get_head_info(&repo_path).unwrap().oid;
+
+    write_file(&repo_path, "a.txt", "one\n");
+    s.commit(&repo_path, "add a").unwrap();
+    write_file(&repo_path, "a.txt", "two\n");
+    write_file(&repo_path, "b.txt", "b\n");
+    s.commit(&repo_path, "edit a, add b").unwrap();
+    let to = s.get_head_info(&repo_path).unwrap().oid;
+
+    let mut diffs = s
+        .get_diffs(
+            DiffTarget::CommitRange {
+                repo_path: Path::new(&repo_path),
+                from_commit: &from,
+                to_commit: &to,
+            },
+            None,
+        )
+        .unwrap();
+    diffs.sort_by(|a, b| a.new_path.cmp(&b.new_path));
+
+    assert_eq!(diffs.len(), 2);
+    assert_eq!(diffs[0].new_path.as_deref(), Some("a.txt"));
+    assert!(matches!(diffs[0].change, DiffChangeKind::Added));
+    assert_eq!(diffs[0].new_content.as_deref(), Some("two\n"));
+    assert_eq!(diffs[1].new_path.as_deref(), Some("b.txt"));
+}
+
 #[test]
 fn commit_in_detached_head_succeeds_via_service() {
     let td = TempDir::new().unwrap();
@@ -0,0 +1,215 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use axum::{
+    Router,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    routing::post,
+};
+use services::services::{
+    config::{Config, NotificationEvent, WebhookConfig, WebhookFormat},
+    notification::{
+        NotificationService, WEBHOOK_EVENT_HEADER, WEBHOOK_SIGNATURE_HEADER, sign_webhook_payload,
+    },
+};
+use tokio::sync::{Mutex, RwLock};
+
+#[derive(Debug, Clone)]
+struct Received {
+    headers: HeaderMap,
+    body: String,
+}
+
+#[derive(Clone, Default)]
+struct StubState {
+    received: Arc<Mutex<Vec<Received>>>,
+    /// Number of requests to reject with a 500 before accepting
+    failures_left: Arc<AtomicUsize>,
+    attempts: Arc<AtomicUsize>,
+}
+
+async fn receive(State(state): State<StubState>, headers: HeaderMap, body: String) -> StatusCode {
+    state.attempts.fetch_add(1, Ordering::SeqCst);
+    if state
+        .failures_left
+        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+        .is_ok()
+    {
+        return StatusCode::INTERNAL_SERVER_ERROR;
+    }
+    state.received.lock().await.push(Received { headers, body });
+    StatusCode::OK
+}
+
+async fn reject(State(state): State<StubState>) -> StatusCode {
+    state.attempts.fetch_add(1, Ordering::SeqCst);
+    StatusCode::GONE
+}
+
+/// Start a local webhook receiver and return its URL; `/gone` next to it rejects every request
+async fn start_stub(state: StubState) -> String {
+    let app = Router::new()
+        .route("/hook", post(receive))
+        .route("/gone", post(reject))
+        .with_state(state);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{addr}/hook")
+}
+
+fn service_with_webhooks(webhooks: Vec<WebhookConfig>) -> NotificationService {
+    let mut config = Config::default();
+    config.notifications.webhooks = webhooks;
+    NotificationService::new(Arc::new(RwLock::new(config)))
+}
+
+fn webhook(url: &str, format: WebhookFormat) -> WebhookConfig {
+    WebhookConfig {
+        url: url.to_string(),
+        enabled: true,
+        format,
+        events: vec![],
+        secret: None,
+    }
+}
+
+#[tokio::test]
+async fn json_webhook_is_signed_with_secret() {
+    let state = StubState::default();
+    let url = start_stub(state.clone()).await;
+    let service = service_with_webhooks(vec![WebhookConfig {
+        secret: Some("s3cret".to_string()),
+        ..webhook(&url, WebhookFormat::Json)
+    }]);
+
+    service
+        .send_webhooks(NotificationEvent::AgentFinished, "Task Complete", "done")
+        .await;
+
+    let received = state.received.lock().await;
+    assert_eq!(received.len(), 1);
+    let request = &received[0];
+
+    let payload: serde_json::Value = serde_json::from_str(&request.body).unwrap();
+    assert_eq!(payload["event"], "agent_finished");
+    assert_eq!(payload["title"], "Task Complete");
+    assert_eq!(payload["message"], "done");
+    assert!(payload["timestamp"].is_string());
+
+    assert_eq!(request.headers[WEBHOOK_EVENT_HEADER], "agent_finished");
+    assert_eq!(
+        request.headers[WEBHOOK_SIGNATURE_HEADER],
+        sign_webhook_payload("s3cret", request.body.as_bytes()).as_str()
+    );
+}
+
+#[tokio::test]
+async fn chat_formats_use_service_specific_payloads() {
+    let state = StubState::default();
+    let url = start_stub(state.clone()).await;
+    let service = service_with_webhooks(vec![
+        webhook(&url, WebhookFormat::Slack),
+        webhook(&url, WebhookFormat::Discord),
+        webhook(&url, WebhookFormat::Teams),
+    ]);
+
+    service
+        .send_webhooks(
+            NotificationEvent::ApprovalPending,
+            "Approval Needed",
+            "Tool 'bash'",
+        )
+        .await;
+
+    let received = state.received.lock().await;
+    assert_eq!(received.len(), 3);
+    let payloads: Vec<serde_json::Value> = received
+        .iter()
+        .map(|r| serde_json::from_str(&r.body).unwrap())
+        .collect();
+    assert!(payloads.iter().all(|p| p.get("event").is_none()));
+    assert!(
+        payloads
+            .iter()
+            .any(|p| p["text"] == "*Approval Needed*\nTool 'bash'")
+    );
+    assert!(
+        payloads
+            .iter()
+            .any(|p| p["content"] == "**Approval Needed**\nTool 'bash'")
+    );
+    assert!(payloads.iter().any(|p| p["@type"] == "MessageCard"
+        && p["title"] == "Approval Needed"
+        && p["text"] == "Tool 'bash'"));
+    assert!(
+        received
+            .iter()
+            .all(|r| !r.headers.contains_key(WEBHOOK_SIGNATURE_HEADER))
+    );
+}
+
+#[tokio::test]
+async fn failed_delivery_is_retried() {
+    let state = StubState::default();
+    state.failures_left.store(2, Ordering::SeqCst);
+    let url = start_stub(state.clone()).await;
+    let service = service_with_webhooks(vec![webhook(&url, WebhookFormat::Json)]);
+
+    service
+        .send_webhooks(
+            NotificationEvent::ExecutionFailed,
+            "Task Complete",
+            "failed",
+        )
+        .await;
+
+    assert_eq!(state.attempts.load(Ordering::SeqCst), 3);
+    assert_eq!(state.received.lock().await.len(), 1);
+}
+
+#[tokio::test]
+async fn client_errors_are_not_retried() {
+    let state = StubState::default();
+    let url = start_stub(state.clone()).await.replace("/hook", "/gone");
+    let service = service_with_webhooks(vec![webhook(&url, WebhookFormat::Json)]);
+
+    service
+        .send_webhooks(NotificationEvent::AgentFinished, "Task Complete", "done")
+        .await;
+
+    assert_eq!(state.attempts.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn webhooks_only_receive_subscribed_events() {
+    let state = StubState::default();
+    let url = start_stub(state.clone()).await;
+    let service = service_with_webhooks(vec![
+        WebhookConfig {
+            events: vec![NotificationEvent::PrMerged],
+            ..webhook(&url, WebhookFormat::Json)
+        },
+        WebhookConfig {
+            enabled: false,
+            ..webhook(&url, WebhookFormat::Json)
+        },
+    ]);
+
+    service
+        .send_webhooks(NotificationEvent::AgentFinished, "Task Complete", "done")
+        .await;
+    assert_eq!(state.attempts.load(Ordering::SeqCst), 0);
+
+    service
+        .send_webhooks(NotificationEvent::PrMerged, "PR Merged", "PR #1 was merged")
+        .await;
+    let received = state.received.lock().await;
+    assert_eq!(received.len(), 1);
+    assert_eq!(received[0].headers[WEBHOOK_EVENT_HEADER], "pr_merged");
+}
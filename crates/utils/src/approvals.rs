@@ -29,6 +29,12 @@ impl ApprovalRequest {
             timeout_at: now + Duration::seconds(APPROVAL_TIMEOUT_SECONDS),
         }
     }
+
+    /// Time out `seconds` after the request was created instead of the default
+    pub fn with_timeout_seconds(mut self, seconds: i64) -> Self {
+        self.timeout_at = self.created_at + Duration::seconds(seconds);
+        self
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
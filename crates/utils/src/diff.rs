@@ -2,7 +2,7 @@ use std::borrow::Cow;
 
 use git2::{DiffOptions, Patch};
 use serde::{Deserialize, Serialize};
-use similar::TextDiff;
+use similar::{DiffTag, TextDiff};
 use ts_rs::TS;
 use uuid::Uuid;
 
@@ -48,9 +48,13 @@ pub enum DiffChangeKind {
 // Unified diff utility functions
 // ==============================
 
+/// Context lines around each hunk. Hunk indices used by [`apply_selected_hunks`] refer to
+/// hunks produced with this radius.
+const HUNK_CONTEXT_RADIUS: usize = 3;
+
 /// Converts a replace diff to a list of unified diff hunks.
 /// Uses a context limit of 3 lines.
-fn create_unified_diff_hunks(old: &str, new: &str) -> Vec<String> {
+pub fn create_unified_diff_hunks(old: &str, new: &str) -> Vec<String> {
     let old = ensure_newline(old);
     let new = ensure_newline(new);
 
@@ -59,7 +63,7 @@ fn create_unified_diff_hunks(old: &str, new: &str) -> Vec<String> {
     // Generate unified diff with context
     let unified_diff = diff
         .unified_diff()
-        .context_radius(3)
+        .context_radius(HUNK_CONTEXT_RADIUS)
         .header("a", "b")
         .to_string();
 
@@ -72,6 +76,60 @@ pub fn create_unified_diff(file_path: &str, old: &str, new: &str) -> String {
     concatenate_diff_hunks(file_path, &hunks)
 }
 
+/// Applies only the selected hunks of the change from `old` to `new` and returns the result.
+/// Hunks are indexed in the order [`create_unified_diff_hunks`] returns them; changes in
+/// unselected hunks keep the `old` side.
+pub fn apply_selected_hunks(old: &str, new: &str, selected: &[usize]) -> String {
+    let old_text = ensure_newline_if_not_empty(old);
+    let new_text = ensure_newline_if_not_empty(new);
+    let diff = TextDiff::from_lines(old_text.as_ref(), new_text.as_ref());
+    let old_lines = diff.old_slices();
+    let new_lines = diff.new_slices();
+
+    // Grouping keeps every change op in order, so the n-th change op in `ops()` is the n-th
+    // change op across the groups
+    let change_hunks = diff
+        .grouped_ops(HUNK_CONTEXT_RADIUS)
+        .iter()
+        .enumerate()
+        .flat_map(|(hunk, ops)| {
+            ops.iter()
+                .filter(|op| op.tag() != DiffTag::Equal)
+                .map(move |_| hunk)
+        })
+        .collect::<Vec<_>>();
+    let mut change_hunks = change_hunks.into_iter();
+
+    let mut result = String::new();
+    // Whether the last line written came from `new`, to restore its missing final newline
+    let mut last_from_new = None;
+    for op in diff.ops() {
+        let take_new = op.tag() != DiffTag::Equal
+            && change_hunks
+                .next()
+                .is_some_and(|hunk| selected.contains(&hunk));
+        let lines = if take_new {
+            &new_lines[op.new_range()]
+        } else {
+            &old_lines[op.old_range()]
+        };
+        if !lines.is_empty() {
+            result.extend(lines.iter().copied());
+            last_from_new = Some(take_new);
+        }
+    }
+
+    let missing_newline = match last_from_new {
+        Some(true) => !new.ends_with('\n'),
+        Some(false) => !old.ends_with('\n'),
+        None => false,
+    };
+    if missing_newline {
+        result.pop();
+    }
+    result
+}
+
 /// Compute addition/deletion counts between two text snapshots.
 pub fn compute_line_change_counts(old: &str, new: &str) -> (usize, usize) {
     let old = ensure_newline(old);
@@ -102,6 +160,15 @@ fn ensure_newline(line: &str) -> Cow<'_, str> {
     }
 }
 
+// like `ensure_newline`, but an empty text stays empty instead of becoming one blank line
+fn ensure_newline_if_not_empty(text: &str) -> Cow<'_, str> {
+    if text.is_empty() {
+        Cow::Borrowed(text)
+    } else {
+        ensure_newline(text)
+    }
+}
+
 /// Extracts unified diff hunks from a string containing a full unified diff.
 /// Tolerates non-diff lines and missing `@@`` hunk headers.
 pub fn extract_unified_diff_hunks(unified_diff: &str) -> Vec<String> {
@@ -246,3 +313,38 @@ pub fn normalize_unified_diff(file_path: &str, unified_diff: &str) -> String {
     let hunks = extract_unified_diff_hunks(unified_diff);
     concatenate_diff_hunks(file_path, &hunks)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbered_lines(count: usize) -> String {
+        (1..=count).map(|i| format!("line {i}\n")).collect()
+    }
+
+    #[test]
+    fn test_apply_selected_hunks() {
+        let old = numbered_lines(30);
+        let new = old
+            .replace("line 2\n", "line two\n")
+            .replace("line 25\n", "line twenty-five\n");
+        assert_eq!(create_unified_diff_hunks(&old, &new).len(), 2);
+
+        assert_eq!(apply_selected_hunks(&old, &new, &[]), old);
+        assert_eq!(apply_selected_hunks(&old, &new, &[0, 1]), new);
+        assert_eq!(
+            apply_selected_hunks(&old, &new, &[1]),
+            old.replace("line 25\n", "line twenty-five\n")
+        );
+    }
+
+    #[test]
+    fn test_apply_selected_hunks_keeps_missing_final_newline() {
+        let old = "a\nb\nc";
+        let new = "a\nB\nc\nd";
+        assert_eq!(apply_selected_hunks(old, new, &[]), old);
+        assert_eq!(apply_selected_hunks(old, new, &[0]), new);
+        assert_eq!(apply_selected_hunks("", "x\ny\n", &[0]), "x\ny\n");
+        assert_eq!(apply_selected_hunks("x\ny\n", "", &[0]), "");
+    }
+}
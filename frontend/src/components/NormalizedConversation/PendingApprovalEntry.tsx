@@ -36,22 +36,29 @@ interface PendingApprovalEntryProps {
 
 function useApprovalCountdown(
   requestedAt: string | number | Date,
-  timeoutAt: string | number | Date,
+  timeoutAt: string | number | Date | null,
   paused: boolean
 ) {
   const totalSeconds = useMemo(() => {
+    if (timeoutAt === null) return null;
     const total = Math.floor(
       (new Date(timeoutAt).getTime() - new Date(requestedAt).getTime()) / 1000
     );
     return Math.max(1, total);
   }, [requestedAt, timeoutAt]);
 
-  const [timeLeft, setTimeLeft] = useState<number>(() => {
+  // null once the request has timed out and waits for a response indefinitely
+  const [timeLeft, setTimeLeft] = useState<number | null>(() => {
+    if (timeoutAt === null) return null;
     const remaining = new Date(timeoutAt).getTime() - Date.now();
     return Math.max(0, Math.floor(remaining / 1000));
   });
 
   useEffect(() => {
+    if (timeoutAt === null) {
+      setTimeLeft(null);
+      return;
+    }
     if (paused) return;
     const id = window.setInterval(() => {
       const remaining = new Date(timeoutAt).getTime() - Date.now();
@@ -65,7 +72,12 @@ function useApprovalCountdown(
 
   const percent = useMemo(
     () =>
-      Math.max(0, Math.min(100, Math.round((timeLeft / totalSeconds) * 100))),
+      timeLeft === null || totalSeconds === null
+        ? 100
+        : Math.max(
+            0,
+            Math.min(100, Math.round((timeLeft / totalSeconds) * 100))
+          ),
     [timeLeft, totalSeconds]
   );
 
@@ -205,7 +217,8 @@ const PendingApprovalEntry = ({
     hasResponded
   );
 
-  const disabled = isResponding || hasResponded || timeLeft <= 0;
+  const disabled =
+    isResponding || hasResponded || (timeLeft !== null && timeLeft <= 0);
 
   const shouldEnableApprovalsScope = shouldControlScopes && !disabled;
 
@@ -245,6 +245,11 @@ function ProcessesTab({ sessionId }: ProcessesTabProps) {
                       >
                         {process.status}
                       </span>
+                      {process.stop_reason !== null && (
+                        <p className="text-xs text-muted-foreground mt-1">
+                          {t('processes.stoppedBudgetExceeded')}
+                        </p>
+                      )}
                       {process.exit_code !== null && (
                         <p className="text-xs text-muted-foreground mt-1">
                           {t('processes.exit', {
@@ -529,7 +529,7 @@ export function SessionChatBoxContainer({
   ]);
 
   // Check if approval is timed out
-  const isApprovalTimedOut = pendingApproval
+  const isApprovalTimedOut = pendingApproval?.timeoutAt
     ? new Date() > new Date(pendingApproval.timeoutAt)
     : false;
 
@@ -25,6 +25,7 @@ const RUN_REASON_LABELS: Record<ExecutionProcessRunReason, string> = {
   codingagent: 'Coding Agent',
   setupscript: 'Setup Script',
   cleanupscript: 'Cleanup Script',
+  verifyscript: 'Verify Script',
   devserver: 'Dev Server',
 };
 
@@ -33,6 +34,7 @@ const RUN_REASON_ICONS: Record<ExecutionProcessRunReason, typeof TerminalIcon> =
     codingagent: CodeIcon,
     setupscript: GearIcon,
     cleanupscript: GearIcon,
+    verifyscript: GearIcon,
     devserver: GlobeIcon,
   };
 
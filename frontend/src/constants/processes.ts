@@ -4,6 +4,7 @@ import type { ExecutionProcessRunReason } from 'shared/types';
 export const PROCESS_RUN_REASONS = {
   SETUP_SCRIPT: 'setupscript' as ExecutionProcessRunReason,
   CLEANUP_SCRIPT: 'cleanupscript' as ExecutionProcessRunReason,
+  VERIFY_SCRIPT: 'verifyscript' as ExecutionProcessRunReason,
   CODING_AGENT: 'codingagent' as ExecutionProcessRunReason,
   DEV_SERVER: 'devserver' as ExecutionProcessRunReason,
 } as const;
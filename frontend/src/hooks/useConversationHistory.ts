@@ -118,6 +118,7 @@ export const useConversationHistory = ({
       (ep) =>
         ep.run_reason === 'setupscript' ||
         ep.run_reason === 'cleanupscript' ||
+        ep.run_reason === 'verifyscript' ||
         ep.run_reason === 'codingagent'
     );
   }, [executionProcessesRaw]);
@@ -327,6 +328,9 @@ export const useConversationHistory = ({
               case 'CleanupScript':
                 toolName = 'Cleanup Script';
                 break;
+              case 'VerifyScript':
+                toolName = 'Verify Script';
+                break;
               case 'ToolInstallScript':
                 toolName = 'Tool Install Script';
                 break;
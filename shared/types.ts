@@ -26,9 +26,26 @@ score: bigint, };
 
 export type SearchMatchType = "FileName" | "DirectoryName" | "FullPath";
 
-export type Repo = { id: string, path: string, name: string, display_name: string, setup_script: string | null, cleanup_script: string | null, copy_files: string | null, parallel_setup_script: boolean, dev_server_script: string | null, created_at: Date, updated_at: Date, };
+export type Repo = { id: string, path: string, name: string, display_name: string, setup_script: string | null, cleanup_script: string | null, copy_files: string | null, parallel_setup_script: boolean, dev_server_script: string | null, 
+/**
+ * Overrides provider detection from the remote URL, e.g. for self-hosted GitLab or Gitea
+ */
+git_host_provider: ProviderKind | null, 
+/**
+ * Web base URL of a self-hosted instance when the remote URL doesn't point at it, e.g. an
+ * SSH remote on another host or an instance served under a sub-path
+ */
+git_host_base_url: string | null, 
+/**
+ * Checks (tests, lint, typecheck) run after every coding agent turn
+ */
+verify_script: string | null, 
+/**
+ * Follow-ups queued automatically when the verify script keeps failing
+ */
+verify_max_retries: number, created_at: Date, updated_at: Date, };
 
-export type UpdateRepo = { display_name?: string | null, setup_script?: string | null, cleanup_script?: string | null, copy_files?: string | null, parallel_setup_script?: boolean | null, dev_server_script?: string | null, };
+export type UpdateRepo = { display_name?: string | null, setup_script?: string | null, cleanup_script?: string | null, copy_files?: string | null, parallel_setup_script?: boolean | null, dev_server_script?: string | null, git_host_provider?: ProviderKind | null, git_host_base_url?: string | null, verify_script?: string | null, verify_max_retries?: number | null, };
 
 export type ProjectRepo = { id: string, project_id: string, repo_id: string, };
 
@@ -40,6 +57,14 @@ export type CreateWorkspaceRepo = { repo_id: string, target_branch: string, };
 
 export type RepoWithTargetBranch = { target_branch: string, id: string, path: string, name: string, display_name: string, setup_script: string | null, cleanup_script: string | null, copy_files: string | null, parallel_setup_script: boolean, dev_server_script: string | null, created_at: Date, updated_at: Date, };
 
+export type WorkspaceGroup = { id: string, task_id: string, 
+/**
+ * Workspace picked as the winner; the other members are archived when it is set
+ */
+winner_workspace_id: string | null, created_at: string, updated_at: string, };
+
+export type WorkspaceGroupMember = { workspace_id: string, workspace_group_id: string, executor_profile_id: ExecutorProfileId, created_at: string, };
+
 export type Tag = { id: string, tag_name: string, content: string, created_at: string, updated_at: string, };
 
 export type CreateTag = { tag_name: string, content: string, };
@@ -58,6 +83,155 @@ export type CreateTask = { project_id: string, title: string, description: strin
 
 export type UpdateTask = { title: string | null, description: string | null, status: TaskStatus | null, parent_workspace_id: string | null, image_ids: Array<string> | null, };
 
+export type TaskDependency = { id: string, task_id: string, depends_on_task_id: string, created_at: Date, };
+
+export type CreateTaskDependency = { depends_on_task_id: string, };
+
+export type TaskDependencies = { blocked_by: Array<Task>, blocking: Array<Task>, };
+
+/**
+ * Run limits for the coding agent runs of a task. Limits set here take precedence over the
+ * ones configured on the executor profile variant.
+ */
+export type TaskBudget = { task_id: string, budget_max_tokens: bigint | null, budget_max_minutes: bigint | null, budget_max_tool_calls: bigint | null, created_at: string, updated_at: string, };
+
+/**
+ * The git host issue a task was imported from
+ */
+export type TaskIssueLink = { id: string, task_id: string, repo_id: string, 
+/**
+ * Remote the issue was imported through
+ */
+remote_url: string, 
+/**
+ * Issue number on GitHub, work item id on Azure Boards
+ */
+issue_number: bigint, issue_url: string, 
+/**
+ * When the issue was closed after a PR of the task merged
+ */
+closed_at: string | null, created_at: string, };
+
+/**
+ * Limits for a single coding agent run. A run that exceeds any of them is stopped.
+ */
+export type RunBudget = { budget_max_tokens?: bigint | null, budget_max_minutes?: number | null, budget_max_tool_calls?: number | null, };
+
+/**
+ * Approval rules for the coding agent runs of a project. They are evaluated before the rules
+ * configured on the executor profile variant.
+ */
+export type ProjectApprovalPolicy = { project_id: string, rules: Array<ApprovalRule>, created_at: string, updated_at: string, };
+
+/**
+ * How review comments on the PRs of a project's workspaces are handled
+ */
+export type ProjectPrReviewSetting = { project_id: string, 
+/**
+ * Send new review comments to the workspace's latest session as a follow-up, then push the
+ * result and reply on the PR
+ */
+auto_follow_up: boolean, 
+/**
+ * Send failing CI checks, with an excerpt of their logs, to the workspace's latest session
+ * as a follow-up
+ */
+auto_fix_checks: boolean, 
+/**
+ * When auto follow-up was last turned on; review comments posted before then are not sent
+ */
+auto_follow_up_enabled_at: string | null, created_at: string, updated_at: string, };
+
+/**
+ * Settings left out keep their current value
+ */
+export type UpdateProjectPrReviewSetting = { auto_follow_up?: boolean, auto_fix_checks?: boolean, };
+
+/**
+ * What a timed out approval request resolved to
+ */
+export enum ApprovalTimeoutResolution { denied = "denied", approved = "approved", waiting = "waiting" }
+
+/**
+ * An approval request nobody responded to in time
+ */
+export type ApprovalTimeout = { approval_id: string, execution_process_id: string, tool_name: string, tool_input: JsonValue, tool_call_id: string, resolution: ApprovalTimeoutResolution, requested_at: string, timed_out_at: string, };
+
+/**
+ * What happens to a tool call matched by an approval rule: `allow` runs it without asking,
+ * `deny` refuses it and tells the agent why, `ask` waits for approval as usual
+ */
+export type ApprovalRuleDecision = "allow" | "deny" | "ask";
+
+/**
+ * Matches tool calls that need approval. Every criterion that is set must match; a rule
+ * without criteria matches every tool call.
+ */
+export type ApprovalRule = { 
+/**
+ * Recorded on the tool call when this rule decides it
+ */
+name: string, decision: ApprovalRuleDecision, 
+/**
+ * Tool name as reported by the agent, compared case-insensitively
+ */
+tool_name?: string | null, 
+/**
+ * Regex matched against the command of command runs
+ */
+command_pattern?: string | null, 
+/**
+ * Globs matched against the path of file reads and edits
+ */
+path_globs?: Array<string>, 
+/**
+ * Domains of web fetches; subdomains match too
+ */
+domains?: Array<string>, 
+/**
+ * Sent back to the agent when the rule denies a tool call
+ */
+reason?: string | null, 
+/**
+ * Approve matching calls whose approval times out when the timeout outcome is
+ * `approve_if_safe`
+ */
+safe?: boolean, };
+
+/**
+ * What happens to an approval request nobody responds to in time: `deny` refuses the tool
+ * call, `approve_if_safe` approves it when it matches a rule marked safe and refuses it
+ * otherwise, `wait` keeps the run paused until someone responds
+ */
+export type ApprovalTimeoutOutcome = "deny" | "approve_if_safe" | "wait";
+
+/**
+ * Approval settings of an executor profile variant
+ */
+export type ApprovalPolicy = { approval_rules?: Array<ApprovalRule>, approval_timeout_minutes?: number | null, approval_timeout_outcome?: ApprovalTimeoutOutcome | null, approval_reminder_minutes?: number | null, };
+
+/**
+ * Template for a task that is created and started automatically, either once at `run_at`
+ * or on every tick of `cron_expression`.
+ */
+export type TaskSchedule = { id: string, project_id: string, title: string, description: string | null, executor_profile_id: ExecutorProfileId, repos: Array<CreateWorkspaceRepo>, cron_expression: string | null, run_at: string | null, 
+/**
+ * Start one run on startup if any fire times passed while the app was closed
+ */
+catch_up_missed: boolean, enabled: boolean, next_run_at: string | null, last_run_at: string | null, last_task_id: string | null, 
+/**
+ * Number of fire times skipped because the app was not running
+ */
+missed_runs: bigint, created_at: string, updated_at: string, };
+
+export type CreateTaskSchedule = { project_id: string, title: string, description: string | null, executor_profile_id: ExecutorProfileId, repos: Array<CreateWorkspaceRepo>, cron_expression: string | null, run_at: string | null, catch_up_missed: boolean | null, };
+
+export type UpdateTaskSchedule = { title: string | null, description: string | null, executor_profile_id: ExecutorProfileId | null, repos: Array<CreateWorkspaceRepo> | null, 
+/**
+ * Setting either `cron_expression` or `run_at` replaces the current timing
+ */
+cron_expression: string | null, run_at: string | null, catch_up_missed: boolean | null, enabled: boolean | null, };
+
 export type DraftFollowUpData = { message: string, variant: string | null, };
 
 export type DraftWorkspaceData = { message: string, project_id: string | null, repos: Array<DraftWorkspaceRepo>, selected_profile: ExecutorProfileId | null, };
@@ -80,13 +254,31 @@ export type Image = { id: string, file_path: string, original_name: string, mime
 
 export type CreateImage = { file_path: string, original_name: string, mime_type: string | null, size_bytes: bigint, hash: string, };
 
-export type Workspace = { id: string, task_id: string, container_ref: string | null, branch: string, agent_working_dir: string | null, setup_completed_at: string | null, created_at: string, updated_at: string, archived: boolean, pinned: boolean, name: string | null, };
+export type Workspace = { id: string, task_id: string, container_ref: string | null, branch: string, agent_working_dir: string | null, setup_completed_at: string | null, created_at: string, updated_at: string, archived: boolean, pinned: boolean, name: string | null, 
+/**
+ * Workspace whose branch this one is stacked on, if any
+ */
+base_workspace_id: string | null, };
+
+export type WorkspaceWithStatus = { is_running: boolean, is_errored: boolean, 
+/**
+ * Outcome of the latest verify script run, if any has finished
+ */
+verification_status: WorkspaceVerificationStatus | null, id: string, task_id: string, container_ref: string | null, branch: string, agent_working_dir: string | null, setup_completed_at: string | null, created_at: string, updated_at: string, archived: boolean, pinned: boolean, name: string | null, 
+/**
+ * Workspace whose branch this one is stacked on, if any
+ */
+base_workspace_id: string | null, };
 
-export type WorkspaceWithStatus = { is_running: boolean, is_errored: boolean, id: string, task_id: string, container_ref: string | null, branch: string, agent_working_dir: string | null, setup_completed_at: string | null, created_at: string, updated_at: string, archived: boolean, pinned: boolean, name: string | null, };
+export type WorkspaceVerificationStatus = "verified" | "failed_verification";
 
 export type Session = { id: string, workspace_id: string, executor: string | null, created_at: string, updated_at: string, };
 
 export type ExecutionProcess = { id: string, session_id: string, run_reason: ExecutionProcessRunReason, executor_action: ExecutorAction, status: ExecutionProcessStatus, exit_code: bigint | null, 
+/**
+ * Set when the process was stopped automatically rather than by the user
+ */
+stop_reason: ExecutionProcessStopReason | null, 
 /**
  * dropped: true if this process is excluded from the current
  * history view (due to restore/trimming). Hidden from logs/timeline;
@@ -96,20 +288,131 @@ dropped: boolean, started_at: string, completed_at: string | null, created_at: s
 
 export enum ExecutionProcessStatus { running = "running", completed = "completed", failed = "failed", killed = "killed" }
 
-export type ExecutionProcessRunReason = "setupscript" | "cleanupscript" | "codingagent" | "devserver";
+export type ExecutionProcessRunReason = "setupscript" | "cleanupscript" | "codingagent" | "devserver" | "verifyscript";
+
+export enum ExecutionProcessStopReason { token_budget_exceeded = "token_budget_exceeded", time_budget_exceeded = "time_budget_exceeded", tool_call_budget_exceeded = "tool_call_budget_exceeded" }
 
 export type ExecutionProcessRepoState = { id: string, execution_process_id: string, repo_id: string, before_head_commit: string | null, after_head_commit: string | null, merge_commit: string | null, created_at: Date, updated_at: Date, };
 
+export type ExecutionProcessUsage = { id: string, execution_process_id: string, 
+/**
+ * Executor profile that ran the process, formatted as `EXECUTOR` or `EXECUTOR:VARIANT`
+ */
+executor_profile: string, model: string, input_tokens: bigint, output_tokens: bigint, cache_read_tokens: bigint, cache_write_tokens: bigint, cost_usd: number | null, created_at: string, updated_at: string, };
+
+export type UsageGroupBy = "task" | "project" | "executor_profile" | "day";
+
+/**
+ * Usage summed over one group. Only the key fields of the requested grouping are set:
+ * `task_id` and `project_id` for tasks, `project_id` for projects, `executor_profile` for
+ * executor profiles and `day` (`YYYY-MM-DD`, UTC) for days.
+ */
+export type UsageSummary = { task_id: string | null, project_id: string | null, executor_profile: string | null, day: string | null, 
+/**
+ * Task title or project name
+ */
+label: string | null, execution_count: bigint, input_tokens: bigint, output_tokens: bigint, cache_read_tokens: bigint, cache_write_tokens: bigint, 
+/**
+ * Sum of agent reported costs, null when no execution in the group reported one
+ */
+cost_usd: number | null, };
+
+export type ConversationEntryType = "user_message" | "assistant_message" | "tool_use";
+
+/**
+ * A conversation entry matching a search, with the task and workspace it belongs to
+ */
+export type ConversationSearchHit = { project_id: string, task_id: string, task_title: string, workspace_id: string, workspace_branch: string, session_id: string, execution_process_id: string, 
+/**
+ * Index of the entry in the execution process's normalized conversation
+ */
+entry_index: bigint, entry_type: ConversationEntryType, 
+/**
+ * Excerpt of the entry with matched terms wrapped in `<mark>` tags
+ */
+snippet: string, updated_at: string, };
+
 export type Merge = { "type": "direct" } & DirectMerge | { "type": "pr" } & PrMerge;
 
 export type DirectMerge = { id: string, workspace_id: string, repo_id: string, merge_commit: string, target_branch_name: string, created_at: string, };
 
-export type PrMerge = { id: string, workspace_id: string, repo_id: string, created_at: string, target_branch_name: string, pr_info: PullRequestInfo, };
+export type PrMerge = { id: string, workspace_id: string, repo_id: string, created_at: string, target_branch_name: string, pr_info: PullRequestInfo, 
+/**
+ * CI checks as last reported by the git host; empty until they are first fetched
+ */
+checks: Array<PrCheck>, };
 
 export type MergeStatus = "open" | "merged" | "closed" | "unknown";
 
 export type PullRequestInfo = { number: bigint, url: string, status: MergeStatus, merged_at: string | null, merge_commit_sha: string | null, };
 
+export enum PrCheckStatus { pending = "pending", passed = "passed", failed = "failed", skipped = "skipped" }
+
+/**
+ * A CI check run or commit status reported on a PR
+ */
+export type PrCheck = { name: string, status: PrCheckStatus, 
+/**
+ * Page of the check on the git host or CI service
+ */
+url: string | null, description: string | null, };
+
+export enum MergeQueueStrategy { squash = "squash", fast_forward = "fast_forward" }
+
+export enum MergeQueueStatus { queued = "queued", running = "running", merged = "merged", ejected = "ejected", cancelled = "cancelled" }
+
+export type MergeQueueEntry = { id: string, workspace_id: string, repo_id: string, target_branch_name: string, strategy: MergeQueueStrategy, status: MergeQueueStatus, 
+/**
+ * Why the entry was ejected
+ */
+failure_reason: string | null, 
+/**
+ * The direct merge created when the entry was merged
+ */
+merge_id: string | null, created_at: string, updated_at: string, };
+
+export enum ReviewCommentStatus { open = "open", sent = "sent", outdated = "outdated", resolved = "resolved" }
+
+/**
+ * A comment on a line range of a file in a workspace's diff
+ */
+export type ReviewComment = { id: string, workspace_id: string, repo_id: string, file_path: string, 
+/**
+ * First commented line, 1-based, in the workspace's version of the file
+ */
+line_start: number, 
+/**
+ * Last commented line, inclusive
+ */
+line_end: number, body: string, 
+/**
+ * HEAD of the workspace branch the comment was made against
+ */
+commit_sha: string, 
+/**
+ * The commented lines as they were when the comment was made
+ */
+anchor_text: string, status: ReviewCommentStatus, 
+/**
+ * The coding agent turn the comment was sent in
+ */
+execution_process_id: string | null, created_at: string, updated_at: string, };
+
+export enum PrReviewCommentStatus { pending = "pending", sent = "sent", addressed = "addressed", failed = "failed", skipped = "skipped" }
+
+/**
+ * An inline review comment found on a workspace's open PR
+ */
+export type PrReviewComment = { id: string, merge_id: string, workspace_id: string, repo_id: string, 
+/**
+ * The git host's id of the comment
+ */
+comment_id: string, author: string, body: string, path: string, line: number | null, diff_hunk: string | null, url: string | null, status: PrReviewCommentStatus, 
+/**
+ * The coding agent turn the comment was sent in
+ */
+execution_process_id: string | null, created_at: string, updated_at: string, };
+
 export type ApprovalStatus = { "status": "pending" } | { "status": "approved" } | { "status": "denied", reason?: string, } | { "status": "timed_out" };
 
 export type CreateApprovalRequest = { tool_name: string, tool_input: JsonValue, tool_call_id: string, };
@@ -220,7 +523,11 @@ export type CheckAgentAvailabilityQuery = { executor: BaseCodingAgent, };
 
 export type CurrentUserResponse = { user_id: string, };
 
-export type CreateFollowUpAttempt = { prompt: string, variant: string | null, retry_process_id: string | null, force_when_dirty: boolean | null, perform_git_reset: boolean | null, };
+export type CreateFollowUpAttempt = { prompt: string, variant: string | null, retry_process_id: string | null, force_when_dirty: boolean | null, perform_git_reset: boolean | null, 
+/**
+ * Open review comments of the workspace to send along with the prompt
+ */
+review_comment_ids?: Array<string>, };
 
 export type ChangeTargetBranchRequest = { repo_id: string, new_target_branch: string, };
 
@@ -248,13 +555,41 @@ export type ShareTaskResponse = { shared_task_id: string, };
 
 export type CreateAndStartTaskRequest = { task: CreateTask, executor_profile_id: ExecutorProfileId, repos: Array<WorkspaceRepoInput>, };
 
+export type ImportTasksSource = { "source": "issues", repo_id: string, 
+/**
+ * Issues must have all of these labels (tags on Azure Boards)
+ */
+labels: Array<string>, 
+/**
+ * Milestone title (iteration path on Azure Boards)
+ */
+milestone?: string, query?: string, limit?: number, } | { "source": "markdown", content: string, } | { "source": "csv", content: string, };
+
+export type ImportTasksRequest = { project_id: string, source: ImportTasksSource, };
+
+export type ImportTasksResponse = { created: Array<Task>, 
+/**
+ * Issues left out because they were imported into the project before
+ */
+skipped: number, };
+
 export type CreatePrApiRequest = { title: string, body: string | null, target_branch: string | null, draft: boolean | null, repo_id: string, auto_generate_description: boolean, };
 
 export type ImageResponse = { id: string, file_path: string, original_name: string, mime_type: string | null, size_bytes: bigint, hash: string, created_at: string, updated_at: string, };
 
 export type ImageMetadata = { exists: boolean, file_name: string | null, path: string | null, size_bytes: bigint | null, format: string | null, proxy_url: string | null, };
 
-export type CreateTaskAttemptBody = { task_id: string, executor_profile_id: ExecutorProfileId, repos: Array<WorkspaceRepoInput>, };
+export type CreateTaskAttemptBody = { task_id: string, executor_profile_id: ExecutorProfileId, repos: Array<WorkspaceRepoInput>, 
+/**
+ * Further executor profiles to run the task with in parallel. Each one gets its own
+ * workspace from the same base branches, and all of them are grouped for comparison.
+ */
+fan_out_executor_profile_ids?: Array<ExecutorProfileId>, 
+/**
+ * Stack the new workspace on this workspace: its repos target that workspace's branch
+ * instead of `target_branch`. With no repos given, all of that workspace's repos are used.
+ */
+base_workspace_id?: string, };
 
 export type WorkspaceRepoInput = { repo_id: string, target_branch: string, };
 
@@ -268,13 +603,79 @@ export type RebaseTaskAttemptRequest = { repo_id: string, old_base_branch: strin
 
 export type AbortConflictsRequest = { repo_id: string, };
 
+export type ConflictsQuery = { repo_id: string, };
+
+/**
+ * The conflicted operation of a repo in a workspace and the files it left unmerged
+ */
+export type WorkspaceConflicts = { 
+/**
+ * Null when no rebase, merge, cherry-pick or revert is in progress
+ */
+op: ConflictOp | null, files: Array<ConflictFile>, };
+
+export type ResolveConflictRequest = { repo_id: string, path: string, resolution: ConflictResolution, };
+
+export type ContinueConflictsRequest = { repo_id: string, };
+
+export type ChangeHunksQuery = { repo_id: string, };
+
+/**
+ * Where accepted changes are committed: onto the repo's target branch, or in a new workspace
+ * of the same task branched from it
+ */
+export type SelectionDestination = "target_branch" | "new_workspace";
+
+export type ApplySelectionRequest = { repo_id: string, files: Array<FileSelection>, destination: SelectionDestination, 
+/**
+ * Defaults to the task's merge commit message
+ */
+commit_message?: string, };
+
+export type AppliedSelection = { "type": "target_branch", commit_sha: string, } | { "type": "new_workspace", workspace: Workspace, };
+
+export type DiscardSelectionRequest = { repo_id: string, files: Array<FileSelection>, };
+
+export type CreateReviewCommentRequest = { repo_id: string, 
+/**
+ * Path of the file within the repo
+ */
+file_path: string, 
+/**
+ * First commented line, 1-based, in the workspace's version of the file
+ */
+line_start: number, 
+/**
+ * Last commented line, inclusive
+ */
+line_end: number, body: string, };
+
+export type UpdateReviewCommentRequest = { body: string, };
+
 export type GitOperationError = { "type": "merge_conflicts", message: string, op: ConflictOp, } | { "type": "rebase_in_progress" };
 
+export type MergeQueueQuery = { repo_id: string, 
+/**
+ * Also return merged, ejected and cancelled entries
+ */
+include_finished: boolean, };
+
+export type EnqueueMergeRequest = { workspace_id: string, repo_id: string, 
+/**
+ * Defaults to squash
+ */
+strategy: MergeQueueStrategy | null, };
+
 export type PushError = { "type": "force_push_required" };
 
 export type PrError = { "type": "cli_not_installed", provider: ProviderKind, } | { "type": "cli_not_logged_in", provider: ProviderKind, } | { "type": "git_cli_not_logged_in" } | { "type": "git_cli_not_installed" } | { "type": "target_branch_not_found", branch: string, } | { "type": "unsupported_provider" };
 
 export type BranchStatus = { commits_behind: number | null, commits_ahead: number | null, has_uncommitted_changes: boolean | null, head_oid: string | null, uncommitted_count: number | null, untracked_count: number | null, target_branch_name: string, remote_commits_behind: number | null, remote_commits_ahead: number | null, merges: Array<Merge>, 
+/**
+ * Latest merge queue entry of the workspace for this repo; an ejected entry carries the
+ * reason it was not merged
+ */
+merge_queue_entry: MergeQueueEntry | null, 
 /**
  * True if a `git rebase` is currently in progress in this worktree
  */
@@ -300,11 +701,24 @@ export type GetPrCommentsError = { "type": "no_pr_attached" } | { "type": "cli_n
 
 export type GetPrCommentsQuery = { repo_id: string, };
 
-export type UnifiedPrComment = { "comment_type": "general", id: string, author: string, author_association: string | null, body: string, created_at: string, url: string | null, } | { "comment_type": "review", id: bigint, author: string, author_association: string | null, body: string, created_at: string, url: string | null, path: string, line: bigint | null, side: string | null, diff_hunk: string | null, };
+export type UnifiedPrComment = { "comment_type": "general", id: string, author: string, author_association: string | null, body: string, created_at: string, url: string | null, } | { "comment_type": "review", id: bigint, author: string, author_association: string | null, body: string, created_at: string, url: string | null, path: string, line: bigint | null, side: string | null, diff_hunk: string | null, 
+/**
+ * Whether the comment replies to an earlier comment of its thread
+ */
+is_reply: boolean, 
+/**
+ * Whether the comment's thread was resolved
+ */
+resolved: boolean, };
 
-export type ProviderKind = "git_hub" | "azure_dev_ops" | "unknown";
+export type ProviderKind = "git_hub" | "azure_dev_ops" | "git_lab" | "gitea" | "unknown";
 
 export type RepoBranchStatus = { repo_id: string, repo_name: string, commits_behind: number | null, commits_ahead: number | null, has_uncommitted_changes: boolean | null, head_oid: string | null, uncommitted_count: number | null, untracked_count: number | null, target_branch_name: string, remote_commits_behind: number | null, remote_commits_ahead: number | null, merges: Array<Merge>, 
+/**
+ * Latest merge queue entry of the workspace for this repo; an ejected entry carries the
+ * reason it was not merged
+ */
+merge_queue_entry: MergeQueueEntry | null, 
 /**
  * True if a `git rebase` is currently in progress in this worktree
  */
@@ -368,15 +782,88 @@ export type WorkspaceSummaryResponse = { summaries: Array<WorkspaceSummary>, };
 
 export type DiffStats = { files_changed: number, lines_added: number, lines_removed: number, };
 
+export type WorkspaceGroupAttempt = { workspace: Workspace, executor_profile_id: ExecutorProfileId, 
+/**
+ * Changes against the target branches; null when the worktree is not available
+ */
+diff_stats: DiffStats | null, 
+/**
+ * Status of the latest coding agent run
+ */
+coding_agent_status: ExecutionProcessStatus | null, setup_script_exit_code: bigint | null, cleanup_script_exit_code: bigint | null, 
+/**
+ * Total wall-clock time of the coding agent runs, in seconds
+ */
+agent_duration_seconds: bigint, input_tokens: bigint, output_tokens: bigint, cache_read_tokens: bigint, cache_write_tokens: bigint, 
+/**
+ * Sum of agent reported costs, null when no run reported one
+ */
+cost_usd: number | null, is_winner: boolean, };
+
+export type WorkspaceGroupComparison = { group: WorkspaceGroup, attempts: Array<WorkspaceGroupAttempt>, };
+
+export type PickWorkspaceGroupWinner = { workspace_id: string, };
+
 export type DirectoryEntry = { name: string, path: string, is_directory: boolean, is_git_repo: boolean, last_modified: bigint | null, };
 
 export type DirectoryListResponse = { entries: Array<DirectoryEntry>, current_path: string, };
 
 export type SearchMode = "taskform" | "settings";
 
-export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, language: UiLanguage, git_branch_prefix: string, showcases: ShowcaseState, pr_auto_description_enabled: boolean, pr_auto_description_prompt: string | null, beta_workspaces: boolean, beta_workspaces_invitation_sent: boolean, commit_reminder: boolean, };
+export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, language: UiLanguage, git_branch_prefix: string, showcases: ShowcaseState, pr_auto_description_enabled: boolean, pr_auto_description_prompt: string | null, beta_workspaces: boolean, beta_workspaces_invitation_sent: boolean, commit_reminder: boolean, sandbox: SandboxConfig, auto_rebase: AutoRebaseConfig, };
+
+export type NotificationConfig = { sound_enabled: boolean, push_enabled: boolean, sound_file: SoundFile, webhooks: Array<WebhookConfig>, };
+
+/**
+ * Outbound HTTP notification target
+ */
+export type WebhookConfig = { url: string, enabled: boolean, format: WebhookFormat, 
+/**
+ * Events delivered to this webhook; empty means all events
+ */
+events: Array<NotificationEvent>, 
+/**
+ * When set, payloads are signed with HMAC-SHA256 in the `X-Vibe-Kanban-Signature` header
+ */
+secret: string | null, };
+
+export enum WebhookFormat { JSON = "JSON", SLACK = "SLACK", DISCORD = "DISCORD", TEAMS = "TEAMS" }
 
-export type NotificationConfig = { sound_enabled: boolean, push_enabled: boolean, sound_file: SoundFile, };
+export enum NotificationEvent { AGENT_FINISHED = "AGENT_FINISHED", APPROVAL_PENDING = "APPROVAL_PENDING", PR_MERGED = "PR_MERGED", EXECUTION_FAILED = "EXECUTION_FAILED" }
+
+/**
+ * Confine coding agents with Landlock (Linux only). Agents can read everything but only write to
+ * their workspace, the temp directories and `writable_paths`.
+ */
+export type SandboxConfig = { enabled: boolean, 
+/**
+ * Extra writable paths, such as the agents' own state directories; `~` is the home directory.
+ * The defaults only cover per-agent directories and caches, not shared ones like `~/.config`
+ * where an agent could install something that runs outside the sandbox.
+ */
+writable_paths: Array<string>, network: SandboxNetworkAccess, 
+/**
+ * TCP ports agents may connect to when `network` is `ALLOWED_PORTS`, besides this app's own
+ * port, which its MCP server uses. `BLOCKED` is refused for coding agents, since they reach
+ * their model API over the network.
+ */
+allowed_ports: Array<number>, };
+
+/**
+ * Rebase idle workspaces onto their target branch when it moves. A rebase that stops on
+ * conflicts is left in progress for the user, or handed to the agent when
+ * `resolve_conflicts_with_agent` is set.
+ */
+export type AutoRebaseConfig = { enabled: boolean, resolve_conflicts_with_agent: boolean, };
+
+/**
+ * Outgoing network access for sandboxed processes. `AllowedPorts` permits TCP connections only
+ * to the configured ports, on any host including loopback, and leaves UDP (including DNS) open.
+ * `Blocked` gives the process a network namespace of its own, so no traffic leaves at all, not
+ * even to the host's loopback; coding agents refuse to start under it since they could not reach
+ * their model API.
+ */
+export enum SandboxNetworkAccess { UNRESTRICTED = "UNRESTRICTED", ALLOWED_PORTS = "ALLOWED_PORTS", BLOCKED = "BLOCKED" }
 
 export enum ThemeMode { LIGHT = "LIGHT", DARK = "DARK", SYSTEM = "SYSTEM" }
 
@@ -396,6 +883,44 @@ export type ShowcaseState = { seen_features: Array<string>, };
 
 export type GitBranch = { name: string, is_current: boolean, is_remote: boolean, last_commit_date: Date, };
 
+export type SessionExportFormat = "markdown" | "html" | "json";
+
+export type SessionTranscript = { session_id: string, workspace_id: string, workspace_branch: string, task_id: string, task_title: string, task_description: string | null, executor: string | null, created_at: string, processes: Array<ProcessTranscript>, 
+/**
+ * Changes committed during the session, per repository
+ */
+diffs: Array<RepoDiffTranscript>, };
+
+export type ProcessTranscript = { id: string, run_reason: ExecutionProcessRunReason, status: ExecutionProcessStatus, exit_code: bigint | null, started_at: string, completed_at: string | null, 
+/**
+ * Normalized conversation of a coding agent run
+ */
+conversation: NormalizedConversation | null, 
+/**
+ * Combined stdout/stderr of a setup or cleanup script
+ */
+output: string | null, };
+
+export type RepoDiffTranscript = { repo_name: string, from_commit: string, to_commit: string, files: Array<FileDiffTranscript>, };
+
+export type FileDiffTranscript = { path: string, change: DiffChangeKind, 
+/**
+ * Unified diff, omitted for files too large to include
+ */
+unified_diff: string | null, };
+
+export type ProjectBundleSummary = { version: number, project_name: string, repos: Array<ProjectBundleRepoSummary>, task_count: number, tag_count: number, 
+/**
+ * Workspaces whose history is included
+ */
+workspace_count: number, };
+
+export type ProjectBundleRepoSummary = { id: string, display_name: string, 
+/**
+ * Where the repo was checked out on the exporting machine
+ */
+path: string, };
+
 export type SharedTaskDetails = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, };
 
 export type QueuedMessage = { 
@@ -416,13 +941,50 @@ export type QueueStatus = { "status": "empty" } | { "status": "queued", message:
 
 export type ConflictOp = "rebase" | "merge" | "cherry_pick" | "revert";
 
+/**
+ * A file left unmerged by a conflicted operation, with the version of each side. During a
+ * rebase `ours` is the branch being rebased onto and `theirs` the commit being replayed.
+ */
+export type ConflictFile = { path: string, 
+/**
+ * Null when the side does not have the file
+ */
+base: string | null, ours: string | null, theirs: string | null, 
+/**
+ * True when a side is not UTF-8 text; its contents are left out
+ */
+is_binary: boolean, };
+
+/**
+ * How to resolve a conflicted file: take one side, or replace it with `content`
+ */
+export type ConflictResolution = { "type": "ours" } | { "type": "theirs" } | { "type": "content", content: string, };
+
+/**
+ * A changed file to select, or only some of its hunks
+ */
+export type FileSelection = { path: string, 
+/**
+ * Indices of the selected hunks; the whole file when omitted
+ */
+hunks?: Array<number>, };
+
+/**
+ * A changed file and its hunks, for choosing what to select
+ */
+export type FileHunks = { path: string, old_path: string | null, change: DiffChangeKind, 
+/**
+ * Empty when the file can only be selected as a whole
+ */
+hunks: Array<string>, };
+
 export type ExecutorAction = { typ: ExecutorActionType, next_action: ExecutorAction | null, };
 
 export type McpConfig = { servers: { [key in string]?: JsonValue }, servers_path: Array<string>, template: JsonValue, preconfigured: JsonValue, is_toml_config: boolean, };
 
 export type ExecutorActionType = { "type": "CodingAgentInitialRequest" } & CodingAgentInitialRequest | { "type": "CodingAgentFollowUpRequest" } & CodingAgentFollowUpRequest | { "type": "ScriptRequest" } & ScriptRequest | { "type": "ReviewRequest" } & ReviewRequest;
 
-export type ScriptContext = "SetupScript" | "CleanupScript" | "DevServer" | "ToolInstallScript";
+export type ScriptContext = "SetupScript" | "CleanupScript" | "DevServer" | "ToolInstallScript" | "VerifyScript";
 
 export type ScriptRequest = { script: string, language: ScriptRequestLanguage, context: ScriptContext, 
 /**
@@ -465,13 +1027,13 @@ export type ExecutorConfigs = { executors: { [key in BaseCodingAgent]?: Executor
 
 export enum BaseAgentCapability { SESSION_FORK = "SESSION_FORK", SETUP_HELPER = "SETUP_HELPER" }
 
-export type ClaudeCode = { append_prompt: AppendPrompt, claude_code_router?: boolean | null, plan?: boolean | null, approvals?: boolean | null, model?: string | null, dangerously_skip_permissions?: boolean | null, disable_api_key?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type ClaudeCode = { append_prompt: AppendPrompt, claude_code_router?: boolean | null, plan?: boolean | null, approvals?: boolean | null, model?: string | null, dangerously_skip_permissions?: boolean | null, disable_api_key?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, budget_max_tokens?: bigint | null, budget_max_minutes?: number | null, budget_max_tool_calls?: number | null, approval_rules?: Array<ApprovalRule>, approval_timeout_minutes?: number | null, approval_timeout_outcome?: ApprovalTimeoutOutcome | null, approval_reminder_minutes?: number | null, };
 
-export type Gemini = { append_prompt: AppendPrompt, model?: string | null, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Gemini = { append_prompt: AppendPrompt, model?: string | null, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, budget_max_tokens?: bigint | null, budget_max_minutes?: number | null, budget_max_tool_calls?: number | null, approval_rules?: Array<ApprovalRule>, approval_timeout_minutes?: number | null, approval_timeout_outcome?: ApprovalTimeoutOutcome | null, approval_reminder_minutes?: number | null, };
 
-export type Amp = { append_prompt: AppendPrompt, dangerously_allow_all?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Amp = { append_prompt: AppendPrompt, dangerously_allow_all?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, budget_max_tokens?: bigint | null, budget_max_minutes?: number | null, budget_max_tool_calls?: number | null, approval_rules?: Array<ApprovalRule>, approval_timeout_minutes?: number | null, approval_timeout_outcome?: ApprovalTimeoutOutcome | null, approval_reminder_minutes?: number | null, };
 
-export type Codex = { append_prompt: AppendPrompt, sandbox?: SandboxMode | null, ask_for_approval?: AskForApproval | null, oss?: boolean | null, model?: string | null, model_reasoning_effort?: ReasoningEffort | null, model_reasoning_summary?: ReasoningSummary | null, model_reasoning_summary_format?: ReasoningSummaryFormat | null, profile?: string | null, base_instructions?: string | null, include_apply_patch_tool?: boolean | null, model_provider?: string | null, compact_prompt?: string | null, developer_instructions?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Codex = { append_prompt: AppendPrompt, sandbox?: SandboxMode | null, ask_for_approval?: AskForApproval | null, oss?: boolean | null, model?: string | null, model_reasoning_effort?: ReasoningEffort | null, model_reasoning_summary?: ReasoningSummary | null, model_reasoning_summary_format?: ReasoningSummaryFormat | null, profile?: string | null, base_instructions?: string | null, include_apply_patch_tool?: boolean | null, model_provider?: string | null, compact_prompt?: string | null, developer_instructions?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, budget_max_tokens?: bigint | null, budget_max_minutes?: number | null, budget_max_tool_calls?: number | null, approval_rules?: Array<ApprovalRule>, approval_timeout_minutes?: number | null, approval_timeout_outcome?: ApprovalTimeoutOutcome | null, approval_reminder_minutes?: number | null, };
 
 export type SandboxMode = "auto" | "read-only" | "workspace-write" | "danger-full-access";
 
@@ -483,19 +1045,19 @@ export type ReasoningSummary = "auto" | "concise" | "detailed" | "none";
 
 export type ReasoningSummaryFormat = "none" | "experimental";
 
-export type CursorAgent = { append_prompt: AppendPrompt, force?: boolean | null, model?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type CursorAgent = { append_prompt: AppendPrompt, force?: boolean | null, model?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, budget_max_tokens?: bigint | null, budget_max_minutes?: number | null, budget_max_tool_calls?: number | null, approval_rules?: Array<ApprovalRule>, approval_timeout_minutes?: number | null, approval_timeout_outcome?: ApprovalTimeoutOutcome | null, approval_reminder_minutes?: number | null, };
 
-export type Copilot = { append_prompt: AppendPrompt, model?: string | null, allow_all_tools?: boolean | null, allow_tool?: string | null, deny_tool?: string | null, add_dir?: Array<string> | null, disable_mcp_server?: Array<string> | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Copilot = { append_prompt: AppendPrompt, model?: string | null, allow_all_tools?: boolean | null, allow_tool?: string | null, deny_tool?: string | null, add_dir?: Array<string> | null, disable_mcp_server?: Array<string> | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, budget_max_tokens?: bigint | null, budget_max_minutes?: number | null, budget_max_tool_calls?: number | null, approval_rules?: Array<ApprovalRule>, approval_timeout_minutes?: number | null, approval_timeout_outcome?: ApprovalTimeoutOutcome | null, approval_reminder_minutes?: number | null, };
 
 export type Opencode = { append_prompt: AppendPrompt, model?: string | null, mode?: string | null, 
 /**
  * Auto-approve agent actions
  */
-auto_approve: boolean, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+auto_approve: boolean, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, budget_max_tokens?: bigint | null, budget_max_minutes?: number | null, budget_max_tool_calls?: number | null, approval_rules?: Array<ApprovalRule>, approval_timeout_minutes?: number | null, approval_timeout_outcome?: ApprovalTimeoutOutcome | null, approval_reminder_minutes?: number | null, };
 
-export type QwenCode = { append_prompt: AppendPrompt, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type QwenCode = { append_prompt: AppendPrompt, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, budget_max_tokens?: bigint | null, budget_max_minutes?: number | null, budget_max_tool_calls?: number | null, approval_rules?: Array<ApprovalRule>, approval_timeout_minutes?: number | null, approval_timeout_outcome?: ApprovalTimeoutOutcome | null, approval_reminder_minutes?: number | null, };
 
-export type Droid = { append_prompt: AppendPrompt, autonomy: Autonomy, model?: string | null, reasoning_effort?: DroidReasoningEffort | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Droid = { append_prompt: AppendPrompt, autonomy: Autonomy, model?: string | null, reasoning_effort?: DroidReasoningEffort | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, budget_max_tokens?: bigint | null, budget_max_minutes?: number | null, budget_max_tool_calls?: number | null, approval_rules?: Array<ApprovalRule>, approval_timeout_minutes?: number | null, approval_timeout_outcome?: ApprovalTimeoutOutcome | null, approval_reminder_minutes?: number | null, };
 
 export type Autonomy = "normal" | "low" | "medium" | "high" | "skip-permissions-unsafe";
 
@@ -541,6 +1103,8 @@ export type CommandExitStatus = { "type": "exit_code", code: number, } | { "type
 
 export type CommandRunResult = { exit_status: CommandExitStatus | null, output: string | null, };
 
+export type NormalizedConversation = { entries: Array<NormalizedEntry>, session_id: string | null, executor_type: string, prompt: string | null, summary: string | null, };
+
 export type NormalizedEntry = { timestamp: string | null, entry_type: NormalizedEntryType, content: string, };
 
 export type NormalizedEntryType = { "type": "user_message" } | { "type": "user_feedback", denied_tool: string, } | { "type": "assistant_message" } | { "type": "tool_use", tool_name: string, action_type: ActionType, status: ToolStatus, } | { "type": "system_message" } | { "type": "error_message", error_type: NormalizedEntryError, } | { "type": "thinking" } | { "type": "loading" } | { "type": "next_action", failed: boolean, execution_processes: number, needs_setup: boolean, } | { "type": "token_usage_info" } & TokenUsageInfo;
@@ -571,7 +1135,11 @@ value: JsonValue, };
 
 export type ToolResultValueType = { "type": "markdown" } | { "type": "json" };
 
-export type ToolStatus = { "status": "created" } | { "status": "success" } | { "status": "failed" } | { "status": "denied", reason: string | null, } | { "status": "pending_approval", approval_id: string, requested_at: string, timeout_at: string, } | { "status": "timed_out" };
+export type ToolStatus = { "status": "created" } | { "status": "success" } | { "status": "failed" } | { "status": "denied", reason: string | null, } | { "status": "pending_approval", approval_id: string, requested_at: string, 
+/**
+ * `None` once the request has timed out and waits for a response indefinitely
+ */
+timeout_at: string | null, } | { "status": "timed_out" };
 
 export type PatchType = { "type": "NORMALIZED_ENTRY", "content": NormalizedEntry } | { "type": "STDOUT", "content": string } | { "type": "STDERR", "content": string } | { "type": "DIFF", "content": Diff };
 